@@ -0,0 +1,54 @@
+//! 大配置下的配置读取基准：300 个带内嵌图标的小程序
+//!
+//! 运行：`cargo bench --features bench --bench config`
+//! 目标：`get_config` 返回的数据（读取 + 去掉内嵌图标 + 序列化）在 5ms 以内
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use webapp_hub_lib::bench::{strip_inline, AppConfig, ConfigManager, JsonFileStore, WebApp};
+
+const WEBAPPS: usize = 300;
+const ICON_BYTES: usize = 16 * 1024;
+
+fn large_config() -> AppConfig {
+    let icon = format!("data:image/png;base64,{}", "A".repeat(ICON_BYTES / 3 * 4));
+    let webapps = (0..WEBAPPS)
+        .map(|i| {
+            let mut webapp = WebApp::new(format!("App {}", i), format!("https://app{}.example.com", i));
+            webapp.order = i as u32;
+            webapp.icon = Some(icon.clone());
+            webapp
+        })
+        .collect();
+    AppConfig {
+        webapps,
+        ..Default::default()
+    }
+}
+
+fn config_benchmarks(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join(format!("webapp-hub-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.json");
+    std::fs::write(&path, serde_json::to_string(&large_config()).unwrap()).unwrap();
+
+    let open = |path: &std::path::Path| ConfigManager::new(Box::new(JsonFileStore::new(path.to_path_buf())));
+    c.bench_function("startup_load", |b| b.iter(|| open(black_box(&path))));
+
+    let manager = open(&path);
+    c.bench_function("read", |b| b.iter(|| black_box(manager.read())));
+    c.bench_function("get_config_payload", |b| {
+        b.iter(|| {
+            let mut config = manager.read();
+            strip_inline(&mut config);
+            black_box(serde_json::to_string(&config).unwrap())
+        })
+    });
+    c.bench_function("full_payload", |b| {
+        b.iter(|| black_box(serde_json::to_string(&manager.read()).unwrap()))
+    });
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+criterion_group!(benches, config_benchmarks);
+criterion_main!(benches);
@@ -1,4 +1,150 @@
+/// 应用的全部命令（与 `lib.rs` 的 `generate_handler!` 一致）
+///
+/// 声明后每个命令都需要权限才能调用：远程网页（webapp-bridge）只允许 `bridge_event` 和 `notify`，
+/// 其他命令只授予本地界面（default）
+const APP_COMMANDS: &[&str] = &[
+    "get_config",
+    "save_config",
+    "add_webapp",
+    "update_webapp",
+    "bulk_update_webapps",
+    "delete_webapp",
+    "open_webapp",
+    "open_webapp_at",
+    "toggle_webapp",
+    "close_webapp",
+    "set_max_active_windows",
+    "set_proxy_config",
+    "register_shortcut",
+    "unregister_shortcut",
+    "get_shortcut_failures",
+    "open_webapp_window",
+    "close_webapp_window",
+    "toggle_webapp_window",
+    "run_automation",
+    "run_automation_source",
+    "run_macro",
+    "set_dnd",
+    "get_dnd_status",
+    "bridge_event",
+    "set_webapp_muted",
+    "set_mute_all",
+    "toggle_mute_all",
+    "reader_mode",
+    "exit_reader_mode",
+    "speak_selection",
+    "stop_speaking",
+    "translate_selection",
+    "list_cookie_sources",
+    "import_browser_cookies",
+    "get_accessibility",
+    "set_accessibility",
+    "open_settings",
+    "complete_onboarding",
+    "share_webapp",
+    "import_share_code",
+    "lint_config",
+    "set_staging_mode",
+    "get_platform_capabilities",
+    "get_ui_prefs",
+    "set_ui_prefs",
+    "get_backup_status",
+    "run_backup_now",
+    "get_maintenance_report",
+    "run_maintenance_now",
+    "get_storage_usage",
+    "set_protocol_handler",
+    "set_protocol_enabled",
+    "remove_protocol_handler",
+    "get_share_candidates",
+    "share_to_webapp",
+    "set_clipboard_watch",
+    "get_policy_status",
+    "lock_config",
+    "unlock_config",
+    "relock_config",
+    "disable_config_lock",
+    "set_blocked_domains",
+    "get_managed_policy",
+    "get_badges",
+    "get_launcher_grid",
+    "activate_index",
+    "get_recently_closed",
+    "reopen_last_closed",
+    "get_usage_stats",
+    "recover_webapp",
+    "get_memory_status",
+    "get_request_log",
+    "clear_request_log",
+    "add_trusted_ca",
+    "list_trusted_cas",
+    "remove_trusted_ca",
+    "submit_proxy_credentials",
+    "cancel_proxy_auth",
+    "forget_proxy_credentials",
+    "set_monitor_zoom",
+    "snapshot_state",
+    "restore_state",
+    "patch_config",
+    "wipe_webapp_data",
+    "get_webapp_icon",
+    "export_config",
+    "import_config",
+    "set_auto_start",
+    "get_auto_start_status",
+    "get_user_scripts",
+    "save_user_script",
+    "delete_user_script",
+    "run_user_script",
+    "export_diagnostics",
+    "clone_window",
+    "set_log_level",
+    "get_active_webapps",
+    "refresh_webapp_icon",
+    "open_in_browser",
+    "get_groups",
+    "save_group",
+    "delete_group",
+    "open_group",
+    "close_group",
+    "get_routing_decisions",
+    "set_webapp_pin",
+    "list_shortcuts",
+    "validate_shortcut",
+    "set_mute_all_shortcut",
+    "set_network_conditions",
+    "subscribe",
+    "export_proxy_env",
+    "import_from",
+    "notify",
+    "get_webapp_stats",
+    "reset_stats",
+    "reorder_webapps",
+    "bulk_delete",
+    "bulk_set_proxy",
+    "get_storage_backend",
+    "set_storage_backend",
+    "set_webapp_zoom",
+    "zoom_webapp",
+    "run_script",
+    "get_recent_script_runs",
+    "list_downloads",
+    "open_download_folder",
+    "search_webapps",
+    "activate_switcher_result",
+    "export_webapp_shortcut",
+    "get_quiet_hours_status",
+    "set_quiet_hours_override",
+    "query_state",
+    "get_safe_mode_status",
+    "exit_safe_mode",
+    "set_icon_theme",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(APP_COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
 }
-
@@ -0,0 +1,171 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+
+use crate::badges;
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::events::{self, Channel};
+use crate::models::{AccessibilityConfig, WebApp};
+use crate::window::{window_label, window_title, WindowManager};
+
+/// 各小程序当前的页面标题（由桥接脚本上报），用于生成窗口标题
+static PAGE_TITLES: Mutex<Option<HashMap<String, String>>> = parking_lot::const_mutex(None);
+
+/// 读屏播报：写入焦点窗口中不可见的 `aria-live` 区域，由读屏软件读出
+const ANNOUNCE_SCRIPT: &str = r#"(function(text) {
+    var id = '__webapp_hub_announcer__';
+    var region = document.getElementById(id);
+    if (!region) {
+        region = document.createElement('div');
+        region.id = id;
+        region.setAttribute('role', 'status');
+        region.setAttribute('aria-live', 'polite');
+        region.style.cssText = 'position:fixed;width:1px;height:1px;margin:-1px;overflow:hidden;clip:rect(0 0 0 0);white-space:nowrap;';
+        (document.body || document.documentElement).appendChild(region);
+    }
+    // 先清空再写入，相同的内容也会再次播报
+    region.textContent = '';
+    setTimeout(function() { region.textContent = text; }, 100);
+})(__TEXT__);"#;
+
+/// 保存无障碍设置并实时应用到所有已打开的小程序窗口
+pub fn set_settings(app: &AppHandle, settings: AccessibilityConfig) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().update(|config| {
+        config.accessibility = settings.clone();
+        config.clone()
+    })?;
+
+    if let Some(wm) = app.try_state::<WindowManager>() {
+        wm.refresh_styles(app, &config);
+    }
+    events::emit(app, Channel::Config, "accessibility://changed", &settings);
+
+    log::info!("Accessibility settings updated (enabled: {})", settings.enabled);
+    Ok(())
+}
+
+/// 切换无障碍样式总开关，返回切换后的状态
+pub fn toggle(app: &AppHandle) -> AppResult<bool> {
+    let mut settings = app.state::<ConfigManager>().read().accessibility;
+    settings.enabled = !settings.enabled;
+    let enabled = settings.enabled;
+    set_settings(app, settings)?;
+    Ok(enabled)
+}
+
+/// 去掉页面标题开头或结尾的未读数量（如 `(3) Inbox`），数量单独显示在窗口标题中
+fn strip_count(title: &str) -> &str {
+    let title = title.trim();
+    let is_count = |word: &str| badges::parse_title_count(word).is_some();
+    let Some(first) = title.split_whitespace().next() else {
+        return title;
+    };
+    if is_count(first) {
+        return title[first.len()..].trim_start();
+    }
+    match title.rsplit_once(char::is_whitespace) {
+        Some((rest, last)) if is_count(last) => rest.trim_end(),
+        _ => title,
+    }
+}
+
+/// 读屏友好的窗口标题：小程序名称、未读数量和页面标题（与名称相同时省略），例如 `Gmail（3 条未读） - Inbox`
+fn descriptive_title(webapp: &WebApp, count: Option<u32>, page_title: Option<&str>) -> String {
+    let mut title = window_title(webapp);
+    if let Some(count) = count.filter(|c| *c > 0) {
+        title.push_str(&format!("（{} 条未读）", count));
+    }
+    if let Some(page) = page_title.map(strip_count).filter(|p| !p.is_empty() && !p.eq_ignore_ascii_case(&webapp.name)) {
+        title.push_str(" - ");
+        title.push_str(page);
+    }
+    title
+}
+
+/// 按当前的未读数量和页面标题更新小程序窗口的标题
+fn refresh_title(app: &AppHandle, webapp_id: &str) {
+    let Some(window) = app.get_webview_window(&window_label(webapp_id)) else {
+        return;
+    };
+    let config = app.state::<ConfigManager>().read();
+    let Some(webapp) = config.webapps.iter().find(|w| w.id == webapp_id) else {
+        return;
+    };
+    let page_title = PAGE_TITLES
+        .lock()
+        .as_ref()
+        .and_then(|titles| titles.get(webapp_id).cloned());
+    let title = descriptive_title(webapp, badges::count_for(app, webapp_id), page_title.as_deref());
+    if let Err(e) = window.set_title(&title) {
+        log::debug!("Failed to update title of {}: {}", webapp_id, e);
+    }
+}
+
+/// 页面标题变化
+pub fn page_title_changed(app: &AppHandle, webapp_id: &str, title: &str) {
+    PAGE_TITLES
+        .lock()
+        .get_or_insert_with(HashMap::new)
+        .insert(webapp_id.to_string(), title.to_string());
+    refresh_title(app, webapp_id);
+}
+
+/// 未读数量变化：更新窗口标题，开启详细播报时播报新的数量
+pub fn badge_changed(app: &AppHandle, webapp_id: &str, count: Option<u32>) {
+    refresh_title(app, webapp_id);
+
+    let config = app.state::<ConfigManager>().read();
+    if !config.accessibility.verbose_announcements {
+        return;
+    }
+    if let Some(webapp) = config.webapps.iter().find(|w| w.id == webapp_id) {
+        let text = match count {
+            Some(count) => format!("{} 有 {} 条未读", webapp.name, count),
+            None => format!("{} 没有未读", webapp.name),
+        };
+        announce(app, &text);
+    }
+}
+
+/// 窗口关闭时清除记录的页面标题
+pub fn forget(webapp_id: &str) {
+    if let Some(titles) = PAGE_TITLES.lock().as_mut() {
+        titles.remove(webapp_id);
+    }
+}
+
+/// 播报一条消息：写入当前有焦点的窗口（小程序窗口或主窗口），没有焦点窗口时只通知前端
+pub fn announce(app: &AppHandle, text: &str) {
+    let focused = app
+        .webview_windows()
+        .into_values()
+        .find(|window| window.is_focused().unwrap_or(false));
+    if let Some(window) = focused {
+        let literal = serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string());
+        if let Err(e) = window.eval(ANNOUNCE_SCRIPT.replace("__TEXT__", &literal)) {
+            log::debug!("Failed to announce in {}: {}", window.label(), e);
+        }
+    }
+    events::emit(app, Channel::Config, "accessibility://announce", serde_json::json!({ "text": text }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descriptive_title() {
+        let mut webapp = WebApp::new("Gmail".to_string(), "https://mail.google.com".to_string());
+        assert_eq!(descriptive_title(&webapp, None, None), "Gmail");
+        assert_eq!(descriptive_title(&webapp, None, Some("gmail")), "Gmail");
+        assert_eq!(
+            descriptive_title(&webapp, Some(3), Some("(3) Inbox - you@example.com")),
+            "Gmail（3 条未读） - Inbox - you@example.com"
+        );
+        assert_eq!(descriptive_title(&webapp, Some(0), Some("Inbox [12]")), "Gmail - Inbox");
+
+        webapp.javascript_enabled = false;
+        assert_eq!(descriptive_title(&webapp, None, None), "Gmail（已禁用 JavaScript）");
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Channel};
+use crate::models::{AppConfig, WebApp};
+use crate::tray::TrayMenuState;
+use crate::window::window_label;
+
+/// 静音状态变化事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MuteChanged {
+    /// 是否全部静音
+    pub mute_all: bool,
+    /// 单个小程序的静音变化（全部静音切换时为 None）
+    pub webapp_id: Option<String>,
+    pub muted: bool,
+}
+
+/// 小程序窗口的实际静音状态
+pub fn effective_muted(config: &AppConfig, webapp: &WebApp) -> bool {
+    config.mute_all || webapp.muted
+}
+
+/// 生成设置页面静音状态的脚本（依赖桥接脚本）
+pub fn mute_script(muted: bool) -> String {
+    format!(
+        "window.__WEBAPP_HUB__ && window.__WEBAPP_HUB__.setMuted({})",
+        muted
+    )
+}
+
+/// 将静音状态推送到所有已打开的小程序窗口
+fn apply_to_windows(app: &AppHandle, config: &AppConfig) {
+    for webapp in &config.webapps {
+        if let Some(window) = app.get_webview_window(&window_label(&webapp.id)) {
+            let _ = window.eval(mute_script(effective_muted(config, webapp)));
+        }
+    }
+}
+
+/// 设置全部静音
+pub fn set_mute_all(app: &AppHandle, muted: bool) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().update(|config| {
+        config.mute_all = muted;
+        config.clone()
+    })?;
+
+    apply_to_windows(app, &config);
+
+    if let Some(tray) = app.try_state::<TrayMenuState>() {
+        let _ = tray.mute_all.set_checked(muted);
+    }
+    events::emit(
+        app,
+        Channel::Config,
+        "mute://changed",
+        MuteChanged {
+            mute_all: muted,
+            webapp_id: None,
+            muted,
+        },
+    );
+
+    log::info!("Mute all webapps: {}", muted);
+    Ok(())
+}
+
+/// 切换全部静音，返回切换后的状态
+pub fn toggle_mute_all(app: &AppHandle) -> AppResult<bool> {
+    let muted = !app.state::<ConfigManager>().read().mute_all;
+    set_mute_all(app, muted)?;
+    Ok(muted)
+}
+
+/// 设置单个小程序静音
+pub fn set_webapp_muted(app: &AppHandle, webapp_id: &str, muted: bool) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().update(|config| {
+        let webapp = config.webapps.iter_mut().find(|w| w.id == webapp_id)?;
+        webapp.muted = muted;
+        Some(config.clone())
+    })?;
+    let config = config.ok_or_else(|| AppError::not_found("小程序不存在"))?;
+
+    apply_to_windows(app, &config);
+    events::emit(
+        app,
+        Channel::Config,
+        "mute://changed",
+        MuteChanged {
+            mute_all: config.mute_all,
+            webapp_id: Some(webapp_id.to_string()),
+            muted,
+        },
+    );
+
+    log::info!("Set webapp {} muted: {}", webapp_id, muted);
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::window::{window_label, WindowManager};
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 各窗口最近一次加载页面的时间（包括自动刷新和页面内跳转）
+static LOADED: Mutex<Option<HashMap<String, Instant>>> = parking_lot::const_mutex(None);
+
+/// 页面开始加载时调用，重新开始计时
+pub fn loaded(webapp_id: &str) {
+    LOADED
+        .lock()
+        .get_or_insert_with(HashMap::new)
+        .insert(webapp_id.to_string(), Instant::now());
+}
+
+/// 窗口关闭时停止计时
+pub fn forget(webapp_id: &str) {
+    if let Some(loaded) = LOADED.lock().as_mut() {
+        loaded.remove(webapp_id);
+    }
+}
+
+/// 距离上次加载已超过刷新间隔（没有加载记录时从现在开始计时）
+fn is_due(last_loaded: Option<Instant>, now: Instant, minutes: u32) -> bool {
+    last_loaded.is_some_and(|at| now.duration_since(at) >= Duration::from_secs(minutes as u64 * 60))
+}
+
+fn check(app: &AppHandle) {
+    let config = app.state::<ConfigManager>().read();
+    let window_manager = app.state::<WindowManager>();
+    let now = Instant::now();
+
+    for webapp in config.webapps.iter().filter(|w| w.enabled) {
+        let minutes = match webapp.auto_reload_minutes.filter(|m| *m > 0) {
+            Some(minutes) => minutes,
+            None => continue,
+        };
+        let window = match app.get_webview_window(&window_label(&webapp.id)) {
+            Some(window) => window,
+            None => continue,
+        };
+        let last_loaded = LOADED.lock().get_or_insert_with(HashMap::new).get(&webapp.id).copied();
+        if last_loaded.is_none() {
+            loaded(&webapp.id);
+        }
+        if !is_due(last_loaded, now, minutes) {
+            continue;
+        }
+        // 正在使用的窗口和有未保存内容的窗口推迟到下次检查
+        if window.is_focused().unwrap_or(false) || window_manager.is_dirty(&webapp.id) {
+            continue;
+        }
+
+        match window.reload() {
+            Ok(()) => {
+                loaded(&webapp.id);
+                log::info!("Auto-reloaded webapp {} after {} minutes", webapp.id, minutes);
+            }
+            Err(e) => log::warn!("Failed to auto-reload webapp {}: {}", webapp.id, e),
+        }
+    }
+}
+
+/// 定时刷新设置了 `auto_reload_minutes` 的已打开窗口（如状态看板）
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            check(&app);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due() {
+        let start = Instant::now();
+        assert!(!is_due(None, start, 5));
+        assert!(!is_due(Some(start), start + Duration::from_secs(299), 5));
+        assert!(is_due(Some(start), start + Duration::from_secs(300), 5));
+    }
+}
@@ -0,0 +1,74 @@
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+
+/// 开机自启时附加的启动参数
+pub const LAUNCH_ARG: &str = "--autostart";
+
+/// 开机自启状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoStartStatus {
+    /// 配置中的设置
+    pub configured: bool,
+    /// 系统中实际的登录项状态（读取失败时为 None）
+    pub system: Option<bool>,
+}
+
+/// 需要对系统登录项做的修改：Some(true) 启用，Some(false) 关闭，None 无需修改
+fn reconcile_action(configured: bool, system: Option<bool>) -> Option<bool> {
+    match system {
+        Some(actual) if actual == configured => None,
+        _ => Some(configured),
+    }
+}
+
+fn apply(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let manager = app.autolaunch();
+    let result = if enabled { manager.enable() } else { manager.disable() };
+    result.map_err(|e| format!("无法修改开机自启: {}", e))
+}
+
+/// 让系统登录项与配置一致（启动时和保存配置后调用），失败只记录日志
+pub fn reconcile(app: &AppHandle, configured: bool) {
+    let system = app.autolaunch().is_enabled().ok();
+    if let Some(enabled) = reconcile_action(configured, system) {
+        match apply(app, enabled) {
+            Ok(()) => log::info!("Auto start {}", if enabled { "enabled" } else { "disabled" }),
+            Err(e) => log::warn!("Failed to reconcile auto start: {}", e),
+        }
+    }
+}
+
+/// 开机自启的当前状态
+pub fn status(app: &AppHandle, config_manager: &ConfigManager) -> AutoStartStatus {
+    AutoStartStatus {
+        configured: config_manager.read().auto_start,
+        system: app.autolaunch().is_enabled().ok(),
+    }
+}
+
+/// 修改开机自启：先修改系统登录项，成功后再保存到配置
+pub fn set(app: &AppHandle, config_manager: &ConfigManager, enabled: bool) -> AppResult<AutoStartStatus> {
+    apply(app, enabled)?;
+    config_manager.update(|config| config.auto_start = enabled)?;
+    Ok(status(app, config_manager))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconcile_action() {
+        assert_eq!(reconcile_action(true, Some(true)), None);
+        assert_eq!(reconcile_action(false, Some(false)), None);
+        assert_eq!(reconcile_action(true, Some(false)), Some(true));
+        assert_eq!(reconcile_action(false, Some(true)), Some(false));
+        // 读取失败时按配置设置
+        assert_eq!(reconcile_action(true, None), Some(true));
+    }
+}
@@ -0,0 +1,198 @@
+//! 窗口和快捷键操作的抽象：正式运行时由 `AppHandle` 实现，
+//! 测试中用 [`mock`] 中的实现代替，让窗口管理器和快捷键管理器的逻辑可以直接测试
+
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppResult;
+use crate::models::{ShortcutAction, WebApp};
+use crate::shortcuts::ShortcutFailure;
+
+/// 窗口的可见性和焦点
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WindowStatus {
+    pub visible: bool,
+    pub focused: bool,
+    pub minimized: bool,
+}
+
+/// 按标签操作已存在的窗口
+pub trait WindowBackend {
+    /// 窗口的状态，窗口不存在时为 None
+    fn status(&self, label: &str) -> Option<WindowStatus>;
+    fn show(&self, label: &str) -> Result<(), String>;
+    fn hide(&self, label: &str) -> Result<(), String>;
+    fn focus(&self, label: &str) -> Result<(), String>;
+}
+
+fn with_window<R>(
+    app: &AppHandle,
+    label: &str,
+    f: impl FnOnce(tauri::WebviewWindow) -> tauri::Result<R>,
+) -> Result<R, String> {
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("窗口不存在: {}", label))?;
+    f(window).map_err(|e| e.to_string())
+}
+
+impl WindowBackend for AppHandle {
+    fn status(&self, label: &str) -> Option<WindowStatus> {
+        let window = self.get_webview_window(label)?;
+        Some(WindowStatus {
+            visible: window.is_visible().unwrap_or(false),
+            focused: window.is_focused().unwrap_or(false),
+            minimized: window.is_minimized().unwrap_or(false),
+        })
+    }
+
+    fn show(&self, label: &str) -> Result<(), String> {
+        with_window(self, label, |w| w.show())
+    }
+
+    fn hide(&self, label: &str) -> Result<(), String> {
+        with_window(self, label, |w| w.hide())
+    }
+
+    fn focus(&self, label: &str) -> Result<(), String> {
+        with_window(self, label, |w| w.set_focus())
+    }
+}
+
+/// 创建新窗口和关闭被淘汰的窗口（正式运行时见 `window::WebviewOpener`，构建 WebView 并发送生命周期事件）
+pub trait WindowOpener {
+    /// 创建小程序的新窗口
+    fn create(&self, webapp: &WebApp) -> AppResult<()>;
+    /// 关闭为新窗口腾出位置的最久未使用窗口
+    fn evict(&self, webapp_id: &str);
+    /// 最久未使用的窗口有未保存内容，没有被关闭
+    fn eviction_skipped(&self, _webapp_id: &str) {}
+}
+
+/// 向系统注册全局快捷键（Tauri 实现见 `shortcuts` 模块）
+pub trait ShortcutRegistrar {
+    /// 是否通过桌面门户绑定快捷键（Wayland），门户每次绑定全部快捷键
+    fn uses_portal(&self) -> bool {
+        false
+    }
+
+    /// 通过门户绑定全部快捷键
+    fn bind_portal(&self, _bindings: Vec<(String, ShortcutAction)>) {}
+
+    /// 注册快捷键，按下时执行动作（generation 用于识别过期的回调）
+    fn register(&self, shortcut: &str, action: &ShortcutAction, generation: u64) -> Result<(), String>;
+
+    fn unregister(&self, shortcut: &str) -> Result<(), String>;
+
+    /// 通知前端快捷键注册失败
+    fn report_failure(&self, failure: &ShortcutFailure);
+}
+
+#[cfg(any(test, feature = "e2e"))]
+pub mod mock {
+    use parking_lot::Mutex;
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::error::AppError;
+
+    /// 内存中的窗口，记录每次操作
+    #[derive(Default)]
+    pub struct MockWindows {
+        pub windows: Mutex<HashMap<String, WindowStatus>>,
+    }
+
+    impl MockWindows {
+        pub fn with(label: &str, status: WindowStatus) -> Self {
+            let mock = Self::default();
+            mock.windows.lock().insert(label.to_string(), status);
+            mock
+        }
+
+        pub fn get(&self, label: &str) -> Option<WindowStatus> {
+            self.windows.lock().get(label).copied()
+        }
+
+        fn update(&self, label: &str, f: impl FnOnce(&mut WindowStatus)) -> Result<(), String> {
+            let mut windows = self.windows.lock();
+            let status = windows.get_mut(label).ok_or_else(|| format!("窗口不存在: {}", label))?;
+            f(status);
+            Ok(())
+        }
+    }
+
+    impl WindowBackend for MockWindows {
+        fn status(&self, label: &str) -> Option<WindowStatus> {
+            self.get(label)
+        }
+
+        fn show(&self, label: &str) -> Result<(), String> {
+            self.update(label, |s| {
+                s.visible = true;
+                s.minimized = false;
+            })
+        }
+
+        fn hide(&self, label: &str) -> Result<(), String> {
+            self.update(label, |s| {
+                s.visible = false;
+                s.focused = false;
+            })
+        }
+
+        fn focus(&self, label: &str) -> Result<(), String> {
+            let mut windows = self.windows.lock();
+            if !windows.contains_key(label) {
+                return Err(format!("窗口不存在: {}", label));
+            }
+            for (other, status) in windows.iter_mut() {
+                status.focused = other == label;
+            }
+            Ok(())
+        }
+    }
+
+    /// 新窗口创建后可见并获得焦点，被淘汰的窗口直接移除
+    impl WindowOpener for MockWindows {
+        fn create(&self, webapp: &WebApp) -> AppResult<()> {
+            let label = crate::window::window_label(&webapp.id);
+            self.windows.lock().insert(label.clone(), WindowStatus::default());
+            self.show(&label).map_err(AppError::Window)?;
+            self.focus(&label).map_err(AppError::Window)
+        }
+
+        fn evict(&self, webapp_id: &str) {
+            self.windows.lock().remove(&crate::window::window_label(webapp_id));
+        }
+    }
+
+    /// 内存中的快捷键注册表，`taken` 中的快捷键模拟已被其他程序占用
+    #[derive(Default)]
+    pub struct MockShortcuts {
+        pub registered: Mutex<HashMap<String, ShortcutAction>>,
+        /// 注册时的代数（与正式回调捕获的代数相同）
+        pub generations: Mutex<HashMap<String, u64>>,
+        pub taken: Mutex<HashSet<String>>,
+        pub failures: Mutex<Vec<ShortcutFailure>>,
+    }
+
+    impl ShortcutRegistrar for MockShortcuts {
+        fn register(&self, shortcut: &str, action: &ShortcutAction, generation: u64) -> Result<(), String> {
+            if self.taken.lock().contains(shortcut) {
+                return Err(format!("注册快捷键失败: {} 已被占用", shortcut));
+            }
+            self.registered.lock().insert(shortcut.to_string(), action.clone());
+            self.generations.lock().insert(shortcut.to_string(), generation);
+            Ok(())
+        }
+
+        fn unregister(&self, shortcut: &str) -> Result<(), String> {
+            self.registered.lock().remove(shortcut);
+            self.generations.lock().remove(shortcut);
+            Ok(())
+        }
+
+        fn report_failure(&self, failure: &ShortcutFailure) {
+            self.failures.lock().push(failure.clone());
+        }
+    }
+}
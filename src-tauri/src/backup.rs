@@ -0,0 +1,193 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::models::AppConfig;
+use crate::secrets;
+
+/// 定时备份的检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 两次定时备份之间的最短间隔（每天一次）
+const BACKUP_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// 备份文件名前缀，清理旧备份时只处理带此前缀的文件
+const FILE_PREFIX: &str = "webapp-hub-backup-";
+
+/// 备份状态，供前端显示
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupStatus {
+    pub enabled: bool,
+    pub directory: Option<String>,
+    /// 最近一次成功备份的时间戳
+    pub last_backup_at: Option<u64>,
+    pub last_backup_path: Option<String>,
+    /// 最近一次备份失败的原因（成功后清除）
+    pub last_error: Option<String>,
+    /// 下一次定时备份的时间戳
+    pub next_backup_at: Option<u64>,
+}
+
+/// 最近一次备份的结果
+#[derive(Debug, Default)]
+struct LastBackup {
+    at: Option<u64>,
+    path: Option<PathBuf>,
+    error: Option<String>,
+}
+
+/// 备份管理状态
+pub struct BackupState {
+    last: Mutex<LastBackup>,
+}
+
+impl BackupState {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(LastBackup::default()),
+        }
+    }
+}
+
+impl Default for BackupState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 按文件名排序的备份文件（新的在前）
+fn list_backups(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(FILE_PREFIX) && n.ends_with(".json"))
+        })
+        .collect();
+    files.sort();
+    files.reverse();
+    files
+}
+
+/// 目录中最新备份的修改时间（用于重启后确定下次备份时间）
+fn latest_backup(dir: &Path) -> Option<(u64, PathBuf)> {
+    let path = list_backups(dir).into_iter().next()?;
+    let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+    let at = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some((at, path))
+}
+
+/// 写入一份备份并清理超出保留数量的旧备份
+fn write_backup(config: &AppConfig) -> Result<PathBuf, String> {
+    let dir = config
+        .backup
+        .directory
+        .as_deref()
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .ok_or("未设置备份目录")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("无法创建备份目录: {}", e))?;
+
+    let file_name = format!(
+        "{}{}.json",
+        FILE_PREFIX,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let path = dir.join(file_name);
+    // 与配置文件一样，代理密码只以钥匙串引用的形式保存
+    let mut config = config.clone();
+    secrets::store_proxy_passwords(&mut config);
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("写入备份失败: {}", e))?;
+
+    for old in list_backups(&dir).into_iter().skip(config.backup.keep.max(1) as usize) {
+        if let Err(e) = std::fs::remove_file(&old) {
+            log::warn!("Failed to remove old backup {}: {}", old.display(), e);
+        }
+    }
+
+    Ok(path)
+}
+
+/// 立即备份当前配置到用户选择的目录
+pub fn run_backup(app: &AppHandle) -> Result<PathBuf, String> {
+    let config = app.state::<ConfigManager>().read();
+    let result = write_backup(&config);
+
+    let state = app.state::<BackupState>();
+    let mut last = state.last.lock();
+    match &result {
+        Ok(path) => {
+            log::info!("Config backed up to {}", path.display());
+            *last = LastBackup {
+                at: Some(now_secs()),
+                path: Some(path.clone()),
+                error: None,
+            };
+        }
+        Err(e) => {
+            log::error!("Config backup failed: {}", e);
+            last.error = Some(e.clone());
+        }
+    }
+
+    result
+}
+
+/// 获取备份状态
+pub fn status(app: &AppHandle) -> BackupStatus {
+    let config = app.state::<ConfigManager>().read();
+    let directory = config.backup.directory.clone().filter(|d| !d.is_empty());
+
+    let state = app.state::<BackupState>();
+    let mut last = state.last.lock();
+    // 本次运行尚未备份时，从备份目录中找到上一次的备份
+    if last.at.is_none() {
+        if let Some((at, path)) = directory.as_deref().and_then(|d| latest_backup(Path::new(d))) {
+            last.at = Some(at);
+            last.path = Some(path);
+        }
+    }
+
+    let enabled = config.backup.enabled && directory.is_some();
+    BackupStatus {
+        enabled,
+        directory,
+        last_backup_at: last.at,
+        last_backup_path: last.path.as_ref().map(|p| p.display().to_string()),
+        last_error: last.error.clone(),
+        next_backup_at: enabled.then(|| last.at.map_or(now_secs(), |at| at + BACKUP_INTERVAL_SECS)),
+    }
+}
+
+/// 启动定时备份：每小时检查一次，距上次备份超过一天时执行
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let status = status(&app);
+            let due = status.next_backup_at.is_some_and(|next| next <= now_secs());
+            if due {
+                let app = app.clone();
+                let _ = tauri::async_runtime::spawn_blocking(move || run_backup(&app)).await;
+            }
+        }
+    });
+}
@@ -0,0 +1,279 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+
+use crate::accessibility;
+use crate::config::ConfigManager;
+use crate::dnd;
+use crate::events::{self, Channel};
+use crate::models::WebhookEvent;
+use crate::webhooks;
+use crate::window::WindowManager;
+
+/// 启动器图标上的角标
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Badge {
+    pub webapp_id: String,
+    /// 未读数量（页面没有报告时为 None）
+    pub count: Option<u32>,
+    /// 窗口是否正在运行
+    pub running: bool,
+}
+
+/// 页面通过桥接上报的角标（`badge` 事件）
+/// `navigator.setAppBadge` 上报 count，标题变化上报 title
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadgeReport {
+    #[serde(default)]
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 页面调用了 clearAppBadge
+    #[serde(default)]
+    pub cleared: bool,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    count: Option<u32>,
+    /// 页面使用了 Badging API，之后不再从标题解析数量
+    explicit: bool,
+    /// 已发送给前端的数量（勿扰模式期间数量变化暂不发送，与 count 不同）
+    shown: Option<u32>,
+}
+
+/// 各小程序的未读数量
+pub struct BadgeState {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl BadgeState {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for BadgeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 从页面标题解析未读数量，支持 `(3) Inbox`、`[12] Chat`、`Inbox (3)` 等常见格式
+pub fn parse_title_count(title: &str) -> Option<u32> {
+    let title = title.trim();
+    let enclosed = |s: &str| -> Option<u32> {
+        let inner = s
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .or_else(|| s.strip_prefix('[').and_then(|s| s.strip_suffix(']')))?;
+        let inner = inner.trim_end_matches('+');
+        if inner.is_empty() || !inner.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        inner.parse().ok()
+    };
+
+    let first = title.split_whitespace().next()?;
+    if let Some(count) = enclosed(first) {
+        return Some(count);
+    }
+    title.split_whitespace().last().and_then(enclosed)
+}
+
+/// 根据上报更新数量，返回数量是否变化
+fn apply_report(entry: &mut Entry, report: &BadgeReport) -> bool {
+    let count = if report.cleared {
+        entry.explicit = true;
+        None
+    } else if report.count.is_some() {
+        entry.explicit = true;
+        report.count.filter(|c| *c > 0)
+    } else if let Some(title) = &report.title {
+        if entry.explicit {
+            return false;
+        }
+        parse_title_count(title).filter(|c| *c > 0)
+    } else {
+        return false;
+    };
+
+    let changed = entry.count != count;
+    entry.count = count;
+    changed
+}
+
+fn badge_for(app: &AppHandle, webapp_id: &str, count: Option<u32>) -> Badge {
+    let running = app
+        .try_state::<WindowManager>()
+        .is_some_and(|manager| manager.is_window_active(webapp_id));
+    Badge {
+        webapp_id: webapp_id.to_string(),
+        count,
+        running,
+    }
+}
+
+fn notify(app: &AppHandle, badge: Badge) {
+    events::emit(app, Channel::Badges, "badge://changed", badge);
+}
+
+/// 未读数量变化：通知主窗口、读屏器和订阅了 `badge-changed` 的 Webhook
+fn count_changed(app: &AppHandle, webapp_id: &str, count: Option<u32>) {
+    notify(app, badge_for(app, webapp_id, count));
+    accessibility::badge_changed(app, webapp_id, count);
+    webhooks::dispatch(
+        app,
+        WebhookEvent::BadgeChanged,
+        serde_json::json!({ "webappId": webapp_id, "count": count }),
+    );
+}
+
+/// 勿扰模式期间暂存的数量变化：返回发送前与已发送数量不同的小程序，并记为已发送
+fn take_held(entries: &mut HashMap<String, Entry>) -> Vec<(String, Option<u32>)> {
+    let mut held: Vec<(String, Option<u32>)> = entries
+        .iter_mut()
+        .filter(|(_, entry)| entry.shown != entry.count)
+        .map(|(webapp_id, entry)| {
+            entry.shown = entry.count;
+            (webapp_id.clone(), entry.count)
+        })
+        .collect();
+    held.sort();
+    held
+}
+
+/// 处理页面上报的角标（勿扰模式期间只记录数量，结束后由 `flush_held` 发送）
+pub fn handle_event(app: &AppHandle, webapp_id: &str, report: BadgeReport) {
+    let state = match app.try_state::<BadgeState>() {
+        Some(state) => state,
+        None => return,
+    };
+    let muted = dnd::current_status(app).active;
+    let count = {
+        let mut entries = state.entries.lock();
+        let entry = entries.entry(webapp_id.to_string()).or_default();
+        if !apply_report(entry, &report) {
+            return;
+        }
+        if muted {
+            log::debug!("Held badge update from {} during do not disturb", webapp_id);
+            return;
+        }
+        entry.shown = entry.count;
+        entry.count
+    };
+    count_changed(app, webapp_id, count);
+}
+
+/// 勿扰模式结束后发送期间暂存的数量变化
+pub fn flush_held(app: &AppHandle) {
+    let held = match app.try_state::<BadgeState>() {
+        Some(state) => take_held(&mut state.entries.lock()),
+        None => return,
+    };
+    for (webapp_id, count) in held {
+        count_changed(app, &webapp_id, count);
+    }
+}
+
+/// 窗口打开或关闭时通知主窗口（关闭时清除数量）
+pub fn set_running(app: &AppHandle, webapp_id: &str, running: bool) {
+    let mut count = None;
+    if let Some(state) = app.try_state::<BadgeState>() {
+        let mut entries = state.entries.lock();
+        if running {
+            count = entries.get(webapp_id).and_then(|e| e.shown);
+        } else {
+            entries.remove(webapp_id);
+        }
+    }
+    notify(
+        app,
+        Badge {
+            webapp_id: webapp_id.to_string(),
+            count,
+            running,
+        },
+    );
+}
+
+/// 某个小程序显示的未读数量（勿扰模式期间为开启前的数量）
+pub fn count_for(app: &AppHandle, webapp_id: &str) -> Option<u32> {
+    app.try_state::<BadgeState>()
+        .and_then(|state| state.entries.lock().get(webapp_id).and_then(|e| e.shown))
+}
+
+/// 所有小程序的角标快照（按配置顺序），供主窗口启动后立即显示
+pub fn snapshot(app: &AppHandle) -> Vec<Badge> {
+    let config = app.state::<ConfigManager>().read();
+    config
+        .webapps
+        .iter()
+        .map(|w| badge_for(app, &w.id, count_for(app, &w.id)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_title_count() {
+        assert_eq!(parse_title_count("(3) Inbox - Mail"), Some(3));
+        assert_eq!(parse_title_count("[12] Team Chat"), Some(12));
+        assert_eq!(parse_title_count("Inbox (99+)"), Some(99));
+        assert_eq!(parse_title_count("Inbox"), None);
+        assert_eq!(parse_title_count("(draft) Notes"), None);
+        assert_eq!(parse_title_count(""), None);
+    }
+
+    #[test]
+    fn test_explicit_badge_overrides_title() {
+        let mut entry = Entry::default();
+        let title = |t: &str| BadgeReport {
+            title: Some(t.to_string()),
+            ..BadgeReport::default()
+        };
+
+        assert!(apply_report(&mut entry, &title("(2) Inbox")));
+        assert_eq!(entry.count, Some(2));
+        assert!(!apply_report(&mut entry, &title("(2) Inbox")));
+
+        let explicit = BadgeReport {
+            count: Some(5),
+            ..BadgeReport::default()
+        };
+        assert!(apply_report(&mut entry, &explicit));
+        assert!(!apply_report(&mut entry, &title("(1) Inbox")));
+        assert_eq!(entry.count, Some(5));
+
+        let cleared = BadgeReport {
+            cleared: true,
+            ..BadgeReport::default()
+        };
+        assert!(apply_report(&mut entry, &cleared));
+        assert_eq!(entry.count, None);
+    }
+
+    #[test]
+    fn test_held_badges_flush_once() {
+        let mut entries: HashMap<String, Entry> = HashMap::new();
+        let report = BadgeReport {
+            count: Some(3),
+            ..BadgeReport::default()
+        };
+        // 勿扰模式期间只更新数量，不记为已发送
+        assert!(apply_report(entries.entry("mail".to_string()).or_default(), &report));
+        assert_eq!(entries["mail"].shown, None);
+
+        assert_eq!(take_held(&mut entries), [("mail".to_string(), Some(3))]);
+        assert_eq!(entries["mail"].shown, Some(3));
+        assert!(take_held(&mut entries).is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::accessibility;
+use crate::badges::{self, BadgeReport};
+use crate::media::{MediaSession, MediaState};
+use crate::reader;
+use crate::request_log::{self, RequestEntry};
+use crate::selection;
+use crate::viewstate::{self, ViewReport};
+use crate::watchdog;
+use crate::window::WindowManager;
+
+/// 注入到每个小程序窗口的桥接脚本
+/// 页面通过 `window.__WEBAPP_HUB__.emit(kind, payload)` 向后端上报事件，
+/// 后端通过 eval 调用 `window.__WEBAPP_HUB__` 上的方法控制页面
+const BRIDGE_SCRIPT: &str = r#"(function() {
+    if (window.__WEBAPP_HUB__) return;
+
+    function invoke(cmd, args) {
+        var internals = window.__TAURI_INTERNALS__;
+        if (!internals || !internals.invoke) return Promise.resolve();
+        return internals.invoke(cmd, args).catch(function(e) {
+            console.debug('[WebApp Hub] bridge error:', e);
+        });
+    }
+
+    var hub = {
+        emit: function(kind, payload) {
+            return invoke('bridge_event', { kind: kind, payload: payload === undefined ? null : payload });
+        }
+    };
+
+    // ---- 媒体播放 ----
+    var actionHandlers = {};
+    if (navigator.mediaSession && navigator.mediaSession.setActionHandler) {
+        var nativeSetActionHandler = navigator.mediaSession.setActionHandler.bind(navigator.mediaSession);
+        navigator.mediaSession.setActionHandler = function(action, handler) {
+            actionHandlers[action] = handler;
+            try { return nativeSetActionHandler(action, handler); } catch (e) {}
+        };
+    }
+
+    function mediaElements() {
+        return Array.prototype.slice.call(document.querySelectorAll('audio, video'));
+    }
+
+    function isPlaying() {
+        return mediaElements().some(function(m) { return !m.paused && !m.ended; });
+    }
+
+    function reportMedia() {
+        var meta = navigator.mediaSession && navigator.mediaSession.metadata;
+        var artwork = meta && meta.artwork && meta.artwork.length
+            ? meta.artwork[meta.artwork.length - 1].src : null;
+        hub.emit('media', {
+            playing: isPlaying(),
+            title: (meta && meta.title) || document.title || null,
+            artist: (meta && meta.artist) || null,
+            album: (meta && meta.album) || null,
+            artwork: artwork
+        });
+    }
+
+    // 媒体事件不冒泡，在捕获阶段监听
+    ['play', 'pause', 'ended'].forEach(function(type) {
+        document.addEventListener(type, reportMedia, true);
+    });
+
+    hub.media = function(action) {
+        if (action === 'toggle') action = isPlaying() ? 'pause' : 'play';
+        var sessionActions = { play: 'play', pause: 'pause', stop: 'stop', next: 'nexttrack', previous: 'previoustrack' };
+        var handler = actionHandlers[sessionActions[action]];
+        if (handler) {
+            handler({ action: sessionActions[action] });
+            return;
+        }
+        mediaElements().forEach(function(m) {
+            if (action === 'play') m.play();
+            else if (action === 'pause' || action === 'stop') m.pause();
+        });
+    };
+
+    // ---- 未保存内容检测 ----
+    // 派发一个可取消的 beforeunload 事件，页面拦截（preventDefault 或返回提示文本）即视为有未保存内容
+    function hasUnsavedChanges() {
+        var event = new Event('beforeunload', { cancelable: true });
+        var handler = window.onbeforeunload;
+        var returned;
+        window.onbeforeunload = null;
+        try {
+            window.dispatchEvent(event);
+            if (typeof handler === 'function') returned = handler.call(window, event);
+        } catch (e) {
+        } finally {
+            window.onbeforeunload = handler;
+        }
+        return event.defaultPrevented || (typeof returned === 'string' && returned.length > 0);
+    }
+
+    var lastDirty = null;
+    function reportDirty() {
+        var dirty = hasUnsavedChanges();
+        if (dirty !== lastDirty) {
+            lastDirty = dirty;
+            hub.emit('dirty', dirty);
+        }
+    }
+    // 窗口失焦或隐藏时才可能被 LRU 淘汰，此时上报状态
+    window.addEventListener('blur', reportDirty);
+    document.addEventListener('visibilitychange', reportDirty);
+
+    // ---- 静音 ----
+    var muted = false;
+    document.addEventListener('play', function(e) {
+        if (muted && e.target && 'muted' in e.target) e.target.muted = true;
+    }, true);
+
+    hub.setMuted = function(value) {
+        muted = !!value;
+        mediaElements().forEach(function(m) { m.muted = muted; });
+    };
+
+    // ---- 选中文本 ----
+    // 后端请求当前选中的文本，purpose 原样返回以区分用途（朗读、翻译等）
+    hub.sendSelection = function(purpose) {
+        var selection = window.getSelection ? String(window.getSelection()) : '';
+        var active = document.activeElement;
+        if (!selection && active && typeof active.selectionStart === 'number') {
+            selection = active.value.substring(active.selectionStart, active.selectionEnd);
+        }
+        hub.emit('selection', { purpose: purpose, text: selection });
+    };
+
+    // ---- 未读角标 ----
+    // 提供 Badging API，未使用它的页面从标题（如 "(3) Inbox"）解析未读数量
+    navigator.setAppBadge = function(count) {
+        hub.emit('badge', { count: count === undefined ? 1 : Math.max(0, Math.floor(count)) });
+        return Promise.resolve();
+    };
+    navigator.clearAppBadge = function() {
+        hub.emit('badge', { cleared: true });
+        return Promise.resolve();
+    };
+
+    var lastTitle = null;
+    function reportTitle() {
+        if (document.title === lastTitle) return;
+        lastTitle = document.title;
+        hub.emit('badge', { title: lastTitle });
+    }
+    function observeTitle() {
+        reportTitle();
+        var target = document.querySelector('head') || document.documentElement;
+        if (target && window.MutationObserver) {
+            new MutationObserver(reportTitle).observe(target, { subtree: true, childList: true, characterData: true });
+        }
+    }
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', observeTitle);
+    } else {
+        observeTitle();
+    }
+
+    // ---- 滚动位置和缩放 ----
+    // 隐藏、离开页面和停止滚动后上报，重新打开窗口时由后端恢复
+    function reportView() {
+        hub.emit('view', {
+            url: location.href,
+            scrollX: window.scrollX,
+            scrollY: window.scrollY,
+            devicePixelRatio: window.devicePixelRatio || 1
+        });
+    }
+    var viewTimer = null;
+    window.addEventListener('scroll', function() {
+        clearTimeout(viewTimer);
+        viewTimer = setTimeout(reportView, 1000);
+    }, { passive: true });
+    document.addEventListener('visibilitychange', function() {
+        if (document.hidden) reportView();
+    });
+    window.addEventListener('pagehide', reportView);
+
+    // 动态加载内容的页面一开始可能不够高，滚动不到位时重试一段时间
+    hub.restoreScroll = function(x, y) {
+        var tries = 0;
+        (function attempt() {
+            window.scrollTo(x, y);
+            var reached = Math.abs(window.scrollX - x) <= 2 && Math.abs(window.scrollY - y) <= 2;
+            if (!reached && ++tries < 25) setTimeout(attempt, 200);
+        })();
+    };
+
+    window.__WEBAPP_HUB__ = hub;
+})();"#;
+
+/// 获取桥接脚本（作为 initialization_script 注入）
+pub fn bridge_script() -> &'static str {
+    BRIDGE_SCRIPT
+}
+
+/// 从窗口标签解析小程序 ID
+pub fn webapp_id_from_label(label: &str) -> Option<&str> {
+    label.strip_prefix("webapp-")
+}
+
+/// 处理页面通过桥接上报的事件
+pub fn handle_event(app: &AppHandle, webapp_id: &str, kind: &str, payload: Value) -> Result<(), String> {
+    match kind {
+        "media" => {
+            let state: MediaState = serde_json::from_value(payload).map_err(|e| e.to_string())?;
+            if let Some(session) = app.try_state::<MediaSession>() {
+                session.update(webapp_id, state);
+            }
+            Ok(())
+        }
+        "dirty" => {
+            let dirty = payload.as_bool().unwrap_or(false);
+            if let Some(window_manager) = app.try_state::<WindowManager>() {
+                window_manager.set_dirty(webapp_id, dirty);
+            }
+            Ok(())
+        }
+        "selection" => selection::handle_event(app, webapp_id, payload),
+        "request" => {
+            let entry: RequestEntry = serde_json::from_value(payload).map_err(|e| e.to_string())?;
+            request_log::record(app, webapp_id, entry);
+            Ok(())
+        }
+        "pong" => {
+            watchdog::touch(app, webapp_id);
+            Ok(())
+        }
+        "badge" => {
+            let report: BadgeReport = serde_json::from_value(payload).map_err(|e| e.to_string())?;
+            if let Some(title) = report.title.as_deref() {
+                accessibility::page_title_changed(app, webapp_id, title);
+            }
+            badges::handle_event(app, webapp_id, report);
+            Ok(())
+        }
+        "view" => {
+            let report: ViewReport = serde_json::from_value(payload).map_err(|e| e.to_string())?;
+            viewstate::handle_event(app, webapp_id, report);
+            Ok(())
+        }
+        "reader" => {
+            let active = payload.get("active").and_then(Value::as_bool).unwrap_or(false);
+            let error = payload.get("error").and_then(Value::as_str).map(str::to_string);
+            reader::set_active(app, webapp_id, active, error);
+            Ok(())
+        }
+        _ => {
+            log::debug!("Ignoring unknown bridge event '{}' from {}", kind, webapp_id);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+use url::Url;
+
+use crate::config::ConfigManager;
+use crate::window::window_label;
+
+/// 只有网页地址交给系统浏览器，避免打开本地文件或其他协议
+fn is_browsable(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+}
+
+/// 小程序当前页面的网址，窗口未打开或页面不是网页时使用小程序主页
+fn current_url(app: &AppHandle, webapp_id: &str, home: &str) -> Result<Url, String> {
+    let current = app
+        .get_webview_window(&window_label(webapp_id))
+        .and_then(|window| window.url().ok())
+        .filter(is_browsable);
+    match current {
+        Some(url) => Ok(url),
+        None => Url::parse(home)
+            .ok()
+            .filter(is_browsable)
+            .ok_or_else(|| "小程序网址无效".to_string()),
+    }
+}
+
+/// 在系统默认浏览器中打开小程序当前的页面，返回打开的网址
+pub fn open_in_browser(app: &AppHandle, webapp_id: &str) -> Result<String, String> {
+    let home = app
+        .state::<ConfigManager>()
+        .read()
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .map(|w| w.url.clone())
+        .ok_or("小程序不存在")?;
+    let url = current_url(app, webapp_id, &home)?;
+
+    open_url(app, &url)?;
+    log::info!("Opened webapp {} in system browser", webapp_id);
+    Ok(url.to_string())
+}
+
+/// 在系统默认浏览器中打开网页地址
+pub fn open_url(app: &AppHandle, url: &Url) -> Result<(), String> {
+    if !is_browsable(url) {
+        return Err(format!("不支持在浏览器中打开: {}", url));
+    }
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| format!("无法打开系统浏览器: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_web_urls_are_browsable() {
+        assert!(is_browsable(&Url::parse("https://mail.example.com/inbox").unwrap()));
+        assert!(is_browsable(&Url::parse("http://127.0.0.1:18080/").unwrap()));
+        assert!(!is_browsable(&Url::parse("file:///etc/passwd").unwrap()));
+        assert!(!is_browsable(&Url::parse("about:blank").unwrap()));
+    }
+}
@@ -0,0 +1,252 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+
+/// 已加载的信任证书（DER），后台 HTTP 客户端构建时加入根证书
+static ROOTS: RwLock<Vec<Vec<u8>>> = parking_lot::const_rwlock(Vec::new());
+
+/// 信任的根证书
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedCa {
+    /// 证书 DER 的 SHA-256（十六进制），同时是证书文件名
+    pub fingerprint: String,
+    /// 添加时的文件名，用于显示
+    pub label: String,
+    /// 添加时间（Unix 秒）
+    pub added_at: u64,
+    /// 是否已安装到系统（当前用户）的信任存储，小程序窗口使用系统信任存储
+    #[serde(default)]
+    pub system_installed: bool,
+}
+
+/// 从 PEM 文本中提取所有证书（DER）
+fn parse_pem(content: &str) -> Result<Vec<Vec<u8>>, String> {
+    const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+    const END: &str = "-----END CERTIFICATE-----";
+
+    let mut certs = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(BEGIN) {
+        let body = &rest[start + BEGIN.len()..];
+        let end = body.find(END).ok_or("PEM 证书不完整")?;
+        let base64: String = body[..end].chars().filter(|c| !c.is_whitespace()).collect();
+        let der = STANDARD
+            .decode(base64)
+            .map_err(|e| format!("证书内容无效: {}", e))?;
+        certs.push(der);
+        rest = &body[end + END.len()..];
+    }
+
+    if certs.is_empty() {
+        return Err("文件中没有 PEM 格式的证书".to_string());
+    }
+    Ok(certs)
+}
+
+fn fingerprint(der: &[u8]) -> String {
+    sha2::Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn to_pem(der: &[u8]) -> String {
+    let encoded = STANDARD.encode(der);
+    let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+    pem.push_str("-----END CERTIFICATE-----\n");
+    pem
+}
+
+fn certs_dir(app: &AppHandle) -> PathBuf {
+    app.path().app_data_dir().unwrap_or_default().join("certs")
+}
+
+fn cert_path(app: &AppHandle, fingerprint: &str) -> PathBuf {
+    certs_dir(app).join(format!("{}.pem", fingerprint))
+}
+
+/// 启动时加载信任的证书（需要在构建 HTTP 客户端之前调用）
+pub fn load(app: &AppHandle) {
+    let config = app.state::<ConfigManager>().read();
+    let mut roots = Vec::new();
+    for ca in &config.trusted_cas {
+        let path = cert_path(app, &ca.fingerprint);
+        match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|c| parse_pem(&c)) {
+            Ok(certs) => roots.extend(certs),
+            Err(e) => log::warn!("Failed to load trusted CA {:?}: {}", path, e),
+        }
+    }
+    *ROOTS.write() = roots;
+}
+
+/// 后台 HTTP 客户端额外信任的根证书
+pub fn root_certificates() -> Vec<reqwest::Certificate> {
+    ROOTS
+        .read()
+        .iter()
+        .filter_map(|der| reqwest::Certificate::from_der(der).ok())
+        .collect()
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let output = command.output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// 安装到当前用户的系统信任存储（系统可能弹出确认或密码提示）
+fn install_system(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        run(Command::new("certutil").args(["-user", "-addstore", "Root"]).arg(path))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let keychain = std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join("Library/Keychains/login.keychain-db"))
+            .ok_or("无法确定用户钥匙串位置")?;
+        run(Command::new("security")
+            .args(["add-trusted-cert", "-r", "trustRoot", "-k"])
+            .arg(keychain)
+            .arg(path))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        // p11-kit 的信任存储，通常需要管理员权限
+        run(Command::new("trust").args(["anchor", "--store"]).arg(path))
+    }
+}
+
+fn uninstall_system(path: &Path, der: &[u8]) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = path;
+        let thumbprint: String = sha1::Sha1::digest(der).iter().map(|b| format!("{:02x}", b)).collect();
+        run(Command::new("certutil").args(["-user", "-delstore", "Root", &thumbprint]))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = der;
+        run(Command::new("security").arg("remove-trusted-cert").arg(path))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = der;
+        run(Command::new("trust").args(["anchor", "--remove"]).arg(path))
+    }
+}
+
+/// 添加 PEM 文件中的根证书；install_system 为 true 时同时安装到系统信任存储，
+/// 小程序窗口才会信任它（后台请求总是信任已添加的证书）
+pub fn add(app: &AppHandle, source: &Path, install: bool) -> AppResult<Vec<TrustedCa>> {
+    let content = std::fs::read_to_string(source).map_err(|e| AppError::Io(format!("无法读取证书文件: {}", e)))?;
+    let certs = parse_pem(&content).map_err(AppError::InvalidInput)?;
+    let label = source
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    std::fs::create_dir_all(certs_dir(app))?;
+    let mut added = Vec::new();
+    for der in certs {
+        let fingerprint = fingerprint(&der);
+        let path = cert_path(app, &fingerprint);
+        std::fs::write(&path, to_pem(&der))?;
+
+        let system_installed = install
+            && match install_system(&path) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Failed to install CA {} into system store: {}", fingerprint, e);
+                    false
+                }
+            };
+        added.push(TrustedCa {
+            fingerprint,
+            label: label.clone(),
+            added_at: now,
+            system_installed,
+        });
+    }
+
+    let list = app.state::<ConfigManager>().update(|config| {
+        for ca in &added {
+            config.trusted_cas.retain(|c| c.fingerprint != ca.fingerprint);
+            config.trusted_cas.push(ca.clone());
+        }
+        config.trusted_cas.clone()
+    })?;
+    load(app);
+    log::info!("Added {} trusted CA certificates from {:?}", added.len(), source);
+    Ok(list)
+}
+
+/// 移除信任的根证书（已安装到系统时一并移除）
+pub fn remove(app: &AppHandle, fingerprint: &str) -> AppResult<Vec<TrustedCa>> {
+    let config = app.state::<ConfigManager>().read();
+    let ca = config
+        .trusted_cas
+        .iter()
+        .find(|c| c.fingerprint == fingerprint)
+        .ok_or_else(|| AppError::not_found("证书不存在"))?;
+    let path = cert_path(app, fingerprint);
+
+    if ca.system_installed {
+        let der = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| parse_pem(&content).ok())
+            .and_then(|certs| certs.into_iter().next())
+            .unwrap_or_default();
+        if let Err(e) = uninstall_system(&path, &der) {
+            log::warn!("Failed to remove CA {} from system store: {}", fingerprint, e);
+        }
+    }
+    let _ = std::fs::remove_file(&path);
+
+    let list = app.state::<ConfigManager>().update(|config| {
+        config.trusted_cas.retain(|c| c.fingerprint != fingerprint);
+        config.trusted_cas.clone()
+    })?;
+    load(app);
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pem_round_trip() {
+        let der = b"not really a certificate".to_vec();
+        let bundle = format!("# corporate roots\n{}\n{}", to_pem(&der), to_pem(b"second"));
+
+        let certs = parse_pem(&bundle).unwrap();
+        assert_eq!(certs, vec![der, b"second".to_vec()]);
+        assert!(parse_pem("hello").is_err());
+        assert!(parse_pem("-----BEGIN CERTIFICATE-----\nAAAA").is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::config_store;
+use crate::models::{AppConfig, WebApp};
+use crate::safe_mode;
+use crate::window::WindowManager;
+
+/// 应用数据目录名，与 tauri.conf.json 中的 identifier 一致（Tauri 的 `app_data_dir`）
+const IDENTIFIER: &str = "com.webapp-hub.app";
+
+const USAGE: &str = "用法:
+  webapp-hub open <名称或 ID>                     打开小程序（已运行时交给运行中的实例）
+  webapp-hub list [--json]                          列出所有小程序
+  webapp-hub add --name <名称> --url <网址> [--shortcut <快捷键>]
+                                                    添加小程序
+  webapp-hub help                                   显示此帮助
+  webapp-hub --safe-mode                            以安全模式启动（不注册快捷键、不注入脚本、不恢复会话）";
+
+/// 命令行子命令（没有子命令时正常启动界面）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    Open { target: String },
+    List { json: bool },
+    Add {
+        name: String,
+        url: String,
+        shortcut: Option<String>,
+    },
+    Help,
+}
+
+/// `list --json` 输出的一个小程序
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListedWebApp<'a> {
+    id: &'a str,
+    name: &'a str,
+    url: &'a str,
+    enabled: bool,
+    shortcut: Option<&'a str>,
+}
+
+/// 读取 `--name value` 或 `--name=value` 形式的选项
+fn options(args: &[String]) -> Result<Vec<(String, Option<String>)>, String> {
+    let mut parsed = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let option = arg.strip_prefix("--").ok_or_else(|| format!("无法识别的参数: {}", arg))?;
+        match option.split_once('=') {
+            Some((key, value)) => parsed.push((key.to_string(), Some(value.to_string()))),
+            None if option == "json" => parsed.push((option.to_string(), None)),
+            None => {
+                let value = iter.next().ok_or_else(|| format!("--{} 缺少参数值", option))?;
+                parsed.push((option.to_string(), Some(value.clone())));
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// 解析命令行参数（不含程序名）；第一个参数不是子命令时返回 None，
+/// 例如开机自启参数和系统转交的协议链接
+pub fn parse(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(None);
+    };
+    let command = match command.as_str() {
+        "open" => {
+            let target = rest.join(" ");
+            if target.trim().is_empty() {
+                return Err("open 需要小程序的名称或 ID".to_string());
+            }
+            CliCommand::Open {
+                target: target.trim().to_string(),
+            }
+        }
+        "list" => {
+            let mut json = false;
+            for (key, _) in options(rest)? {
+                match key.as_str() {
+                    "json" => json = true,
+                    _ => return Err(format!("list 不支持 --{}", key)),
+                }
+            }
+            CliCommand::List { json }
+        }
+        "add" => {
+            let (mut name, mut url, mut shortcut) = (None, None, None);
+            for (key, value) in options(rest)? {
+                match key.as_str() {
+                    "name" => name = value,
+                    "url" => url = value,
+                    "shortcut" => shortcut = value.filter(|s| !s.is_empty()),
+                    _ => return Err(format!("add 不支持 --{}", key)),
+                }
+            }
+            CliCommand::Add {
+                name: name.filter(|n| !n.trim().is_empty()).ok_or("add 需要 --name")?,
+                url: url.filter(|u| !u.trim().is_empty()).ok_or("add 需要 --url")?,
+                shortcut,
+            }
+        }
+        "help" | "--help" | "-h" => CliCommand::Help,
+        _ => return Ok(None),
+    };
+    Ok(Some(command))
+}
+
+/// 按 ID 或名称（不区分大小写）查找小程序
+fn find<'a>(config: &'a AppConfig, target: &str) -> Option<&'a WebApp> {
+    let name = target.to_lowercase();
+    config
+        .webapps
+        .iter()
+        .find(|w| w.id == target)
+        .or_else(|| config.webapps.iter().find(|w| w.name.to_lowercase() == name))
+}
+
+/// `list` 的文本输出：每行一个小程序（名称、网址、ID，用制表符分隔），停用的小程序标注在末尾
+fn render_list(config: &AppConfig) -> String {
+    let mut webapps: Vec<&WebApp> = config.webapps.iter().collect();
+    webapps.sort_by_key(|w| w.order);
+    webapps
+        .iter()
+        .map(|w| {
+            let disabled = if w.enabled { "" } else { "\t(已停用)" };
+            format!("{}\t{}\t{}{}\n", w.name, w.url, w.id, disabled)
+        })
+        .collect()
+}
+
+/// 直接在配置中添加小程序（运行中的实例会在检查配置文件时读到新的小程序）
+fn add(config_manager: &ConfigManager, name: &str, url: &str, shortcut: Option<String>) -> Result<WebApp, String> {
+    let parsed = url::Url::parse(url.trim()).map_err(|_| format!("网址无效: {}", url))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("网址无效: {}", url));
+    }
+    let config = config_manager.read();
+    if config.policy.locked {
+        return Err("配置已被管理员锁定，请在界面中解锁后再添加".to_string());
+    }
+
+    let mut webapp = WebApp::new(name.trim().to_string(), parsed.to_string());
+    webapp.shortcut = shortcut;
+    config_manager
+        .update(|config| {
+            if let Some(existing) = config.find_duplicate(&webapp.name, &webapp.url) {
+                return Err(format!("已存在相同名称或网址的小程序: {}", existing.name));
+            }
+            webapp.order = config.webapps.len() as u32;
+            config.webapps.push(webapp.clone());
+            Ok(webapp.clone())
+        })
+        .map_err(|e| e.to_string())?
+}
+
+/// 不启动界面执行 `list`、`add` 和 `help`，返回进程退出码；`open` 需要界面，返回 None
+pub fn run_offline(command: &CliCommand) -> Option<i32> {
+    if matches!(command, CliCommand::Open { .. }) {
+        return None;
+    }
+    if let CliCommand::Help = command {
+        println!("{}", USAGE);
+        return Some(0);
+    }
+    let Some(dir) = dirs::data_dir().map(|dir| dir.join(IDENTIFIER)) else {
+        eprintln!("无法确定应用数据目录");
+        return Some(1);
+    };
+    let config_manager = ConfigManager::new(config_store::open(&dir));
+
+    let result = match command {
+        CliCommand::List { json: false } => Ok(render_list(&config_manager.read())),
+        CliCommand::List { json: true } => {
+            let config = config_manager.read();
+            let listed: Vec<ListedWebApp> = config
+                .webapps
+                .iter()
+                .map(|w| ListedWebApp {
+                    id: &w.id,
+                    name: &w.name,
+                    url: &w.url,
+                    enabled: w.enabled,
+                    shortcut: w.shortcut.as_deref(),
+                })
+                .collect();
+            serde_json::to_string_pretty(&listed)
+                .map(|json| json + "\n")
+                .map_err(|e| e.to_string())
+        }
+        CliCommand::Add { name, url, shortcut } => add(&config_manager, name, url, shortcut.clone())
+            .map(|webapp| format!("已添加 {}（{}）\n", webapp.name, webapp.id)),
+        CliCommand::Open { .. } | CliCommand::Help => unreachable!(),
+    };
+    // 等待后台任务把配置写完再退出
+    config_manager.flush();
+
+    match result {
+        Ok(output) => {
+            print!("{}", output);
+            Some(0)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            Some(1)
+        }
+    }
+}
+
+/// 发布版在 Windows 上是图形界面程序，没有控制台：连接到启动它的终端，让输出可以显示
+pub fn attach_console() {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        // 从资源管理器启动时没有父控制台，连接失败不影响运行
+        let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+    }
+}
+
+/// 命令行 `open`：打开或聚焦小程序窗口（启动时或运行中的实例收到再次启动的参数时调用）
+pub fn open(app: &AppHandle, target: &str) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = find(&config, target).ok_or_else(|| format!("找不到小程序: {}", target))?;
+    app.state::<WindowManager>()
+        .open_webapp(app, webapp, config.proxy_url_for(webapp))?;
+    log::info!("Opened webapp {} from command line", webapp.id);
+    Ok(())
+}
+
+/// 运行中的实例收到再次启动的参数：`open` 打开小程序并返回 true，其他参数返回 false
+pub fn handle_second_instance(app: &AppHandle, argv: &[String]) -> bool {
+    // 已运行的实例不会切换到安全模式，忽略 `--safe-mode` 后处理其余参数
+    let (_, args) = safe_mode::take_flag(argv.get(1..).unwrap_or_default().to_vec());
+    match parse(&args) {
+        Ok(Some(CliCommand::Open { target })) => {
+            if let Err(e) = open(app, &target) {
+                log::warn!("Failed to open {} from command line: {}", target, e);
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse(&args(&[])), Ok(None));
+        assert_eq!(parse(&args(&["--autostart"])), Ok(None));
+        assert_eq!(
+            parse(&args(&["open", "Google", "Calendar"])),
+            Ok(Some(CliCommand::Open {
+                target: "Google Calendar".to_string()
+            }))
+        );
+        assert_eq!(parse(&args(&["list", "--json"])), Ok(Some(CliCommand::List { json: true })));
+        assert_eq!(
+            parse(&args(&["add", "--name", "Mail", "--url=https://mail.example.com"])),
+            Ok(Some(CliCommand::Add {
+                name: "Mail".to_string(),
+                url: "https://mail.example.com".to_string(),
+                shortcut: None,
+            }))
+        );
+        assert!(parse(&args(&["add", "--name", "Mail"])).is_err());
+        assert!(parse(&args(&["add", "--name"])).is_err());
+        assert!(parse(&args(&["open"])).is_err());
+
+        let config = AppConfig {
+            webapps: vec![WebApp::new("Gmail".to_string(), "https://mail.google.com".to_string())],
+            ..Default::default()
+        };
+        assert!(find(&config, "gmail").is_some());
+        assert!(find(&config, &config.webapps[0].id).is_some());
+        assert!(render_list(&config).starts_with("Gmail\thttps://mail.google.com\t"));
+    }
+}
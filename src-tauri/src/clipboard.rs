@@ -0,0 +1,198 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_notification::NotificationExt;
+use url::Url;
+
+use crate::config::ConfigManager;
+use crate::dnd;
+use crate::events::{self, Channel};
+use crate::models::AppConfig;
+use crate::window;
+
+/// 剪贴板检查间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 超过这个长度的剪贴板内容不会是单个网址，直接忽略
+const MAX_URL_LEN: usize = 4096;
+
+/// 打开建议事件（`clipboard://suggestion`），主窗口据此显示“在小程序中打开”按钮
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardSuggestion {
+    pub webapp_id: String,
+    pub webapp_name: String,
+    pub url: String,
+}
+
+/// 限制提示频率：同一个网址只提示一次，两次提示之间至少间隔设定的时间
+#[derive(Default)]
+struct RateLimiter {
+    last_url: Option<String>,
+    last_at: Option<Instant>,
+}
+
+impl RateLimiter {
+    fn allow(&mut self, url: &str, now: Instant, min_interval: Duration) -> bool {
+        if self.last_url.as_deref() == Some(url) {
+            return false;
+        }
+        if self.last_at.is_some_and(|at| now.duration_since(at) < min_interval) {
+            return false;
+        }
+        self.last_url = Some(url.to_string());
+        self.last_at = Some(now);
+        true
+    }
+}
+
+/// 剪贴板监听状态
+pub struct ClipboardWatcher {
+    last_text: Mutex<Option<String>>,
+    limiter: Mutex<RateLimiter>,
+}
+
+impl ClipboardWatcher {
+    pub fn new() -> Self {
+        Self {
+            last_text: Mutex::new(None),
+            limiter: Mutex::new(RateLimiter::default()),
+        }
+    }
+}
+
+impl Default for ClipboardWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 复制的内容是属于某个小程序的网址时，返回打开建议
+fn suggestion(config: &AppConfig, text: &str) -> Option<ClipboardSuggestion> {
+    let text = text.trim();
+    if text.len() > MAX_URL_LEN || text.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = Url::parse(text).ok()?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return None;
+    }
+
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.enabled && w.is_url_in_scope(&url))?;
+    Some(ClipboardSuggestion {
+        webapp_id: webapp.id.clone(),
+        webapp_name: webapp.name.clone(),
+        url: url.to_string(),
+    })
+}
+
+fn check(app: &AppHandle, watcher: &ClipboardWatcher) {
+    let config = app.state::<ConfigManager>().read();
+    if !config.clipboard_watch.enabled {
+        // 关闭期间复制的内容不应在重新开启后触发提示
+        *watcher.last_text.lock() = None;
+        return;
+    }
+
+    let text = match app.clipboard().read_text() {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    {
+        let mut last_text = watcher.last_text.lock();
+        let first_read = last_text.is_none();
+        if last_text.as_deref() == Some(text.as_str()) {
+            return;
+        }
+        *last_text = Some(text.clone());
+        // 开启监听时剪贴板里已有的内容不提示
+        if first_read {
+            return;
+        }
+    }
+
+    let suggestion = match suggestion(&config, &text) {
+        Some(suggestion) => suggestion,
+        None => return,
+    };
+    // 从该小程序窗口里复制的链接不需要提示
+    if window::focused_webapp_id(app).as_deref() == Some(suggestion.webapp_id.as_str()) {
+        return;
+    }
+    if dnd::current_status(app).active {
+        return;
+    }
+    let min_interval = Duration::from_secs(config.clipboard_watch.min_interval_secs);
+    if !watcher.limiter.lock().allow(&suggestion.url, Instant::now(), min_interval) {
+        return;
+    }
+
+    log::info!("Copied URL matches webapp {}", suggestion.webapp_id);
+    let host = Url::parse(&suggestion.url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(format!("在 {} 中打开？", suggestion.webapp_name))
+        .body(format!("已复制 {} 的链接，可在 WebApp Hub 中打开", host))
+        .show()
+    {
+        log::warn!("Failed to show clipboard notification: {}", e);
+    }
+    events::emit(app, Channel::Prompts, "clipboard://suggestion", suggestion);
+}
+
+/// 启动剪贴板监听（未开启时只检查设置，不读取剪贴板）
+pub fn start_monitor(app: AppHandle) {
+    app.manage(ClipboardWatcher::new());
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let watcher = app.state::<ClipboardWatcher>();
+            check(&app, &watcher);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WebApp;
+
+    #[test]
+    fn test_suggestion_matches_webapp_domain() {
+        let mut config = AppConfig::default();
+        config
+            .webapps
+            .push(WebApp::new("Docs".to_string(), "https://docs.example.com".to_string()));
+
+        let found = suggestion(&config, " https://docs.example.com/d/123 ").unwrap();
+        assert_eq!(found.webapp_name, "Docs");
+        assert_eq!(found.url, "https://docs.example.com/d/123");
+
+        assert!(suggestion(&config, "https://other.example.org/").is_none());
+        assert!(suggestion(&config, "see https://docs.example.com/d/123").is_none());
+        assert!(suggestion(&config, "not a url").is_none());
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        let interval = Duration::from_secs(30);
+
+        assert!(limiter.allow("https://a/", start, interval));
+        assert!(!limiter.allow("https://a/", start + Duration::from_secs(60), interval));
+        assert!(!limiter.allow("https://b/", start + Duration::from_secs(5), interval));
+        assert!(limiter.allow("https://b/", start + Duration::from_secs(31), interval));
+    }
+}
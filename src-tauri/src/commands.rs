@@ -1,15 +1,87 @@
-use tauri::{AppHandle, Manager, State, WebviewUrl};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State, WebviewWindow};
 
+use crate::accessibility;
+use crate::audio;
+use crate::autostart::{self, AutoStartStatus};
+use crate::backup::{self, BackupStatus};
+use crate::badges::{self, Badge};
+use crate::bridge;
+use crate::browser;
+use crate::certs::{self, TrustedCa};
 use crate::config::ConfigManager;
-use crate::models::{AppConfig, ProxyConfig, WebApp};
+use crate::config_store::StorageBackend;
+use crate::control::ControlApi;
+use crate::cookies::{self, CookieImportReport, CookieSource};
+use crate::diagnostics;
+use crate::dnd::{self, DndStatus};
+use crate::dock;
+use crate::downloads::{self, DownloadItem, DownloadManager};
+use crate::error::AppError;
+use crate::events::{self, Channel, EventBus};
+use crate::favicon;
+use crate::groups;
+use crate::icon_themes;
+use crate::icons;
+use crate::importers::{self, ForeignImport, ImportSource};
+use crate::launcher::{self, LauncherPage};
+use crate::launchers;
+use crate::lint::{self, LintReport};
+use crate::logging;
+use crate::macros;
+use crate::maintenance::{self, MaintenanceReport, MaintenanceState};
+use crate::managed::ManagedPolicy;
+use crate::memory::{self, MemoryStatus};
+use crate::models::{
+    AccessibilityConfig, AppConfig, ChangePreview, IconTheme, NetworkProfile, ProtocolHandler, ProxyConfig,
+    ShortcutAction, UiPreferences, UserScript, WebApp, WebAppGroup, WebAppPatch,
+};
+use crate::navigation;
+use crate::net::{self, HttpClient};
+use crate::notifications;
+use crate::onboarding;
+use crate::patch;
+use crate::platform::{self, PlatformCapabilities};
+use crate::policy::{self, PolicyStatus};
+use crate::profiles;
+use crate::protocols;
 use crate::proxy::ProxyManager;
-use crate::shortcuts::{load_shortcuts_from_config, ShortcutManager};
-use crate::window::WindowManager;
+use crate::proxy_auth;
+use crate::proxy_env::{self, EnvFormat};
+use crate::proxy_routes::{self, RoutingDecision};
+use crate::query;
+use crate::quiet_hours::{self, QuietHoursStatus};
+use crate::reader;
+use crate::recent::{self, ClosedWebApp};
+use crate::request_log::{self, RequestEntry};
+use crate::safe_mode::{self, SafeModeStatus};
+use crate::scripting;
+use crate::selection::{self, SelectionPurpose};
+use crate::settings;
+use crate::share::{self, ShareImport};
+use crate::share_target::{self, ShareCandidate, SharePayload};
+use crate::shortcut_check::{self, RegisteredShortcut, ShortcutCheck, ShortcutStatus};
+use crate::shortcuts::{load_shortcuts_from_config, ShortcutFailure, ShortcutManager};
+use crate::snapshot::{self, SnapshotSummary};
+use crate::speech;
+use crate::storage::{self, ProfileUsage};
+use crate::switcher::{self, SwitcherResult};
+use crate::theme;
+use crate::throttle;
+use crate::transfer::{self, ImportSummary};
+use crate::usage::{self, UsageStats, WebAppStats};
+use crate::userscripts::{self, ScriptRun};
+use crate::watchdog;
+use crate::window::{focused_webapp_id, ActiveWebApp, OpenOptions, ToggleResult, WindowManager};
+use crate::window_class;
+use crate::zoom::{self, ZoomStep};
 
 /// 获取应用配置
 #[tauri::command]
-pub async fn get_config(config_manager: State<'_, ConfigManager>) -> Result<AppConfig, String> {
-    Ok(config_manager.read())
+pub async fn get_config(config_manager: State<'_, ConfigManager>) -> Result<AppConfig, AppError> {
+    let mut config = config_manager.read();
+    icons::strip_inline(&mut config);
+    Ok(config)
 }
 
 /// 保存应用配置
@@ -17,26 +89,73 @@ pub async fn get_config(config_manager: State<'_, ConfigManager>) -> Result<AppC
 pub async fn save_config(
     app: AppHandle,
     config_manager: State<'_, ConfigManager>,
-    config: AppConfig,
-) -> Result<(), String> {
-    // 验证代理配置
+    mut config: AppConfig,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    // 锁定设置只能通过锁定相关的命令修改
+    let current = config_manager.read();
+    config.policy = current.policy.clone();
+    icons::restore_inline(&mut config, &current);
+
+    // 验证代理配置和自动化脚本的运行时间
     ProxyManager::validate_config(&config.proxy)?;
+    scripting::validate_schedules(&mut config.automations)?;
 
     // 保存配置
     config_manager.replace(config.clone())?;
+    apply_config(&app, &config)?;
+
+    log::info!("Configuration saved successfully");
+    Ok(())
+}
 
+/// 将整份配置应用到运行中的各个模块（保存配置、从快照恢复或配置文件被外部修改后调用）
+pub(crate) fn apply_config(app: &AppHandle, config: &AppConfig) -> Result<(), AppError> {
     // 应用代理设置
     ProxyManager::apply_proxy(&config.proxy);
+    if let Some(http) = app.try_state::<HttpClient>() {
+        http.apply(&config.proxy, &config.network);
+    }
 
-    // 更新窗口管理器的最大窗口数
+    // 更新窗口管理器的最大窗口数，并实时应用样式
     if let Some(wm) = app.try_state::<WindowManager>() {
         wm.set_max_windows(config.max_active_windows);
+        wm.refresh_styles(app, config);
+    }
+
+    // 按需重启本地控制 API
+    if let Some(control_api) = app.try_state::<ControlApi>() {
+        control_api.apply(app, &config.control_api);
+    }
+
+    // 同步开机自启
+    autostart::reconcile(app, config.auto_start);
+
+    if let Err(e) = logging::apply(config.log_level.as_deref()) {
+        log::warn!("Invalid log level in config: {}", e);
     }
 
     // 重新加载快捷键
-    load_shortcuts_from_config(&app, &config)?;
+    load_shortcuts_from_config(app, config)
+}
 
-    log::info!("Configuration saved successfully");
+/// 检查小程序输入中需要验证的字段，并规范化下载目录和认证域名
+fn validate_patch(patch: &mut WebAppPatch) -> Result<(), AppError> {
+    if let Some(dir) = patch.download_dir.as_deref() {
+        patch.download_dir = Some(downloads::normalize_dir(dir)?.unwrap_or_default());
+    }
+    if let Some(proxy) = patch.proxy_override.as_ref().filter(|proxy| !proxy.host.is_empty()) {
+        ProxyManager::validate_config(proxy)?;
+    }
+    if let Some(class) = patch.window_class.as_deref().filter(|class| !class.is_empty()) {
+        window_class::validate(class)?;
+    }
+    if let Some(ua) = patch.user_agent.as_deref() {
+        validate_user_agent(ua)?;
+    }
+    if let Some(domains) = patch.auth_domains.take() {
+        patch.auth_domains = Some(navigation::normalize_domains(domains));
+    }
     Ok(())
 }
 
@@ -45,42 +164,55 @@ pub async fn save_config(
 pub async fn add_webapp(
     app: AppHandle,
     config_manager: State<'_, ConfigManager>,
-    name: String,
-    url: String,
-    icon: Option<String>,
-    shortcut: Option<String>,
-    width: Option<u32>,
-    height: Option<u32>,
-    inject_script: Option<String>,
-    inject_on_load: Option<bool>,
-    inject_on_shortcut: Option<bool>,
-) -> Result<WebApp, String> {
+    mut input: WebAppPatch,
+    allow_duplicates: Option<bool>,
+) -> Result<WebApp, AppError> {
+    policy::ensure_unlocked(&app)?;
+    validate_patch(&mut input)?;
+    let (Some(name), Some(url)) = (input.name.clone(), input.url.clone()) else {
+        return Err(AppError::invalid_input("小程序名称和网址不能为空"));
+    };
     // 创建新的webapp
     let mut webapp = WebApp::new(name, url);
-    webapp.icon = icon;
-    webapp.shortcut = shortcut.clone();
-    webapp.width = width.unwrap_or(1024);
-    webapp.height = height.unwrap_or(768);
-    webapp.inject_script = inject_script;
-    webapp.inject_on_load = inject_on_load.unwrap_or(false);
-    webapp.inject_on_shortcut = inject_on_shortcut.unwrap_or(false);
+    input.apply(&mut webapp);
 
     // 使用 ConfigManager 原子更新配置，并获取正确的 order 值
+    // 默认拒绝名称或网址重复的小程序，allow_duplicates 为 true 时跳过检查
+    let allow_duplicates = allow_duplicates.unwrap_or(false);
     let final_webapp = config_manager.update(|config| {
+        if !allow_duplicates {
+            if let Some(existing) = config.find_duplicate(&webapp.name, &webapp.url) {
+                return Err(AppError::invalid_input(format!("已存在相同名称或网址的小程序: {}", existing.name)));
+            }
+        }
+        if let Some(group_id) = input.group_id.as_deref().filter(|id| !id.is_empty()) {
+            if !config.groups.iter().any(|g| g.id == group_id) {
+                return Err(AppError::not_found(format!("分组不存在: {}", group_id)));
+            }
+        }
         webapp.order = config.webapps.len() as u32;
         config.webapps.push(webapp.clone());
-        webapp.clone()
-    })?;
+        if let Some(group_id) = &input.group_id {
+            groups::move_webapp(config, &webapp.id, group_id)?;
+        }
+        Ok(webapp.clone())
+    })??;
 
     // 注册快捷键
-    if let Some(shortcut_str) = &shortcut {
-        if !shortcut_str.is_empty() {
-            if let Some(manager) = app.try_state::<ShortcutManager>() {
-                let _ = manager.register(&app, shortcut_str, &final_webapp.id);
-            }
+    if let Some(shortcut_str) = &final_webapp.shortcut {
+        if let Some(manager) = app.try_state::<ShortcutManager>() {
+            let action = ShortcutAction::ToggleWebApp {
+                webapp_id: final_webapp.id.clone(),
+            };
+            let _ = manager.register_tracked(&app, shortcut_str, action);
         }
     }
 
+    // 未设置图标时在后台获取网站图标
+    if final_webapp.icon.as_deref().unwrap_or_default().is_empty() {
+        favicon::fetch_in_background(&app, &final_webapp.id);
+    }
+
     log::info!("Added webapp: {} ({})", final_webapp.name, final_webapp.id);
     Ok(final_webapp)
 }
@@ -90,65 +222,28 @@ pub async fn add_webapp(
 pub async fn update_webapp(
     app: AppHandle,
     config_manager: State<'_, ConfigManager>,
-    id: String,
-    name: Option<String>,
-    url: Option<String>,
-    icon: Option<String>,
-    shortcut: Option<String>,
-    width: Option<u32>,
-    height: Option<u32>,
-    use_proxy: Option<bool>,
-    order: Option<u32>,
-    inject_script: Option<String>,
-    inject_on_load: Option<bool>,
-    inject_on_shortcut: Option<bool>,
-) -> Result<WebApp, String> {
+    mut patch: WebAppPatch,
+) -> Result<WebApp, AppError> {
+    policy::ensure_unlocked(&app)?;
+    validate_patch(&mut patch)?;
     // 使用 ConfigManager 原子更新配置
     let (old_shortcut, updated_webapp) = config_manager.update(|config| {
-        if let Some(webapp) = config.webapps.iter_mut().find(|w| w.id == id) {
-            let old_shortcut = webapp.shortcut.clone();
-
-            if let Some(n) = name.clone() {
-                webapp.name = n;
-            }
-            if let Some(u) = url.clone() {
-                webapp.url = u;
-            }
-            if icon.is_some() {
-                webapp.icon = icon.clone();
-            }
-            if let Some(s) = shortcut.clone() {
-                webapp.shortcut = if s.is_empty() { None } else { Some(s) };
-            }
-            if let Some(w) = width {
-                webapp.width = w;
+        if let Some(group_id) = patch.group_id.as_deref().filter(|id| !id.is_empty()) {
+            if !config.groups.iter().any(|g| g.id == group_id) {
+                return Err(AppError::not_found(format!("分组不存在: {}", group_id)));
             }
-            if let Some(h) = height {
-                webapp.height = h;
-            }
-            if let Some(p) = use_proxy {
-                webapp.use_proxy = p;
-            }
-            if let Some(o) = order {
-                webapp.order = o;
-            }
-            if let Some(script) = inject_script.clone() {
-                webapp.inject_script = if script.is_empty() { None } else { Some(script) };
-            }
-            if let Some(on_load) = inject_on_load {
-                webapp.inject_on_load = on_load;
-            }
-            if let Some(on_shortcut) = inject_on_shortcut {
-                webapp.inject_on_shortcut = on_shortcut;
-            }
-
-            (old_shortcut, Some(webapp.clone()))
-        } else {
-            (None, None)
         }
-    })?;
-
-    let updated_webapp = updated_webapp.ok_or("小程序不存在")?;
+        let Some(webapp) = config.webapps.iter_mut().find(|w| w.id == patch.id) else {
+            return Err(AppError::not_found("小程序不存在"));
+        };
+        let old_shortcut = webapp.shortcut.clone();
+        patch.apply(webapp);
+        let updated = webapp.clone();
+        if let Some(group_id) = &patch.group_id {
+            groups::move_webapp(config, &patch.id, group_id)?;
+        }
+        Ok((old_shortcut, updated))
+    })??;
 
     // 更新快捷键
     if let Some(manager) = app.try_state::<ShortcutManager>() {
@@ -158,32 +253,155 @@ pub async fn update_webapp(
         }
         // 注册新快捷键
         if let Some(new) = &updated_webapp.shortcut {
-            if !new.is_empty() {
-                let _ = manager.register(&app, new, &updated_webapp.id);
-            }
+            let action = ShortcutAction::ToggleWebApp {
+                webapp_id: updated_webapp.id.clone(),
+            };
+            let _ = manager.register_tracked(&app, new, action);
+        }
+    }
+
+    // 窗口级设置实时应用到已打开的窗口
+    if patch.touches_window_settings() {
+        if let Some(wm) = app.try_state::<WindowManager>() {
+            wm.apply_window_settings(&app, &updated_webapp);
+        }
+    }
+
+    // 样式变化时实时应用到已打开的窗口
+    if patch.custom_css.is_some() {
+        if let Some(wm) = app.try_state::<WindowManager>() {
+            wm.refresh_styles(&app, &config_manager.read());
         }
     }
+    if patch.dark_css.is_some() || patch.force_dark.is_some() {
+        theme::refresh_window(&app, &updated_webapp);
+    }
+    if patch.dock_edge.is_some() {
+        dock::refresh_window(&app, &updated_webapp);
+    }
 
     log::info!("Updated webapp: {} ({})", updated_webapp.name, updated_webapp.id);
     Ok(updated_webapp)
 }
 
-/// 删除网页小程序
+/// 批量更新小程序（启用/停用、分组、代理），只写入一次配置并只重新加载一次快捷键
+#[tauri::command]
+pub async fn bulk_update_webapps(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    mut updates: Vec<WebAppPatch>,
+) -> Result<Vec<WebApp>, AppError> {
+    policy::ensure_unlocked(&app)?;
+    for patch in &mut updates {
+        validate_patch(patch)?;
+    }
+    let (config, updated) = config_manager.update(|config| {
+        // 先检查所有 ID，避免只应用部分修改
+        if let Some(patch) = updates
+            .iter()
+            .find(|p| !config.webapps.iter().any(|w| w.id == p.id))
+        {
+            return Err(AppError::not_found(format!("小程序不存在: {}", patch.id)));
+        }
+        if let Some(group_id) = updates
+            .iter()
+            .filter_map(|p| p.group_id.as_deref())
+            .find(|id| !id.is_empty() && !config.groups.iter().any(|g| g.id == *id))
+        {
+            return Err(AppError::not_found(format!("分组不存在: {}", group_id)));
+        }
+
+        let mut updated = Vec::new();
+        for patch in &updates {
+            if let Some(group_id) = &patch.group_id {
+                groups::move_webapp(config, &patch.id, group_id)?;
+            }
+            if let Some(webapp) = config.webapps.iter_mut().find(|w| w.id == patch.id) {
+                patch.apply(webapp);
+                updated.push(webapp.clone());
+            }
+        }
+        Ok((config.clone(), updated))
+    })??;
+
+    // 关闭被停用的小程序窗口
+    if let Some(wm) = app.try_state::<WindowManager>() {
+        for webapp in updated.iter().filter(|w| !w.enabled) {
+            let _ = wm.close_webapp(&app, &webapp.id);
+        }
+    }
+
+    load_shortcuts_from_config(&app, &config)?;
+
+    log::info!("Bulk updated {} webapps", updated.len());
+    Ok(updated)
+}
+
+/// 检查自定义 User-Agent：不能包含换行等控制字符（会被当作请求头的一部分发送）
+fn validate_user_agent(user_agent: &str) -> Result<(), AppError> {
+    if user_agent.chars().any(char::is_control) {
+        return Err(AppError::invalid_input("User-Agent 不能包含换行或控制字符"));
+    }
+    if user_agent.len() > 512 {
+        return Err(AppError::invalid_input("User-Agent 过长"));
+    }
+    Ok(())
+}
+
+/// 计算删除这些小程序会带来的变更
+fn preview_removal(app: &AppHandle, webapps: &[WebApp], dry_run: bool) -> ChangePreview {
+    let window_manager = app.try_state::<WindowManager>();
+    ChangePreview {
+        dry_run,
+        removed_webapps: webapps.iter().map(|w| w.id.clone()).collect(),
+        replaced_webapps: Vec::new(),
+        closed_windows: webapps
+            .iter()
+            .filter(|w| window_manager.as_ref().is_some_and(|wm| wm.is_window_active(&w.id)))
+            .map(|w| w.id.clone())
+            .collect(),
+        released_shortcuts: webapps
+            .iter()
+            .filter_map(|w| w.shortcut.clone())
+            .filter(|s| !s.is_empty())
+            .collect(),
+    }
+}
+
+/// 删除网页小程序，`dry_run` 为 true 时只返回将发生的变更
 #[tauri::command]
 pub async fn delete_webapp(
     app: AppHandle,
     config_manager: State<'_, ConfigManager>,
     id: String,
-) -> Result<(), String> {
+    dry_run: Option<bool>,
+) -> Result<ChangePreview, AppError> {
+    if dry_run.unwrap_or(false) {
+        let config = config_manager.read();
+        let targets: Vec<WebApp> = config.webapps.into_iter().filter(|w| w.id == id).collect();
+        return Ok(preview_removal(&app, &targets, true));
+    }
+    policy::ensure_unlocked(&app)?;
+
+    if config_manager.read().webapps.iter().any(|w| w.id == id && w.managed) {
+        return Err(AppError::Locked("该小程序由系统策略管理，不能删除".to_string()));
+    }
+
     // 使用 ConfigManager 原子更新配置
     let deleted_webapp = config_manager.update(|config| {
         let webapp = config.webapps.iter().find(|w| w.id == id).cloned();
         config.webapps.retain(|w| w.id != id);
+        for group in &mut config.groups {
+            group.webapp_ids.retain(|w| w != &id);
+        }
         webapp
     })?;
 
+    let deleted: Vec<WebApp> = deleted_webapp.into_iter().collect();
+    let preview = preview_removal(&app, &deleted, false);
+
     // 注销快捷键
-    if let Some(w) = deleted_webapp {
+    if let Some(w) = deleted.first() {
         if let Some(shortcut) = &w.shortcut {
             if let Some(manager) = app.try_state::<ShortcutManager>() {
                 let _ = manager.unregister(&app, shortcut);
@@ -198,7 +416,7 @@ pub async fn delete_webapp(
         log::info!("Deleted webapp: {} ({})", w.name, id);
     }
 
-    Ok(())
+    Ok(preview)
 }
 
 /// 打开小程序窗口
@@ -208,44 +426,95 @@ pub async fn open_webapp(
     config_manager: State<'_, ConfigManager>,
     window_manager: State<'_, WindowManager>,
     id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let config = config_manager.read();
 
     let webapp = config
         .webapps
         .iter()
         .find(|w| w.id == id)
-        .ok_or("小程序不存在")?
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?
         .clone();
 
-    let proxy_url = if webapp.use_proxy && config.proxy.enabled {
-        config.proxy.get_proxy_url()
-    } else {
-        None
-    };
+    let proxy_url = config.proxy_url_for(&webapp);
 
     window_manager.open_webapp(&app, &webapp, proxy_url)
 }
 
+/// 打开小程序窗口并导航到指定 URL（URL 必须在小程序允许的域名范围内）
+#[tauri::command]
+pub async fn open_webapp_at(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    window_manager: State<'_, WindowManager>,
+    id: String,
+    url: String,
+) -> Result<(), AppError> {
+    let config = config_manager.read();
+
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == id)
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?
+        .clone();
+
+    let target = url.parse::<url::Url>().map_err(|e| AppError::invalid_input(e.to_string()))?;
+    if !webapp.is_url_in_scope(&target) {
+        return Err(AppError::invalid_input(format!("URL 不在小程序允许的域名范围内: {}", url)));
+    }
+
+    let proxy_url = config.proxy_url_for(&webapp);
+    let options = OpenOptions {
+        target_url: Some(target),
+        ..Default::default()
+    };
+    window_manager.open_webapp_with(&app, &webapp, proxy_url, options)
+}
+
+/// 切换小程序窗口，行为与全局快捷键一致（包括快捷键脚本注入）
+#[tauri::command]
+pub async fn toggle_webapp(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    window_manager: State<'_, WindowManager>,
+    id: String,
+) -> Result<ToggleResult, AppError> {
+    let config = config_manager.read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == id)
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+
+    let result = window_manager.toggle_webapp(&app, webapp, config.proxy_url_for(webapp))?;
+    if result == ToggleResult::ShownExisting {
+        window_manager.inject_shortcut_script(&app, webapp)?;
+    }
+    Ok(result)
+}
+
 /// 关闭小程序窗口
 #[tauri::command]
 pub async fn close_webapp(
     app: AppHandle,
     window_manager: State<'_, WindowManager>,
     id: String,
-) -> Result<(), String> {
-    window_manager.close_webapp(&app, &id)
+) -> Result<(), AppError> {
+    window_manager.request_close(&app, &id)
 }
 
 /// 设置最大活跃窗口数量
 #[tauri::command]
 pub async fn set_max_active_windows(
+    app: AppHandle,
     config_manager: State<'_, ConfigManager>,
     window_manager: State<'_, WindowManager>,
     max: usize,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
     if max == 0 {
-        return Err("最大窗口数量不能为0".to_string());
+        return Err(AppError::invalid_input("最大窗口数量不能为0"));
     }
 
     window_manager.set_max_windows(max);
@@ -263,9 +532,14 @@ pub async fn set_max_active_windows(
 /// 设置代理配置
 #[tauri::command]
 pub async fn set_proxy_config(
+    app: AppHandle,
     config_manager: State<'_, ConfigManager>,
     proxy: ProxyConfig,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    if config_manager.managed().is_some_and(|m| m.locks_proxy()) {
+        return Err(AppError::Locked("代理设置由系统策略管理".to_string()));
+    }
     // 验证配置
     ProxyManager::validate_config(&proxy)?;
 
@@ -278,6 +552,9 @@ pub async fn set_proxy_config(
 
     // 应用代理
     ProxyManager::apply_proxy(&proxy);
+    if let Some(http) = app.try_state::<HttpClient>() {
+        http.apply(&proxy, &config_manager.read().network);
+    }
 
     log::info!("Proxy configuration updated");
     Ok(())
@@ -289,17 +566,92 @@ pub async fn register_shortcut(
     app: AppHandle,
     shortcut: String,
     webapp_id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    let manager = app
+        .try_state::<ShortcutManager>()
+        .ok_or("快捷键管理器未初始化")?;
+
+    manager.register(&app, &shortcut, ShortcutAction::ToggleWebApp { webapp_id })
+}
+
+/// 获取注册失败的快捷键（用于在界面上标记失效的绑定）
+#[tauri::command]
+pub async fn get_shortcut_failures(app: AppHandle) -> Result<Vec<ShortcutFailure>, AppError> {
+    let manager = app
+        .try_state::<ShortcutManager>()
+        .ok_or("快捷键管理器未初始化")?;
+
+    Ok(manager.get_failures())
+}
+
+/// 获取已注册的快捷键及其目标名称（按快捷键排序）
+#[tauri::command]
+pub async fn list_shortcuts(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+) -> Result<Vec<RegisteredShortcut>, AppError> {
+    let manager = app
+        .try_state::<ShortcutManager>()
+        .ok_or("快捷键管理器未初始化")?;
+
+    Ok(shortcut_check::list(&config_manager.read(), manager.registered()))
+}
+
+/// 保存前检查快捷键：能否解析、是否与已注册的快捷键冲突、是否为系统保留的快捷键
+/// `action` 为正在编辑的绑定，已注册到同一动作时不算冲突
+#[tauri::command]
+pub async fn validate_shortcut(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    shortcut: String,
+    action: Option<ShortcutAction>,
+) -> Result<ShortcutCheck, AppError> {
     let manager = app
         .try_state::<ShortcutManager>()
         .ok_or("快捷键管理器未初始化")?;
+    let registered = shortcut_check::list(&config_manager.read(), manager.registered());
 
-    manager.register(&app, &shortcut, &webapp_id)
+    Ok(shortcut_check::check(&shortcut, &registered, action.as_ref()))
+}
+
+/// 设置全部静音快捷键（空字符串或 None 表示清除），保存前检查能否解析、是否冲突或为系统保留
+#[tauri::command]
+pub async fn set_mute_all_shortcut(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    shortcut: Option<String>,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    let shortcut = shortcut.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if let Some(shortcut) = &shortcut {
+        let manager = app
+            .try_state::<ShortcutManager>()
+            .ok_or("快捷键管理器未初始化")?;
+        let registered = shortcut_check::list(&config_manager.read(), manager.registered());
+        let check = shortcut_check::check(shortcut, &registered, Some(&ShortcutAction::MuteAll));
+        let message = check.message.unwrap_or_default();
+        match check.status {
+            ShortcutStatus::Available => {}
+            ShortcutStatus::Conflict => return Err(AppError::ShortcutConflict(message)),
+            ShortcutStatus::Invalid | ShortcutStatus::Reserved => return Err(AppError::InvalidShortcut(message)),
+        }
+    }
+
+    let config = config_manager.update(|config| {
+        config.mute_all_shortcut = shortcut.clone();
+        config.clone()
+    })?;
+    load_shortcuts_from_config(&app, &config)?;
+
+    log::info!("Set mute-all shortcut to: {}", shortcut.as_deref().unwrap_or("none"));
+    Ok(())
 }
 
 /// 注销快捷键
 #[tauri::command]
-pub async fn unregister_shortcut(app: AppHandle, shortcut: String) -> Result<(), String> {
+pub async fn unregister_shortcut(app: AppHandle, shortcut: String) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
     let manager = app
         .try_state::<ShortcutManager>()
         .ok_or("快捷键管理器未初始化")?;
@@ -307,108 +659,1272 @@ pub async fn unregister_shortcut(app: AppHandle, shortcut: String) -> Result<(),
     manager.unregister(&app, &shortcut)
 }
 
-/// 打开小程序窗口（新窗口模式）
+/// 打开小程序窗口（新窗口模式，与 open_webapp 共享代理、注入和 LRU 逻辑）
 #[tauri::command]
 pub async fn open_webapp_window(
     app: AppHandle,
     config_manager: State<'_, ConfigManager>,
+    window_manager: State<'_, WindowManager>,
+    webapp_id: String,
+) -> Result<(), AppError> {
+    open_webapp(app, config_manager, window_manager, webapp_id).await
+}
+
+/// 关闭小程序窗口
+#[tauri::command]
+pub async fn close_webapp_window(
+    app: AppHandle,
+    window_manager: State<'_, WindowManager>,
+    webapp_id: String,
+) -> Result<(), AppError> {
+    window_manager.request_close(&app, &webapp_id)
+}
+
+/// 切换小程序窗口（显示/隐藏），返回窗口当前是否可见
+#[tauri::command]
+pub async fn toggle_webapp_window(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    window_manager: State<'_, WindowManager>,
     webapp_id: String,
-) -> Result<(), String> {
+) -> Result<bool, AppError> {
     let config = config_manager.read();
     let webapp = config
         .webapps
         .iter()
         .find(|w| w.id == webapp_id)
-        .ok_or("小程序不存在")?;
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
 
-    let window_label = format!("webapp-{}", webapp_id);
+    let result = window_manager.toggle_webapp(&app, webapp, config.proxy_url_for(webapp))?;
+    Ok(result != ToggleResult::Hidden)
+}
 
-    // 检查窗口是否已存在
-    if let Some(window) = app.get_webview_window(&window_label) {
-        // 窗口已存在，显示并聚焦
-        let _ = window.show();
-        let _ = window.set_focus();
-        return Ok(());
-    }
+/// 运行已保存的自动化脚本
+#[tauri::command]
+pub async fn run_automation(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    id: String,
+) -> Result<(), AppError> {
+    let automation = config_manager
+        .read()
+        .automations
+        .into_iter()
+        .find(|a| a.id == id)
+        .ok_or_else(|| AppError::not_found("自动化脚本不存在"))?;
 
-    // 创建新窗口
-    let url = webapp.url.parse::<url::Url>().map_err(|e| e.to_string())?;
-    
-    let _window = tauri::WebviewWindowBuilder::new(
-        &app,
-        &window_label,
-        WebviewUrl::External(url),
-    )
-    .title(&webapp.name)
-    .inner_size(webapp.width as f64, webapp.height as f64)
-    .resizable(true)
-    .center()
-    .build()
-    .map_err(|e| e.to_string())?;
-
-    log::info!("Opened webapp window: {}", webapp_id);
-    Ok(())
+    log::info!("Running automation: {}", automation.name);
+    Ok(scripting::run_script(app, automation.source).await?)
 }
 
-/// 关闭小程序窗口
+/// 直接运行一段自动化脚本源码（用于编辑器中调试）
 #[tauri::command]
-pub async fn close_webapp_window(app: AppHandle, webapp_id: String) -> Result<(), String> {
-    let window_label = format!("webapp-{}", webapp_id);
+pub async fn run_automation_source(app: AppHandle, source: String) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    Ok(scripting::run_script(app, source).await?)
+}
 
-    if let Some(window) = app.get_webview_window(&window_label) {
-        window.close().map_err(|e| e.to_string())?;
-        log::info!("Closed webapp window: {}", webapp_id);
-    }
+/// 运行已保存的宏，等待所有步骤执行完成
+#[tauri::command]
+pub async fn run_macro(app: AppHandle, id: String) -> Result<(), AppError> {
+    macros::run_macro(app, id).await
+}
+
+/// 手动开启/关闭勿扰模式
+#[tauri::command]
+pub async fn set_dnd(app: AppHandle, enabled: bool) -> Result<DndStatus, AppError> {
+    policy::ensure_unlocked(&app)?;
+    dnd::set_manual(&app, enabled)
+}
+
+/// 获取当前勿扰模式状态
+#[tauri::command]
+pub async fn get_dnd_status(app: AppHandle) -> Result<DndStatus, AppError> {
+    Ok(dnd::current_status(&app))
+}
+
+/// 小程序页面通过桥接脚本上报事件
+#[tauri::command]
+pub async fn bridge_event(
+    app: AppHandle,
+    webview_window: WebviewWindow,
+    kind: String,
+    payload: serde_json::Value,
+) -> Result<(), AppError> {
+    let webapp_id = bridge::webapp_id_from_label(webview_window.label())
+        .ok_or("仅小程序窗口可以使用桥接")?
+        .to_string();
+
+    Ok(bridge::handle_event(&app, &webapp_id, &kind, payload)?)
+}
+
+/// 设置单个小程序静音
+#[tauri::command]
+pub async fn set_webapp_muted(app: AppHandle, id: String, muted: bool) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    audio::set_webapp_muted(&app, &id, muted)
+}
+
+/// 设置全部静音
+#[tauri::command]
+pub async fn set_mute_all(app: AppHandle, muted: bool) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    audio::set_mute_all(&app, muted)
+}
+
+/// 切换全部静音，返回切换后的状态
+#[tauri::command]
+pub async fn toggle_mute_all(app: AppHandle) -> Result<bool, AppError> {
+    policy::ensure_unlocked(&app)?;
+    audio::toggle_mute_all(&app)
+}
+
+/// 在小程序窗口中进入阅读模式（提取正文并以简洁页面显示）
+#[tauri::command]
+pub async fn reader_mode(app: AppHandle, webapp_id: String) -> Result<(), AppError> {
+    Ok(reader::enter(&app, &webapp_id)?)
+}
+
+/// 退出阅读模式，恢复原页面
+#[tauri::command]
+pub async fn exit_reader_mode(app: AppHandle, webapp_id: String) -> Result<(), AppError> {
+    Ok(reader::exit(&app, &webapp_id)?)
+}
+
+/// 用系统语音朗读小程序窗口中选中的文本
+#[tauri::command]
+pub async fn speak_selection(app: AppHandle, webapp_id: String) -> Result<(), AppError> {
+    Ok(selection::request(&app, &webapp_id, SelectionPurpose::Speak)?)
+}
+
+/// 在翻译小程序中翻译小程序窗口中选中的文本
+#[tauri::command]
+pub async fn translate_selection(app: AppHandle, webapp_id: String) -> Result<(), AppError> {
+    Ok(selection::request(&app, &webapp_id, SelectionPurpose::Translate)?)
+}
 
+/// 停止朗读
+#[tauri::command]
+pub async fn stop_speaking(app: AppHandle) -> Result<(), AppError> {
+    speech::stop(&app);
     Ok(())
 }
 
-/// 切换小程序窗口（显示/隐藏）
+/// 获取无障碍设置
 #[tauri::command]
-pub async fn toggle_webapp_window(
+pub async fn get_accessibility(
+    config_manager: State<'_, ConfigManager>,
+) -> Result<AccessibilityConfig, AppError> {
+    Ok(config_manager.read().accessibility)
+}
+
+/// 保存无障碍设置并应用到所有小程序窗口
+#[tauri::command]
+pub async fn set_accessibility(app: AppHandle, settings: AccessibilityConfig) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    accessibility::set_settings(&app, settings)
+}
+
+/// 打开设置窗口，可选跳转到指定分区（如 `proxy`）
+#[tauri::command]
+pub async fn open_settings(app: AppHandle, section: Option<String>) -> Result<(), AppError> {
+    Ok(settings::open(&app, section.as_deref())?)
+}
+
+/// 完成首次启动引导，`remove_samples` 为 true 时删除所有示例小程序
+#[tauri::command]
+pub async fn complete_onboarding(
     app: AppHandle,
     config_manager: State<'_, ConfigManager>,
-    webapp_id: String,
-) -> Result<bool, String> {
-    let window_label = format!("webapp-{}", webapp_id);
-
-    if let Some(window) = app.get_webview_window(&window_label) {
-        let is_visible = window.is_visible().unwrap_or(false);
-        let is_focused = window.is_focused().unwrap_or(false);
-
-        if is_visible && is_focused {
-            window.hide().map_err(|e| e.to_string())?;
-            return Ok(false);
-        } else {
-            window.show().map_err(|e| e.to_string())?;
-            window.set_focus().map_err(|e| e.to_string())?;
-            return Ok(true);
+    remove_samples: Option<bool>,
+) -> Result<AppConfig, AppError> {
+    policy::ensure_unlocked(&app)?;
+    let removed = config_manager
+        .update(|config| onboarding::complete(config, remove_samples.unwrap_or(false)))?;
+
+    // 关闭被删除示例的窗口，并注销它们的快捷键
+    if !removed.is_empty() {
+        if let Some(wm) = app.try_state::<WindowManager>() {
+            for webapp in &removed {
+                let _ = wm.close_webapp(&app, &webapp.id);
+            }
         }
+        load_shortcuts_from_config(&app, &config_manager.read())?;
     }
 
-    // 窗口不存在，创建新窗口
+    log::info!("Onboarding completed, removed {} sample webapps", removed.len());
+    Ok(config_manager.read())
+}
+
+/// 生成小程序的分享码，可在另一台设备上通过 import_share_code 导入
+#[tauri::command]
+pub async fn share_webapp(
+    config_manager: State<'_, ConfigManager>,
+    id: String,
+) -> Result<String, AppError> {
     let config = config_manager.read();
     let webapp = config
         .webapps
         .iter()
+        .find(|w| w.id == id)
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+
+    Ok(share::encode(webapp)?)
+}
+
+/// 从分享码导入小程序（默认拒绝与已有小程序重复的名称或网址）
+///
+/// 分享码中的注入脚本和自定义样式默认不导入，`include_scripts` 为 true 时才导入；
+/// `dry_run` 为 true 时只返回将要添加的小程序和其中的脚本，供用户确认
+#[tauri::command]
+pub async fn import_share_code(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    code: String,
+    allow_duplicates: Option<bool>,
+    include_scripts: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<ShareImport, AppError> {
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        policy::ensure_unlocked(&app)?;
+    }
+    let mut import = share::decode(&code, include_scripts.unwrap_or(false))?;
+    import.dry_run = dry_run;
+    let allow_duplicates = allow_duplicates.unwrap_or(false);
+    let check_duplicate = |config: &AppConfig, webapp: &WebApp| match config.find_duplicate(&webapp.name, &webapp.url) {
+        Some(existing) if !allow_duplicates => Err(AppError::invalid_input(format!(
+            "已存在相同名称或网址的小程序: {}",
+            existing.name
+        ))),
+        _ => Ok(()),
+    };
+    if dry_run {
+        check_duplicate(&config_manager.read(), &import.webapp)?;
+        return Ok(import);
+    }
+
+    let webapp = &mut import.webapp;
+    config_manager.update(|config| {
+        check_duplicate(&*config, &*webapp)?;
+        webapp.order = config.webapps.len() as u32;
+        config.webapps.push(webapp.clone());
+        Ok::<_, AppError>(())
+    })??;
+
+    log::info!(
+        "Imported webapp from share code: {} ({}, scripts {})",
+        import.webapp.name,
+        import.webapp.id,
+        if import.scripts_included { "included" } else { "skipped" }
+    );
+    Ok(import)
+}
+
+/// 检查配置中的问题，`check_network` 为 true 时同时检查小程序网址能否访问
+#[tauri::command]
+pub async fn lint_config(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    check_network: Option<bool>,
+) -> Result<LintReport, AppError> {
+    let config = config_manager.read();
+    let mut issues = lint::lint(&config);
+
+    if check_network.unwrap_or(false) {
+        let (client, _) = net::client(&app);
+        issues.extend(lint::check_reachability(client, &config).await);
+    }
+
+    Ok(LintReport::new(issues))
+}
+
+/// 开启/关闭测试环境模式（只影响之后打开或导航的页面）
+#[tauri::command]
+pub async fn set_staging_mode(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    log::info!("Staging mode {}", if enabled { "enabled" } else { "disabled" });
+    config_manager.update(|config| config.staging_mode = enabled)
+}
+
+/// 开启或关闭剪贴板网址监听
+#[tauri::command]
+pub async fn set_clipboard_watch(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    log::info!("Clipboard watch {}", if enabled { "enabled" } else { "disabled" });
+    config_manager.update(|config| config.clipboard_watch.enabled = enabled)
+}
+
+/// 获取当前平台的窗口和快捷键能力（如 Wayland 上不支持移动窗口）
+#[tauri::command]
+pub async fn get_platform_capabilities() -> Result<PlatformCapabilities, AppError> {
+    Ok(platform::capabilities())
+}
+
+/// 获取界面偏好
+#[tauri::command]
+pub async fn get_ui_prefs(config_manager: State<'_, ConfigManager>) -> Result<UiPreferences, AppError> {
+    Ok(config_manager.read().ui_preferences)
+}
+
+/// 保存界面偏好
+#[tauri::command]
+pub async fn set_ui_prefs(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    prefs: UiPreferences,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    config_manager.update(|config| config.ui_preferences = prefs)
+}
+
+/// 获取定时备份状态
+#[tauri::command]
+pub async fn get_backup_status(app: AppHandle) -> Result<BackupStatus, AppError> {
+    Ok(backup::status(&app))
+}
+
+/// 立即备份配置到备份目录
+#[tauri::command]
+pub async fn run_backup_now(app: AppHandle) -> Result<BackupStatus, AppError> {
+    backup::run_backup(&app)?;
+    Ok(backup::status(&app))
+}
+
+/// 获取最近一次清理的结果
+#[tauri::command]
+pub async fn get_maintenance_report(
+    maintenance: State<'_, MaintenanceState>,
+) -> Result<Option<MaintenanceReport>, AppError> {
+    Ok(maintenance.last_report())
+}
+
+/// 立即清理缓存、图标和日志，返回释放的空间
+#[tauri::command]
+pub async fn run_maintenance_now(app: AppHandle) -> Result<MaintenanceReport, AppError> {
+    tauri::async_runtime::spawn_blocking(move || maintenance::run_now(&app))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// 统计每个小程序配置文件的磁盘占用（缓存、Cookie、网站存储）
+#[tauri::command]
+pub async fn get_storage_usage(app: AppHandle) -> Result<Vec<ProfileUsage>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || storage::usage(&app))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// 列出本机可导入 Cookie 的浏览器配置文件
+#[tauri::command]
+pub async fn list_cookie_sources() -> Result<Vec<CookieSource>, AppError> {
+    Ok(cookies::list_sources())
+}
+
+/// 从系统浏览器导入小程序域名的 Cookie，免去重新登录
+/// 前端必须先向用户说明并取得确认；未指定域名时使用小程序网址的域名
+#[tauri::command]
+pub async fn import_browser_cookies(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    webapp_id: String,
+    source_path: PathBuf,
+    domain: Option<String>,
+    consent: bool,
+) -> Result<CookieImportReport, AppError> {
+    policy::ensure_unlocked(&app)?;
+    if !consent {
+        return Err(AppError::invalid_input("导入浏览器 Cookie 需要用户确认"));
+    }
+
+    let webapp = config_manager
+        .read()
+        .webapps
+        .into_iter()
         .find(|w| w.id == webapp_id)
-        .ok_or("小程序不存在")?;
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+    let domain = match domain.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()) {
+        Some(domain) => domain,
+        None => url::Url::parse(&webapp.url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .ok_or("无法确定小程序的域名")?,
+    };
+
+    Ok(cookies::import(&app, &webapp, &source_path, &domain).await?)
+}
+
+/// 让小程序处理某个链接协议（如 `mailto`），并在系统中注册
+#[tauri::command]
+pub async fn set_protocol_handler(
+    app: AppHandle,
+    webapp_id: String,
+    handler: ProtocolHandler,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    protocols::set_handler(&app, &webapp_id, handler)
+}
+
+/// 启用或停用小程序的某个链接协议
+#[tauri::command]
+pub async fn set_protocol_enabled(
+    app: AppHandle,
+    webapp_id: String,
+    scheme: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    protocols::set_enabled(&app, &webapp_id, &scheme, enabled)
+}
+
+/// 移除小程序的链接协议处理
+#[tauri::command]
+pub async fn remove_protocol_handler(app: AppHandle, webapp_id: String, scheme: String) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    protocols::remove_handler(&app, &webapp_id, &scheme)
+}
+
+/// 列出可以接收分享内容的小程序
+#[tauri::command]
+pub async fn get_share_candidates(
+    config_manager: State<'_, ConfigManager>,
+    payload: SharePayload,
+) -> Result<Vec<ShareCandidate>, AppError> {
+    Ok(share_target::candidates(&config_manager.read(), &payload))
+}
+
+/// 在选中的小程序中打开分享的内容
+#[tauri::command]
+pub async fn share_to_webapp(app: AppHandle, webapp_id: String, payload: SharePayload) -> Result<(), AppError> {
+    share_target::share_to(&app, &webapp_id, &payload)
+}
+
+/// 获取管理员锁定状态
+#[tauri::command]
+pub async fn get_policy_status(app: AppHandle) -> Result<PolicyStatus, AppError> {
+    Ok(policy::status(&app))
+}
+
+/// 启用管理员锁定：之后修改配置需要先输入密码解锁
+#[tauri::command]
+pub async fn lock_config(app: AppHandle, passphrase: String) -> Result<PolicyStatus, AppError> {
+    policy::lock(&app, &passphrase)
+}
+
+/// 输入密码临时解锁配置
+#[tauri::command]
+pub async fn unlock_config(app: AppHandle, passphrase: String) -> Result<PolicyStatus, AppError> {
+    policy::unlock(&app, &passphrase)
+}
+
+/// 结束临时解锁，恢复只读
+#[tauri::command]
+pub async fn relock_config(app: AppHandle) -> Result<PolicyStatus, AppError> {
+    Ok(policy::relock(&app))
+}
+
+/// 关闭管理员锁定（需要先解锁）
+#[tauri::command]
+pub async fn disable_config_lock(app: AppHandle) -> Result<PolicyStatus, AppError> {
+    policy::disable(&app)
+}
+
+/// 设置禁止访问的域名（需要先解锁）
+#[tauri::command]
+pub async fn set_blocked_domains(app: AppHandle, domains: Vec<String>) -> Result<PolicyStatus, AppError> {
+    policy::set_blocked_domains(&app, domains)
+}
+
+/// 获取系统级策略（前端据此禁用由策略决定的设置），没有策略文件时返回 None
+#[tauri::command]
+pub async fn get_managed_policy(
+    config_manager: State<'_, ConfigManager>,
+) -> Result<Option<ManagedPolicy>, AppError> {
+    Ok(config_manager.managed().cloned())
+}
+
+/// 获取所有小程序的角标（未读数量和运行状态），之后的变化通过 `badge://changed` 事件推送
+#[tauri::command]
+pub async fn get_badges(app: AppHandle) -> Result<Vec<Badge>, AppError> {
+    Ok(badges::snapshot(&app))
+}
+
+/// 获取启动器网格（分组、排序、角标和运行状态），顺序与 `activate_index` 一致
+#[tauri::command]
+pub async fn get_launcher_grid(
+    app: AppHandle,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<LauncherPage, AppError> {
+    Ok(launcher::grid(&app, offset.unwrap_or(0), limit))
+}
+
+/// 打开启动器网格中第 index 个小程序（从 0 开始）
+#[tauri::command]
+pub async fn activate_index(app: AppHandle, index: usize) -> Result<(), AppError> {
+    launcher::activate_index(&app, index)
+}
+
+/// 获取最近关闭的小程序窗口（新的在前）
+#[tauri::command]
+pub async fn get_recently_closed(app: AppHandle) -> Result<Vec<ClosedWebApp>, AppError> {
+    Ok(recent::list(&app))
+}
+
+/// 重新打开最近关闭的小程序窗口，返回被打开的记录
+#[tauri::command]
+pub async fn reopen_last_closed(app: AppHandle) -> Result<ClosedWebApp, AppError> {
+    recent::reopen_last(&app)
+}
+
+/// 获取使用统计（各小程序的崩溃次数等）
+#[tauri::command]
+pub async fn get_usage_stats(app: AppHandle) -> Result<UsageStats, AppError> {
+    Ok(usage::snapshot(&app))
+}
+
+/// 处理无响应提示：reload 为 true 时重新加载页面，否则关闭窗口
+#[tauri::command]
+pub async fn recover_webapp(app: AppHandle, webapp_id: String, reload: bool) -> Result<(), AppError> {
+    watchdog::recover(&app, &webapp_id, reload)
+}
+
+/// 获取系统内存状态（是否低于内存压力阈值）
+#[tauri::command]
+pub async fn get_memory_status(app: AppHandle) -> Result<MemoryStatus, AppError> {
+    tauri::async_runtime::spawn_blocking(move || memory::status(&app))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// 获取小程序的请求记录（需要在小程序设置中开启请求记录）
+#[tauri::command]
+pub async fn get_request_log(app: AppHandle, webapp_id: String) -> Result<Vec<RequestEntry>, AppError> {
+    Ok(request_log::get(&app, &webapp_id))
+}
+
+/// 清空小程序的请求记录
+#[tauri::command]
+pub async fn clear_request_log(app: AppHandle, webapp_id: String) -> Result<(), AppError> {
+    request_log::clear(&app, &webapp_id);
+    Ok(())
+}
+
+/// 刷新后台 HTTP 客户端，使证书变化生效
+fn refresh_http_client(app: &AppHandle) {
+    if let Some(http) = app.try_state::<HttpClient>() {
+        let config = app.state::<ConfigManager>().read();
+        http.apply(&config.proxy, &config.network);
+    }
+}
+
+/// 添加 PEM 文件中的企业根证书；install_system 为 true 时安装到系统信任存储，
+/// 小程序窗口才会信任（系统可能要求确认）
+#[tauri::command]
+pub async fn add_trusted_ca(app: AppHandle, path: PathBuf, install_system: bool) -> Result<Vec<TrustedCa>, AppError> {
+    policy::ensure_unlocked(&app)?;
+    let app_clone = app.clone();
+    let list = tauri::async_runtime::spawn_blocking(move || certs::add(&app_clone, &path, install_system))
+        .await
+        .map_err(|e| e.to_string())??;
+    refresh_http_client(&app);
+    Ok(list)
+}
+
+/// 获取信任的根证书
+#[tauri::command]
+pub async fn list_trusted_cas(config_manager: State<'_, ConfigManager>) -> Result<Vec<TrustedCa>, AppError> {
+    Ok(config_manager.read().trusted_cas)
+}
+
+/// 移除信任的根证书
+#[tauri::command]
+pub async fn remove_trusted_ca(app: AppHandle, fingerprint: String) -> Result<Vec<TrustedCa>, AppError> {
+    policy::ensure_unlocked(&app)?;
+    let app_clone = app.clone();
+    let list = tauri::async_runtime::spawn_blocking(move || certs::remove(&app_clone, &fingerprint))
+        .await
+        .map_err(|e| e.to_string())??;
+    refresh_http_client(&app);
+    Ok(list)
+}
+
+/// 提交代理认证提示中输入的凭据，remember 为 true 时保存到系统钥匙串；
+/// 使用代理的小程序窗口会以新凭据重新打开
+#[tauri::command]
+pub async fn submit_proxy_credentials(
+    app: AppHandle,
+    username: String,
+    password: String,
+    remember: bool,
+) -> Result<(), AppError> {
+    Ok(proxy_auth::submit(&app, &username, &password, remember)?)
+}
+
+/// 关闭代理认证提示
+#[tauri::command]
+pub async fn cancel_proxy_auth(app: AppHandle) -> Result<(), AppError> {
+    proxy_auth::cancel(&app);
+    Ok(())
+}
+
+/// 删除保存在系统钥匙串中的代理凭据
+#[tauri::command]
+pub async fn forget_proxy_credentials(app: AppHandle) -> Result<(), AppError> {
+    Ok(proxy_auth::forget(&app)?)
+}
+
+/// 为小程序窗口当前所在的显示器单独设置缩放（百分比），zoom_percent 为空时恢复默认缩放
+#[tauri::command]
+pub async fn set_monitor_zoom(app: AppHandle, webapp_id: String, zoom_percent: Option<u32>) -> Result<WebApp, AppError> {
+    policy::ensure_unlocked(&app)?;
+    zoom::set_monitor_zoom(&app, &webapp_id, zoom_percent)
+}
+
+/// 保存完整的应用状态快照（配置、图标、证书、使用统计和窗口布局），用于迁移和灾难恢复；
+/// path 为空时保存到备份目录或应用数据目录
+#[tauri::command]
+pub async fn snapshot_state(app: AppHandle, path: Option<PathBuf>) -> Result<SnapshotSummary, AppError> {
+    let result = tauri::async_runtime::spawn_blocking(move || snapshot::snapshot(&app, path))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?;
+    Ok(result?)
+}
+
+/// 从快照恢复应用状态，配置立即生效，会话和使用统计在重启后生效
+#[tauri::command]
+pub async fn restore_state(app: AppHandle, path: PathBuf) -> Result<AppConfig, AppError> {
+    policy::ensure_unlocked(&app)?;
+    let app_clone = app.clone();
+    let config = tauri::async_runtime::spawn_blocking(move || snapshot::restore(&app_clone, &path))
+        .await
+        .map_err(|e| e.to_string())??;
+    certs::load(&app);
+    apply_config(&app, &config)?;
+    Ok(config)
+}
+
+/// 按 JSON Pointer 修改配置中的单个值（如 `/webapps/0/width`），修改后重新应用代理、快捷键等设置，
+/// 并发出 `config://patched` 事件
+#[tauri::command]
+pub async fn patch_config(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    pointer: String,
+    value: serde_json::Value,
+) -> Result<AppConfig, AppError> {
+    policy::ensure_unlocked(&app)?;
+    if patch::touches(&pointer, "/proxy") && config_manager.managed().is_some_and(|m| m.locks_proxy()) {
+        return Err(AppError::Locked("代理设置由系统策略管理".to_string()));
+    }
 
-    let url = webapp.url.parse::<url::Url>().map_err(|e| e.to_string())?;
-    
-    let _window = tauri::WebviewWindowBuilder::new(
+    let patch_value = value.clone();
+    let config = config_manager.update(|config| {
+        let patched = patch::apply(config, &pointer, patch_value)?;
+        *config = patched;
+        Ok::<_, AppError>(config.clone())
+    })??;
+    apply_config(&app, &config)?;
+
+    events::emit(
         &app,
-        &window_label,
-        WebviewUrl::External(url),
-    )
-    .title(&webapp.name)
-    .inner_size(webapp.width as f64, webapp.height as f64)
-    .resizable(true)
-    .center()
-    .build()
-    .map_err(|e| e.to_string())?;
+        Channel::Config,
+        "config://patched",
+        serde_json::json!({ "pointer": pointer, "value": value }),
+    );
+    log::info!("Configuration patched: {}", pointer);
+    Ok(config)
+}
+
+/// 清除使用独立配置文件的小程序保存的 Cookie、缓存和网站存储
+#[tauri::command]
+pub async fn wipe_webapp_data(app: AppHandle, webapp_id: String) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    profiles::wipe(&app, &webapp_id).await
+}
+
+/// 获取小程序的内嵌图标数据（`get_config` 中的 `icon-ref:` 引用），以二进制返回；
+/// 按 `theme`（未指定时为设置中的图标主题）处理后返回
+#[tauri::command]
+pub async fn get_webapp_icon(
+    config_manager: State<'_, ConfigManager>,
+    webapp_id: String,
+    theme: Option<IconTheme>,
+) -> Result<tauri::ipc::Response, AppError> {
+    let config = config_manager.read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .cloned()
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+    let theme = theme.unwrap_or(config.icon_theme);
+    let bytes = tauri::async_runtime::spawn_blocking(move || icon_themes::themed(&webapp, theme))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))??;
+    Ok(tauri::ipc::Response::new(bytes))
+}
 
-    log::info!("Created webapp window: {}", webapp_id);
-    Ok(true)
+/// 导出完整配置（小程序、快捷键、代理等）到用户选择的 JSON 文件
+#[tauri::command]
+pub async fn export_config(config_manager: State<'_, ConfigManager>, path: PathBuf) -> Result<(), AppError> {
+    let config = config_manager.read();
+    let result = tauri::async_runtime::spawn_blocking(move || transfer::export(&config, &path))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?;
+    Ok(result?)
 }
 
+/// 从导出的 JSON 文件导入配置：merge 为 true 时追加其中的小程序，否则替换当前配置
+///
+/// `dry_run` 为 true 时只返回结果和将发生的变更（替换时删除或替换的小程序、关闭的窗口和注销的快捷键）
+#[tauri::command]
+pub async fn import_config(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    path: PathBuf,
+    merge: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<ImportSummary, AppError> {
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        policy::ensure_unlocked(&app)?;
+    }
+    let merge = merge.unwrap_or(false);
+    let current = config_manager.read();
+    let source = current.clone();
+    let (mut config, mut summary) =
+        tauri::async_runtime::spawn_blocking(move || transfer::import(&source, &path, merge))
+            .await
+            .map_err(|e| e.to_string())??;
+    scripting::validate_schedules(&mut config.automations)?;
+
+    summary.changes = if merge {
+        ChangePreview::default()
+    } else {
+        transfer::replacement_changes(&current, &config)
+    };
+    summary.changes.dry_run = dry_run;
+    if let Some(wm) = app.try_state::<WindowManager>() {
+        summary.changes.closed_windows = summary
+            .changes
+            .removed_webapps
+            .iter()
+            .filter(|id| wm.is_window_active(id))
+            .cloned()
+            .collect();
+    }
+    if dry_run {
+        return Ok(summary);
+    }
+
+    config_manager.replace(config.clone())?;
+    if let Some(wm) = app.try_state::<WindowManager>() {
+        for id in &summary.changes.closed_windows {
+            let _ = wm.close_webapp(&app, id);
+        }
+    }
+    apply_config(&app, &config)?;
+    log::info!(
+        "Imported {} webapps ({} with new IDs)",
+        summary.webapps,
+        summary.renamed_ids
+    );
+    Ok(summary)
+}
+
+/// 开启或关闭开机自启
+#[tauri::command]
+pub async fn set_auto_start(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    enabled: bool,
+) -> Result<AutoStartStatus, AppError> {
+    policy::ensure_unlocked(&app)?;
+    autostart::set(&app, &config_manager, enabled)
+}
+
+/// 获取开机自启状态（配置和系统登录项）
+#[tauri::command]
+pub async fn get_auto_start_status(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+) -> Result<AutoStartStatus, AppError> {
+    Ok(autostart::status(&app, &config_manager))
+}
+
+/// 获取脚本库
+#[tauri::command]
+pub async fn get_user_scripts(config_manager: State<'_, ConfigManager>) -> Result<Vec<UserScript>, AppError> {
+    Ok(config_manager.read().scripts)
+}
+
+/// 添加或修改脚本库中的脚本（ID 为空时新建），已打开的窗口重新打开后生效
+#[tauri::command]
+pub async fn save_user_script(app: AppHandle, script: UserScript) -> Result<UserScript, AppError> {
+    policy::ensure_unlocked(&app)?;
+    userscripts::save(&app, script)
+}
+
+/// 删除脚本库中的脚本
+#[tauri::command]
+pub async fn delete_user_script(app: AppHandle, script_id: String) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    userscripts::delete(&app, &script_id)
+}
+
+/// 在小程序窗口的当前页面中执行脚本库中的脚本
+#[tauri::command]
+pub async fn run_user_script(app: AppHandle, webapp_id: String, script_id: String) -> Result<(), AppError> {
+    Ok(userscripts::run(&app, &webapp_id, &script_id)?)
+}
+
+/// 导出诊断包（代理凭据、令牌和请求头中的凭据已清理）
+#[tauri::command]
+pub async fn export_diagnostics(app: AppHandle, path: PathBuf) -> Result<(), AppError> {
+    Ok(diagnostics::export(&app, &path)?)
+}
+
+/// 复制小程序窗口，在新窗口中打开当前页面（需开启多实例），返回新窗口的标签
+#[tauri::command]
+pub async fn clone_window(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    window_manager: State<'_, WindowManager>,
+    webapp_id: String,
+) -> Result<String, AppError> {
+    let config = config_manager.read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+    window_manager.clone_window(&app, webapp, config.proxy_url_for(webapp))
+}
+
+/// 设置日志过滤规则并立即生效（格式同 `RUST_LOG`，如 `warn,window=debug`），为空时恢复为 `RUST_LOG`
+#[tauri::command]
+pub async fn set_log_level(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    level: Option<String>,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    let level = level.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    logging::apply(level.as_deref())?;
+    config_manager.update(|config| config.log_level = level)
+}
+
+/// 获取已打开的小程序窗口及其可见性和焦点（最近使用的在前）
+#[tauri::command]
+pub async fn get_active_webapps(
+    app: AppHandle,
+    window_manager: State<'_, WindowManager>,
+) -> Result<Vec<ActiveWebApp>, AppError> {
+    Ok(window_manager.active_webapps(&app))
+}
+
+/// 重新获取小程序的网站图标并替换当前图标，返回前端使用的图标引用
+#[tauri::command]
+pub async fn refresh_webapp_icon(app: AppHandle, webapp_id: String) -> Result<Option<String>, AppError> {
+    policy::ensure_unlocked(&app)?;
+    favicon::refresh(&app, &webapp_id, favicon::Overwrite::Always).await
+}
+
+/// 在系统默认浏览器中打开小程序当前的页面（窗口未打开时为主页），返回打开的网址
+#[tauri::command]
+pub async fn open_in_browser(app: AppHandle, webapp_id: String) -> Result<String, AppError> {
+    Ok(browser::open_in_browser(&app, &webapp_id)?)
+}
+
+/// 获取所有小程序分组
+#[tauri::command]
+pub async fn get_groups(config_manager: State<'_, ConfigManager>) -> Result<Vec<WebAppGroup>, AppError> {
+    Ok(config_manager.read().groups)
+}
+
+/// 添加或修改小程序分组（ID 为空时新建）
+#[tauri::command]
+pub async fn save_group(app: AppHandle, group: WebAppGroup) -> Result<WebAppGroup, AppError> {
+    policy::ensure_unlocked(&app)?;
+    groups::save(&app, group)
+}
+
+/// 删除小程序分组（不删除其中的小程序）
+#[tauri::command]
+pub async fn delete_group(app: AppHandle, group_id: String) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    groups::delete(&app, &group_id)
+}
+
+/// 打开分组中的小程序（不超过最大活跃窗口数），返回打开的小程序 ID
+#[tauri::command]
+pub async fn open_group(app: AppHandle, group_id: String) -> Result<Vec<String>, AppError> {
+    groups::open(&app, &group_id).await
+}
+
+/// 关闭分组中已打开的小程序窗口，返回关闭的小程序 ID
+#[tauri::command]
+pub async fn close_group(app: AppHandle, group_id: String) -> Result<Vec<String>, AppError> {
+    groups::close(&app, &group_id)
+}
+
+/// 获取最近的代理路由决策（按域名路由的小程序选择了哪个代理及原因，最新的在前）
+#[tauri::command]
+pub async fn get_routing_decisions() -> Result<Vec<RoutingDecision>, AppError> {
+    Ok(proxy_routes::decisions())
+}
+
+/// 切换已打开的小程序窗口是否置顶（临时生效，不修改小程序设置）
+#[tauri::command]
+pub async fn set_webapp_pin(
+    app: AppHandle,
+    window_manager: State<'_, WindowManager>,
+    webapp_id: String,
+    pinned: bool,
+) -> Result<(), AppError> {
+    window_manager.set_pin(&app, &webapp_id, pinned)
+}
+
+/// 设置小程序模拟的网络条件（开发者选项，None 为不限制），立即应用到已打开的窗口
+#[tauri::command]
+pub async fn set_network_conditions(
+    app: AppHandle,
+    webapp_id: String,
+    profile: Option<NetworkProfile>,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    throttle::set_conditions(&app, &webapp_id, profile)
+}
+
+/// 设置当前窗口订阅的事件频道（替换之前的订阅），返回订阅后的频道
+///
+/// 未调用时窗口接收除请求记录外的所有频道
+#[tauri::command]
+pub async fn subscribe(
+    webview_window: WebviewWindow,
+    event_bus: State<'_, EventBus>,
+    channels: Vec<Channel>,
+) -> Result<Vec<Channel>, AppError> {
+    if bridge::webapp_id_from_label(webview_window.label()).is_some() {
+        return Err(AppError::invalid_input("小程序窗口不能订阅事件"));
+    }
+    Ok(event_bus.subscribe(webview_window.label(), channels))
+}
+
+/// 导出全局代理设置为 shell、PowerShell 或 .env 格式的环境变量片段，供终端工具使用
+///
+/// 包含凭据时会先弹窗确认，并在导出时从系统钥匙串读取密码
+#[tauri::command]
+pub async fn export_proxy_env(
+    app: AppHandle,
+    format: EnvFormat,
+    include_credentials: Option<bool>,
+) -> Result<String, AppError> {
+    proxy_env::export(&app, format, include_credentials.unwrap_or(false)).await
+}
+
+/// 从 WebCatalog、Ferdium 或 Rambox 的配置文件导入小程序
+///
+/// `source` 为空时根据文件内容判断来源；`dry_run` 为 true 时只返回将导入的小程序和跳过的服务
+#[tauri::command]
+pub async fn import_from(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    path: PathBuf,
+    source: Option<ImportSource>,
+    dry_run: Option<bool>,
+) -> Result<ForeignImport, AppError> {
+    let current = config_manager.read();
+    let mut result = tauri::async_runtime::spawn_blocking(move || importers::read(&current, &path, source))
+        .await
+        .map_err(|e| e.to_string())??;
+    if dry_run.unwrap_or(false) {
+        return Ok(result);
+    }
+
+    policy::ensure_unlocked(&app)?;
+    result.dry_run = false;
+    let webapps = config_manager.update(|config| {
+        // 预览之后配置可能已经变化，再次跳过重复的小程序
+        let mut added = Vec::new();
+        for mut webapp in result.webapps.drain(..) {
+            if config.find_duplicate(&webapp.name, &webapp.url).is_some() {
+                continue;
+            }
+            webapp.order = config.webapps.len() as u32;
+            config.webapps.push(webapp.clone());
+            added.push(webapp);
+        }
+        added
+    })?;
+    result.webapps = webapps;
+
+    for webapp in result.webapps.iter().filter(|w| w.icon.is_none()) {
+        favicon::fetch_in_background(&app, &webapp.id);
+    }
+    log::info!(
+        "Imported {} webapps from {:?} ({} skipped)",
+        result.webapps.len(),
+        result.source,
+        result.skipped.len()
+    );
+    Ok(result)
+}
+
+/// 小程序页面的网页通知（由注入的脚本转发），显示为带小程序名称的系统通知
+#[tauri::command]
+pub async fn notify(
+    app: AppHandle,
+    webview_window: WebviewWindow,
+    title: String,
+    body: Option<String>,
+) -> Result<(), AppError> {
+    let webapp_id = bridge::webapp_id_from_label(webview_window.label())
+        .ok_or_else(|| AppError::invalid_input("仅小程序窗口可以发送通知"))?;
+    notifications::show(&app, webapp_id, &title, body.as_deref())
+}
+
+/// 获取各小程序的使用统计（打开次数、最近打开时间、前台时间），最常用的在前
+#[tauri::command]
+pub async fn get_webapp_stats(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+) -> Result<Vec<WebAppStats>, AppError> {
+    Ok(usage::ranked(&app, &config_manager.read().webapps))
+}
+
+/// 清除使用统计，`webapp_id` 为空时清除所有小程序的统计
+#[tauri::command]
+pub async fn reset_stats(app: AppHandle, webapp_id: Option<String>) -> Result<(), AppError> {
+    usage::reset(&app, webapp_id.as_deref());
+    Ok(())
+}
+
+/// 按给定顺序重新排列小程序（一次写入配置），未列出的小程序排在后面
+#[tauri::command]
+pub async fn reorder_webapps(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    ids: Vec<String>,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    config_manager.update(|config| launcher::reorder(config, &ids))??;
+    log::info!("Reordered {} webapps", ids.len());
+    Ok(())
+}
+
+/// 批量删除小程序，`dry_run` 为 true 时只返回将发生的变更；任一 ID 不存在或由策略管理时不删除任何小程序
+#[tauri::command]
+pub async fn bulk_delete(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    ids: Vec<String>,
+    dry_run: Option<bool>,
+) -> Result<ChangePreview, AppError> {
+    if dry_run.unwrap_or(false) {
+        let config = config_manager.read();
+        let targets: Vec<WebApp> = config.webapps.into_iter().filter(|w| ids.contains(&w.id)).collect();
+        return Ok(preview_removal(&app, &targets, true));
+    }
+    policy::ensure_unlocked(&app)?;
+
+    let deleted = config_manager.update(|config| {
+        for id in &ids {
+            match config.webapps.iter().find(|w| &w.id == id) {
+                None => return Err(AppError::not_found(format!("小程序不存在: {}", id))),
+                Some(webapp) if webapp.managed => {
+                    return Err(AppError::Locked(format!("{} 由系统策略管理，不能删除", webapp.name)))
+                }
+                Some(_) => {}
+            }
+        }
+        let deleted: Vec<WebApp> = config.webapps.iter().filter(|w| ids.contains(&w.id)).cloned().collect();
+        config.webapps.retain(|w| !ids.contains(&w.id));
+        for group in &mut config.groups {
+            group.webapp_ids.retain(|w| !ids.contains(w));
+        }
+        Ok(deleted)
+    })??;
+    let preview = preview_removal(&app, &deleted, false);
+
+    if let Some(manager) = app.try_state::<ShortcutManager>() {
+        for shortcut in &preview.released_shortcuts {
+            let _ = manager.unregister(&app, shortcut);
+        }
+    }
+    if let Some(wm) = app.try_state::<WindowManager>() {
+        for webapp in &deleted {
+            let _ = wm.close_webapp(&app, &webapp.id);
+        }
+    }
+
+    log::info!("Bulk deleted {} webapps", deleted.len());
+    Ok(preview)
+}
+
+/// 批量设置小程序的代理：`proxy_override` 为空时不修改单独的代理，主机为空时清除单独的代理（改用全局代理）
+///
+/// 新的代理在窗口重新打开后生效
+#[tauri::command]
+pub async fn bulk_set_proxy(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    ids: Vec<String>,
+    use_proxy: bool,
+    proxy_override: Option<ProxyConfig>,
+) -> Result<Vec<WebApp>, AppError> {
+    policy::ensure_unlocked(&app)?;
+    if let Some(proxy) = proxy_override.as_ref().filter(|proxy| !proxy.host.is_empty()) {
+        ProxyManager::validate_config(proxy)?;
+    }
+
+    let updated = config_manager.update(|config| {
+        if let Some(id) = ids.iter().find(|id| !config.webapps.iter().any(|w| &w.id == *id)) {
+            return Err(AppError::not_found(format!("小程序不存在: {}", id)));
+        }
+        let mut updated = Vec::new();
+        for webapp in config.webapps.iter_mut().filter(|w| ids.contains(&w.id)) {
+            webapp.use_proxy = use_proxy;
+            if let Some(proxy) = &proxy_override {
+                webapp.proxy_override = Some(proxy.clone()).filter(|proxy| !proxy.host.is_empty());
+            }
+            updated.push(webapp.clone());
+        }
+        Ok(updated)
+    })??;
+
+    log::info!("Updated proxy settings of {} webapps", updated.len());
+    Ok(updated)
+}
+
+/// 获取配置的存储方式
+#[tauri::command]
+pub async fn get_storage_backend(config_manager: State<'_, ConfigManager>) -> Result<StorageBackend, AppError> {
+    Ok(config_manager.backend())
+}
+
+/// 切换配置的存储方式（JSON 文件或 SQLite 数据库），当前配置会写入新的存储
+#[tauri::command]
+pub async fn set_storage_backend(app: AppHandle, backend: StorageBackend) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn_blocking(move || app_clone.state::<ConfigManager>().switch_backend(backend))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+}
+
+/// 设置小程序的默认缩放（百分比）并立即应用到打开的窗口，zoom_percent 为空时恢复 100%
+#[tauri::command]
+pub async fn set_webapp_zoom(app: AppHandle, webapp_id: String, zoom_percent: Option<u32>) -> Result<WebApp, AppError> {
+    policy::ensure_unlocked(&app)?;
+    zoom::set_zoom_percent(&app, &webapp_id, zoom_percent)
+}
+
+/// 放大、缩小或恢复小程序窗口的缩放（未指定小程序时为当前聚焦的小程序窗口），管理员锁定时不保存到配置
+#[tauri::command]
+pub async fn zoom_webapp(app: AppHandle, webapp_id: Option<String>, step: ZoomStep) -> Result<WebApp, AppError> {
+    let webapp_id = webapp_id
+        .or_else(|| focused_webapp_id(&app))
+        .ok_or_else(|| AppError::not_found("没有聚焦的小程序窗口"))?;
+    zoom::step_zoom(&app, &webapp_id, step)
+}
+
+/// 在任意打开的小程序窗口中运行脚本库中的脚本（"在...中运行脚本"），并记录到最近运行
+#[tauri::command]
+pub async fn run_script(app: AppHandle, script_id: String, webapp_id: String) -> Result<(), AppError> {
+    userscripts::run_in(&app, &script_id, &webapp_id)
+}
+
+/// 获取最近运行过的脚本和窗口组合，新的在前
+#[tauri::command]
+pub async fn get_recent_script_runs(config_manager: State<'_, ConfigManager>) -> Result<Vec<ScriptRun>, AppError> {
+    Ok(userscripts::recent_runs(&config_manager.read()))
+}
+
+/// 获取本次运行中小程序窗口的下载记录，新的在前
+#[tauri::command]
+pub async fn list_downloads(manager: State<'_, DownloadManager>) -> Result<Vec<DownloadItem>, AppError> {
+    Ok(manager.list())
+}
+
+/// 在文件管理器中打开小程序的下载目录（未指定小程序时打开全局下载目录），返回目录路径
+#[tauri::command]
+pub async fn open_download_folder(app: AppHandle, webapp_id: Option<String>) -> Result<String, AppError> {
+    Ok(downloads::open_folder(&app, webapp_id.as_deref())?)
+}
+
+/// 快速切换窗口：按名称和网址模糊搜索小程序
+#[tauri::command]
+pub async fn search_webapps(app: AppHandle, query: String) -> Result<Vec<SwitcherResult>, AppError> {
+    Ok(switcher::search(&app, &query))
+}
+
+/// 快速切换窗口：隐藏快速切换窗口并切换选中的小程序窗口
+#[tauri::command]
+pub async fn activate_switcher_result(app: AppHandle, id: String) -> Result<(), AppError> {
+    switcher::activate(&app, &id)
+}
+
+/// 为小程序生成系统启动项（出现在系统的应用列表和程序坞中），返回生成的文件路径
+#[tauri::command]
+pub async fn export_webapp_shortcut(app: AppHandle, id: String) -> Result<String, AppError> {
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn_blocking(move || launchers::export(&app_clone, &id))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+        .map_err(AppError::from)
+}
+
+/// 获取当前静默时段状态
+#[tauri::command]
+pub async fn get_quiet_hours_status(app: AppHandle) -> Result<QuietHoursStatus, AppError> {
+    Ok(quiet_hours::current_status(&app))
+}
+
+/// 临时解除静默时段 `minutes` 分钟（为空时取消临时解除）
+#[tauri::command]
+pub async fn set_quiet_hours_override(app: AppHandle, minutes: Option<u32>) -> Result<QuietHoursStatus, AppError> {
+    policy::ensure_unlocked(&app)?;
+    quiet_hours::set_override(&app, minutes)
+}
+
+/// 只读查询合并后的状态（配置、已打开的窗口、使用统计和角标），返回匹配的节点，语法见 `query` 模块
+#[tauri::command]
+pub async fn query_state(app: AppHandle, query: String) -> Result<Vec<serde_json::Value>, AppError> {
+    Ok(query::run(&app, &query)?)
+}
+
+/// 获取安全模式状态
+#[tauri::command]
+pub async fn get_safe_mode_status(app: AppHandle) -> Result<SafeModeStatus, AppError> {
+    Ok(safe_mode::status(&app))
+}
+
+/// 退出安全模式：注册快捷键、启动自动化定时任务并恢复会话
+#[tauri::command]
+pub async fn exit_safe_mode(app: AppHandle) -> Result<SafeModeStatus, AppError> {
+    safe_mode::exit(&app)
+}
+
+/// 切换启动器的图标主题（前端收到 `icons://theme-changed` 后重新获取内嵌图标）
+#[tauri::command]
+pub async fn set_icon_theme(
+    app: AppHandle,
+    config_manager: State<'_, ConfigManager>,
+    theme: IconTheme,
+) -> Result<(), AppError> {
+    policy::ensure_unlocked(&app)?;
+    config_manager.update(|config| config.icon_theme = theme)?;
+    log::info!("Icon theme set to {:?}", theme);
+    events::emit(&app, Channel::Config, "icons://theme-changed", serde_json::json!({ "theme": theme }));
+    Ok(())
+}
@@ -1,31 +1,128 @@
-use parking_lot::RwLock;
+use parking_lot::{Condvar, Mutex, RwLock};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
+use crate::config_store::{self, ConfigStore, StorageBackend};
+use crate::error::{AppError, AppResult};
+use crate::managed::{self, ManagedPolicy};
+use crate::metrics;
+use crate::migrate;
 use crate::models::AppConfig;
+use crate::onboarding;
+use crate::secrets;
+
+/// 退出前等待配置写入完成的最长时间
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 排队等待写入文件的配置
+struct PendingWrite {
+    version: u64,
+    config: AppConfig,
+}
+
+/// 后台写入的进度，用于 `flush` 等待
+#[derive(Default)]
+struct WriteProgress {
+    written: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl WriteProgress {
+    fn mark_written(&self, version: u64) {
+        let mut written = self.written.lock();
+        if version > *written {
+            *written = version;
+        }
+        self.changed.notify_all();
+    }
+}
+
+/// 最近一次与存储同步的内容（本程序写入或读取的内容），用于区分外部修改
+type Synced = Arc<Mutex<Option<String>>>;
+
+/// 当前使用的存储（切换存储方式时替换，后台写入任务每次写入时读取）
+type SharedStore = Arc<RwLock<Arc<dyn ConfigStore>>>;
+
+/// 合并外部修改的结果（随 `config://changed` 事件发送给前端）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalChange {
+    /// 文件和内存中都修改过且不一致的顶层设置（以文件中的内容为准）
+    pub conflicts: Vec<String>,
+    /// 内存中有文件里没有的修改，合并后已重新写入文件
+    pub merged: bool,
+}
 
 /// 配置管理器 - 提供线程安全的配置读写
 pub struct ConfigManager {
     /// 内存中的配置缓存
     config: RwLock<AppConfig>,
-    /// 配置文件路径
-    path: PathBuf,
+    /// 配置的存储（JSON 文件或 SQLite 数据库）
+    store: SharedStore,
+    /// 系统级策略（启动时读取），每次加载和修改后覆盖到配置上
+    managed: Option<ManagedPolicy>,
+    /// 写入队列：由后台任务按顺序写入存储，避免在命令处理中阻塞异步运行时
+    writer: UnboundedSender<PendingWrite>,
+    /// 最近一次排队写入的版本号
+    queued: AtomicU64,
+    progress: Arc<WriteProgress>,
+    synced: Synced,
 }
 
 impl ConfigManager {
-    /// 创建新的配置管理器
-    pub fn new(path: PathBuf) -> Self {
-        let config = if path.exists() {
-            std::fs::read_to_string(&path)
-                .ok()
-                .and_then(|content| serde_json::from_str(&content).ok())
-                .unwrap_or_default()
-        } else {
-            AppConfig::default()
-        };
+    /// 使用指定的存储创建配置管理器（应用数据目录中的存储见 `config_store::open`）
+    pub fn new(store: Box<dyn ConfigStore>) -> Self {
+        let store: Arc<dyn ConfigStore> = Arc::from(store);
+        let (mut config, content, needs_write) = load(store.as_ref());
+
+        let managed = managed::load();
+        if let Some(policy) = &managed {
+            policy.apply(&mut config);
+        }
+
+        let (writer, receiver) = mpsc::unbounded_channel();
+        let progress = Arc::new(WriteProgress::default());
+        let synced = Arc::new(Mutex::new(content));
+        let store = Arc::new(RwLock::new(store));
+        tauri::async_runtime::spawn(run_writer(store.clone(), receiver, progress.clone(), synced.clone()));
 
-        Self {
+        let manager = Self {
             config: RwLock::new(config),
-            path,
+            store,
+            managed,
+            writer,
+            queued: AtomicU64::new(0),
+            progress,
+            synced,
+        };
+        // 升级后的配置立即写回（升级前的文件已复制一份），明文保存的代理密码同时移到钥匙串
+        if needs_write {
+            if let Err(e) = manager.enqueue_write(&manager.config.read()) {
+                log::warn!("Failed to save migrated config: {}", e);
+            }
+        }
+        manager
+    }
+
+    /// 当前的存储方式
+    pub fn backend(&self) -> StorageBackend {
+        self.store.read().backend()
+    }
+
+    /// 系统级策略（没有策略文件时为 None）
+    pub fn managed(&self) -> Option<&ManagedPolicy> {
+        self.managed.as_ref()
+    }
+
+    fn apply_managed(&self, config: &mut AppConfig) {
+        if let Some(policy) = &self.managed {
+            policy.apply(config);
         }
     }
 
@@ -34,53 +131,543 @@ impl ConfigManager {
         self.config.read().clone()
     }
 
-    /// 更新配置（原子操作：修改内存 + 写入文件）
+    /// 更新配置（原子操作：修改内存 + 排队写入存储）
     /// 闭包可以返回任意类型 R，用于返回更新后的数据
-    pub fn update<F, R>(&self, f: F) -> Result<R, String>
+    pub fn update<F, R>(&self, f: F) -> AppResult<R>
     where
         F: FnOnce(&mut AppConfig) -> R,
     {
-        let (result, config_copy) = {
-            let mut config = self.config.write();
-            let result = f(&mut config);
-            (result, config.clone())
-        }; // 写锁在此释放
-        
-        // 在锁释放后写入文件，避免阻塞其他读取
-        self.write_to_file(&config_copy)?;
+        let mut config = self.config.write();
+        let result = f(&mut config);
+        self.apply_managed(&mut config);
+        // 持有写锁时排队，保证文件写入的顺序与内存中的修改顺序一致
+        self.enqueue_write(&config)?;
         Ok(result)
     }
 
     /// 替换整个配置
-    pub fn replace(&self, new_config: AppConfig) -> Result<(), String> {
+    pub fn replace(&self, mut new_config: AppConfig) -> AppResult<()> {
+        self.apply_managed(&mut new_config);
         let mut config = self.config.write();
         *config = new_config;
-        self.write_to_file(&config)
+        self.enqueue_write(&config)
     }
 
-    /// 写入配置到文件
-    fn write_to_file(&self, config: &AppConfig) -> Result<(), String> {
-        // 确保目录存在
-        if let Some(parent) = self.path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-        }
-
-        let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
-        std::fs::write(&self.path, content).map_err(|e| e.to_string())?;
+    /// 将配置交给后台任务写入存储（调用方需持有写锁）
+    fn enqueue_write(&self, config: &AppConfig) -> AppResult<()> {
+        let version = self.queued.fetch_add(1, Ordering::SeqCst) + 1;
+        self.writer
+            .send(PendingWrite {
+                version,
+                config: config.clone(),
+            })
+            .map_err(|_| AppError::Io("配置写入任务已停止".to_string()))
+    }
 
-        Ok(())
+    /// 等待已排队的配置写入存储（退出前调用，最多等待 FLUSH_TIMEOUT），超时返回 false
+    ///
+    /// 先合并存储中的外部修改，使被推迟的写入重新排队，否则会一直等到超时
+    pub fn flush(&self) -> bool {
+        match self.check_external() {
+            Ok(Some(change)) if !change.conflicts.is_empty() => {
+                let conflicts = change.conflicts.join(", ");
+                log::warn!("Merged config changed on disk before flushing, file wins for: {}", conflicts);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to merge config changed on disk before flushing: {}", e),
+        }
+        let target = self.queued.load(Ordering::SeqCst);
+        let mut written = self.progress.written.lock();
+        while *written < target {
+            if self
+                .progress
+                .changed
+                .wait_for(&mut written, FLUSH_TIMEOUT)
+                .timed_out()
+            {
+                log::warn!("Timed out waiting for config to be written");
+                return false;
+            }
+        }
+        true
     }
 
-    /// 重新从文件加载配置
-    pub fn reload(&self) -> Result<(), String> {
-        if self.path.exists() {
-            let content = std::fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
-            let new_config: AppConfig =
-                serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    /// 重新从存储加载配置
+    pub fn reload(&self) -> AppResult<()> {
+        let store = self.store.read().clone();
+        if let Some(content) = store.read().map_err(AppError::Io)? {
+            let mut new_config = migrate::parse(&content).map_err(AppError::InvalidInput)?.config;
+            secrets::resolve_proxy_passwords(&mut new_config);
+            self.apply_managed(&mut new_config);
             let mut config = self.config.write();
             *config = new_config;
+            *self.synced.lock() = Some(content);
         }
         Ok(())
     }
+
+    /// 检查配置文件是否被外部修改（手动编辑、云盘同步等），有修改时合并到内存中
+    ///
+    /// 以上次同步的内容为基准，按顶层设置（小程序按 ID）三方合并：只有一方修改的设置采用修改后的值，
+    /// 双方都修改且不一致时以文件为准并记为冲突；内存中有文件里没有的修改时重新写入文件。
+    /// 文件未变化或不存在时返回 None，文件内容无效时返回错误且保持内存中的配置
+    pub fn check_external(&self) -> AppResult<Option<ExternalChange>> {
+        let store = self.store.read().clone();
+        let content = match store.read().map_err(AppError::Io)? {
+            Some(content) => content,
+            None => return Ok(None),
+        };
+        let mut synced = self.synced.lock();
+        if synced.as_deref() == Some(content.as_str()) {
+            return Ok(None);
+        }
+
+        let mut theirs = migrate::parse(&content).map_err(AppError::InvalidInput)?.config;
+        secrets::resolve_proxy_passwords(&mut theirs);
+        self.apply_managed(&mut theirs);
+        let base = synced
+            .as_deref()
+            .and_then(|content| migrate::parse(content).ok())
+            .map(|parsed| {
+                let mut base = parsed.config;
+                secrets::resolve_proxy_passwords(&mut base);
+                self.apply_managed(&mut base);
+                base
+            });
+
+        let mut config = self.config.write();
+        let (merged, change) = merge(base.as_ref(), &config, &theirs)?;
+        *config = merged;
+        *synced = Some(content);
+        // 尚未写入（包括被推迟）的版本不包含文件中的修改，用合并后的配置重新排队覆盖它们
+        let unwritten = self.queued.load(Ordering::SeqCst) > *self.progress.written.lock();
+        if change.merged || unwritten {
+            self.enqueue_write(&config)?;
+        }
+        Ok(Some(change))
+    }
+
+    /// 切换存储方式：把当前配置写入新的存储并停用旧的存储（会阻塞）
+    pub fn switch_backend(&self, backend: StorageBackend) -> AppResult<()> {
+        let current = self.store.read().clone();
+        if current.backend() == backend {
+            return Ok(());
+        }
+        if !self.flush() {
+            return Err(AppError::Io("配置仍在写入中，请稍后再试".to_string()));
+        }
+        let dir = current.location().parent().map(PathBuf::from).unwrap_or_default();
+
+        // 持有写锁，切换完成前不会有新的修改排队
+        let config = self.config.write();
+        let store: Arc<dyn ConfigStore> = Arc::from(config_store::create(&dir, backend).map_err(AppError::Io)?);
+        let content = serialize(&config).map_err(AppError::Io)?;
+        store.write(&content).map_err(AppError::Io)?;
+        if let Err(e) = current.retire() {
+            log::warn!("Failed to retire config storage {:?}: {}", current.location(), e);
+        }
+        *self.store.write() = store;
+        *self.synced.lock() = Some(content);
+        log::info!("Switched config storage from {:?} to {:?}", current.backend(), backend);
+        Ok(())
+    }
+}
+
+/// 加载配置，返回配置、存储中的内容和是否需要写回（升级了格式或有明文代理密码）
+///
+/// 内容无法解析时不使用默认配置覆盖，而是隔离（如 `config.json.corrupt-时间`）后以默认配置启动；
+/// 升级前和来自更新版本的配置先保留一份（如 `config.json.v版本`），后者避免保存时丢失本版本不认识的设置
+fn load(store: &dyn ConfigStore) -> (AppConfig, Option<String>, bool) {
+    let location = store.location();
+    let content = match store.read() {
+        Ok(Some(content)) => content,
+        // 首次启动，使用带示例小程序的配置
+        Ok(None) => return (onboarding::first_run_config(), None, false),
+        Err(e) => {
+            log::error!("Failed to read config {:?}, using defaults: {}", location, e);
+            return (AppConfig::default(), None, false);
+        }
+    };
+
+    match migrate::parse(&content) {
+        Ok(parsed) => {
+            if parsed.written_by_newer() {
+                log::warn!(
+                    "Config {:?} was written by a newer version (format {}), keeping a copy",
+                    location,
+                    parsed.from_version
+                );
+            }
+            if parsed.written_by_newer() || parsed.upgraded() {
+                store.keep_version(parsed.from_version);
+            }
+            let needs_write = parsed.upgraded() || secrets::has_plaintext_passwords(&parsed.config);
+            let mut config = parsed.config;
+            secrets::resolve_proxy_passwords(&mut config);
+            (config, Some(content), needs_write)
+        }
+        Err(e) => {
+            match store.quarantine() {
+                Ok(target) => log::error!("Config {:?} is invalid ({}), moved it to {}", location, e, target),
+                Err(move_error) => {
+                    log::error!("Config {:?} is invalid ({}) and could not be moved aside: {}", location, e, move_error)
+                }
+            }
+            (AppConfig::default(), None, false)
+        }
+    }
+}
+
+fn to_object(config: &AppConfig) -> serde_json::Map<String, Value> {
+    match serde_json::to_value(config) {
+        Ok(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    }
+}
+
+/// 按 `id` 三方合并小程序列表：只有一方添加、删除或修改的小程序采用该方的结果，
+/// 双方都修改且不一致时以文件为准并记为冲突（如 `webapps/<id>`）。顺序以文件为准，本程序新增的排在最后
+fn merge_webapps(base: Option<&Value>, ours: &Value, theirs: &Value, change: &mut ExternalChange) -> Value {
+    fn by_id(list: Option<&Value>) -> HashMap<&str, &Value> {
+        list.and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|item| Some((item.get("id")?.as_str()?, item)))
+            .collect()
+    }
+    let (base_items, ours_items) = (by_id(base), by_id(Some(ours)));
+    let theirs_list = theirs.as_array().cloned().unwrap_or_default();
+    let mut merged = Vec::new();
+    let mut seen = HashSet::new();
+
+    for item in theirs_list {
+        let Some(id) = item.get("id").and_then(Value::as_str).map(str::to_string) else {
+            merged.push(item);
+            continue;
+        };
+        seen.insert(id.clone());
+        let base_item = base_items.get(id.as_str()).copied();
+        match ours_items.get(id.as_str()).copied() {
+            Some(ours_item) if base_item == Some(ours_item) || ours_item == &item => merged.push(item),
+            Some(ours_item) if base_item == Some(&item) => {
+                merged.push(ours_item.clone());
+                change.merged = true;
+            }
+            // 文件中新增了本程序也新增的同 ID 小程序，或双方都修改了
+            Some(_) => {
+                change.conflicts.push(format!("webapps/{}", id));
+                merged.push(item);
+            }
+            // 本程序删除了文件中未修改的小程序
+            None if base_item == Some(&item) => change.merged = true,
+            None if base_item.is_some() => {
+                change.conflicts.push(format!("webapps/{}", id));
+                merged.push(item);
+            }
+            None => merged.push(item),
+        }
+    }
+
+    for item in ours.as_array().into_iter().flatten() {
+        let Some(id) = item.get("id").and_then(Value::as_str) else {
+            continue;
+        };
+        if seen.contains(id) {
+            continue;
+        }
+        match base_items.get(id).copied() {
+            // 本程序新增的小程序
+            None => {
+                merged.push(item.clone());
+                change.merged = true;
+            }
+            // 文件中删除了本程序修改过的小程序
+            Some(base_item) if base_item != item => change.conflicts.push(format!("webapps/{}", id)),
+            Some(_) => {}
+        }
+    }
+    Value::Array(merged)
+}
+
+/// 三方合并内存中的配置（ours）和文件中的配置（theirs），没有基准时直接采用文件中的配置
+///
+/// 顶层设置整体合并，小程序列表按 `id` 逐个合并（见 `merge_webapps`）
+fn merge(base: Option<&AppConfig>, ours: &AppConfig, theirs: &AppConfig) -> AppResult<(AppConfig, ExternalChange)> {
+    let mut change = ExternalChange::default();
+    let base = match base {
+        Some(base) => to_object(base),
+        None => return Ok((theirs.clone(), change)),
+    };
+    let mut merged = to_object(theirs);
+
+    for (key, ours_value) in to_object(ours) {
+        let base_value = base.get(&key);
+        if base_value == Some(&ours_value) {
+            continue;
+        }
+        let theirs_value = merged.get(&key);
+        if theirs_value == Some(&ours_value) {
+            continue;
+        }
+        if key == "webapps" {
+            let theirs_value = theirs_value.cloned().unwrap_or(Value::Null);
+            let webapps = merge_webapps(base_value, &ours_value, &theirs_value, &mut change);
+            merged.insert(key, webapps);
+        } else if theirs_value == base_value {
+            merged.insert(key, ours_value);
+            change.merged = true;
+        } else {
+            change.conflicts.push(key);
+        }
+    }
+
+    let config = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| AppError::invalid_input(format!("合并后的配置无效: {}", e)))?;
+    Ok((config, change))
+}
+
+/// 序列化要保存的配置：代理密码保存到系统钥匙串，内容中只保留引用（会阻塞）
+fn serialize(config: &AppConfig) -> Result<String, String> {
+    let mut config = config.clone();
+    secrets::store_proxy_passwords(&mut config);
+    serde_json::to_string_pretty(&config).map_err(|e| e.to_string())
+}
+
+/// 存储中是否有尚未合并的外部修改（synced 为最近一次同步的内容）
+fn changed_externally(store: &dyn ConfigStore, synced: Option<&str>) -> bool {
+    match store.read() {
+        Ok(Some(content)) => synced.is_some_and(|synced| synced != content),
+        _ => false,
+    }
+}
+
+/// 配置写入任务：按排队顺序写入，积压时只写最新的一份
+///
+/// 存储中有尚未合并的外部修改时推迟这次写入（不计入已写入的进度），由 `check_external` 合并后重新写入，
+/// 避免覆盖外部修改。检查和写入时持有 `synced` 锁，与 `check_external` 的合并互斥
+async fn run_writer(
+    store: SharedStore,
+    mut receiver: UnboundedReceiver<PendingWrite>,
+    progress: Arc<WriteProgress>,
+    synced: Synced,
+) {
+    while let Some(mut pending) = receiver.recv().await {
+        while let Ok(newer) = receiver.try_recv() {
+            pending = newer;
+        }
+        let version = pending.version;
+        let current = store.read().clone();
+        let synced = synced.clone();
+        let write = tokio::task::spawn_blocking(move || {
+            let location = current.location();
+            let mut synced = synced.lock();
+            if changed_externally(current.as_ref(), synced.as_deref()) {
+                log::warn!("Config {:?} changed on disk, postponing write until it is merged", location);
+                return false;
+            }
+            match serialize(&pending.config).and_then(|content| current.write(&content).map(|_| content)) {
+                Ok(content) => {
+                    *synced = Some(content);
+                    metrics::config_written();
+                }
+                Err(e) => log::error!("Failed to write config to {:?}: {}", location, e),
+            }
+            true
+        });
+        match write.await {
+            Ok(true) => progress.mark_written(version),
+            Ok(false) => {}
+            Err(e) => {
+                log::error!("Config write task failed: {}", e);
+                progress.mark_written(version);
+            }
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WebApp;
+    use std::path::Path;
+
+    fn temp_config_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("webapp-hub-config-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("config.json")
+    }
+
+    fn json_store(path: &Path) -> Box<dyn ConfigStore> {
+        Box::new(config_store::JsonFileStore::new(path.to_path_buf()))
+    }
+
+    fn saved(path: &Path) -> AppConfig {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn test_concurrent_updates_are_all_persisted() {
+        let path = temp_config_path("stress");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::to_string(&AppConfig::default()).unwrap()).unwrap();
+        let manager = Arc::new(ConfigManager::new(json_store(&path)));
+        manager.update(|c| c.session_restore_delay_ms = 0).unwrap();
+
+        let tasks: Vec<_> = (0..32)
+            .map(|_| {
+                let manager = manager.clone();
+                tokio::spawn(async move {
+                    for _ in 0..50 {
+                        manager.update(|c| c.session_restore_delay_ms += 1).unwrap();
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert!(manager.flush());
+        assert_eq!(manager.read().session_restore_delay_ms, 32 * 50);
+        assert_eq!(saved(&path).session_restore_delay_ms, 32 * 50);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_last_write_wins_on_disk() {
+        let path = temp_config_path("ordering");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::to_string(&AppConfig::default()).unwrap()).unwrap();
+        let manager = ConfigManager::new(json_store(&path));
+
+        for delay in 0..200u64 {
+            manager.update(|c| c.session_restore_delay_ms = delay).unwrap();
+        }
+        let mut replacement = manager.read();
+        replacement.max_active_windows = 7;
+        manager.replace(replacement).unwrap();
+
+        assert!(manager.flush());
+        let on_disk = saved(&path);
+        assert_eq!(on_disk.session_restore_delay_ms, 199);
+        assert_eq!(on_disk.max_active_windows, 7);
+        assert!(!path.with_extension("json.tmp").exists());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config_is_quarantined() {
+        let path = temp_config_path("quarantine");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, r#"{ "webapps": "not a list" }"#).unwrap();
+
+        let manager = ConfigManager::new(json_store(&path));
+        assert!(manager.read().webapps.is_empty());
+        assert!(!path.exists());
+        let moved: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("config.json.corrupt-"))
+            .collect();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(std::fs::read_to_string(moved[0].path()).unwrap(), r#"{ "webapps": "not a list" }"#);
+
+        // 没有版本号的旧配置升级后写回，并保留一份升级前的文件
+        std::fs::write(&path, r#"{ "maxActiveWindows": 4 }"#).unwrap();
+        let manager = ConfigManager::new(json_store(&path));
+        assert!(manager.flush());
+        let on_disk = saved(&path);
+        assert_eq!((on_disk.version, on_disk.max_active_windows), (migrate::CONFIG_VERSION, 4));
+        assert!(path.with_extension("json.v0").exists());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_external_change_is_merged() {
+        let path = temp_config_path("external");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::to_string(&AppConfig::default()).unwrap()).unwrap();
+        let manager = ConfigManager::new(json_store(&path));
+
+        // 本程序自己的写入不算外部修改
+        manager.update(|c| c.max_active_windows = 3).unwrap();
+        assert!(manager.flush());
+        assert_eq!(manager.check_external(), Ok(None));
+
+        // 外部修改后，尚未合并前的写入被推迟，合并后两边的修改都保留
+        let mut external = saved(&path);
+        external.session_restore_delay_ms = 42;
+        std::fs::write(&path, serde_json::to_string_pretty(&external).unwrap()).unwrap();
+        manager.update(|c| c.max_active_windows = 9).unwrap();
+        let change = manager.check_external().unwrap().unwrap();
+        assert!(change.merged && change.conflicts.is_empty());
+        assert!(manager.flush());
+        let on_disk = saved(&path);
+        assert_eq!((on_disk.max_active_windows, on_disk.session_restore_delay_ms), (9, 42));
+        assert_eq!(manager.read().session_restore_delay_ms, 42);
+
+        // flush 先合并外部修改，被推迟的写入不会一直等到超时
+        let mut external = saved(&path);
+        external.session_restore_delay_ms = 7;
+        std::fs::write(&path, serde_json::to_string_pretty(&external).unwrap()).unwrap();
+        manager.update(|c| c.max_active_windows = 5).unwrap();
+        assert!(manager.flush());
+        let on_disk = saved(&path);
+        assert_eq!((on_disk.max_active_windows, on_disk.session_restore_delay_ms), (5, 7));
+        assert_eq!(manager.check_external(), Ok(None));
+
+        // 内容无效时保持内存中的配置
+        std::fs::write(&path, "{").unwrap();
+        assert!(manager.check_external().is_err());
+        assert_eq!(manager.read().max_active_windows, 9);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_merge_conflict_prefers_file() {
+        let base = AppConfig::default();
+        let mut ours = base.clone();
+        ours.max_active_windows = 2;
+        let mut theirs = base.clone();
+        theirs.max_active_windows = 4;
+
+        let (merged, change) = merge(Some(&base), &ours, &theirs).unwrap();
+        assert_eq!(merged.max_active_windows, 4);
+        assert_eq!(change.conflicts, vec!["maxActiveWindows".to_string()]);
+        assert!(!change.merged);
+    }
+
+    #[test]
+    fn test_merge_webapps_by_id() {
+        let webapp = |id: &str, name: &str| {
+            let mut webapp = WebApp::new(name.to_string(), format!("https://{}.example.com", id));
+            webapp.id = id.to_string();
+            webapp
+        };
+        let base = AppConfig {
+            webapps: vec![webapp("a", "A"), webapp("b", "B"), webapp("c", "C")],
+            ..AppConfig::default()
+        };
+        // 本程序修改了 a、删除了 c、新增了 d；文件中修改了 b、新增了 e
+        let mut ours = base.clone();
+        ours.webapps[0].name = "A2".to_string();
+        ours.webapps.remove(2);
+        ours.webapps.push(webapp("d", "D"));
+        let mut theirs = base.clone();
+        theirs.webapps[1].name = "B2".to_string();
+        theirs.webapps.push(webapp("e", "E"));
+
+        let (merged, change) = merge(Some(&base), &ours, &theirs).unwrap();
+        let names: Vec<_> = merged.webapps.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["A2", "B2", "E", "D"]);
+        assert!(change.merged && change.conflicts.is_empty());
+
+        // 双方修改同一个小程序时以文件为准
+        let mut theirs = base.clone();
+        theirs.webapps[0].name = "A3".to_string();
+        let (merged, change) = merge(Some(&base), &ours, &theirs).unwrap();
+        assert_eq!(merged.webapps[0].name, "A3");
+        assert_eq!(change.conflicts, vec!["webapps/a".to_string()]);
+    }
+}
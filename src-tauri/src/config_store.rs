@@ -0,0 +1,360 @@
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// JSON 文件存储的文件名
+pub const CONFIG_FILE: &str = "config.json";
+
+/// SQLite 存储的数据库文件名
+pub const DATABASE_FILE: &str = "config.db";
+
+/// 保留的滚动备份数（config.json.1 为最新）
+const BACKUP_COUNT: usize = 5;
+
+/// 两次滚动备份之间的最短间隔，避免频繁修改很快把旧备份挤掉
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// SQLite 存储保留的历史版本数
+const HISTORY_LIMIT: i64 = 200;
+
+/// 配置的存储方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// 单个 JSON 文件（config.json），便于手动编辑和云盘同步
+    Json,
+    /// SQLite 数据库（config.db），保存每次修改后的历史版本
+    Sqlite,
+}
+
+/// 配置的持久化方式：保存和读取序列化后的配置内容（JSON 文本）
+///
+/// 格式升级、外部修改的合并等由 `ConfigManager` 处理；方法会阻塞，异步代码中通过 `spawn_blocking` 调用
+pub trait ConfigStore: Send + Sync {
+    fn backend(&self) -> StorageBackend;
+
+    /// 存储的位置（用于日志）
+    fn location(&self) -> &Path;
+
+    /// 读取最新的配置内容，从未保存过时返回 None
+    fn read(&self) -> Result<Option<String>, String>;
+
+    /// 保存配置内容
+    fn write(&self, content: &str) -> Result<(), String>;
+
+    /// 把无法解析的内容移到一旁（之后读取时返回 None），返回移到的位置
+    fn quarantine(&self) -> Result<String, String>;
+
+    /// 升级格式前保留一份指定版本的内容
+    fn keep_version(&self, version: u32);
+
+    /// 切换到其他存储方式后把数据移到一旁，下次启动时不再选中此存储
+    fn retire(&self) -> Result<(), String>;
+}
+
+/// 在文件名后追加后缀
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%Y%m%d-%H%M%S").to_string()
+}
+
+/// JSON 文件存储（默认）
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl ConfigStore for JsonFileStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Json
+    }
+
+    fn location(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<Option<String>, String> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// 先写临时文件再替换，避免写到一半时退出留下损坏的配置；覆盖前复制一份滚动备份
+    fn write(&self, content: &str) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let temp = self.path.with_extension("json.tmp");
+        std::fs::write(&temp, content).map_err(|e| e.to_string())?;
+        if let Err(e) = rotate_backups(&self.path, BACKUP_INTERVAL) {
+            log::warn!("Failed to rotate config backups: {}", e);
+        }
+        std::fs::rename(&temp, &self.path).map_err(|e| e.to_string())
+    }
+
+    /// 改名为 `config.json.corrupt-时间`
+    fn quarantine(&self) -> Result<String, String> {
+        let target = with_suffix(&self.path, &format!(".corrupt-{}", timestamp()));
+        std::fs::rename(&self.path, &target).map_err(|e| e.to_string())?;
+        Ok(target.display().to_string())
+    }
+
+    /// 复制为 `config.json.v版本`（已存在时不覆盖）
+    fn keep_version(&self, version: u32) {
+        let target = with_suffix(&self.path, &format!(".v{}", version));
+        if target.exists() {
+            return;
+        }
+        match std::fs::copy(&self.path, &target) {
+            Ok(_) => log::info!("Kept a copy of config format {} in {:?}", version, target),
+            Err(e) => log::warn!("Failed to keep a copy of config format {}: {}", version, e),
+        }
+    }
+
+    fn retire(&self) -> Result<(), String> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        std::fs::rename(&self.path, with_suffix(&self.path, ".migrated")).map_err(|e| e.to_string())
+    }
+}
+
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    with_suffix(path, &format!(".{}", index))
+}
+
+/// 覆盖前把当前配置文件复制到滚动备份（距上次备份不足 min_interval 时跳过）
+fn rotate_backups(path: &Path, min_interval: Duration) -> Result<(), String> {
+    if std::fs::metadata(path).is_err() {
+        return Ok(());
+    }
+    let latest = backup_path(path, 1);
+    if let Ok(modified) = std::fs::metadata(&latest).and_then(|m| m.modified()) {
+        if modified.elapsed().is_ok_and(|age| age < min_interval) {
+            return Ok(());
+        }
+    }
+
+    for index in (1..BACKUP_COUNT).rev() {
+        let from = backup_path(path, index);
+        if from.exists() {
+            std::fs::rename(&from, backup_path(path, index + 1)).map_err(|e| e.to_string())?;
+        }
+    }
+    std::fs::copy(path, &latest).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// SQLite 存储：每次保存新增一个版本（保留最近 `HISTORY_LIMIT` 个），读取时使用最新的版本
+pub struct SqliteStore {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let connection = Connection::open(&path).map_err(|e| format!("无法打开配置数据库: {}", e))?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS config_revisions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    saved_at INTEGER NOT NULL,
+                    content TEXT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS quarantined_revisions (
+                    id INTEGER PRIMARY KEY,
+                    saved_at INTEGER NOT NULL,
+                    content TEXT NOT NULL
+                );",
+            )
+            .map_err(|e| format!("无法初始化配置数据库: {}", e))?;
+        Ok(Self {
+            path,
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl ConfigStore for SqliteStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Sqlite
+    }
+
+    fn location(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<Option<String>, String> {
+        self.connection
+            .lock()
+            .query_row("SELECT content FROM config_revisions ORDER BY id DESC LIMIT 1", [], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    fn write(&self, content: &str) -> Result<(), String> {
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        let mut connection = self.connection.lock();
+        let transaction = connection.transaction().map_err(|e| e.to_string())?;
+        transaction
+            .execute(
+                "INSERT INTO config_revisions (saved_at, content) VALUES (?1, ?2)",
+                params![saved_at, content],
+            )
+            .map_err(|e| e.to_string())?;
+        transaction
+            .execute(
+                "DELETE FROM config_revisions WHERE id <= (SELECT MAX(id) FROM config_revisions) - ?1",
+                params![HISTORY_LIMIT],
+            )
+            .map_err(|e| e.to_string())?;
+        transaction.commit().map_err(|e| e.to_string())
+    }
+
+    /// 最新的版本移到 `quarantined_revisions` 表，之前的版本仍保留在历史中
+    fn quarantine(&self) -> Result<String, String> {
+        let mut connection = self.connection.lock();
+        let transaction = connection.transaction().map_err(|e| e.to_string())?;
+        let id: Option<i64> = transaction
+            .query_row("SELECT MAX(id) FROM config_revisions", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        let id = id.ok_or("配置数据库中没有配置")?;
+        transaction
+            .execute(
+                "INSERT OR REPLACE INTO quarantined_revisions SELECT id, saved_at, content FROM config_revisions WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| e.to_string())?;
+        transaction
+            .execute("DELETE FROM config_revisions WHERE id = ?1", params![id])
+            .map_err(|e| e.to_string())?;
+        transaction.commit().map_err(|e| e.to_string())?;
+        Ok(format!("{} (quarantined revision {})", self.path.display(), id))
+    }
+
+    /// 旧版本的内容已保留在历史中
+    fn keep_version(&self, version: u32) {
+        log::info!("Config format {} is kept in the history of {:?}", version, self.path);
+    }
+
+    fn retire(&self) -> Result<(), String> {
+        // 连接关闭前不能改名（Windows），改为导出一份副本后清空数据库
+        let target = with_suffix(&self.path, &format!(".migrated-{}", timestamp()));
+        let connection = self.connection.lock();
+        connection
+            .execute("VACUUM INTO ?1", params![target.to_string_lossy()])
+            .map_err(|e| e.to_string())?;
+        connection
+            .execute_batch("DELETE FROM config_revisions; DELETE FROM quarantined_revisions;")
+            .map_err(|e| e.to_string())?;
+        drop(connection);
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            log::debug!("Config database {:?} is still in use, leaving it empty: {}", self.path, e);
+        }
+        Ok(())
+    }
+}
+
+/// 打开应用数据目录中的配置存储：只有配置数据库、没有 config.json 时使用 SQLite，否则使用 JSON 文件
+///
+/// 切换存储方式时旧的 config.json 会被改名，切换回 JSON 文件时会重新写入 config.json
+pub fn open(dir: &Path) -> Box<dyn ConfigStore> {
+    let database = dir.join(DATABASE_FILE);
+    if database.exists() && !dir.join(CONFIG_FILE).exists() {
+        match SqliteStore::open(database) {
+            Ok(store) => return Box::new(store),
+            Err(e) => log::error!("Failed to open config database, using {}: {}", CONFIG_FILE, e),
+        }
+    }
+    Box::new(JsonFileStore::new(dir.join(CONFIG_FILE)))
+}
+
+/// 在应用数据目录中创建指定方式的存储（用于切换存储方式）
+pub fn create(dir: &Path, backend: StorageBackend) -> Result<Box<dyn ConfigStore>, String> {
+    Ok(match backend {
+        StorageBackend::Json => Box::new(JsonFileStore::new(dir.join(CONFIG_FILE))),
+        StorageBackend::Sqlite => Box::new(SqliteStore::open(dir.join(DATABASE_FILE))?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("webapp-hub-store-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_rotate_backups() {
+        let path = temp_dir("backups").join(CONFIG_FILE);
+        for i in 0..BACKUP_COUNT + 2 {
+            std::fs::write(&path, i.to_string()).unwrap();
+            rotate_backups(&path, Duration::ZERO).unwrap();
+        }
+
+        assert_eq!(std::fs::read_to_string(backup_path(&path, 1)).unwrap(), (BACKUP_COUNT + 1).to_string());
+        assert_eq!(std::fs::read_to_string(backup_path(&path, BACKUP_COUNT)).unwrap(), "2");
+        assert!(!backup_path(&path, BACKUP_COUNT + 1).exists());
+
+        // 间隔内再次写入不会产生新的备份
+        std::fs::write(&path, "latest").unwrap();
+        rotate_backups(&path, BACKUP_INTERVAL).unwrap();
+        assert_eq!(std::fs::read_to_string(backup_path(&path, 1)).unwrap(), (BACKUP_COUNT + 1).to_string());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_sqlite_store_keeps_history() {
+        let dir = temp_dir("sqlite");
+        assert_eq!(open(&dir).backend(), StorageBackend::Json);
+
+        let store = SqliteStore::open(dir.join(DATABASE_FILE)).unwrap();
+        assert_eq!(store.read(), Ok(None));
+        store.write("{\"version\":1}").unwrap();
+        store.write("{ broken").unwrap();
+        assert_eq!(store.read().unwrap().as_deref(), Some("{ broken"));
+
+        // 隔离最新的版本后回到上一个版本
+        store.quarantine().unwrap();
+        assert_eq!(store.read().unwrap().as_deref(), Some("{\"version\":1}"));
+        drop(store);
+        assert_eq!(open(&dir).backend(), StorageBackend::Sqlite);
+
+        let store = SqliteStore::open(dir.join(DATABASE_FILE)).unwrap();
+        for i in 0..HISTORY_LIMIT + 5 {
+            store.write(&i.to_string()).unwrap();
+        }
+        let count: i64 = store
+            .connection
+            .lock()
+            .query_row("SELECT COUNT(*) FROM config_revisions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, HISTORY_LIMIT);
+        assert_eq!(store.read().unwrap(), Some((HISTORY_LIMIT + 4).to_string()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
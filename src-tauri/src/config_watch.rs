@@ -0,0 +1,52 @@
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::commands;
+use crate::config::ConfigManager;
+use crate::events::{self, Channel};
+
+/// 检查配置文件的间隔
+///
+/// 使用定期比较文件内容而不是文件系统通知：云盘同步工具通常用新文件替换配置文件，
+/// 监听原文件会失效，而比较内容也能直接忽略本程序自己的写入
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 合并一次外部修改，并把新配置应用到代理、窗口和快捷键等
+fn check(app: &AppHandle, last_error: &mut Option<String>) {
+    let config_manager = app.state::<ConfigManager>();
+    match config_manager.check_external() {
+        Ok(Some(change)) => {
+            *last_error = None;
+            if change.conflicts.is_empty() {
+                log::info!("Reloaded config changed on disk");
+            } else {
+                log::warn!("Reloaded config changed on disk, file wins for: {}", change.conflicts.join(", "));
+            }
+            if let Err(e) = commands::apply_config(app, &config_manager.read()) {
+                log::warn!("Failed to apply config changed on disk: {}", e);
+            }
+            events::emit(app, Channel::Config, "config://changed", change);
+        }
+        Ok(None) => *last_error = None,
+        Err(e) => {
+            // 文件可能正在同步，只在错误变化时记录一次，等下次检查再试
+            let message = e.to_string();
+            if last_error.as_deref() != Some(message.as_str()) {
+                log::warn!("Ignoring invalid config file change: {}", message);
+                *last_error = Some(message);
+            }
+        }
+    }
+}
+
+/// 启动配置文件监视：外部修改后自动重新加载并发送 `config://changed` 事件
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        let mut last_error = None;
+        loop {
+            interval.tick().await;
+            check(&app, &mut last_error);
+        }
+    });
+}
@@ -0,0 +1,350 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::io::Cursor;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::metrics;
+use crate::models::ControlApiConfig;
+use crate::query;
+use crate::scripting;
+use crate::window::{window_label, WindowManager};
+
+/// 控制 API 协议版本，动作 ID 格式变化时递增
+const API_VERSION: u32 = 1;
+
+/// 可被外部设备（Stream Deck、MIDI 控制器等）触发的动作描述
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionDescriptor {
+    /// 稳定的动作 ID，格式为 `<kind>:<target_id>`
+    pub id: String,
+    /// 动作类型: open / toggle / close / run-script
+    pub kind: &'static str,
+    /// 显示名称
+    pub title: String,
+    /// 图标URL或base64
+    pub icon: Option<String>,
+    /// 当前状态，用于按键上的视觉反馈
+    pub state: ActionState,
+}
+
+/// 动作目标的当前状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionState {
+    /// 窗口是否已创建
+    pub open: bool,
+    /// 窗口是否可见
+    pub visible: bool,
+}
+
+/// 发现/清单接口的返回内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub api_version: u32,
+    pub actions: Vec<ActionDescriptor>,
+}
+
+/// 收集当前可用的全部动作及其状态
+pub fn build_manifest(app: &AppHandle) -> Manifest {
+    let config = app.state::<ConfigManager>().read();
+    let mut actions = Vec::new();
+
+    for webapp in &config.webapps {
+        let state = match app.get_webview_window(&window_label(&webapp.id)) {
+            Some(window) => ActionState {
+                open: true,
+                visible: window.is_visible().unwrap_or(false),
+            },
+            None => ActionState::default(),
+        };
+
+        for kind in ["open", "toggle", "close"] {
+            actions.push(ActionDescriptor {
+                id: format!("{}:{}", kind, webapp.id),
+                kind,
+                title: webapp.name.clone(),
+                icon: webapp.icon.clone(),
+                state: state.clone(),
+            });
+        }
+    }
+
+    for automation in &config.automations {
+        actions.push(ActionDescriptor {
+            id: format!("run-script:{}", automation.id),
+            kind: "run-script",
+            title: automation.name.clone(),
+            icon: None,
+            state: ActionState::default(),
+        });
+    }
+
+    Manifest {
+        name: "WebApp Hub",
+        version: env!("CARGO_PKG_VERSION"),
+        api_version: API_VERSION,
+        actions,
+    }
+}
+
+/// 执行动作 ID 对应的操作
+pub fn execute_action(app: &AppHandle, action_id: &str) -> AppResult<()> {
+    let (kind, target) = action_id
+        .split_once(':')
+        .ok_or_else(|| AppError::invalid_input(format!("无效的动作 ID: {}", action_id)))?;
+    let config = app.state::<ConfigManager>().read();
+
+    match kind {
+        "open" | "toggle" | "close" => {
+            let webapp = config
+                .webapps
+                .iter()
+                .find(|w| w.id == target)
+                .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+            let window_manager = app.state::<WindowManager>();
+            let proxy_url = config.proxy_url_for(webapp);
+
+            match kind {
+                "open" => window_manager.open_webapp(app, webapp, proxy_url),
+                "toggle" => window_manager
+                    .toggle_webapp(app, webapp, proxy_url)
+                    .map(|_| ()),
+                _ => window_manager.request_close(app, &webapp.id),
+            }
+        }
+        "run-script" => {
+            let automation = config
+                .automations
+                .iter()
+                .find(|a| a.id == target)
+                .ok_or_else(|| AppError::not_found("自动化脚本不存在"))?;
+            let name = automation.name.clone();
+            let source = automation.source.clone();
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = scripting::run_script(app, source).await {
+                    log::error!("Automation {} failed: {}", name, e);
+                }
+            });
+            Ok(())
+        }
+        _ => Err(AppError::invalid_input(format!("未知的动作类型: {}", kind))),
+    }
+}
+
+/// 本地控制 API 服务状态
+pub struct ControlApi {
+    /// 当前运行中的服务及其配置
+    running: Mutex<Option<(ControlApiConfig, Arc<Server>)>>,
+}
+
+impl ControlApi {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+
+    /// 按配置启动、重启或停止服务（配置未变化时不做任何事）
+    pub fn apply(&self, app: &AppHandle, config: &ControlApiConfig) {
+        let config = &ensure_token(app, config);
+        let mut running = self.running.lock();
+
+        if let Some((current, _)) = running.as_ref() {
+            if current == config {
+                return;
+            }
+        }
+
+        if let Some((_, server)) = running.take() {
+            server.unblock();
+            log::info!("Stopped control API");
+        }
+
+        if !config.enabled {
+            return;
+        }
+
+        match Server::http(("127.0.0.1", config.port)) {
+            Ok(server) => {
+                let server = Arc::new(server);
+                let server_clone = server.clone();
+                let app_handle = app.clone();
+                let config_clone = config.clone();
+                std::thread::spawn(move || serve(app_handle, server_clone, config_clone));
+
+                *running = Some((config.clone(), server));
+                log::info!("Control API listening on 127.0.0.1:{}", config.port);
+            }
+            Err(e) => {
+                log::error!("Failed to start control API on port {}: {}", config.port, e);
+            }
+        }
+    }
+}
+
+impl Default for ControlApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 启用时没有令牌则生成随机令牌并保存到配置（不允许无令牌访问）
+fn ensure_token(app: &AppHandle, config: &ControlApiConfig) -> ControlApiConfig {
+    let mut config = config.clone();
+    if !config.enabled || config.token.as_deref().is_some_and(|t| !t.is_empty()) {
+        return config;
+    }
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    config.token = Some(token.clone());
+    if let Some(manager) = app.try_state::<ConfigManager>() {
+        if let Err(e) = manager.update(|c| c.control_api.token = Some(token)) {
+            log::warn!("Failed to save generated control API token: {}", e);
+        }
+    }
+    log::info!("Generated control API token");
+    config
+}
+
+fn serve(app: AppHandle, server: Arc<Server>, config: ControlApiConfig) {
+    for request in server.incoming_requests() {
+        let response = handle_request(&app, &request, &config);
+        if let Err(e) = request.respond(response) {
+            log::debug!("Failed to respond to control API request: {}", e);
+        }
+    }
+}
+
+fn handle_request(
+    app: &AppHandle,
+    request: &Request,
+    config: &ControlApiConfig,
+) -> Response<Cursor<Vec<u8>>> {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv(name))
+            .map(|h| h.value.as_str())
+    };
+    if let Err(status) = authorize(header("Host"), header("Origin"), header("Authorization"), config) {
+        let error = if status == 403 { "forbidden" } else { "unauthorized" };
+        return json_response(status, &serde_json::json!({ "error": error }));
+    }
+
+    let path = request.url().split('?').next().unwrap_or_default();
+
+    match (request.method(), path) {
+        (Method::Get, "/manifest") => json_response(200, &build_manifest(app)),
+        (Method::Get, "/metrics") if config.metrics => text_response(200, metrics::render(app)),
+        (Method::Get, "/state") => {
+            let query = request
+                .url()
+                .split_once('?')
+                .and_then(|(_, params)| {
+                    url::form_urlencoded::parse(params.as_bytes())
+                        .find(|(key, _)| key == "q")
+                        .map(|(_, value)| value.into_owned())
+                })
+                .unwrap_or_else(|| "$".to_string());
+            match query::run(app, &query) {
+                Ok(result) => json_response(200, &result),
+                Err(e) => json_response(400, &serde_json::json!({ "error": e })),
+            }
+        }
+        (Method::Post, path) if path.starts_with("/actions/") => {
+            let action_id = &path["/actions/".len()..];
+            match execute_action(app, action_id) {
+                Ok(()) => json_response(200, &serde_json::json!({ "ok": true })),
+                Err(e) => json_response(400, &serde_json::json!({ "error": e })),
+            }
+        }
+        _ => json_response(404, &serde_json::json!({ "error": "not found" })),
+    }
+}
+
+/// 检查请求：Host 必须是本机地址加监听端口（防止 DNS 重绑定），带 `Origin` 的请求来自浏览器中的网页，
+/// 一律拒绝（防止跨站请求），并且必须携带令牌；失败时返回 HTTP 状态码
+fn authorize(
+    host: Option<&str>,
+    origin: Option<&str>,
+    authorization: Option<&str>,
+    config: &ControlApiConfig,
+) -> Result<(), u16> {
+    let local_host = host.is_some_and(|host| {
+        [
+            format!("127.0.0.1:{}", config.port),
+            format!("localhost:{}", config.port),
+        ]
+        .iter()
+        .any(|allowed| host.eq_ignore_ascii_case(allowed))
+    });
+    if !local_host || origin.is_some() {
+        return Err(403);
+    }
+    let token = match config.token.as_deref() {
+        Some(t) if !t.is_empty() => t,
+        _ => return Err(401),
+    };
+    if authorization != Some(format!("Bearer {}", token).as_str()) {
+        return Err(401);
+    }
+    Ok(())
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response<Cursor<Vec<u8>>> {
+    let content = serde_json::to_string(body).unwrap_or_else(|_| "{}".to_string());
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+
+    Response::from_string(content)
+        .with_status_code(status)
+        .with_header(header)
+}
+
+/// Prometheus 文本格式的响应
+fn text_response(status: u16, body: String) -> Response<Cursor<Vec<u8>>> {
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4; charset=utf-8"[..])
+        .expect("static header is valid");
+
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize_local_requests_with_token() {
+        let mut config = ControlApiConfig {
+            enabled: true,
+            ..ControlApiConfig::default()
+        };
+        let host = format!("127.0.0.1:{}", config.port);
+        let host = Some(host.as_str());
+        // 没有令牌时不允许访问
+        assert_eq!(authorize(host, None, None, &config), Err(401));
+
+        config.token = Some("secret".to_string());
+        let bearer = Some("Bearer secret");
+        assert_eq!(authorize(host, None, bearer, &config), Ok(()));
+        assert_eq!(authorize(host, None, Some("Bearer wrong"), &config), Err(401));
+        let localhost = format!("localhost:{}", config.port);
+        assert_eq!(authorize(Some(localhost.as_str()), None, bearer, &config), Ok(()));
+
+        // DNS 重绑定和浏览器中网页发起的请求
+        assert_eq!(authorize(Some("evil.example.com"), None, bearer, &config), Err(403));
+        assert_eq!(authorize(None, None, bearer, &config), Err(403));
+        assert_eq!(authorize(host, Some("https://evil.example.com"), bearer, &config), Err(403));
+    }
+}
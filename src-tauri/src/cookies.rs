@@ -0,0 +1,560 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::domains;
+use crate::models::WebApp;
+use crate::window::window_label;
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+/// Chromium 加密 Cookie 使用的固定盐和初始向量
+const CHROMIUM_SALT: &[u8] = b"saltysalt";
+const CHROMIUM_IV: [u8; 16] = [b' '; 16];
+
+/// Chromium 从这个数据库版本开始在明文前加上 32 字节的域名哈希
+const CHROMIUM_HASH_PREFIX_VERSION: i64 = 24;
+
+/// Windows 时间纪元（1601-01-01）与 Unix 纪元之间的秒数
+const WINDOWS_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+/// 可导入 Cookie 的浏览器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Browser {
+    Chrome,
+    Chromium,
+    Firefox,
+}
+
+/// 本机检测到的浏览器配置文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieSource {
+    pub browser: Browser,
+    /// 配置文件名称（如 `Default`、`Profile 1`）
+    pub profile: String,
+    /// Cookie 数据库路径
+    pub path: PathBuf,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieImportReport {
+    pub imported: usize,
+    /// 无法解密或写入的 Cookie 数量
+    pub skipped: usize,
+}
+
+/// 从浏览器数据库读出的 Cookie
+#[derive(Debug, Clone)]
+struct BrowserCookie {
+    host: String,
+    name: String,
+    value: String,
+    path: String,
+    /// 过期时间（Unix 秒），会话 Cookie 为 None
+    expires: Option<i64>,
+    secure: bool,
+    http_only: bool,
+}
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).map(PathBuf::from)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn home_dir() -> Option<PathBuf> {
+    env_dir("HOME").or_else(|| env_dir("USERPROFILE"))
+}
+
+/// Chromium 系浏览器的用户数据目录
+fn chromium_roots() -> Vec<(Browser, PathBuf)> {
+    #[cfg(target_os = "macos")]
+    {
+        let base = match home_dir() {
+            Some(home) => home.join("Library/Application Support"),
+            None => return Vec::new(),
+        };
+        vec![
+            (Browser::Chrome, base.join("Google/Chrome")),
+            (Browser::Chromium, base.join("Chromium")),
+        ]
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let base = match env_dir("LOCALAPPDATA") {
+            Some(base) => base,
+            None => return Vec::new(),
+        };
+        vec![
+            (Browser::Chrome, base.join("Google\\Chrome\\User Data")),
+            (Browser::Chromium, base.join("Chromium\\User Data")),
+        ]
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let base = match home_dir() {
+            Some(home) => home.join(".config"),
+            None => return Vec::new(),
+        };
+        vec![
+            (Browser::Chrome, base.join("google-chrome")),
+            (Browser::Chromium, base.join("chromium")),
+        ]
+    }
+}
+
+/// Firefox 配置文件所在目录
+fn firefox_root() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        home_dir().map(|home| home.join("Library/Application Support/Firefox/Profiles"))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        env_dir("APPDATA").map(|base| base.join("Mozilla\\Firefox\\Profiles"))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        home_dir().map(|home| home.join(".mozilla/firefox"))
+    }
+}
+
+fn subdirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// 列出本机可导入 Cookie 的浏览器配置文件
+pub fn list_sources() -> Vec<CookieSource> {
+    let mut sources = Vec::new();
+
+    for (browser, root) in chromium_roots() {
+        for dir in subdirs(&root) {
+            let profile = dir_name(&dir);
+            if profile != "Default" && !profile.starts_with("Profile ") {
+                continue;
+            }
+            // 新版本的 Cookie 数据库在 Network 子目录中
+            let path = [dir.join("Network").join("Cookies"), dir.join("Cookies")]
+                .into_iter()
+                .find(|p| p.is_file());
+            if let Some(path) = path {
+                sources.push(CookieSource { browser, profile, path });
+            }
+        }
+    }
+
+    if let Some(root) = firefox_root() {
+        for dir in subdirs(&root) {
+            let path = dir.join("cookies.sqlite");
+            if path.is_file() {
+                sources.push(CookieSource {
+                    browser: Browser::Firefox,
+                    profile: dir_name(&dir),
+                    path,
+                });
+            }
+        }
+    }
+
+    sources
+}
+
+/// Cookie 是否属于指定域名（包括子域名，以及对其生效的父域名 Cookie）
+fn matches_domain(host: &str, domain: &str) -> bool {
+    let domain = domain.trim_matches('.').to_ascii_lowercase();
+    let domain_cookie = host.starts_with('.');
+    let host = host.trim_start_matches('.').to_ascii_lowercase();
+    if domain.is_empty() || host.is_empty() {
+        return false;
+    }
+
+    host == domain
+        || host.ends_with(&format!(".{}", domain))
+        || (domain_cookie && domain.ends_with(&format!(".{}", host)))
+}
+
+/// 检查要导入的域名：必须在小程序的范围内（主页域名或允许的域名），且不能是公共后缀
+fn check_domain(webapp: &WebApp, domain: &str) -> Result<String, String> {
+    let domain = domain.trim().trim_matches('.').to_ascii_lowercase();
+    if domain.is_empty() || domains::is_public_suffix(&domain) {
+        return Err(format!("不能导入整个公共后缀 {} 下的 Cookie", domain));
+    }
+    let in_scope = url::Url::parse(&format!("https://{}/", domain))
+        .map(|url| webapp.is_url_in_scope(&url))
+        .unwrap_or(false);
+    if !in_scope {
+        return Err(format!("{} 不在小程序 {} 的域名范围内", domain, webapp.name));
+    }
+    Ok(domain)
+}
+
+/// Chromium 的过期时间（1601 年起的微秒数）转为 Unix 秒
+fn chromium_expiry(expires_utc: i64) -> Option<i64> {
+    if expires_utc <= 0 {
+        return None;
+    }
+    Some(expires_utc / 1_000_000 - WINDOWS_EPOCH_OFFSET)
+}
+
+/// Firefox 的过期时间转为 Unix 秒（新版本改为毫秒存储）
+fn firefox_expiry(expiry: i64) -> Option<i64> {
+    match expiry {
+        e if e <= 0 => None,
+        e if e > 100_000_000_000 => Some(e / 1000),
+        e => Some(e),
+    }
+}
+
+/// 浏览器运行时会锁住数据库，先复制到临时目录再读取
+/// 返回的临时文件由调用方删除
+fn copy_database(path: &Path) -> Result<Vec<PathBuf>, String> {
+    let base = std::env::temp_dir().join(format!("webapp-hub-cookies-{}.sqlite", uuid::Uuid::new_v4()));
+    std::fs::copy(path, &base).map_err(|e| format!("无法读取浏览器 Cookie 数据库: {}", e))?;
+
+    let mut copies = vec![base.clone()];
+    // 未合并的写入日志也需要一起复制，否则会缺少最近的 Cookie
+    let wal = PathBuf::from(format!("{}-wal", path.display()));
+    if wal.is_file() {
+        let wal_copy = PathBuf::from(format!("{}-wal", base.display()));
+        if std::fs::copy(&wal, &wal_copy).is_ok() {
+            copies.push(wal_copy);
+        }
+    }
+    Ok(copies)
+}
+
+/// 打开临时副本（不能只读打开，否则无法合并复制过来的写入日志）
+fn open_database(path: &Path) -> Result<Connection, String> {
+    Connection::open(path)
+        .map_err(|e| format!("无法打开浏览器 Cookie 数据库: {}", e))
+}
+
+fn read_firefox(conn: &Connection, domain: &str) -> Result<Vec<BrowserCookie>, String> {
+    let mut stmt = conn
+        .prepare("SELECT host, name, value, path, expiry, isSecure, isHttpOnly FROM moz_cookies")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(BrowserCookie {
+                host: row.get(0)?,
+                name: row.get(1)?,
+                value: row.get(2)?,
+                path: row.get(3)?,
+                expires: firefox_expiry(row.get(4)?),
+                secure: row.get::<_, i64>(5)? != 0,
+                http_only: row.get::<_, i64>(6)? != 0,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .filter(|c| matches_domain(&c.host, domain))
+        .collect())
+}
+
+/// Chromium 加密 Cookie 的密钥（`v10` 和 `v11` 前缀分别对应）
+#[derive(Default)]
+struct ChromiumKeys {
+    v10: Option<[u8; 16]>,
+    v11: Option<[u8; 16]>,
+}
+
+fn derive_key(password: &[u8], iterations: u32) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password, CHROMIUM_SALT, iterations, &mut key);
+    key
+}
+
+/// 读取系统钥匙串中的浏览器密码（macOS 会弹出系统授权对话框）
+#[cfg(target_os = "macos")]
+fn chromium_keys(browser: Browser) -> Result<ChromiumKeys, String> {
+    let service = match browser {
+        Browser::Chromium => "Chromium Safe Storage",
+        _ => "Chrome Safe Storage",
+    };
+    let output = std::process::Command::new("security")
+        .args(["find-generic-password", "-w", "-s", service])
+        .output()
+        .map_err(|e| format!("无法访问系统钥匙串: {}", e))?;
+    if !output.status.success() {
+        return Err("无法从系统钥匙串读取浏览器密钥（可能已拒绝授权）".to_string());
+    }
+
+    let password = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(ChromiumKeys {
+        v10: Some(derive_key(password.as_bytes(), 1003)),
+        v11: None,
+    })
+}
+
+/// `v10` 使用内置密码，`v11` 的密码保存在桌面环境的密钥环中
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn chromium_keys(browser: Browser) -> Result<ChromiumKeys, String> {
+    let application = match browser {
+        Browser::Chromium => "chromium",
+        _ => "chrome",
+    };
+    let keyring = std::process::Command::new("secret-tool")
+        .args(["lookup", "application", application])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|p| !p.is_empty());
+    if keyring.is_none() {
+        log::info!("No {} password in the desktop keyring, only v10 cookies can be decrypted", application);
+    }
+
+    Ok(ChromiumKeys {
+        v10: Some(derive_key(b"peanuts", 1)),
+        v11: keyring.map(|p| derive_key(p.as_bytes(), 1)),
+    })
+}
+
+/// Windows 上的 Cookie 由 DPAPI 和 AES-GCM 保护，暂不支持
+#[cfg(target_os = "windows")]
+fn chromium_keys(_browser: Browser) -> Result<ChromiumKeys, String> {
+    Err("Windows 上暂不支持导入 Chrome Cookie，请使用 Firefox".to_string())
+}
+
+/// 解密 Chromium 的 Cookie 值，无法解密时返回 None
+fn decrypt_chromium(encrypted: &[u8], keys: &ChromiumKeys, strip_hash: bool) -> Option<String> {
+    let (key, data) = match encrypted.split_at(3.min(encrypted.len())) {
+        (b"v10", data) => (keys.v10?, data),
+        (b"v11", data) => (keys.v11?, data),
+        _ => return None,
+    };
+
+    let mut buf = data.to_vec();
+    let plain = Aes128CbcDec::new_from_slices(&key, &CHROMIUM_IV)
+        .ok()?
+        .decrypt_padded_mut::<Pkcs7>(&mut buf)
+        .ok()?;
+    let plain = if strip_hash {
+        plain.get(32..)?
+    } else {
+        plain
+    };
+    String::from_utf8(plain.to_vec()).ok()
+}
+
+fn read_chromium(
+    conn: &Connection,
+    browser: Browser,
+    domain: &str,
+    report: &mut CookieImportReport,
+) -> Result<Vec<BrowserCookie>, String> {
+    let version: i64 = conn
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| row.get::<_, String>(0))
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let strip_hash = version >= CHROMIUM_HASH_PREFIX_VERSION;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT host_key, name, value, encrypted_value, path, expires_utc, is_secure, is_httponly \
+             FROM cookies",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            let cookie = BrowserCookie {
+                host: row.get(0)?,
+                name: row.get(1)?,
+                value: row.get(2)?,
+                path: row.get(4)?,
+                expires: chromium_expiry(row.get(5)?),
+                secure: row.get::<_, i64>(6)? != 0,
+                http_only: row.get::<_, i64>(7)? != 0,
+            };
+            Ok((cookie, row.get::<_, Vec<u8>>(3)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut keys: Option<ChromiumKeys> = None;
+    let mut cookies = Vec::new();
+    for (mut cookie, encrypted) in rows.filter_map(|r| r.ok()) {
+        if !matches_domain(&cookie.host, domain) {
+            continue;
+        }
+        if cookie.value.is_empty() && !encrypted.is_empty() {
+            // 只有确实需要解密时才读取钥匙串，避免无谓的系统授权弹窗
+            if keys.is_none() {
+                keys = Some(chromium_keys(browser)?);
+            }
+            match keys.as_ref().and_then(|k| decrypt_chromium(&encrypted, k, strip_hash)) {
+                Some(value) => cookie.value = value,
+                None => {
+                    report.skipped += 1;
+                    continue;
+                }
+            }
+        }
+        cookies.push(cookie);
+    }
+    Ok(cookies)
+}
+
+/// 从浏览器配置文件读取指定域名的 Cookie（会阻塞，需在后台线程调用）
+fn read_cookies(source: &CookieSource, domain: &str, report: &mut CookieImportReport) -> Result<Vec<BrowserCookie>, String> {
+    let copies = copy_database(&source.path)?;
+    let result = open_database(&copies[0]).and_then(|conn| match source.browser {
+        Browser::Firefox => read_firefox(&conn, domain),
+        browser => read_chromium(&conn, browser, domain, report),
+    });
+    for copy in &copies {
+        let _ = std::fs::remove_file(copy);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    Ok(result?
+        .into_iter()
+        .filter(|c| c.expires.map(|e| e > now).unwrap_or(true))
+        .collect())
+}
+
+fn to_cookie(cookie: BrowserCookie) -> Option<cookie::Cookie<'static>> {
+    let mut builder = cookie::Cookie::build((cookie.name, cookie.value))
+        .domain(cookie.host)
+        .path(cookie.path)
+        .secure(cookie.secure)
+        .http_only(cookie.http_only);
+    if let Some(expires) = cookie.expires {
+        builder = builder.expires(cookie::time::OffsetDateTime::from_unix_timestamp(expires).ok()?);
+    }
+    Some(builder.build())
+}
+
+/// 将浏览器中指定域名的 Cookie 导入小程序
+///
+/// Cookie 通过小程序窗口写入，使用独立配置文件的小程序会写入它自己的数据目录，因此需要先打开窗口
+pub async fn import(
+    app: &AppHandle,
+    webapp: &WebApp,
+    source_path: &Path,
+    domain: &str,
+) -> Result<CookieImportReport, String> {
+    let domain = check_domain(webapp, domain)?;
+    // 只允许读取检测到的浏览器数据库，不接受任意路径
+    let source = list_sources()
+        .into_iter()
+        .find(|s| s.path == source_path)
+        .ok_or("未找到该浏览器配置文件")?;
+    let window = app
+        .get_webview_window(&window_label(&webapp.id))
+        .ok_or("请先打开小程序窗口再导入 Cookie")?;
+
+    let domain_owned = domain.clone();
+    let (cookies, mut report) = tauri::async_runtime::spawn_blocking(move || {
+        let mut report = CookieImportReport::default();
+        read_cookies(&source, &domain_owned, &mut report).map(|cookies| (cookies, report))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    for cookie in cookies {
+        let name = cookie.name.clone();
+        match to_cookie(cookie).map(|c| window.set_cookie(c)) {
+            Some(Ok(())) => report.imported += 1,
+            Some(Err(e)) => {
+                log::warn!("Failed to import cookie {}: {}", name, e);
+                report.skipped += 1;
+            }
+            None => report.skipped += 1,
+        }
+    }
+
+    log::info!(
+        "Imported {} cookies for {} into {} ({} skipped)",
+        report.imported,
+        domain,
+        webapp.id,
+        report.skipped
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+
+    #[test]
+    fn test_matches_domain() {
+        assert!(matches_domain("example.com", "example.com"));
+        assert!(matches_domain(".example.com", "example.com"));
+        assert!(matches_domain("mail.example.com", "example.com"));
+        assert!(matches_domain(".example.com", "mail.example.com"));
+        assert!(!matches_domain("example.com", "mail.example.com"));
+        assert!(!matches_domain("notexample.com", "example.com"));
+    }
+
+    #[test]
+    fn test_check_domain() {
+        let mut webapp = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        webapp.allowed_domains = vec!["*.example-cdn.net".to_string()];
+
+        assert_eq!(check_domain(&webapp, ".Mail.Example.com").unwrap(), "mail.example.com");
+        assert!(check_domain(&webapp, "static.example-cdn.net").is_ok());
+        assert!(check_domain(&webapp, "com").is_err());
+        assert!(check_domain(&webapp, "github.io").is_err());
+        assert!(check_domain(&webapp, "other.org").is_err());
+        assert!(check_domain(&webapp, "").is_err());
+    }
+
+    #[test]
+    fn test_expiry_conversion() {
+        assert_eq!(chromium_expiry(0), None);
+        assert_eq!(chromium_expiry(13_000_000_000_000_000), Some(1_355_526_400));
+        assert_eq!(firefox_expiry(1_700_000_000), Some(1_700_000_000));
+        assert_eq!(firefox_expiry(1_700_000_000_000), Some(1_700_000_000));
+    }
+
+    #[test]
+    fn test_decrypt_chromium_v10() {
+        let key = derive_key(b"peanuts", 1);
+        let keys = ChromiumKeys {
+            v10: Some(key),
+            v11: None,
+        };
+
+        let plain = b"session-token";
+        let mut buf = [0u8; 32];
+        let encrypted = cbc::Encryptor::<aes::Aes128>::new_from_slices(&key, &CHROMIUM_IV)
+            .unwrap()
+            .encrypt_padded_b2b_mut::<Pkcs7>(plain, &mut buf)
+            .unwrap();
+        let mut data = b"v10".to_vec();
+        data.extend_from_slice(encrypted);
+
+        assert_eq!(decrypt_chromium(&data, &keys, false).as_deref(), Some("session-token"));
+        assert_eq!(decrypt_chromium(b"v11abc", &keys, false), None);
+    }
+}
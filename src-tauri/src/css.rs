@@ -0,0 +1,129 @@
+use crate::models::{AccessibilityConfig, AppConfig, WebApp};
+
+/// 注入的 <style> 元素 ID，重复注入时据此替换内容
+const STYLE_ELEMENT_ID: &str = "__webapp_hub_css__";
+
+/// 字体缩放的允许范围（百分比）
+const FONT_SCALE_RANGE: (u32, u32) = (50, 300);
+
+/// 生成无障碍样式，未开启或没有生效的选项时返回 None
+pub fn accessibility_css(settings: &AccessibilityConfig) -> Option<String> {
+    if !settings.enabled {
+        return None;
+    }
+
+    let mut rules = Vec::new();
+    let scale = settings.font_scale.clamp(FONT_SCALE_RANGE.0, FONT_SCALE_RANGE.1);
+    if scale != 100 {
+        rules.push(format!("html {{ font-size: {}% !important; }}", scale));
+    }
+    if settings.high_contrast {
+        rules.push(
+            "html { filter: contrast(1.4) !important; }\n\
+             a { text-decoration: underline !important; }\n\
+             :focus { outline: 3px solid #ffbf00 !important; outline-offset: 2px !important; }"
+                .to_string(),
+        );
+    }
+    if settings.reduced_motion {
+        rules.push(
+            "*, *::before, *::after { animation-duration: 0.01ms !important; \
+             animation-iteration-count: 1 !important; transition-duration: 0.01ms !important; \
+             scroll-behavior: auto !important; }"
+                .to_string(),
+        );
+    }
+
+    if rules.is_empty() {
+        None
+    } else {
+        Some(rules.join("\n"))
+    }
+}
+
+/// 合并全局样式、小程序自定义样式和无障碍样式
+/// （全局在前，小程序样式可覆盖全局，无障碍样式最后应用）
+pub fn combined_css(config: &AppConfig, webapp: &WebApp) -> Option<String> {
+    let accessibility = accessibility_css(&config.accessibility);
+    let parts: Vec<&str> = [
+        config.global_css.as_deref(),
+        webapp.custom_css.as_deref(),
+        accessibility.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|css| !css.trim().is_empty())
+    .collect();
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("\n"))
+    }
+}
+
+/// 生成注入样式的脚本
+/// 脚本可重复执行：已存在的 <style> 会被替换，用于样式的实时更新
+pub fn style_injection_script(css: &str) -> String {
+    let css_literal = serde_json::to_string(css).unwrap_or_else(|_| "\"\"".to_string());
+
+    format!(
+        r#"(function() {{
+    var css = {css};
+    function applyStyle() {{
+        var style = document.getElementById('{id}');
+        if (!style) {{
+            style = document.createElement('style');
+            style.id = '{id}';
+            (document.head || document.documentElement).appendChild(style);
+        }}
+        style.textContent = css;
+    }}
+    if (document.documentElement) {{
+        applyStyle();
+    }} else {{
+        document.addEventListener('DOMContentLoaded', applyStyle);
+    }}
+}})();"#,
+        css = css_literal,
+        id = STYLE_ELEMENT_ID
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessibility_css_requires_enabled() {
+        let mut settings = AccessibilityConfig {
+            enabled: false,
+            font_scale: 150,
+            high_contrast: true,
+            reduced_motion: true,
+            verbose_announcements: false,
+        };
+        assert_eq!(accessibility_css(&settings), None);
+
+        settings.enabled = true;
+        let css = accessibility_css(&settings).unwrap();
+        assert!(css.contains("font-size: 150%"));
+        assert!(css.contains("contrast(1.4)"));
+        assert!(css.contains("animation-duration"));
+    }
+
+    #[test]
+    fn test_accessibility_css_is_last_and_clamped() {
+        let mut config = AppConfig {
+            global_css: Some("body { color: red; }".to_string()),
+            ..Default::default()
+        };
+        config.accessibility.enabled = true;
+        config.accessibility.font_scale = 1000;
+        let webapp = WebApp::new("A".to_string(), "https://a.example.com".to_string());
+
+        let css = combined_css(&config, &webapp).unwrap();
+        assert!(css.starts_with("body { color: red; }"));
+        assert!(css.ends_with("html { font-size: 300% !important; }"));
+    }
+}
@@ -0,0 +1,268 @@
+use chrono::{Datelike, Timelike};
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::badges;
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::events::{self, Channel};
+use crate::models::{DndConfig, DndSchedule};
+use crate::tray::TrayMenuState;
+
+/// 计划状态检查间隔
+const MONITOR_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 勿扰模式状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DndStatus {
+    /// 当前是否处于勿扰状态
+    pub active: bool,
+    /// 是否手动开启
+    pub manual: bool,
+    /// 是否处于计划时间段内
+    pub scheduled: bool,
+    /// 临时解除计划到此时间（Unix 秒），已过期时为 None
+    pub override_until: Option<u64>,
+}
+
+/// 当前本地时间：(星期（0 = 周一）, 当天的分钟数, Unix 秒)
+fn local_now() -> (u8, u32, u64) {
+    let now = chrono::Local::now();
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    (now.weekday().num_days_from_monday() as u8, now.hour() * 60 + now.minute(), secs)
+}
+
+/// 解析 HH:MM 为当天的分钟数
+fn parse_minutes(time: &str) -> Option<u32> {
+    let (h, m) = time.split_once(':')?;
+    let h: u32 = h.trim().parse().ok()?;
+    let m: u32 = m.trim().parse().ok()?;
+    if h < 24 && m < 60 {
+        Some(h * 60 + m)
+    } else {
+        None
+    }
+}
+
+impl DndSchedule {
+    /// 给定星期（0 = 周一）和分钟数，判断是否落在该时间段内
+    pub(crate) fn contains(&self, weekday: u8, minutes: u32) -> bool {
+        let (start, end) = match (parse_minutes(&self.start), parse_minutes(&self.end)) {
+            (Some(s), Some(e)) => (s, e),
+            _ => return false,
+        };
+        let day_matches = |day: u8| self.days.is_empty() || self.days.contains(&day);
+
+        if start <= end {
+            day_matches(weekday) && minutes >= start && minutes < end
+        } else if minutes >= start {
+            day_matches(weekday)
+        } else if minutes < end {
+            // 跨午夜的后半段属于前一天开始的时间段
+            day_matches((weekday + 6) % 7)
+        } else {
+            false
+        }
+    }
+
+    /// 从给定的分钟数到该时间段结束的分钟数（需在时间段内）
+    fn minutes_until_end(&self, minutes: u32) -> u32 {
+        let end = parse_minutes(&self.end).unwrap_or(0);
+        if end > minutes {
+            end - minutes
+        } else {
+            24 * 60 - minutes + end
+        }
+    }
+}
+
+impl DndConfig {
+    /// 计算指定时刻的勿扰状态（`now` 为 Unix 秒，用于判断临时解除是否过期）
+    pub fn status_at(&self, weekday: u8, minutes: u32, now: u64) -> DndStatus {
+        let scheduled =
+            self.schedule_enabled && self.schedules.iter().any(|s| s.contains(weekday, minutes));
+        let override_until = self.override_until.filter(|until| *until > now);
+        DndStatus {
+            active: self.manual || (scheduled && override_until.is_none()),
+            manual: self.manual,
+            scheduled,
+            override_until,
+        }
+    }
+
+    /// 计算当前本地时间的勿扰状态
+    pub fn status_now(&self) -> DndStatus {
+        let (weekday, minutes, now) = local_now();
+        self.status_at(weekday, minutes, now)
+    }
+
+    /// 反转指定时刻的勿扰状态，返回反转后的状态
+    ///
+    /// 开启时关闭手动开启，处于计划时间段内时临时解除到所有当前时间段结束；
+    /// 关闭时取消临时解除（计划时间段内）或手动开启
+    pub fn toggle_at(&mut self, weekday: u8, minutes: u32, now: u64) -> DndStatus {
+        let status = self.status_at(weekday, minutes, now);
+        if status.active {
+            self.manual = false;
+            self.override_until = self
+                .schedules
+                .iter()
+                .filter(|s| status.scheduled && s.contains(weekday, minutes))
+                .map(|s| now + u64::from(s.minutes_until_end(minutes)) * 60)
+                .max();
+        } else if status.scheduled {
+            self.override_until = None;
+        } else {
+            self.manual = true;
+        }
+        self.status_at(weekday, minutes, now)
+    }
+}
+
+/// 读取当前勿扰状态
+pub fn current_status(app: &AppHandle) -> DndStatus {
+    app.state::<ConfigManager>().read().dnd.status_now()
+}
+
+/// 手动开启/关闭勿扰模式，并同步托盘与前端
+pub fn set_manual(app: &AppHandle, enabled: bool) -> AppResult<DndStatus> {
+    let status = app.state::<ConfigManager>().update(|config| {
+        config.dnd.manual = enabled;
+        config.dnd.status_now()
+    })?;
+
+    notify_changed(app, status);
+    log::info!("Do Not Disturb manual mode set to: {}", enabled);
+    Ok(status)
+}
+
+/// 反转当前的勿扰状态（托盘菜单），计划时间段内关闭时临时解除到时间段结束
+pub fn toggle(app: &AppHandle) -> AppResult<DndStatus> {
+    let (weekday, minutes, now) = local_now();
+    let status = app
+        .state::<ConfigManager>()
+        .update(|config| config.dnd.toggle_at(weekday, minutes, now))?;
+
+    notify_changed(app, status);
+    log::info!("Do Not Disturb toggled to: {}", if status.active { "on" } else { "off" });
+    Ok(status)
+}
+
+fn notify_changed(app: &AppHandle, status: DndStatus) {
+    if let Some(tray) = app.try_state::<TrayMenuState>() {
+        let _ = tray.dnd.set_checked(status.active);
+    }
+    events::emit(app, Channel::Config, "dnd://changed", status);
+    if !status.active {
+        badges::flush_held(app);
+    }
+}
+
+/// 监听计划时间段的切换，保持托盘勾选状态并通知前端
+pub fn start_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = current_status(&app);
+        let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            let status = current_status(&app);
+            if status != last {
+                log::info!("Do Not Disturb is now {}", if status.active { "on" } else { "off" });
+                notify_changed(&app, status);
+                last = status;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(start: &str, end: &str, days: Vec<u8>) -> DndSchedule {
+        DndSchedule {
+            start: start.to_string(),
+            end: end.to_string(),
+            days,
+        }
+    }
+
+    #[test]
+    fn test_same_day_range() {
+        let s = schedule("12:00", "13:30", vec![]);
+        assert!(s.contains(2, 12 * 60));
+        assert!(s.contains(2, 13 * 60 + 29));
+        assert!(!s.contains(2, 13 * 60 + 30));
+        assert!(!s.contains(2, 11 * 60 + 59));
+    }
+
+    #[test]
+    fn test_overnight_range_uses_start_day() {
+        // 周一到周五 22:00 - 次日 08:00
+        let s = schedule("22:00", "08:00", vec![0, 1, 2, 3, 4]);
+        assert!(s.contains(4, 23 * 60)); // 周五晚上
+        assert!(s.contains(5, 7 * 60)); // 周六早上，属于周五开始的时间段
+        assert!(!s.contains(5, 23 * 60)); // 周六晚上
+        assert!(!s.contains(0, 7 * 60)); // 周一早上，周日未启用
+    }
+
+    #[test]
+    fn test_manual_and_schedule_switch() {
+        let mut config = DndConfig {
+            manual: false,
+            schedule_enabled: false,
+            schedules: vec![schedule("00:00", "23:59", vec![])],
+            override_until: None,
+        };
+        assert!(!config.status_at(0, 600, 1000).active);
+
+        config.schedule_enabled = true;
+        assert!(config.status_at(0, 600, 1000).scheduled);
+
+        config.schedule_enabled = false;
+        config.manual = true;
+        assert!(config.status_at(0, 600, 1000).active);
+    }
+
+    #[test]
+    fn test_toggle_always_inverts() {
+        let mut config = DndConfig {
+            manual: false,
+            schedule_enabled: true,
+            schedules: vec![schedule("22:00", "08:00", vec![])],
+            override_until: None,
+        };
+
+        // 计划时间段内关闭：临时解除到时间段结束（23:00 到次日 08:00 为 9 小时）
+        let status = config.toggle_at(0, 23 * 60, 1000);
+        assert!(!status.active && status.scheduled);
+        assert_eq!(status.override_until, Some(1000 + 9 * 3600));
+        // 再次切换取消临时解除
+        assert!(config.toggle_at(0, 23 * 60, 1000).active);
+        assert_eq!((config.manual, config.override_until), (false, None));
+
+        // 手动开启且在计划时间段内时，关闭后两者都不再生效
+        config.manual = true;
+        assert!(!config.toggle_at(0, 23 * 60, 1000).active);
+        assert!(!config.manual);
+        // 临时解除过期后计划重新生效
+        assert!(config.status_at(1, 7 * 60, 1000 + 9 * 3600).active);
+
+        // 计划时间段外开启为手动开启
+        let status = config.toggle_at(1, 12 * 60, 1000);
+        assert!(status.active && status.manual);
+        assert!(!config.toggle_at(1, 12 * 60, 1000).active);
+    }
+
+    #[test]
+    fn test_invalid_time_is_ignored() {
+        let s = schedule("25:00", "08:00", vec![]);
+        assert!(!s.contains(0, 60));
+    }
+}
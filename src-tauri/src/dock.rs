@@ -0,0 +1,334 @@
+//! 停靠模式：小程序窗口贴在屏幕边缘（沿边铺满显示器），失去焦点时滑到屏幕外只留一条细边，
+//! 鼠标移到细边上或按快捷键显示窗口时滑出，适合待办清单这类侧边栏式的小程序
+//!
+//! 位置使用物理像素；停靠在与其他显示器相邻的一侧时，收起的窗口会露在相邻的显示器上
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, LogicalSize, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::models::{DockEdge, WebApp};
+use crate::placement::Rect;
+use crate::platform;
+use crate::window::window_label;
+
+/// 收起后留在屏幕内的细边宽度（逻辑像素）
+const STRIP_WIDTH: f64 = 4.0;
+
+/// 失去焦点后多久收起（切换窗口时不会来回闪动）
+const COLLAPSE_DELAY: Duration = Duration::from_millis(400);
+
+/// 检查鼠标是否在细边上的间隔
+const HOVER_INTERVAL: Duration = Duration::from_millis(150);
+
+/// 滑动动画的帧数和每帧间隔
+const SLIDE_FRAMES: u32 = 8;
+const FRAME_INTERVAL: Duration = Duration::from_millis(15);
+
+#[derive(Debug, Clone, Copy)]
+struct DockedWindow {
+    edge: DockEdge,
+    /// 停靠的显示器区域
+    monitor: Rect,
+    /// 窗口的宽和高（左右停靠时只用宽度，上下停靠时只用高度）
+    size: (f64, f64),
+    /// 细边宽度
+    strip: f64,
+    collapsed: bool,
+    /// 收起计时器的代数，焦点变化时递增以取消旧的计时器
+    timer: u64,
+    /// 滑动动画的代数，收起状态变化时递增以停止旧的动画
+    animation: u64,
+}
+
+/// 已停靠的窗口
+pub struct DockState {
+    windows: Mutex<HashMap<String, DockedWindow>>,
+}
+
+impl DockState {
+    pub fn new() -> Self {
+        Self {
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for DockState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 停靠窗口的区域 (x, y, width, height)：沿停靠边铺满显示器，收起时只有 `strip` 宽的一条留在显示器内
+fn docked_rect(edge: DockEdge, monitor: Rect, size: (f64, f64), strip: f64, collapsed: bool) -> Option<Rect> {
+    let (mx, my, mw, mh) = monitor;
+    let (w, h) = (size.0.min(mw), size.1.min(mh));
+    let rect = match edge {
+        DockEdge::Off => return None,
+        DockEdge::Left => (if collapsed { mx - w + strip } else { mx }, my, w, mh),
+        DockEdge::Right => (if collapsed { mx + mw - strip } else { mx + mw - w }, my, w, mh),
+        DockEdge::Top => (mx, if collapsed { my - h + strip } else { my }, mw, h),
+        DockEdge::Bottom => (mx, if collapsed { my + mh - strip } else { my + mh - h }, mw, h),
+    };
+    Some(rect)
+}
+
+/// 收起后留在显示器内的细边区域
+fn strip_rect(edge: DockEdge, monitor: Rect, strip: f64) -> Option<Rect> {
+    let (mx, my, mw, mh) = monitor;
+    match edge {
+        DockEdge::Off => None,
+        DockEdge::Left => Some((mx, my, strip, mh)),
+        DockEdge::Right => Some((mx + mw - strip, my, strip, mh)),
+        DockEdge::Top => Some((mx, my, mw, strip)),
+        DockEdge::Bottom => Some((mx, my + mh - strip, mw, strip)),
+    }
+}
+
+fn contains(rect: Rect, point: (f64, f64)) -> bool {
+    let (x, y, w, h) = rect;
+    point.0 >= x && point.0 < x + w && point.1 >= y && point.1 < y + h
+}
+
+fn place(window: &WebviewWindow, rect: Rect) {
+    let _ = window.set_size(PhysicalSize::new(rect.2.round() as u32, rect.3.round() as u32));
+    let _ = window.set_position(PhysicalPosition::new(rect.0.round() as i32, rect.1.round() as i32));
+}
+
+/// 窗口是否处于停靠模式（停靠窗口的位置不记录到配置中）
+pub fn is_docked(app: &AppHandle, webapp_id: &str) -> bool {
+    app.try_state::<DockState>()
+        .is_some_and(|dock| dock.windows.lock().contains_key(webapp_id))
+}
+
+/// 按小程序的停靠设置把窗口贴到屏幕边缘（窗口创建后和修改设置后调用），设置为不停靠时恢复普通窗口
+pub fn attach(app: &AppHandle, window: &WebviewWindow, webapp: &WebApp) {
+    let Some(dock) = app.try_state::<DockState>() else {
+        return;
+    };
+    let previous = dock.windows.lock().get(&webapp.id).copied();
+
+    if webapp.dock_edge == DockEdge::Off {
+        if previous.is_some() {
+            dock.windows.lock().remove(&webapp.id);
+            let _ = window.set_size(LogicalSize::new(webapp.width as f64, webapp.height as f64));
+            let _ = window.center();
+            log::info!("Undocked webapp {}", webapp.id);
+        }
+        return;
+    }
+    if !platform::capabilities().window_positioning {
+        log::warn!("Window positioning is not supported, not docking webapp {}", webapp.id);
+        return;
+    }
+
+    // 重新停靠时沿用原来的显示器（收起的窗口大部分在显示器外，不一定能取到所在的显示器）
+    let monitor = match previous {
+        Some(previous) => Some(previous.monitor),
+        None => window
+            .current_monitor()
+            .ok()
+            .flatten()
+            .or_else(|| window.primary_monitor().ok().flatten())
+            .map(|monitor| {
+                (
+                    monitor.position().x as f64,
+                    monitor.position().y as f64,
+                    monitor.size().width as f64,
+                    monitor.size().height as f64,
+                )
+            }),
+    };
+    let Some(monitor) = monitor else {
+        log::warn!("No monitor found for docked webapp {}", webapp.id);
+        return;
+    };
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let collapsed = !window.is_focused().unwrap_or(false);
+    let docked = DockedWindow {
+        edge: webapp.dock_edge,
+        monitor,
+        size: (webapp.width as f64 * scale, webapp.height as f64 * scale),
+        strip: (STRIP_WIDTH * scale).round(),
+        collapsed,
+        timer: previous.map_or(0, |previous| previous.timer + 1),
+        animation: previous.map_or(0, |previous| previous.animation + 1),
+    };
+    if let Some(rect) = docked_rect(docked.edge, monitor, docked.size, docked.strip, collapsed) {
+        place(window, rect);
+    }
+    dock.windows.lock().insert(webapp.id.clone(), docked);
+    log::info!("Docked webapp {} to {:?} edge", webapp.id, webapp.dock_edge);
+}
+
+/// 修改停靠设置后应用到已打开的窗口
+pub fn refresh_window(app: &AppHandle, webapp: &WebApp) {
+    if let Some(window) = app.get_webview_window(&window_label(&webapp.id)) {
+        attach(app, &window, webapp);
+    }
+}
+
+/// 窗口关闭时移除记录
+pub fn forget(app: &AppHandle, webapp_id: &str) {
+    if let Some(dock) = app.try_state::<DockState>() {
+        dock.windows.lock().remove(webapp_id);
+    }
+}
+
+fn current(app: &AppHandle, webapp_id: &str) -> Option<DockedWindow> {
+    let dock = app.try_state::<DockState>()?;
+    let docked = dock.windows.lock().get(webapp_id).copied();
+    docked
+}
+
+/// 收起或展开窗口（从当前位置滑动到目标位置）
+fn set_collapsed(app: &AppHandle, webapp_id: &str, collapsed: bool) {
+    let Some(dock) = app.try_state::<DockState>() else {
+        return;
+    };
+    let (docked, generation) = {
+        let mut windows = dock.windows.lock();
+        let Some(docked) = windows.get_mut(webapp_id) else {
+            return;
+        };
+        if docked.collapsed == collapsed {
+            return;
+        }
+        docked.collapsed = collapsed;
+        docked.animation += 1;
+        (*docked, docked.animation)
+    };
+    let Some(window) = app.get_webview_window(&window_label(webapp_id)) else {
+        return;
+    };
+    let Some(target) = docked_rect(docked.edge, docked.monitor, docked.size, docked.strip, collapsed) else {
+        return;
+    };
+    let start = window
+        .outer_position()
+        .map(|position| (position.x as f64, position.y as f64))
+        .unwrap_or((target.0, target.1));
+
+    let app = app.clone();
+    let webapp_id = webapp_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        for frame in 1..=SLIDE_FRAMES {
+            tokio::time::sleep(FRAME_INTERVAL).await;
+            // 动画过程中又切换了状态，交给新的动画
+            if current(&app, &webapp_id).map(|docked| docked.animation) != Some(generation) {
+                return;
+            }
+            let t = f64::from(frame) / f64::from(SLIDE_FRAMES);
+            let x = start.0 + (target.0 - start.0) * t;
+            let y = start.1 + (target.1 - start.1) * t;
+            let _ = window.set_position(PhysicalPosition::new(x.round() as i32, y.round() as i32));
+        }
+    });
+}
+
+/// 焦点变化（由 `WindowManager` 转发）：获得焦点时展开，失去焦点一小段时间后收起
+pub fn focus_changed(app: &AppHandle, webapp_id: &str, focused: bool) {
+    let Some(dock) = app.try_state::<DockState>() else {
+        return;
+    };
+    let generation = {
+        let mut windows = dock.windows.lock();
+        let Some(docked) = windows.get_mut(webapp_id) else {
+            return;
+        };
+        docked.timer += 1;
+        docked.timer
+    };
+
+    if focused {
+        set_collapsed(app, webapp_id, false);
+        return;
+    }
+    let app = app.clone();
+    let webapp_id = webapp_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(COLLAPSE_DELAY).await;
+        if current(&app, &webapp_id).map(|docked| docked.timer) != Some(generation) {
+            return;
+        }
+        let focused = app
+            .get_webview_window(&window_label(&webapp_id))
+            .is_some_and(|window| window.is_focused().unwrap_or(false));
+        if !focused {
+            set_collapsed(&app, &webapp_id, true);
+        }
+    });
+}
+
+/// 鼠标在收起的窗口的细边上时展开并聚焦该窗口
+fn expand_under_cursor(app: &AppHandle) {
+    let Some(dock) = app.try_state::<DockState>() else {
+        return;
+    };
+    let strips: Vec<(String, Rect)> = dock
+        .windows
+        .lock()
+        .iter()
+        .filter(|(_, docked)| docked.collapsed)
+        .filter_map(|(id, docked)| Some((id.clone(), strip_rect(docked.edge, docked.monitor, docked.strip)?)))
+        .collect();
+    // 没有收起的窗口时不读取鼠标位置
+    if strips.is_empty() {
+        return;
+    }
+    let Ok(cursor) = app.cursor_position() else {
+        return;
+    };
+
+    for (webapp_id, strip) in strips {
+        if !contains(strip, (cursor.x, cursor.y)) {
+            continue;
+        }
+        if let Some(window) = app.get_webview_window(&window_label(&webapp_id)) {
+            // 隐藏的窗口（快捷键隐藏）不因鼠标经过而显示
+            if window.is_visible().unwrap_or(false) {
+                let _ = window.set_focus();
+                set_collapsed(app, &webapp_id, false);
+            }
+        }
+    }
+}
+
+/// 定时检查鼠标是否移到收起的停靠窗口上
+pub fn start_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(HOVER_INTERVAL);
+        loop {
+            interval.tick().await;
+            expand_under_cursor(&app);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Rect = (1920.0, 0.0, 2560.0, 1440.0);
+
+    #[test]
+    fn test_docked_rect_and_strip() {
+        let rect = |edge, collapsed| docked_rect(edge, MONITOR, (400.0, 300.0), 4.0, collapsed).unwrap();
+
+        assert_eq!(rect(DockEdge::Left, false), (1920.0, 0.0, 400.0, 1440.0));
+        assert_eq!(rect(DockEdge::Left, true), (1524.0, 0.0, 400.0, 1440.0));
+        assert_eq!(rect(DockEdge::Right, false), (4080.0, 0.0, 400.0, 1440.0));
+        assert_eq!(rect(DockEdge::Right, true), (4476.0, 0.0, 400.0, 1440.0));
+        assert_eq!(rect(DockEdge::Top, true), (1920.0, -296.0, 2560.0, 300.0));
+        assert_eq!(rect(DockEdge::Bottom, false), (1920.0, 1140.0, 2560.0, 300.0));
+        assert_eq!(docked_rect(DockEdge::Off, MONITOR, (400.0, 300.0), 4.0, true), None);
+
+        // 收起后留在显示器内的部分就是细边
+        let strip = strip_rect(DockEdge::Right, MONITOR, 4.0).unwrap();
+        assert!(contains(strip, (4479.0, 700.0)));
+        assert!(!contains(strip, (4470.0, 700.0)));
+        assert!(contains(strip_rect(DockEdge::Left, MONITOR, 4.0).unwrap(), (1920.0, 0.0)));
+    }
+}
@@ -0,0 +1,149 @@
+use url::Url;
+
+/// 常见的多级公共后缀（内置的一小部分，单级的顶级域名全部视为公共后缀）
+const PUBLIC_SUFFIXES: &[&str] = &[
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "com.cn", "net.cn", "org.cn", "gov.cn", "edu.cn",
+    "com.hk", "com.tw", "co.jp", "ne.jp", "or.jp", "co.kr", "com.au", "net.au", "org.au", "co.nz",
+    "co.in", "com.br", "com.sg", "github.io", "gitlab.io", "pages.dev", "vercel.app", "netlify.app",
+    "web.app", "firebaseapp.com", "herokuapp.com", "appspot.com", "blogspot.com", "cloudfront.net",
+    "azurewebsites.net",
+];
+
+/// 判断主机名是否匹配域名模式（不区分大小写）
+/// - `example.com` 只匹配该域名
+/// - `*.example.com` 匹配 example.com 及其所有子域名
+/// - `*` 匹配任意域名
+pub fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+
+    if pattern.is_empty() || host.is_empty() {
+        return false;
+    }
+    if pattern == "*" {
+        return true;
+    }
+
+    match pattern.strip_prefix("*.") {
+        Some(base) => host == base || host.ends_with(&format!(".{}", base)),
+        None => host == pattern,
+    }
+}
+
+/// 域名是否是公共后缀（如 `com`、`co.uk`、`github.io`），其下的网站归不同的人所有
+pub fn is_public_suffix(domain: &str) -> bool {
+    let domain = domain.trim().trim_matches('.').to_ascii_lowercase();
+    !domain.contains('.') || PUBLIC_SUFFIXES.contains(&domain.as_str())
+}
+
+/// 主机名是否匹配任意一个域名模式
+pub fn host_matches_any<S: AsRef<str>>(patterns: &[S], host: &str) -> bool {
+    patterns.iter().any(|p| host_matches(p.as_ref(), host))
+}
+
+/// 判断目标 URL 是否在小程序的范围内：
+/// 仅允许 http/https，主机名需与主页相同或匹配任意允许的域名模式
+pub fn url_in_scope<S: AsRef<str>>(home: &Url, allowed_domains: &[S], target: &Url) -> bool {
+    if !matches!(target.scheme(), "http" | "https") {
+        return false;
+    }
+
+    let host = match target.host_str() {
+        Some(host) => host,
+        None => return false,
+    };
+
+    home.host_str()
+        .map(|home_host| host_matches(home_host, host))
+        .unwrap_or(false)
+        || host_matches_any(allowed_domains, host)
+}
+
+/// 规范化 URL 用于重复检测：忽略协议、`www.` 前缀、默认端口、结尾斜杠和片段
+/// 无法解析的 URL 按去除空白并转小写处理
+pub fn normalize_url(raw: &str) -> String {
+    let url = match Url::parse(raw.trim()) {
+        Ok(url) => url,
+        Err(_) => return raw.trim().trim_end_matches('/').to_ascii_lowercase(),
+    };
+
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let port = url.port().map(|p| format!(":{}", p)).unwrap_or_default();
+    let path = url.path().trim_end_matches('/');
+    let query = url.query().map(|q| format!("?{}", q)).unwrap_or_default();
+
+    format!("{}{}{}{}", host, port, path, query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_domain() {
+        assert!(host_matches("example.com", "example.com"));
+        assert!(host_matches("Example.COM", "example.com."));
+        assert!(!host_matches("example.com", "www.example.com"));
+        assert!(!host_matches("example.com", "badexample.com"));
+    }
+
+    #[test]
+    fn test_wildcard_domain() {
+        assert!(host_matches("*.google.com", "accounts.google.com"));
+        assert!(host_matches("*.google.com", "a.b.google.com"));
+        assert!(host_matches("*.google.com", "google.com"));
+        assert!(!host_matches("*.google.com", "notgoogle.com"));
+        assert!(host_matches("*", "anything.local"));
+    }
+
+    #[test]
+    fn test_url_in_scope() {
+        let home: Url = "https://jira.example.com/secure/Dashboard.jspa".parse().unwrap();
+        let allowed = vec!["*.atlassian.net".to_string()];
+        let check = |url: &str| url_in_scope(&home, &allowed, &url.parse().unwrap());
+
+        assert!(check("https://jira.example.com/browse/ABC-123"));
+        assert!(check("https://team.atlassian.net/wiki"));
+        assert!(!check("https://example.com/"));
+        assert!(!check("file:///etc/passwd"));
+        assert!(!check("javascript:alert(1)"));
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("https://www.Example.com/"),
+            normalize_url("http://example.com")
+        );
+        assert_eq!(
+            normalize_url("https://example.com/app/#inbox"),
+            normalize_url("https://example.com/app")
+        );
+        assert_eq!(normalize_url("https://example.com:443/"), "example.com");
+        assert_ne!(
+            normalize_url("https://example.com/?tab=1"),
+            normalize_url("https://example.com/?tab=2")
+        );
+        assert_ne!(
+            normalize_url("https://example.com:8080"),
+            normalize_url("https://example.com")
+        );
+    }
+
+    #[test]
+    fn test_public_suffix() {
+        assert!(is_public_suffix("com"));
+        assert!(is_public_suffix(".co.uk"));
+        assert!(is_public_suffix("GitHub.io"));
+        assert!(!is_public_suffix("example.com"));
+        assert!(!is_public_suffix("example.co.uk"));
+    }
+
+    #[test]
+    fn test_empty_inputs() {
+        assert!(!host_matches("", "example.com"));
+        assert!(!host_matches("example.com", ""));
+        assert!(!host_matches_any::<&str>(&[], "example.com"));
+    }
+}
@@ -0,0 +1,271 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::webview::DownloadEvent;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+use url::Url;
+
+use crate::config::ConfigManager;
+use crate::events::{self, Channel};
+use crate::models::{AppConfig, WebApp};
+
+/// 最多记录的下载数
+const MAX_ITEMS: usize = 200;
+
+/// 无法从网址得到文件名时使用的名称
+const FALLBACK_NAME: &str = "download";
+
+/// 下载状态（系统 WebView 只报告开始和结束，不提供下载进度的字节数）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadState {
+    InProgress,
+    Completed,
+    Failed,
+}
+
+/// 小程序窗口中的一次下载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadItem {
+    pub id: String,
+    pub webapp_id: String,
+    pub url: String,
+    /// 保存的文件路径
+    pub path: String,
+    pub state: DownloadState,
+    /// 开始时间（Unix 秒）
+    pub started_at: u64,
+    /// 结束时间（Unix 秒）
+    pub finished_at: Option<u64>,
+}
+
+/// 本次运行中的下载，新的在前
+pub struct DownloadManager {
+    items: Mutex<VecDeque<DownloadItem>>,
+}
+
+impl DownloadManager {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn list(&self) -> Vec<DownloadItem> {
+        self.items.lock().iter().cloned().collect()
+    }
+}
+
+impl Default for DownloadManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 小程序的下载目录：小程序的设置优先，其次是全局设置，最后是系统的下载目录
+pub fn directory_for(app: &AppHandle, config: &AppConfig, webapp: Option<&WebApp>) -> Option<PathBuf> {
+    webapp
+        .and_then(|w| w.download_dir.as_deref())
+        .or(config.download_dir.as_deref())
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| app.path().download_dir().ok())
+}
+
+/// 检查设置的下载目录：去掉首尾空白，空字符串表示使用默认目录，必须是绝对路径
+pub fn normalize_dir(dir: &str) -> Result<Option<String>, String> {
+    let dir = dir.trim();
+    if dir.is_empty() {
+        return Ok(None);
+    }
+    if !Path::new(dir).is_absolute() {
+        return Err(format!("下载目录必须是绝对路径: {}", dir));
+    }
+    Ok(Some(dir.to_string()))
+}
+
+/// 去掉文件名中的路径分隔符和各平台不允许的字符
+pub fn sanitize(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').to_string();
+    if cleaned.is_empty() {
+        FALLBACK_NAME.to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// 文件名：使用 WebView 建议的文件名，没有时取网址的最后一段
+fn file_name(url: &Url, suggested: &Path) -> String {
+    let name = suggested
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .or_else(|| {
+            url.path_segments()
+                .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+                .map(|segment| {
+                    percent_encoding::percent_decode_str(segment)
+                        .decode_utf8_lossy()
+                        .into_owned()
+                })
+        })
+        .unwrap_or_default();
+    sanitize(&name)
+}
+
+/// 目录中不存在的文件路径：同名文件已存在时改为 `name (1).ext`、`name (2).ext` ……
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    let extension = path.extension().and_then(|e| e.to_str());
+    (1..)
+        .map(|n| match extension {
+            Some(extension) => dir.join(format!("{} ({}).{}", stem, n, extension)),
+            None => dir.join(format!("{} ({})", stem, n)),
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(candidate)
+}
+
+/// 记录开始的下载，返回保存路径；无法确定下载目录时返回 None（使用 WebView 的默认位置）
+fn start(app: &AppHandle, webapp_id: &str, url: &Url, suggested: &Path) -> Option<PathBuf> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = config.webapps.iter().find(|w| w.id == webapp_id);
+    let dir = directory_for(app, &config, webapp)?;
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::warn!("Failed to create download directory {}: {}", dir.display(), e);
+        return None;
+    }
+    let path = unique_path(&dir, &file_name(url, suggested));
+
+    let item = DownloadItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        webapp_id: webapp_id.to_string(),
+        url: url.to_string(),
+        path: path.to_string_lossy().into_owned(),
+        state: DownloadState::InProgress,
+        started_at: now_secs(),
+        finished_at: None,
+    };
+    if let Some(manager) = app.try_state::<DownloadManager>() {
+        let mut items = manager.items.lock();
+        items.push_front(item.clone());
+        items.truncate(MAX_ITEMS);
+    }
+    log::info!("Downloading {} for webapp {} to {}", url, webapp_id, path.display());
+    events::emit(app, Channel::Downloads, "download://started", item);
+    Some(path)
+}
+
+/// 更新结束的下载（按小程序和网址匹配最近一条进行中的下载）
+fn finish(app: &AppHandle, webapp_id: &str, url: &Url, path: Option<PathBuf>, success: bool) {
+    let Some(manager) = app.try_state::<DownloadManager>() else {
+        return;
+    };
+    let item = {
+        let mut items = manager.items.lock();
+        let Some(item) = items
+            .iter_mut()
+            .find(|i| i.webapp_id == webapp_id && i.url == url.as_str() && i.state == DownloadState::InProgress)
+        else {
+            return;
+        };
+        item.state = if success {
+            DownloadState::Completed
+        } else {
+            DownloadState::Failed
+        };
+        item.finished_at = Some(now_secs());
+        if let Some(path) = path {
+            item.path = path.to_string_lossy().into_owned();
+        }
+        item.clone()
+    };
+    if success {
+        log::info!("Download finished for webapp {}: {}", webapp_id, item.path);
+    } else {
+        log::warn!("Download failed for webapp {}: {}", webapp_id, url);
+    }
+    events::emit(app, Channel::Downloads, "download://finished", item);
+}
+
+/// 处理小程序窗口的下载事件（`on_download` 回调），返回 false 时取消下载
+pub fn handle(app: &AppHandle, webapp_id: &str, event: DownloadEvent<'_>) -> bool {
+    match event {
+        DownloadEvent::Requested { url, destination } => {
+            if let Some(path) = start(app, webapp_id, &url, destination) {
+                *destination = path;
+            }
+        }
+        DownloadEvent::Finished { url, path, success } => finish(app, webapp_id, &url, path, success),
+        _ => {}
+    }
+    true
+}
+
+/// 在文件管理器中打开下载目录（`webapp_id` 为空时打开全局下载目录）
+pub fn open_folder(app: &AppHandle, webapp_id: Option<&str>) -> Result<String, String> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = match webapp_id {
+        Some(id) => Some(config.webapps.iter().find(|w| w.id == id).ok_or("小程序不存在")?),
+        None => None,
+    };
+    let dir = directory_for(app, &config, webapp).ok_or("无法确定下载目录")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("无法创建下载目录: {}", e))?;
+    let dir = dir.to_string_lossy().into_owned();
+    app.opener()
+        .open_path(dir.as_str(), None::<&str>)
+        .map_err(|e| format!("无法打开下载目录: {}", e))?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_file_names() {
+        let url = Url::parse("https://example.com/files/%E6%8A%A5%E5%91%8A.pdf?x=1").unwrap();
+        assert_eq!(file_name(&url, Path::new("")), "报告.pdf");
+        assert_eq!(file_name(&url, Path::new("/tmp/invoice.pdf")), "invoice.pdf");
+        assert_eq!(file_name(&Url::parse("https://example.com/").unwrap(), Path::new("")), FALLBACK_NAME);
+        assert_eq!(sanitize("a:b*c?.txt"), "a_b_c_.txt");
+        assert_eq!(sanitize(" .. "), FALLBACK_NAME);
+        assert_eq!(normalize_dir("  "), Ok(None));
+        assert!(normalize_dir("downloads").is_err());
+
+        let dir = std::env::temp_dir().join(format!("webapp-hub-downloads-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(unique_path(&dir, "a.txt"), dir.join("a.txt"));
+        std::fs::write(dir.join("a.txt"), b"1").unwrap();
+        std::fs::write(dir.join("a (1).txt"), b"2").unwrap();
+        assert_eq!(unique_path(&dir, "a.txt"), dir.join("a (2).txt"));
+        std::fs::write(dir.join("README"), b"3").unwrap();
+        assert_eq!(unique_path(&dir, "README"), dir.join("README (1)"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// 命令和各模块返回的错误：前端可以按 `code` 区分错误类型，`message` 沿用原来的提示文字
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// 小程序、分组、窗口等不存在
+    NotFound(String),
+    /// 快捷键已被其他绑定使用
+    ShortcutConflict(String),
+    /// 快捷键无法解析或注册
+    InvalidShortcut(String),
+    /// 代理配置无效
+    InvalidProxy(String),
+    /// 参数或配置内容无效
+    InvalidInput(String),
+    /// 配置已被管理员锁定
+    Locked(String),
+    /// 读写文件失败
+    Io(String),
+    /// 创建或操作窗口失败
+    Window(String),
+    /// 其他错误
+    Other(String),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    /// 错误代码（前端据此判断错误类型）
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "notFound",
+            AppError::ShortcutConflict(_) => "shortcutConflict",
+            AppError::InvalidShortcut(_) => "invalidShortcut",
+            AppError::InvalidProxy(_) => "invalidProxy",
+            AppError::InvalidInput(_) => "invalidInput",
+            AppError::Locked(_) => "locked",
+            AppError::Io(_) => "io",
+            AppError::Window(_) => "window",
+            AppError::Other(_) => "other",
+        }
+    }
+
+    /// 提示文字
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NotFound(message)
+            | AppError::ShortcutConflict(message)
+            | AppError::InvalidShortcut(message)
+            | AppError::InvalidProxy(message)
+            | AppError::InvalidInput(message)
+            | AppError::Locked(message)
+            | AppError::Io(message)
+            | AppError::Window(message)
+            | AppError::Other(message) => message,
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound(message.into())
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        AppError::InvalidInput(message.into())
+    }
+
+    pub fn window(error: impl fmt::Display) -> Self {
+        AppError::Window(error.to_string())
+    }
+
+    /// 在提示文字前加上上下文，保留错误类型
+    pub fn context(self, context: impl fmt::Display) -> Self {
+        let message = format!("{}: {}", context, self.message());
+        match self {
+            AppError::NotFound(_) => AppError::NotFound(message),
+            AppError::ShortcutConflict(_) => AppError::ShortcutConflict(message),
+            AppError::InvalidShortcut(_) => AppError::InvalidShortcut(message),
+            AppError::InvalidProxy(_) => AppError::InvalidProxy(message),
+            AppError::InvalidInput(_) => AppError::InvalidInput(message),
+            AppError::Locked(_) => AppError::Locked(message),
+            AppError::Io(_) => AppError::Io(message),
+            AppError::Window(_) => AppError::Window(message),
+            AppError::Other(_) => AppError::Other(message),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// 序列化为 `{ "code": "...", "message": "..." }`
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+/// 尚未区分类型的错误文字
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        AppError::Io(error.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(error: tauri::Error) -> Self {
+        AppError::Window(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let error = AppError::not_found("小程序不存在");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "code": "notFound", "message": "小程序不存在" })
+        );
+
+        let error = AppError::not_found("小程序不存在").context("宏 晨会 第 1 步失败");
+        assert_eq!(error.code(), "notFound");
+        assert_eq!(error.message(), "宏 晨会 第 1 步失败: 小程序不存在");
+        assert_eq!(AppError::from("失败".to_string()).code(), "other");
+    }
+}
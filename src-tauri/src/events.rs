@@ -0,0 +1,120 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, EventTarget, Manager};
+
+use crate::bridge;
+
+/// 事件频道：前端窗口按频道订阅后端事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Channel {
+    /// 小程序窗口的打开、关闭、淘汰、进程和无响应状态
+    Windows,
+    /// 配置、静音、勿扰、无障碍和图标变化
+    Config,
+    /// 快捷键注册失败和快捷键触发的请求
+    Shortcuts,
+    /// 下载进度（供下载管理使用）
+    Downloads,
+    /// 未读角标
+    Badges,
+    /// 需要用户处理的请求（代理认证、分享、剪贴板建议、被拦截的导航）
+    Prompts,
+    /// 小程序的请求记录（数量多，默认不发送）
+    Requests,
+}
+
+impl Channel {
+    /// 未调用 `subscribe` 的窗口默认接收的频道
+    pub const DEFAULT: &'static [Channel] = &[
+        Channel::Windows,
+        Channel::Config,
+        Channel::Shortcuts,
+        Channel::Downloads,
+        Channel::Badges,
+        Channel::Prompts,
+    ];
+}
+
+/// 各窗口订阅的频道（按窗口标签）
+pub struct EventBus {
+    subscriptions: Mutex<HashMap<String, HashSet<Channel>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            subscriptions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 替换窗口订阅的频道，返回订阅后的频道
+    pub fn subscribe(&self, label: &str, channels: Vec<Channel>) -> Vec<Channel> {
+        let channels: HashSet<Channel> = channels.into_iter().collect();
+        let mut subscribed: Vec<Channel> = channels.iter().copied().collect();
+        subscribed.sort_by_key(|channel| *channel as u8);
+        self.subscriptions.lock().insert(label.to_string(), channels);
+        log::debug!("Window {} subscribed to {:?}", label, subscribed);
+        subscribed
+    }
+
+    /// 窗口关闭后移除订阅
+    pub fn forget(&self, label: &str) {
+        self.subscriptions.lock().remove(label);
+    }
+
+    /// 窗口是否接收该频道的事件：小程序窗口只接收显式订阅的频道
+    fn wants(&self, label: &str, channel: Channel) -> bool {
+        match self.subscriptions.lock().get(label) {
+            Some(channels) => channels.contains(&channel),
+            None => bridge::webapp_id_from_label(label).is_none() && Channel::DEFAULT.contains(&channel),
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 向订阅了频道的窗口发送事件（事件总线未初始化时发送给所有窗口）
+pub fn emit<S: Serialize + Clone>(app: &AppHandle, channel: Channel, event: &str, payload: S) {
+    let bus = match app.try_state::<EventBus>() {
+        Some(bus) => bus,
+        None => {
+            if let Err(e) = app.emit(event, payload) {
+                log::debug!("Failed to emit {}: {}", event, e);
+            }
+            return;
+        }
+    };
+    for label in app.webview_windows().into_keys().filter(|label| bus.wants(label, channel)) {
+        if let Err(e) = app.emit_to(EventTarget::labeled(label.as_str()), event, payload.clone()) {
+            log::debug!("Failed to emit {} to {}: {}", event, label, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriptions() {
+        let bus = EventBus::new();
+        assert!(bus.wants("main", Channel::Windows));
+        assert!(!bus.wants("main", Channel::Requests));
+        // 小程序窗口默认不接收任何事件
+        assert!(!bus.wants("webapp-mail", Channel::Windows));
+
+        let subscribed = bus.subscribe("main", vec![Channel::Requests, Channel::Config, Channel::Requests]);
+        assert_eq!(subscribed, vec![Channel::Config, Channel::Requests]);
+        assert!(bus.wants("main", Channel::Requests));
+        assert!(!bus.wants("main", Channel::Windows));
+
+        bus.forget("main");
+        assert!(bus.wants("main", Channel::Windows));
+    }
+}
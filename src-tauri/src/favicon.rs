@@ -0,0 +1,389 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use parking_lot::Mutex;
+use regex::Regex;
+use reqwest::Client;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Channel};
+use crate::icons;
+use crate::models::{AppConfig, NetworkConfig, ProxyConfig, WebApp};
+use crate::net;
+
+/// 单个图标文件的大小上限
+const MAX_ICON_BYTES: usize = 512 * 1024;
+
+/// 获取页面和图标的超时
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 定时刷新图标的检查间隔
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 启动后等待一段时间再进行第一次刷新，避免拖慢启动
+const REFRESH_STARTUP_DELAY: Duration = Duration::from_secs(15 * 60);
+
+/// 定时刷新时，同一主机两次获取之间的最短间隔
+const HOST_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 定时刷新时相邻两次获取之间的间隔
+const REFRESH_SPACING: Duration = Duration::from_secs(5);
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// 各主机上一次获取图标的时间
+static HOST_FETCHES: Mutex<Option<HashMap<String, Instant>>> = parking_lot::const_mutex(None);
+
+/// 获取到图标后是否覆盖小程序当前的图标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overwrite {
+    /// 只在小程序没有图标时设置（获取期间用户已设置图标则不覆盖）
+    IfMissing,
+    /// 只替换自动获取的图标（定时刷新）
+    IfAutoFetched,
+    /// 总是替换
+    Always,
+}
+
+fn link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<link\b[^>]*>").unwrap())
+}
+
+fn attr_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"(?is)\b(rel|href|sizes)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap()
+    })
+}
+
+/// 图标声明的尺寸（取最大边），用于挑选最清晰的图标
+fn declared_size(rel: &str, sizes: Option<&str>) -> u32 {
+    match sizes.map(str::trim) {
+        Some(sizes) if sizes.eq_ignore_ascii_case("any") => 1024,
+        Some(sizes) => sizes
+            .split_whitespace()
+            .filter_map(|s| s.to_ascii_lowercase().split('x').next()?.parse().ok())
+            .max()
+            .unwrap_or(0),
+        // apple-touch-icon 默认为 180×180
+        None if rel.contains("apple-touch-icon") => 180,
+        None => 16,
+    }
+}
+
+/// 解析页面中声明的图标（`<link rel="icon">` 等），按尺寸从大到小排列
+fn icon_links(html: &str, base: &Url) -> Vec<Url> {
+    let mut links: Vec<(u32, Url)> = Vec::new();
+    for tag in link_regex().find_iter(html) {
+        let (mut rel, mut href, mut sizes) = (None, None, None);
+        for caps in attr_regex().captures_iter(tag.as_str()) {
+            let value = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map(|m| m.as_str().trim().replace("&amp;", "&"));
+            match caps[1].to_ascii_lowercase().as_str() {
+                "rel" => rel = value.map(|v| v.to_ascii_lowercase()),
+                "href" => href = value,
+                _ => sizes = value,
+            }
+        }
+        let rel = match rel {
+            Some(rel) if rel.split_whitespace().any(|t| t == "icon" || t == "apple-touch-icon") => rel,
+            _ => continue,
+        };
+        if let Some(url) = href.filter(|h| !h.is_empty()).and_then(|h| base.join(&h).ok()) {
+            links.push((declared_size(&rel, sizes.as_deref()), url));
+        }
+    }
+    // 稳定排序：相同尺寸时保持页面中的顺序
+    links.sort_by_key(|(size, _)| Reverse(*size));
+    links.into_iter().map(|(_, url)| url).collect()
+}
+
+/// 按文件内容判断图片类型
+fn sniff_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0, 0, 1, 0]) {
+        Some("image/x-icon")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.len() > 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]).to_ascii_lowercase();
+        (head.contains("<svg")).then_some("image/svg+xml")
+    }
+}
+
+fn extension(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/jpeg" => "jpg",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => "ico",
+    }
+}
+
+async fn download_icon(
+    client: &Client,
+    policy: &NetworkConfig,
+    url: &Url,
+) -> Result<(&'static str, Vec<u8>), String> {
+    let resp = net::send_with_retry(policy, || client.get(url.clone()).timeout(FETCH_TIMEOUT)).await?;
+    if !resp.status().is_success() {
+        return Err(format!("{} 返回 {}", url, resp.status()));
+    }
+    let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+    if bytes.is_empty() || bytes.len() > MAX_ICON_BYTES {
+        return Err(format!("{} 的图标为空或过大", url));
+    }
+    let mime = sniff_mime(&bytes).ok_or_else(|| format!("{} 不是图片", url))?;
+    Ok((mime, bytes.to_vec()))
+}
+
+/// 获取网站图标：先解析页面中声明的图标，再尝试 `/favicon.ico`
+async fn fetch(client: &Client, policy: &NetworkConfig, page: &Url) -> Result<(&'static str, Vec<u8>), String> {
+    let mut candidates = match net::send_with_retry(policy, || client.get(page.clone()).timeout(FETCH_TIMEOUT)).await {
+        Ok(resp) if resp.status().is_success() => {
+            // 跟随重定向后的地址才是相对链接的基准
+            let base = resp.url().clone();
+            let html = resp.text().await.unwrap_or_default();
+            icon_links(&html, &base)
+        }
+        _ => Vec::new(),
+    };
+    if let Ok(fallback) = page.join("/favicon.ico") {
+        if !candidates.contains(&fallback) {
+            candidates.push(fallback);
+        }
+    }
+
+    let mut last_error = "未找到网站图标".to_string();
+    for url in candidates {
+        match download_icon(client, policy, &url).await {
+            Ok(icon) => return Ok(icon),
+            Err(e) => {
+                log::debug!("Favicon candidate failed: {}", e);
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+/// 获取图标使用的客户端：与小程序窗口走同一代理（关闭代理的小程序直接连接）
+fn client_for(app: &AppHandle, webapp: &WebApp) -> (Client, NetworkConfig) {
+    let (shared, policy) = net::client(app);
+    let own_proxy = if webapp.use_proxy {
+        webapp.proxy_override.clone().filter(|p| p.enabled)
+    } else {
+        Some(ProxyConfig::default())
+    };
+    match own_proxy.map(|proxy| net::build_client(&proxy, &policy)) {
+        Some(Ok(client)) => (client, policy),
+        Some(Err(e)) => {
+            log::warn!("Failed to build favicon client for {}: {}", webapp.id, e);
+            (shared, policy)
+        }
+        None => (shared, policy),
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 获取并保存小程序的网站图标，返回前端使用的图标引用
+///
+/// 图标缓存在应用数据目录的 icons 下，配置中保存为内嵌图标；
+/// 图标没有变化时只更新获取时间，不发送 `webapp://icon-updated` 事件
+pub async fn refresh(app: &AppHandle, webapp_id: &str, overwrite: Overwrite) -> AppResult<Option<String>> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+    let page = Url::parse(&webapp.url).map_err(|e| AppError::invalid_input(format!("小程序网址无效: {}", e)))?;
+    if let Some(host) = page.host_str() {
+        HOST_FETCHES
+            .lock()
+            .get_or_insert_with(HashMap::new)
+            .insert(host.to_string(), Instant::now());
+    }
+
+    let (client, policy) = client_for(app, webapp);
+    let (mime, bytes) = fetch(&client, &policy, &page).await?;
+    if let Ok(dir) = app.path().app_data_dir().map(|d| d.join("icons")) {
+        let path = dir.join(format!("{}.{}", webapp_id, extension(mime)));
+        if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, &bytes)) {
+            log::warn!("Failed to cache favicon for {}: {}", webapp_id, e);
+        }
+    }
+
+    let data_url = format!("data:{};base64,{}", mime, STANDARD.encode(&bytes));
+    let updated = app.state::<ConfigManager>().update(|config| {
+        let webapp = config.webapps.iter_mut().find(|w| w.id == webapp_id)?;
+        let has_icon = webapp.icon.as_deref().is_some_and(|i| !i.is_empty());
+        let allowed = match overwrite {
+            Overwrite::IfMissing => !has_icon,
+            Overwrite::IfAutoFetched => webapp.icon_fetched_at.is_some(),
+            Overwrite::Always => true,
+        };
+        if !allowed {
+            return None;
+        }
+        let changed = webapp.icon.as_deref() != Some(data_url.as_str());
+        webapp.icon = Some(data_url);
+        webapp.icon_fetched_at = Some(now_secs());
+        Some((changed, icons::reference(webapp)))
+    })?;
+
+    match updated {
+        Some((true, reference)) => {
+            log::info!("Updated favicon for webapp {}", webapp_id);
+            events::emit(
+                app,
+                Channel::Config,
+                "webapp://icon-updated",
+                serde_json::json!({ "webappId": webapp_id }),
+            );
+            Ok(reference)
+        }
+        Some((false, reference)) => Ok(reference),
+        None => Ok(None),
+    }
+}
+
+/// 在后台获取没有图标的小程序的网站图标（不阻塞添加小程序）
+pub fn fetch_in_background(app: &AppHandle, webapp_id: &str) {
+    let app = app.clone();
+    let webapp_id = webapp_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = refresh(&app, &webapp_id, Overwrite::IfMissing).await {
+            log::info!("Could not fetch favicon for {}: {}", webapp_id, e);
+        }
+    });
+}
+
+/// 自动获取的图标是否已超过刷新间隔
+fn is_due(webapp: &WebApp, now: u64, refresh_days: u32) -> bool {
+    webapp.enabled
+        && webapp
+            .icon_fetched_at
+            .is_some_and(|at| now.saturating_sub(at) >= refresh_days as u64 * DAY_SECS)
+}
+
+/// 主机最近一次获取图标已超过 `HOST_INTERVAL`（同一主机的多个小程序不会同时刷新）
+fn host_ready(last: Option<Instant>, now: Instant) -> bool {
+    !last.is_some_and(|last| now.duration_since(last) < HOST_INTERVAL)
+}
+
+/// 需要刷新图标的小程序，每个主机只取一个
+fn due_webapps(config: &AppConfig, now: u64) -> Vec<(String, String)> {
+    let fetches = HOST_FETCHES.lock();
+    let instant = Instant::now();
+    let mut hosts = Vec::new();
+    let mut due = Vec::new();
+    for webapp in config.webapps.iter().filter(|w| is_due(w, now, config.icon_refresh_days)) {
+        let host = match Url::parse(&webapp.url).ok().and_then(|u| u.host_str().map(str::to_string)) {
+            Some(host) => host,
+            None => continue,
+        };
+        let last = fetches.as_ref().and_then(|f| f.get(&host).copied());
+        if hosts.contains(&host) || !host_ready(last, instant) {
+            continue;
+        }
+        hosts.push(host.clone());
+        due.push((webapp.id.clone(), host));
+    }
+    due
+}
+
+/// 定时重新获取自动获取的网站图标（网站更换图标后启动器随之更新）
+pub fn start_refresh_job(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(REFRESH_STARTUP_DELAY).await;
+        let mut interval = tokio::time::interval(REFRESH_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let config = app.state::<ConfigManager>().read();
+            if config.icon_refresh_days == 0 {
+                continue;
+            }
+            for (webapp_id, host) in due_webapps(&config, now_secs()) {
+                log::debug!("Refreshing favicon for {} ({})", webapp_id, host);
+                if let Err(e) = refresh(&app, &webapp_id, Overwrite::IfAutoFetched).await {
+                    log::info!("Could not refresh favicon for {}: {}", webapp_id, e);
+                }
+                tokio::time::sleep(REFRESH_SPACING).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_links() {
+        let base = Url::parse("https://mail.example.com/inbox/").unwrap();
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/app.css">
+            <link rel="shortcut icon" href="/favicon-16.png">
+            <LINK REL='icon' sizes='32x32 64x64' href='icons/64.png?v=1&amp;t=2'>
+            <link href="https://cdn.example.com/touch.png" rel="apple-touch-icon">
+        </head></html>"#;
+
+        let links: Vec<String> = icon_links(html, &base).iter().map(|u| u.to_string()).collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://cdn.example.com/touch.png",
+                "https://mail.example.com/inbox/icons/64.png?v=1&t=2",
+                "https://mail.example.com/favicon-16.png",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sniff_mime() {
+        assert_eq!(sniff_mime(b"\x89PNG\r\n\x1a\n"), Some("image/png"));
+        assert_eq!(sniff_mime(&[0, 0, 1, 0, 1, 0]), Some("image/x-icon"));
+        assert_eq!(sniff_mime(b"<?xml version=\"1.0\"?><svg xmlns=\"\"></svg>"), Some("image/svg+xml"));
+        assert_eq!(sniff_mime(b"<!doctype html><html>"), None);
+    }
+
+    #[test]
+    fn test_refresh_only_due_auto_icons() {
+        let day = DAY_SECS;
+        let mut webapp = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        assert!(!is_due(&webapp, 30 * day, 7));
+        webapp.icon_fetched_at = Some(20 * day);
+        assert!(!is_due(&webapp, 26 * day, 7));
+        assert!(is_due(&webapp, 27 * day, 7));
+        webapp.enabled = false;
+        assert!(!is_due(&webapp, 27 * day, 7));
+
+        let now = Instant::now();
+        assert!(host_ready(None, now));
+        assert!(!host_ready(Some(now), now));
+    }
+}
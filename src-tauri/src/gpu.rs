@@ -0,0 +1,39 @@
+use tauri::WebviewWindow;
+
+use crate::models::{AppConfig, WebApp};
+use crate::platform;
+
+/// 按设置禁用小程序窗口的硬件加速
+///
+/// Linux 上在窗口创建后修改 WebKitGTK 的硬件加速策略；Windows 上通过创建时的 WebView2 参数禁用
+/// （见 `profiles::browser_args`），这里不需要处理
+pub fn apply(window: &WebviewWindow, config: &AppConfig, webapp: &WebApp) {
+    if config.hardware_acceleration_for(webapp) {
+        return;
+    }
+    if !platform::capabilities().hardware_acceleration {
+        log::debug!("Disabling hardware acceleration is not supported on this platform, ignoring {}", webapp.id);
+        return;
+    }
+    match disable_native(window) {
+        Ok(()) => log::info!("Disabled hardware acceleration for {}", webapp.id),
+        Err(e) => log::warn!("Failed to disable hardware acceleration for {}: {}", webapp.id, e),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn disable_native(window: &WebviewWindow) -> Result<(), String> {
+    window
+        .with_webview(|webview| {
+            use webkit2gtk::{HardwareAccelerationPolicy, SettingsExt, WebViewExt};
+            if let Some(settings) = WebViewExt::settings(&webview.inner()) {
+                settings.set_hardware_acceleration_policy(HardwareAccelerationPolicy::Never);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn disable_native(_window: &WebviewWindow) -> Result<(), String> {
+    Ok(())
+}
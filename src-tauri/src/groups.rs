@@ -0,0 +1,315 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Channel};
+use crate::models::{AppConfig, GroupStep, StepFailurePolicy, WebApp, WebAppGroup};
+use crate::net;
+use crate::shortcuts::load_shortcuts_from_config;
+use crate::window::WindowManager;
+
+/// 等待成员就绪的默认时间
+const DEFAULT_STEP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 就绪检查间隔
+const READY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 单次就绪检查请求的超时
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 颜色为 `#rgb` 或 `#rrggbb`
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn validate(group: &WebAppGroup) -> Result<(), String> {
+    if group.name.trim().is_empty() {
+        return Err("分组名称不能为空".to_string());
+    }
+    if let Some(color) = group.color.as_deref().filter(|c| !c.is_empty()) {
+        if !is_hex_color(color) {
+            return Err(format!("无效的分组颜色: {}", color));
+        }
+    }
+    let position = |id: &str| group.webapp_ids.iter().position(|w| w == id);
+    for step in &group.steps {
+        let at = position(&step.webapp_id).ok_or_else(|| format!("步骤中的小程序不在分组中: {}", step.webapp_id))?;
+        for dependency in &step.depends_on {
+            if !position(dependency).is_some_and(|d| d < at) {
+                return Err(format!("依赖的小程序必须在分组中排在前面: {}", dependency));
+            }
+        }
+        if let Some(url) = step.health_check_url.as_deref().filter(|u| !u.is_empty()) {
+            let valid = url::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https"));
+            if !valid {
+                return Err(format!("就绪检查网址无效: {}", url));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn step_for<'a>(group: &'a WebAppGroup, webapp_id: &str) -> Option<&'a GroupStep> {
+    group.steps.iter().find(|s| s.webapp_id == webapp_id)
+}
+
+/// 分组中已启用的小程序（按分组中的顺序，已删除的小程序忽略）
+pub fn members<'a>(config: &'a AppConfig, group: &WebAppGroup) -> Vec<&'a WebApp> {
+    group
+        .webapp_ids
+        .iter()
+        .filter_map(|id| config.webapps.iter().find(|w| &w.id == id))
+        .filter(|w| w.enabled)
+        .collect()
+}
+
+/// 把小程序移到指定的分组并移出其他分组（group_id 为空时移出所有分组）
+pub fn move_webapp(config: &mut AppConfig, webapp_id: &str, group_id: &str) -> AppResult<()> {
+    if !group_id.is_empty() && !config.groups.iter().any(|g| g.id == group_id) {
+        return Err(AppError::not_found(format!("分组不存在: {}", group_id)));
+    }
+    for group in &mut config.groups {
+        if group.id == group_id {
+            if !group.webapp_ids.iter().any(|id| id == webapp_id) {
+                group.webapp_ids.push(webapp_id.to_string());
+            }
+        } else {
+            group.webapp_ids.retain(|id| id != webapp_id);
+        }
+    }
+    Ok(())
+}
+
+/// 添加或修改分组（ID 为空时新建），返回保存后的分组
+pub fn save(app: &AppHandle, mut group: WebAppGroup) -> AppResult<WebAppGroup> {
+    validate(&group).map_err(AppError::InvalidInput)?;
+    if group.id.is_empty() {
+        group.id = Uuid::new_v4().to_string();
+    }
+    group.color = group.color.filter(|c| !c.is_empty());
+    group.shortcut = group.shortcut.filter(|s| !s.is_empty());
+    let mut seen = HashSet::new();
+    group.webapp_ids.retain(|id| seen.insert(id.clone()));
+    let mut seen = HashSet::new();
+    group.steps.retain(|step| seen.insert(step.webapp_id.clone()));
+    for step in &mut group.steps {
+        step.health_check_url = step.health_check_url.take().filter(|u| !u.is_empty());
+    }
+
+    let saved = group.clone();
+    let config_manager = app.state::<ConfigManager>();
+    config_manager.update(|config| match config.groups.iter_mut().find(|g| g.id == group.id) {
+        Some(existing) => *existing = group,
+        None => config.groups.push(group),
+    })?;
+    load_shortcuts_from_config(app, &config_manager.read())?;
+    log::info!("Saved webapp group {} ({})", saved.name, saved.id);
+    Ok(saved)
+}
+
+/// 删除分组（分组中的小程序不受影响）
+pub fn delete(app: &AppHandle, group_id: &str) -> AppResult<()> {
+    let config_manager = app.state::<ConfigManager>();
+    config_manager.update(|config| {
+        let before = config.groups.len();
+        config.groups.retain(|g| g.id != group_id);
+        if config.groups.len() == before {
+            return Err(AppError::not_found("分组不存在"));
+        }
+        Ok(())
+    })??;
+    load_shortcuts_from_config(app, &config_manager.read())?;
+    log::info!("Deleted webapp group {}", group_id);
+    Ok(())
+}
+
+/// 就绪检查网址返回 2xx（未设置网址时总是就绪）
+async fn is_healthy(app: &AppHandle, url: Option<&str>) -> bool {
+    let Some(url) = url else {
+        return true;
+    };
+    let (client, _) = net::client(app);
+    client
+        .get(url)
+        .timeout(HEALTH_CHECK_TIMEOUT)
+        .send()
+        .await
+        .is_ok_and(|response| response.status().is_success())
+}
+
+/// 等待成员就绪：窗口已打开（启动命令和隧道就绪后才会打开），并且就绪检查通过
+async fn wait_ready(app: &AppHandle, group: &WebAppGroup, webapp_id: &str) -> bool {
+    let step = step_for(group, webapp_id);
+    let timeout = step
+        .and_then(|s| s.timeout_secs)
+        .map(|secs| Duration::from_secs(secs.into()))
+        .unwrap_or(DEFAULT_STEP_TIMEOUT);
+    let health_check_url = step.and_then(|s| s.health_check_url.as_deref());
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if app.state::<WindowManager>().is_window_active(webapp_id) && is_healthy(app, health_check_url).await {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            log::warn!("Timed out waiting for {} in group {} to become ready", webapp_id, group.id);
+            return false;
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+/// 通知前端分组中的成员因依赖未就绪而跳过或停止打开
+fn emit_step_failed(app: &AppHandle, group_id: &str, webapp_id: &str, dependency: &str, policy: StepFailurePolicy) {
+    events::emit(
+        app,
+        Channel::Windows,
+        "group://step-failed",
+        serde_json::json!({
+            "groupId": group_id,
+            "webappId": webapp_id,
+            "dependency": dependency,
+            "policy": policy,
+        }),
+    );
+}
+
+/// 打开分组中的小程序，返回打开的小程序 ID
+///
+/// 最多打开最大活跃窗口数个，避免分组中后打开的小程序把先打开的挤出 LRU；
+/// 有依赖的成员先等待依赖的成员就绪，未就绪时按依赖成员的 `on_failure` 处理
+pub async fn open(app: &AppHandle, group_id: &str) -> AppResult<Vec<String>> {
+    let config = app.state::<ConfigManager>().read();
+    let group = config.groups.iter().find(|g| g.id == group_id).ok_or_else(|| AppError::not_found("分组不存在"))?;
+    let window_manager = app.state::<WindowManager>();
+    let members = members(&config, group);
+    let max = window_manager.get_max_windows();
+    if members.len() > max {
+        log::warn!(
+            "Group {} has {} webapps, only opening the first {}",
+            group_id,
+            members.len(),
+            max
+        );
+    }
+
+    // 已等待过的成员是否就绪，跳过或打开失败的成员记为未就绪
+    let mut readiness: HashMap<String, bool> = HashMap::new();
+    let mut opened = Vec::new();
+    'members: for webapp in members.into_iter().take(max) {
+        let depends_on = step_for(group, &webapp.id).map(|s| s.depends_on.as_slice()).unwrap_or_default();
+        for dependency in depends_on {
+            let ready = match readiness.get(dependency) {
+                Some(ready) => *ready,
+                None => {
+                    let ready = wait_ready(app, group, dependency).await;
+                    readiness.insert(dependency.clone(), ready);
+                    ready
+                }
+            };
+            if ready {
+                continue;
+            }
+            let policy = step_for(group, dependency).map(|s| s.on_failure).unwrap_or_default();
+            match policy {
+                StepFailurePolicy::Continue => {}
+                StepFailurePolicy::SkipDependents => {
+                    log::warn!("Skipping {} in group {}: {} is not ready", webapp.id, group_id, dependency);
+                    emit_step_failed(app, group_id, &webapp.id, dependency, policy);
+                    readiness.insert(webapp.id.clone(), false);
+                    continue 'members;
+                }
+                StepFailurePolicy::Abort => {
+                    log::warn!("Stopped opening group {}: {} is not ready", group_id, dependency);
+                    emit_step_failed(app, group_id, &webapp.id, dependency, policy);
+                    return Ok(opened);
+                }
+            }
+        }
+
+        match window_manager.open_webapp(app, webapp, config.proxy_url_for(webapp)) {
+            Ok(()) => opened.push(webapp.id.clone()),
+            Err(e) => {
+                log::warn!("Failed to open {} in group {}: {}", webapp.id, group_id, e);
+                readiness.insert(webapp.id.clone(), false);
+            }
+        }
+    }
+    Ok(opened)
+}
+
+/// 关闭分组中已打开的小程序窗口（需要确认的小程序先弹窗），返回关闭的小程序 ID
+pub fn close(app: &AppHandle, group_id: &str) -> AppResult<Vec<String>> {
+    let config = app.state::<ConfigManager>().read();
+    let group = config.groups.iter().find(|g| g.id == group_id).ok_or_else(|| AppError::not_found("分组不存在"))?;
+    let window_manager = app.state::<WindowManager>();
+
+    let mut closed = Vec::new();
+    for webapp_id in group.webapp_ids.iter().filter(|id| window_manager.is_window_active(id)) {
+        match window_manager.request_close(app, webapp_id) {
+            Ok(()) => closed.push(webapp_id.clone()),
+            Err(e) => log::warn!("Failed to close {} in group {}: {}", webapp_id, group_id, e),
+        }
+    }
+    Ok(closed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_members_keep_order() {
+        let mut config = AppConfig::default();
+        let mail = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        let chat = WebApp::new("Chat".to_string(), "https://chat.example.com".to_string());
+        let mut docs = WebApp::new("Docs".to_string(), "https://docs.example.com".to_string());
+        docs.enabled = false;
+        let group = WebAppGroup {
+            id: "work".to_string(),
+            name: "Work".to_string(),
+            color: Some("#3366ff".to_string()),
+            webapp_ids: vec![chat.id.clone(), "deleted".to_string(), docs.id.clone(), mail.id.clone()],
+            shortcut: None,
+            steps: Vec::new(),
+        };
+        config.webapps = vec![mail.clone(), chat.clone(), docs];
+
+        let ids: Vec<&str> = members(&config, &group).iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, vec![chat.id.as_str(), mail.id.as_str()]);
+        assert!(validate(&group).is_ok());
+        assert!(validate(&WebAppGroup { color: Some("blue".to_string()), ..group.clone() }).is_err());
+        assert!(validate(&WebAppGroup { name: " ".to_string(), ..group }).is_err());
+    }
+
+    #[test]
+    fn test_group_steps_depend_on_earlier_members() {
+        let step = |webapp_id: &str, depends_on: &[&str]| GroupStep {
+            webapp_id: webapp_id.to_string(),
+            depends_on: depends_on.iter().map(|id| id.to_string()).collect(),
+            health_check_url: Some("https://vpn.example.com/status".to_string()),
+            timeout_secs: None,
+            on_failure: StepFailurePolicy::default(),
+        };
+        let group = WebAppGroup {
+            id: "intranet".to_string(),
+            name: "Intranet".to_string(),
+            color: None,
+            webapp_ids: vec!["vpn".to_string(), "wiki".to_string()],
+            shortcut: None,
+            steps: vec![step("wiki", &["vpn"])],
+        };
+        assert!(validate(&group).is_ok());
+        assert!(validate(&WebAppGroup { steps: vec![step("vpn", &["wiki"])], ..group.clone() }).is_err());
+        assert!(validate(&WebAppGroup { steps: vec![step("mail", &[])], ..group.clone() }).is_err());
+
+        let mut invalid_url = step("wiki", &["vpn"]);
+        invalid_url.health_check_url = Some("ftp://vpn.example.com".to_string());
+        assert!(validate(&WebAppGroup { steps: vec![invalid_url], ..group }).is_err());
+    }
+}
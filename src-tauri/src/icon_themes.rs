@@ -0,0 +1,182 @@
+//! 启动器图标主题：对缓存的内嵌图标做后处理（上色、垫底色和圆角遮罩），处理结果按主题缓存在内存中
+//!
+//! 只处理内嵌图标（网址图标由前端直接加载）；SVG 等无法解码的图标保持原样
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use parking_lot::Mutex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+
+use crate::icons;
+use crate::models::{IconTheme, WebApp};
+
+/// 处理后图标的最小和最大边长（小图标先放大，圆角边缘才不会有明显的锯齿）
+const MIN_SIZE: u32 = 64;
+const MAX_SIZE: u32 = 256;
+
+/// 一套图标主题的处理参数
+struct Pack {
+    /// 按亮度重新上色（白色即灰度）
+    tint: Option<[u8; 3]>,
+    /// 垫在图标透明区域下的底色
+    background: Option<[u8; 3]>,
+    /// 圆角遮罩的半径，为边长的比例（0.5 即圆形）
+    corner_radius: f32,
+}
+
+fn pack(theme: IconTheme) -> Option<Pack> {
+    match theme {
+        IconTheme::Original => None,
+        IconTheme::Monochrome => Some(Pack {
+            tint: Some([255, 255, 255]),
+            background: None,
+            corner_radius: 0.0,
+        }),
+        IconTheme::Rounded => Some(Pack {
+            tint: None,
+            background: Some([255, 255, 255]),
+            corner_radius: 0.22,
+        }),
+    }
+}
+
+/// 已处理的图标：(小程序 ID, 主题) -> (原图标的哈希, 图标数据)，原图标变化后重新处理
+type Variants = HashMap<(String, IconTheme), (u64, Vec<u8>)>;
+
+static VARIANTS: Mutex<Option<Variants>> = parking_lot::const_mutex(None);
+
+fn source_hash(webapp: &WebApp) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    webapp.icon.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 像素 (x, y) 在圆角方形遮罩中的覆盖率（边缘按一个像素做抗锯齿）
+fn mask_coverage(x: u32, y: u32, size: u32, radius: f32) -> f32 {
+    let (cx, cy, size) = (x as f32 + 0.5, y as f32 + 0.5, size as f32);
+    // 到最近的圆角圆心的距离，不在四个角上时完全覆盖
+    let dx = (radius - cx).max(cx - (size - radius)).max(0.0);
+    let dy = (radius - cy).max(cy - (size - radius)).max(0.0);
+    if dx == 0.0 || dy == 0.0 {
+        return 1.0;
+    }
+    (radius - (dx * dx + dy * dy).sqrt() + 0.5).clamp(0.0, 1.0)
+}
+
+/// 按主题处理图标，返回 PNG 数据
+fn render(bytes: &[u8], pack: &Pack) -> Result<Vec<u8>, String> {
+    let source = image::load_from_memory(bytes)
+        .map_err(|e| format!("无法解码图标: {}", e))?
+        .to_rgba8();
+    let (width, height) = source.dimensions();
+    let longest = width.max(height).max(1);
+    let size = longest.clamp(MIN_SIZE, MAX_SIZE);
+
+    // 按比例缩放，非正方形的图标居中放到正方形画布上
+    let scale = size as f32 / longest as f32;
+    let (w, h) = (
+        ((width as f32 * scale).round() as u32).clamp(1, size),
+        ((height as f32 * scale).round() as u32).clamp(1, size),
+    );
+    let scaled = if (w, h) == (width, height) {
+        source
+    } else {
+        imageops::resize(&source, w, h, FilterType::CatmullRom)
+    };
+    let mut canvas = match pack.background {
+        Some([r, g, b]) => RgbaImage::from_pixel(size, size, Rgba([r, g, b, 255])),
+        None => RgbaImage::new(size, size),
+    };
+    imageops::overlay(&mut canvas, &scaled, ((size - w) / 2) as i64, ((size - h) / 2) as i64);
+
+    let radius = pack.corner_radius * size as f32;
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        if let Some(tint) = pack.tint {
+            let [r, g, b, _] = pixel.0;
+            let luma = (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
+            for (channel, tint) in pixel.0[..3].iter_mut().zip(tint) {
+                *channel = (tint as f32 * luma).round() as u8;
+            }
+        }
+        let coverage = mask_coverage(x, y, size, radius);
+        if coverage < 1.0 {
+            pixel.0[3] = (pixel.0[3] as f32 * coverage).round() as u8;
+        }
+    }
+
+    let mut out = Vec::new();
+    DynamicImage::ImageRgba8(canvas)
+        .write_to(&mut Cursor::new(&mut out), ImageFormat::Png)
+        .map_err(|e| format!("无法生成图标: {}", e))?;
+    Ok(out)
+}
+
+/// 小程序的内嵌图标在指定主题下的数据（原始主题和无法处理的图标返回原图标）
+pub fn themed(webapp: &WebApp, theme: IconTheme) -> Result<Vec<u8>, String> {
+    let Some(pack) = pack(theme) else {
+        return icons::decode(webapp).map(|(_, bytes)| bytes);
+    };
+    let hash = source_hash(webapp);
+    let key = (webapp.id.clone(), theme);
+    if let Some((cached, data)) = VARIANTS.lock().as_ref().and_then(|variants| variants.get(&key)) {
+        if *cached == hash {
+            return Ok(data.clone());
+        }
+    }
+
+    let (_, bytes) = icons::decode(webapp)?;
+    let data = render(&bytes, &pack).unwrap_or_else(|e| {
+        log::debug!("Could not apply icon theme {:?} to {}: {}", theme, webapp.id, e);
+        bytes
+    });
+    VARIANTS
+        .lock()
+        .get_or_insert_with(HashMap::new)
+        .insert(key, (hash, data.clone()));
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    #[test]
+    fn test_themed_icons() {
+        assert_eq!(mask_coverage(0, 0, 64, 14.0), 0.0);
+        assert_eq!(mask_coverage(32, 0, 64, 14.0), 1.0);
+        assert_eq!(mask_coverage(0, 0, 64, 0.0), 1.0);
+
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(16, 8, Rgba([200, 40, 40, 255])))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let mut webapp = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        webapp.icon = Some(format!("data:image/png;base64,{}", STANDARD.encode(&png)));
+        assert_eq!(themed(&webapp, IconTheme::Original).unwrap(), png);
+
+        // 放大到最小边长，居中后上下垫白色底，四角透明
+        let rounded = image::load_from_memory(&themed(&webapp, IconTheme::Rounded).unwrap())
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(rounded.dimensions(), (MIN_SIZE, MIN_SIZE));
+        assert_eq!(rounded.get_pixel(0, 0).0[3], 0);
+        assert_eq!(rounded.get_pixel(32, 32).0, [200, 40, 40, 255]);
+        assert_eq!(rounded.get_pixel(32, 4).0, [255, 255, 255, 255]);
+
+        let [r, g, b, a] = image::load_from_memory(&themed(&webapp, IconTheme::Monochrome).unwrap())
+            .unwrap()
+            .to_rgba8()
+            .get_pixel(32, 32)
+            .0;
+        assert!(r == g && g == b && a == 255);
+
+        // 原图标变化后不使用旧的缓存
+        webapp.icon = Some("data:image/svg+xml,%3Csvg%3E%3C%2Fsvg%3E".to_string());
+        assert_eq!(themed(&webapp, IconTheme::Rounded).unwrap(), b"<svg></svg>");
+    }
+}
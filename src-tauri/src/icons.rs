@@ -0,0 +1,114 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::models::{AppConfig, WebApp};
+
+/// 内嵌图标的引用：`icon-ref:<小程序 ID>`，前端用 `get_webapp_icon` 按需获取图标数据
+pub const ICON_REF_PREFIX: &str = "icon-ref:";
+
+fn is_inline(icon: &str) -> bool {
+    icon.starts_with("data:")
+}
+
+/// 返回给前端的图标：内嵌图标换成引用，网址图标保持不变
+pub fn reference(webapp: &WebApp) -> Option<String> {
+    webapp.icon.as_ref().map(|icon| {
+        if is_inline(icon) {
+            format!("{}{}", ICON_REF_PREFIX, webapp.id)
+        } else {
+            icon.clone()
+        }
+    })
+}
+
+/// 将配置中的内嵌图标换成引用，避免每次读取配置都传输大量 base64 数据
+pub fn strip_inline(config: &mut AppConfig) {
+    for webapp in &mut config.webapps {
+        webapp.icon = reference(webapp);
+    }
+}
+
+/// 前端保存配置时带回的是图标引用，换回当前保存的内嵌图标
+pub fn restore_inline(incoming: &mut AppConfig, current: &AppConfig) {
+    for webapp in &mut incoming.webapps {
+        let referenced = match webapp.icon.as_deref().and_then(|i| i.strip_prefix(ICON_REF_PREFIX)) {
+            Some(id) => id.to_string(),
+            None => continue,
+        };
+        webapp.icon = current
+            .webapps
+            .iter()
+            .find(|w| w.id == referenced)
+            .and_then(|w| w.icon.clone());
+    }
+}
+
+/// 更新单个小程序时传入的图标：引用表示不修改
+pub fn is_reference(icon: &str) -> bool {
+    icon.starts_with(ICON_REF_PREFIX)
+}
+
+/// 解码内嵌图标，返回 (MIME 类型, 图片数据)
+pub fn decode(webapp: &WebApp) -> Result<(String, Vec<u8>), String> {
+    let icon = webapp.icon.as_deref().ok_or("该小程序没有图标")?;
+    let data = icon.strip_prefix("data:").ok_or("该小程序的图标不是内嵌图标")?;
+    let (meta, payload) = data.split_once(',').ok_or("图标数据格式无效")?;
+    let mime = meta.trim_end_matches(";base64").to_string();
+    let bytes = if meta.ends_with(";base64") {
+        STANDARD
+            .decode(payload.trim())
+            .map_err(|e| format!("图标数据格式无效: {}", e))?
+    } else {
+        percent_encoding::percent_decode_str(payload).collect()
+    };
+    Ok((mime, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_and_restore_inline_icons() {
+        let mut config = AppConfig::default();
+        let mut inline = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        inline.icon = Some("data:image/png;base64,iVBORw0K".to_string());
+        let mut remote = WebApp::new("Chat".to_string(), "https://chat.example.com".to_string());
+        remote.icon = Some("https://chat.example.com/icon.png".to_string());
+        config.webapps = vec![inline.clone(), remote.clone()];
+
+        let mut light = config.clone();
+        strip_inline(&mut light);
+        assert_eq!(light.webapps[0].icon, Some(format!("{}{}", ICON_REF_PREFIX, inline.id)));
+        assert_eq!(light.webapps[1].icon, remote.icon);
+
+        restore_inline(&mut light, &config);
+        assert_eq!(light.webapps[0].icon, inline.icon);
+
+        let (mime, bytes) = decode(&inline).unwrap();
+        assert_eq!(mime, "image/png");
+        assert_eq!(&bytes[..4], &[0x89, b'P', b'N', b'G']);
+    }
+
+    #[test]
+    fn test_stripped_config_stays_small() {
+        // 300 个带 16KB 内嵌图标的小程序，去掉图标后返回给前端的数据应保持很小
+        let icon = format!("data:image/png;base64,{}", "A".repeat(16 * 1024));
+        let webapps = (0..300)
+            .map(|i| {
+                let mut webapp = WebApp::new(format!("App {}", i), format!("https://app{}.example.com", i));
+                webapp.icon = Some(icon.clone());
+                webapp
+            })
+            .collect();
+        let mut config = AppConfig {
+            webapps,
+            ..Default::default()
+        };
+
+        strip_inline(&mut config);
+        let payload = serde_json::to_string(&config).unwrap();
+        assert!(payload.len() < 1024 * 1024, "payload is {} bytes", payload.len());
+    }
+}
+
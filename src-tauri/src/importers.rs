@@ -0,0 +1,275 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::models::{AppConfig, WebApp};
+
+/// 读取本地图标文件的最大大小
+const MAX_ICON_BYTES: u64 = 512 * 1024;
+
+/// 窗口尺寸的合理范围，超出时使用默认尺寸
+const SIZE_RANGE: std::ops::RangeInclusive<u64> = 200..=8000;
+
+/// Ferdium（以及 Franz）内置服务的网址，自定义网址的服务不需要
+const FERDIUM_RECIPES: &[(&str, &str)] = &[
+    ("discord", "https://discord.com/app"),
+    ("gmail", "https://mail.google.com"),
+    ("googlecalendar", "https://calendar.google.com"),
+    ("messenger", "https://www.messenger.com"),
+    ("notion", "https://www.notion.so"),
+    ("outlook", "https://outlook.live.com/mail"),
+    ("skype", "https://web.skype.com"),
+    ("telegram", "https://web.telegram.org"),
+    ("trello", "https://trello.com"),
+    ("whatsapp", "https://web.whatsapp.com"),
+];
+
+/// 可以导入的其他应用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    /// WebCatalog 的应用列表（`name`、`url`、`icon`）
+    WebCatalog,
+    /// Ferdium / Franz 的服务列表（`name`、`recipeId`、`customUrl`、`team`）
+    Ferdium,
+    /// Rambox 的服务备份（`name`、`type`、`url`、`logo`）
+    Rambox,
+}
+
+/// 未导入的服务及原因
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedService {
+    pub name: String,
+    pub reason: String,
+}
+
+/// 从其他应用导入的结果（`dry_run` 时只是预览）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignImport {
+    pub source: ImportSource,
+    pub dry_run: bool,
+    /// 将被（或已被）添加的小程序
+    pub webapps: Vec<WebApp>,
+    pub skipped: Vec<SkippedService>,
+}
+
+/// 服务列表：根元素是数组，或对象中 `keys` 之一下的数组或以 ID 为键的对象
+fn entries<'a>(root: &'a Value, keys: &[&str]) -> Vec<&'a Value> {
+    if let Some(items) = root.as_array() {
+        return items.iter().collect();
+    }
+    keys.iter()
+        .find_map(|key| match root.get(key) {
+            Some(Value::Array(items)) => Some(items.iter().collect()),
+            Some(Value::Object(map)) => Some(map.values().collect()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// 根据服务的字段判断来源
+fn detect(root: &Value) -> Option<ImportSource> {
+    let items = entries(root, &["services", "apps", "installed"]);
+    if items.iter().any(|item| item.get("recipeId").is_some()) {
+        Some(ImportSource::Ferdium)
+    } else if items.iter().any(|item| item.get("logo").is_some() || item.get("type").is_some()) {
+        Some(ImportSource::Rambox)
+    } else if items.iter().any(|item| item.get("url").is_some()) {
+        Some(ImportSource::WebCatalog)
+    } else {
+        None
+    }
+}
+
+fn text<'a>(item: &'a Value, key: &str) -> Option<&'a str> {
+    item.get(key).and_then(Value::as_str).map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// 窗口尺寸：`width`/`height` 或 `bounds` 中的尺寸
+fn size(item: &Value) -> Option<(u32, u32)> {
+    let source = item.get("bounds").unwrap_or(item);
+    let width = source.get("width").and_then(Value::as_u64)?;
+    let height = source.get("height").and_then(Value::as_u64)?;
+    (SIZE_RANGE.contains(&width) && SIZE_RANGE.contains(&height)).then_some((width as u32, height as u32))
+}
+
+/// 图标：网址和 data URL 直接使用，本地文件路径（可相对于导入的文件）读取为 data URL
+fn icon(value: &str, base: &Path) -> Option<String> {
+    if value.starts_with("data:") || value.starts_with("https://") || value.starts_with("http://") {
+        return Some(value.to_string());
+    }
+    let path = base.join(value.strip_prefix("file://").unwrap_or(value));
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "ico" => "image/x-icon",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        _ => return None,
+    };
+    if std::fs::metadata(&path).ok()?.len() > MAX_ICON_BYTES {
+        return None;
+    }
+    let bytes = std::fs::read(&path).ok()?;
+    Some(format!("data:{};base64,{}", mime, STANDARD.encode(bytes)))
+}
+
+/// 服务的网址，无法确定时返回原因
+fn service_url(source: ImportSource, item: &Value) -> Result<String, String> {
+    match source {
+        ImportSource::WebCatalog => text(item, "url").map(str::to_string).ok_or_else(|| "没有网址".to_string()),
+        ImportSource::Rambox => match text(item, "url") {
+            // Rambox 的服务模板中 `___` 为团队名，未填写时无法确定网址
+            Some(url) if url.contains("___") => Err("服务需要填写团队名".to_string()),
+            Some(url) => Ok(url.to_string()),
+            None => Err("没有网址".to_string()),
+        },
+        ImportSource::Ferdium => {
+            if let Some(url) = text(item, "customUrl") {
+                return Ok(url.to_string());
+            }
+            let recipe = text(item, "recipeId").unwrap_or_default();
+            if recipe == "slack" {
+                return text(item, "team")
+                    .map(|team| format!("https://{}.slack.com", team))
+                    .ok_or_else(|| "服务需要填写团队名".to_string());
+            }
+            FERDIUM_RECIPES
+                .iter()
+                .find(|(id, _)| *id == recipe)
+                .map(|(_, url)| url.to_string())
+                .ok_or_else(|| format!("不支持的 Ferdium 服务类型: {}", recipe))
+        }
+    }
+}
+
+fn to_webapp(source: ImportSource, item: &Value, base: &Path) -> Result<WebApp, String> {
+    let url = service_url(source, item)?;
+    let parsed = url::Url::parse(&url).map_err(|_| format!("网址无效: {}", url))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("网址无效: {}", url));
+    }
+    let name = text(item, "name")
+        .map(str::to_string)
+        .or_else(|| parsed.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.clone());
+
+    let mut webapp = WebApp::new(name, url);
+    let icon_field = match source {
+        ImportSource::WebCatalog => "icon",
+        ImportSource::Ferdium => "iconUrl",
+        ImportSource::Rambox => "logo",
+    };
+    webapp.icon = text(item, icon_field).and_then(|value| icon(value, base));
+    if let Some((width, height)) = size(item) {
+        webapp.width = width;
+        webapp.height = height;
+    }
+    let enabled = match source {
+        ImportSource::Ferdium => item.get("isEnabled"),
+        _ => item.get("enabled"),
+    };
+    webapp.enabled = enabled.and_then(Value::as_bool).unwrap_or(true);
+    let muted = match source {
+        ImportSource::Ferdium => item.get("isMuted"),
+        _ => item.get("muted"),
+    };
+    webapp.muted = muted.and_then(Value::as_bool).unwrap_or(false);
+    Ok(webapp)
+}
+
+/// 从其他应用的服务列表生成小程序，`base` 为解析相对图标路径的目录；
+/// 已存在相同名称或网址的服务不会导入
+fn convert(current: &AppConfig, root: &Value, source: ImportSource, base: &Path) -> ForeignImport {
+    // 包含已接受的服务，文件中重复的服务也只导入一次
+    let mut taken = AppConfig {
+        webapps: current.webapps.clone(),
+        ..Default::default()
+    };
+    let mut webapps = Vec::new();
+    let mut skipped = Vec::new();
+    for item in entries(root, &["services", "apps", "installed"]) {
+        let name = text(item, "name").unwrap_or("未命名服务").to_string();
+        let webapp = match to_webapp(source, item, base) {
+            Ok(webapp) => webapp,
+            Err(reason) => {
+                skipped.push(SkippedService { name, reason });
+                continue;
+            }
+        };
+        if taken.find_duplicate(&webapp.name, &webapp.url).is_some() {
+            skipped.push(SkippedService {
+                name,
+                reason: "已存在相同名称或网址的小程序".to_string(),
+            });
+            continue;
+        }
+        taken.webapps.push(webapp.clone());
+        webapps.push(webapp);
+    }
+    ForeignImport {
+        source,
+        dry_run: true,
+        webapps,
+        skipped,
+    }
+}
+
+/// 读取其他应用的配置文件，`source` 为空时根据内容判断来源
+pub fn read(current: &AppConfig, path: &Path, source: Option<ImportSource>) -> Result<ForeignImport, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("无法读取文件: {}", e))?;
+    let root: Value = serde_json::from_str(&content).map_err(|e| format!("文件不是有效的 JSON: {}", e))?;
+    let source = source
+        .or_else(|| detect(&root))
+        .ok_or("无法识别文件格式，支持 WebCatalog、Ferdium 和 Rambox 的配置")?;
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+    Ok(convert(current, &root, source, base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_services() {
+        let ferdium = serde_json::json!({ "services": [
+            { "name": "Work", "recipeId": "slack", "team": "acme", "isMuted": true },
+            { "name": "Chat", "recipeId": "whatsapp", "isEnabled": false },
+            { "name": "Wiki", "recipeId": "custom", "customUrl": "https://wiki.example.com" },
+            { "name": "Other", "recipeId": "unknown" }
+        ]});
+        assert_eq!(detect(&ferdium), Some(ImportSource::Ferdium));
+        let result = convert(&AppConfig::default(), &ferdium, ImportSource::Ferdium, Path::new("."));
+        let urls: Vec<&str> = result.webapps.iter().map(|w| w.url.as_str()).collect();
+        assert_eq!(urls, ["https://acme.slack.com", "https://web.whatsapp.com", "https://wiki.example.com"]);
+        assert!(result.webapps[0].muted);
+        assert!(!result.webapps[1].enabled);
+        assert_eq!(result.skipped.len(), 1);
+
+        let rambox = serde_json::json!([
+            { "type": "slack", "name": "Team", "url": "https://___.slack.com/", "logo": "slack.png" },
+            { "type": "custom", "name": "Mail", "url": "https://mail.example.com", "logo": "https://mail.example.com/icon.png" }
+        ]);
+        assert_eq!(detect(&rambox), Some(ImportSource::Rambox));
+        let result = convert(&AppConfig::default(), &rambox, ImportSource::Rambox, Path::new("."));
+        assert_eq!(result.webapps.len(), 1);
+        assert_eq!(result.webapps[0].icon.as_deref(), Some("https://mail.example.com/icon.png"));
+
+        let mut current = AppConfig::default();
+        current.webapps.push(WebApp::new("Mail".to_string(), "https://mail.example.com".to_string()));
+        let webcatalog = serde_json::json!({ "apps": {
+            "a": { "name": "Mail", "url": "https://mail.example.com" },
+            "b": { "name": "Docs", "url": "https://docs.example.com", "width": 1280, "height": 900 }
+        }});
+        assert_eq!(detect(&webcatalog), Some(ImportSource::WebCatalog));
+        let result = convert(&current, &webcatalog, ImportSource::WebCatalog, Path::new("."));
+        assert_eq!(result.webapps.len(), 1);
+        assert_eq!((result.webapps[0].width, result.webapps[0].height), (1280, 900));
+        assert_eq!(result.skipped[0].name, "Mail");
+    }
+}
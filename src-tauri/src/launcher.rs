@@ -0,0 +1,228 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::badges;
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::icons;
+use crate::models::{AppConfig, WebApp};
+use crate::window::WindowManager;
+
+/// 启动器中的一个小程序
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherItem {
+    /// 在整个网格中的位置（从 0 开始），与 `activate_index` 对应
+    pub index: usize,
+    pub webapp_id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub badge: Option<u32>,
+    pub running: bool,
+}
+
+/// 启动器中的分组（未分组的小程序 name 为 None）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherGroup {
+    pub name: Option<String>,
+    pub items: Vec<LauncherItem>,
+}
+
+/// 启动器网格的一页（小程序很多时分页加载）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherPage {
+    /// 网格中小程序的总数
+    pub total: usize,
+    pub groups: Vec<LauncherGroup>,
+}
+
+/// 网格中的一个分组：分组名称（未分组时为 None）和其中的项
+type Section<'a, T> = (Option<&'a str>, Vec<T>);
+
+/// 启用的小程序按网格顺序排列：小程序归入包含它的第一个分组，分组按其中第一个小程序的位置排序，
+/// 组内按 order 排序（order 相同时保持配置中的顺序）
+fn ordered(config: &AppConfig) -> Vec<Section<'_, &WebApp>> {
+    let mut webapps: Vec<&WebApp> = config.webapps.iter().filter(|w| w.enabled).collect();
+    webapps.sort_by_key(|w| w.order);
+
+    let mut groups: Vec<Section<&WebApp>> = Vec::new();
+    for webapp in webapps {
+        let group = config
+            .groups
+            .iter()
+            .find(|g| g.webapp_ids.contains(&webapp.id))
+            .map(|g| g.name.as_str());
+        match groups.iter_mut().find(|(name, _)| *name == group) {
+            Some((_, items)) => items.push(webapp),
+            None => groups.push((group, vec![webapp])),
+        }
+    }
+    groups
+}
+
+/// 按给定的 ID 顺序重新编号所有小程序的 order，未列出的小程序保持原有顺序排在后面
+///
+/// 有不存在或重复的 ID 时不做任何修改
+pub fn reorder(config: &mut AppConfig, ids: &[String]) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    for id in ids {
+        if !seen.insert(id.as_str()) {
+            return Err(format!("小程序 ID 重复: {}", id));
+        }
+        if !config.webapps.iter().any(|w| &w.id == id) {
+            return Err(format!("小程序不存在: {}", id));
+        }
+    }
+
+    let mut rest: Vec<&mut WebApp> = config.webapps.iter_mut().filter(|w| !seen.contains(w.id.as_str())).collect();
+    rest.sort_by_key(|w| w.order);
+    for (order, webapp) in rest.into_iter().enumerate() {
+        webapp.order = (ids.len() + order) as u32;
+    }
+    for webapp in config.webapps.iter_mut() {
+        if let Some(position) = ids.iter().position(|id| *id == webapp.id) {
+            webapp.order = position as u32;
+        }
+    }
+    Ok(())
+}
+
+/// 取网格中第 offset 个小程序起的 limit 个（limit 为空时取到末尾），分组保留在页内有小程序的部分
+fn paginate<'a>(
+    groups: Vec<Section<'a, &'a WebApp>>,
+    offset: usize,
+    limit: Option<usize>,
+) -> Vec<Section<'a, (usize, &'a WebApp)>> {
+    let end = limit.map_or(usize::MAX, |limit| offset.saturating_add(limit));
+    let mut index = 0;
+    groups
+        .into_iter()
+        .filter_map(|(name, webapps)| {
+            let items: Vec<(usize, &WebApp)> = webapps
+                .into_iter()
+                .filter_map(|w| {
+                    let item = (index, w);
+                    index += 1;
+                    (item.0 >= offset && item.0 < end).then_some(item)
+                })
+                .collect();
+            (!items.is_empty()).then_some((name, items))
+        })
+        .collect()
+}
+
+/// 启动器网格（顺序与键盘导航一致），图标为引用，由 `get_webapp_icon` 按需加载
+pub fn grid(app: &AppHandle, offset: usize, limit: Option<usize>) -> LauncherPage {
+    let config = app.state::<ConfigManager>().read();
+    let window_manager = app.state::<WindowManager>();
+    let groups = ordered(&config);
+    let total = groups.iter().map(|(_, webapps)| webapps.len()).sum();
+
+    let groups = paginate(groups, offset, limit)
+        .into_iter()
+        .map(|(name, webapps)| LauncherGroup {
+            name: name.map(str::to_string),
+            items: webapps
+                .into_iter()
+                .map(|(index, w)| LauncherItem {
+                    index,
+                    webapp_id: w.id.clone(),
+                    name: w.name.clone(),
+                    icon: icons::reference(w),
+                    badge: badges::count_for(app, &w.id),
+                    running: window_manager.is_window_active(&w.id),
+                })
+                .collect(),
+        })
+        .collect();
+    LauncherPage { total, groups }
+}
+
+/// 打开网格中第 index 个小程序（从 0 开始）
+pub fn activate_index(app: &AppHandle, index: usize) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = ordered(&config)
+        .into_iter()
+        .flat_map(|(_, webapps)| webapps)
+        .nth(index)
+        .ok_or_else(|| format!("启动器中没有第 {} 个小程序", index + 1))?;
+
+    app.state::<WindowManager>()
+        .open_webapp(app, webapp, config.proxy_url_for(webapp))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WebAppGroup;
+
+    #[test]
+    fn test_reorder() {
+        let mut config = AppConfig::default();
+        for (order, id) in ["a", "b", "c", "d"].iter().enumerate() {
+            let mut webapp = WebApp::new(id.to_string(), format!("https://{}.example.com", id));
+            webapp.id = id.to_string();
+            webapp.order = order as u32 * 10;
+            config.webapps.push(webapp);
+        }
+
+        reorder(&mut config, &["c".to_string(), "a".to_string()]).unwrap();
+        let orders: Vec<(&str, u32)> = config.webapps.iter().map(|w| (w.id.as_str(), w.order)).collect();
+        assert_eq!(orders, [("a", 1), ("b", 2), ("c", 0), ("d", 3)]);
+
+        assert!(reorder(&mut config, &["a".to_string(), "a".to_string()]).is_err());
+        assert!(reorder(&mut config, &["missing".to_string()]).is_err());
+        assert_eq!(config.webapps[2].order, 0);
+    }
+
+    #[test]
+    fn test_ordered_groups_follow_first_member() {
+        let mut config = AppConfig::default();
+        let make = |name: &str, order: u32| {
+            let mut webapp = WebApp::new(name.to_string(), format!("https://{}.example.com", name));
+            webapp.id = name.to_string();
+            webapp.order = order;
+            webapp
+        };
+        let mut disabled = make("disabled", 0);
+        disabled.enabled = false;
+        config.webapps = vec![make("mail", 3), make("music", 2), make("chat", 1), make("notes", 4), disabled];
+        config.groups = vec![WebAppGroup {
+            id: "work".to_string(),
+            name: "Work".to_string(),
+            color: None,
+            webapp_ids: vec!["mail".to_string(), "chat".to_string(), "disabled".to_string()],
+            shortcut: None,
+            steps: Vec::new(),
+        }];
+
+        let groups: Vec<Section<&str>> = ordered(&config)
+            .into_iter()
+            .map(|(name, webapps)| (name, webapps.iter().map(|w| w.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![(Some("Work"), vec!["chat", "mail"]), (None, vec!["music", "notes"])]
+        );
+    }
+
+    #[test]
+    fn test_paginate_keeps_global_indexes() {
+        let make = |name: &str| WebApp::new(name.to_string(), format!("https://{}.example.com", name));
+        let (a, b, c, d) = (make("a"), make("b"), make("c"), make("d"));
+        let groups = vec![(Some("Work"), vec![&a, &b, &c]), (None, vec![&d])];
+
+        let page: Vec<Section<(usize, &str)>> = paginate(groups.clone(), 2, Some(2))
+            .into_iter()
+            .map(|(name, items)| (name, items.into_iter().map(|(i, w)| (i, w.name.as_str())).collect()))
+            .collect();
+        assert_eq!(page, vec![(Some("Work"), vec![(2, "c")]), (None, vec![(3, "d")])]);
+
+        assert_eq!(paginate(groups.clone(), 0, None).len(), 2);
+        assert!(paginate(groups, 10, Some(5)).is_empty());
+    }
+}
+
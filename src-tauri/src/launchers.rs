@@ -0,0 +1,326 @@
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::icons;
+use crate::models::WebApp;
+
+/// 启动项中保存的小程序图标（解码后的内嵌图标）
+struct LauncherIcon {
+    mime: String,
+    bytes: Vec<u8>,
+}
+
+fn icon_of(webapp: &WebApp) -> Option<LauncherIcon> {
+    let (mime, bytes) = icons::decode(webapp).ok()?;
+    Some(LauncherIcon { mime, bytes })
+}
+
+/// PNG 图片的宽和高（读取 IHDR）
+#[cfg(any(target_os = "macos", target_os = "windows", test))]
+fn png_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 24 || &bytes[..8] != b"\x89PNG\r\n\x1a\n" || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// `.desktop` 文件 Exec 中的一个参数：加引号并转义，`%` 写成 `%%`，
+/// 再按字符串规则转义反斜杠（规范要求先转义字符串再解析引号）
+#[cfg(any(target_os = "linux", test))]
+fn exec_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted.replace('\\', "\\\\")
+}
+
+/// 去掉换行等控制字符（`.desktop` 和 plist 的值只能有一行）
+fn single_line(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+/// Linux 的启动项：用命令行 `open` 参数唤起本程序，窗口类一致时桌面把窗口归到启动项下
+#[cfg(any(target_os = "linux", test))]
+fn desktop_entry(webapp: &WebApp, exe: &Path, icon: Option<&Path>) -> String {
+    let mut entry = format!(
+        "[Desktop Entry]\nType=Application\nVersion=1.0\nName={}\nComment={}\nExec={} open {}\nTerminal=false\nCategories=Network;\n",
+        single_line(&webapp.name),
+        single_line(&webapp.url),
+        exec_arg(&exe.to_string_lossy()),
+        exec_arg(&webapp.id),
+    );
+    if let Some(icon) = icon {
+        entry.push_str(&format!("Icon={}\n", icon.to_string_lossy()));
+    }
+    if let Some(class) = webapp.window_class.as_deref().filter(|c| !c.is_empty()) {
+        entry.push_str(&format!("StartupWMClass={}\n", class));
+    }
+    entry
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn xml_escape(text: &str) -> String {
+    single_line(text)
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// macOS 启动器包的 Info.plist：不在程序坞中显示，运行后立即退出
+#[cfg(any(target_os = "macos", test))]
+fn info_plist(webapp: &WebApp, has_icon: bool) -> String {
+    let icon = if has_icon {
+        "  <key>CFBundleIconFile</key>\n  <string>icon</string>\n"
+    } else {
+        ""
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>CFBundleName</key>
+  <string>{name}</string>
+  <key>CFBundleDisplayName</key>
+  <string>{name}</string>
+  <key>CFBundleIdentifier</key>
+  <string>com.webapp-hub.app.launcher.{id}</string>
+  <key>CFBundleExecutable</key>
+  <string>launcher</string>
+  <key>CFBundlePackageType</key>
+  <string>APPL</string>
+{icon}  <key>LSUIElement</key>
+  <true/>
+</dict>
+</plist>
+"#,
+        name = xml_escape(&webapp.name),
+        id = xml_escape(&webapp.id),
+        icon = icon,
+    )
+}
+
+/// 把 PNG 包装为 `.icns`（现代 macOS 支持在 icns 中直接存放 PNG），尺寸不是标准图标尺寸时返回 None
+#[cfg(any(target_os = "macos", test))]
+fn icns(png: &[u8]) -> Option<Vec<u8>> {
+    let kind: &[u8; 4] = match png_size(png)? {
+        (16, 16) => b"icp4",
+        (32, 32) => b"icp5",
+        (64, 64) => b"icp6",
+        (128, 128) => b"ic07",
+        (256, 256) => b"ic08",
+        (512, 512) => b"ic09",
+        (1024, 1024) => b"ic10",
+        _ => return None,
+    };
+    let entry_len = (8 + png.len()) as u32;
+    let mut data = Vec::with_capacity(16 + png.len());
+    data.extend_from_slice(b"icns");
+    data.extend_from_slice(&(8 + entry_len).to_be_bytes());
+    data.extend_from_slice(kind);
+    data.extend_from_slice(&entry_len.to_be_bytes());
+    data.extend_from_slice(png);
+    Some(data)
+}
+
+/// 把 PNG 包装为 `.ico`（Windows Vista 起支持 PNG 格式的图标项），超过 256 像素时返回 None
+#[cfg(any(target_os = "windows", test))]
+fn ico(png: &[u8]) -> Option<Vec<u8>> {
+    let (width, height) = png_size(png)?;
+    if width > 256 || height > 256 {
+        return None;
+    }
+    // 图标项中 0 表示 256 像素
+    let dimension = |size: u32| if size == 256 { 0 } else { size as u8 };
+    let mut data = Vec::with_capacity(22 + png.len());
+    data.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    data.extend_from_slice(&[dimension(width), dimension(height), 0, 0]);
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&32u16.to_le_bytes());
+    data.extend_from_slice(&(png.len() as u32).to_le_bytes());
+    data.extend_from_slice(&22u32.to_le_bytes());
+    data.extend_from_slice(png);
+    Some(data)
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("无法创建目录 {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(path, contents).map_err(|e| format!("无法写入 {}: {}", path.display(), e))
+}
+
+#[cfg(target_os = "linux")]
+fn install(app: &AppHandle, webapp: &WebApp, icon: Option<LauncherIcon>) -> Result<PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("无法确定程序路径: {}", e))?;
+    let icon_path = match icon {
+        Some(icon) if icon.mime == "image/png" || icon.mime == "image/svg+xml" => {
+            let extension = if icon.mime == "image/png" { "png" } else { "svg" };
+            let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("launchers");
+            let path = dir.join(format!("{}.{}", webapp.id, extension));
+            write(&path, &icon.bytes)?;
+            Some(path)
+        }
+        _ => None,
+    };
+    let applications = dirs::data_dir().ok_or("无法确定应用程序目录")?.join("applications");
+    let path = applications.join(format!("webapp-hub-{}.desktop", webapp.id));
+    write(&path, desktop_entry(webapp, &exe, icon_path.as_deref()).as_bytes())?;
+    Ok(path)
+}
+
+#[cfg(target_os = "macos")]
+fn install(_app: &AppHandle, webapp: &WebApp, icon: Option<LauncherIcon>) -> Result<PathBuf, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = dirs::home_dir().ok_or("无法确定用户目录")?;
+    let bundle = home
+        .join("Applications")
+        .join(format!("{}.app", crate::downloads::sanitize(&webapp.name)));
+    let contents = bundle.join("Contents");
+
+    let icns = icon.filter(|icon| icon.mime == "image/png").and_then(|icon| icns(&icon.bytes));
+    if let Some(icns) = &icns {
+        write(&contents.join("Resources").join("icon.icns"), icns)?;
+    }
+    write(&contents.join("Info.plist"), info_plist(webapp, icns.is_some()).as_bytes())?;
+
+    // 通过本应用的协议链接打开，不依赖程序安装的位置
+    let executable = contents.join("MacOS").join("launcher");
+    let script = format!(
+        "#!/bin/sh\nexec /usr/bin/open \"{}://open/{}\"\n",
+        crate::protocols::HUB_SCHEME,
+        webapp.id
+    );
+    write(&executable, script.as_bytes())?;
+    std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("无法设置启动器权限: {}", e))?;
+    Ok(bundle)
+}
+
+#[cfg(target_os = "windows")]
+fn install(app: &AppHandle, webapp: &WebApp, icon: Option<LauncherIcon>) -> Result<PathBuf, String> {
+    use windows::core::{Interface, HSTRING};
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, IPersistFile, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+
+    let exe = std::env::current_exe().map_err(|e| format!("无法确定程序路径: {}", e))?;
+    let icon_path = match icon {
+        Some(icon) => {
+            let bytes = match icon.mime.as_str() {
+                "image/x-icon" | "image/vnd.microsoft.icon" => Some(icon.bytes),
+                "image/png" => ico(&icon.bytes),
+                _ => None,
+            };
+            match bytes {
+                Some(bytes) => {
+                    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("launchers");
+                    let path = dir.join(format!("{}.ico", webapp.id));
+                    write(&path, &bytes)?;
+                    Some(path)
+                }
+                None => None,
+            }
+        }
+        None => None,
+    };
+    let programs = dirs::data_dir()
+        .ok_or("无法确定开始菜单目录")?
+        .join(r"Microsoft\Windows\Start Menu\Programs\WebApp Hub");
+    std::fs::create_dir_all(&programs).map_err(|e| format!("无法创建目录 {}: {}", programs.display(), e))?;
+    let path = programs.join(format!("{}.lnk", crate::downloads::sanitize(&webapp.name)));
+
+    let result: windows::core::Result<()> = unsafe {
+        // 当前线程可能已经初始化过 COM，返回值不影响后续调用
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        (|| {
+            let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+            link.SetPath(&HSTRING::from(exe.as_os_str()))?;
+            link.SetArguments(&HSTRING::from(format!("open {}", webapp.id)))?;
+            link.SetDescription(&HSTRING::from(single_line(&webapp.name)))?;
+            if let Some(icon_path) = &icon_path {
+                link.SetIconLocation(&HSTRING::from(icon_path.as_os_str()), 0)?;
+            }
+            link.cast::<IPersistFile>()?
+                .Save(&HSTRING::from(path.as_os_str()), BOOL::from(true))
+        })()
+    };
+    result.map_err(|e| format!("无法创建快捷方式: {}", e))?;
+    Ok(path)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn install(_app: &AppHandle, _webapp: &WebApp, _icon: Option<LauncherIcon>) -> Result<PathBuf, String> {
+    Err("当前平台不支持导出启动项".to_string())
+}
+
+/// 为小程序生成系统启动项（Linux 的 `.desktop` 文件、macOS 的 `.app` 启动器、Windows 开始菜单的快捷方式），
+/// 让小程序出现在系统的应用列表和程序坞中，返回生成的文件路径
+///
+/// 启动项通过命令行 `open` 参数或协议链接唤起本程序，图标使用小程序的内嵌图标；再次导出会覆盖原来的启动项
+pub fn export(app: &AppHandle, webapp_id: &str) -> Result<String, String> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or("小程序不存在")?;
+    let path = install(app, webapp, icon_of(webapp))?;
+    log::info!("Exported launcher for {} to {}", webapp_id, path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn test_launcher_files() {
+        let mut webapp = WebApp::new("Mail & \"Chat\"".to_string(), "https://mail.example.com".to_string());
+        webapp.window_class = Some("mail".to_string());
+        let entry = desktop_entry(&webapp, Path::new("/opt/Web Hub/webapp-hub"), Some(Path::new("/icons/mail.png")));
+        assert!(entry.contains(&format!("Exec=\"/opt/Web Hub/webapp-hub\" open \"{}\"\n", webapp.id)));
+        assert!(entry.contains("Icon=/icons/mail.png\n"));
+        assert!(entry.contains("StartupWMClass=mail\n"));
+        assert_eq!(exec_arg(r#"50% "a"\b"#), r#""50%% \\"a\\"\\\\b""#);
+
+        let plist = info_plist(&webapp, true);
+        assert!(plist.contains("<string>Mail &amp; &quot;Chat&quot;</string>"));
+        assert!(plist.contains("<key>CFBundleIconFile</key>"));
+
+        assert_eq!(png_size(&png(128, 128)), Some((128, 128)));
+        assert_eq!(png_size(b"GIF89a"), None);
+        let icns = icns(&png(128, 128)).unwrap();
+        assert_eq!(&icns[..4], b"icns");
+        assert_eq!(&icns[8..12], b"ic07");
+        assert_eq!(u32::from_be_bytes(icns[4..8].try_into().unwrap()) as usize, icns.len());
+        assert!(super::icns(&png(100, 100)).is_none());
+        let ico = ico(&png(256, 256)).unwrap();
+        assert_eq!(&ico[..8], &[0, 0, 1, 0, 1, 0, 0, 0]);
+        assert!(super::ico(&png(512, 512)).is_none());
+    }
+}
@@ -1,11 +1,131 @@
+mod accessibility;
+mod audio;
+mod auto_reload;
+mod autostart;
+mod backend;
+mod backup;
+mod badges;
+mod bridge;
+mod browser;
+mod certs;
+mod cli;
+mod clipboard;
 mod commands;
 mod config;
+mod config_store;
+mod config_watch;
+mod control;
+mod cookies;
+mod css;
+mod diagnostics;
+mod dnd;
+mod dock;
+mod domains;
+mod downloads;
+mod error;
+mod events;
+mod favicon;
+mod gpu;
+mod groups;
+mod icon_themes;
+mod icons;
+mod importers;
+mod launcher;
+mod launchers;
+mod lint;
+mod logging;
+mod macros;
+mod main_window;
+mod maintenance;
+mod managed;
+mod media;
+mod memory;
+mod metrics;
+mod migrate;
 mod models;
+mod navigation;
+mod net;
+mod notifications;
+mod onboarding;
+mod patch;
+mod placement;
+mod platform;
+mod policy;
+#[cfg(target_os = "linux")]
+mod portal;
+mod presets;
+mod processes;
+mod profiles;
+mod protocols;
 mod proxy;
+mod proxy_auth;
+mod proxy_env;
+mod proxy_routes;
+mod query;
+mod quiet_hours;
+mod reader;
+mod recent;
+mod redact;
+mod request_log;
+mod rewrite;
+mod safe_mode;
+mod scripting;
+mod secrets;
+mod selection;
+mod session;
+mod settings;
+mod share;
+mod share_target;
+mod shims;
+mod shortcut_check;
 mod shortcuts;
+mod shutdown;
+mod snapshot;
+mod speech;
+mod storage;
+mod switcher;
+mod template;
+mod theme;
+mod throttle;
+mod transfer;
+mod tray;
+mod tunnel;
+mod usage;
+mod userscripts;
+mod viewstate;
+mod watchdog;
+mod webhooks;
 mod window;
+mod window_class;
+mod window_effects;
+mod zoom;
+
+/// 基准测试使用的内部接口（`cargo bench --features bench`）
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::config::ConfigManager;
+    pub use crate::config_store::JsonFileStore;
+    pub use crate::icons::strip_inline;
+    pub use crate::models::{AppConfig, WebApp};
+}
+
+/// 端到端测试使用的内部接口和内存中的窗口、快捷键后端（`cargo test --features e2e`）
+#[cfg(feature = "e2e")]
+#[doc(hidden)]
+pub mod e2e {
+    pub use crate::backend::mock::{MockShortcuts, MockWindows};
+    pub use crate::backend::{WindowBackend, WindowOpener, WindowStatus};
+    pub use crate::config::ConfigManager;
+    pub use crate::config_store::JsonFileStore;
+    pub use crate::models::{AppConfig, ShortcutAction, WebApp};
+    pub use crate::shortcuts::{desired_bindings, ShortcutManager};
+    pub use crate::window::{window_label, Evictions, ToggleResult, WindowManager};
+}
 
 use config::ConfigManager;
+use control::ControlApi;
+use media::MediaSession;
 use tauri::Manager;
 use window::WindowManager;
 
@@ -43,32 +163,111 @@ pub fn run() {
         }
     }));
 
-    // 使用 try_init 避免重复初始化导致 panic
-    let _ = env_logger::try_init();
+    // 日志过滤规则可在运行时修改，启动时先按 RUST_LOG 过滤
+    logging::init();
+
+    // 命令行子命令：list、add 不启动界面直接读写配置，open 在启动后打开小程序（已运行时交给运行中的实例）
+    // `--safe-mode` 可以和其他参数一起使用，先取出来
+    let (safe_mode_requested, args) = safe_mode::take_flag(std::env::args().skip(1).collect());
+    let cli_command = match cli::parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            cli::attach_console();
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    if cli_command.is_some() {
+        cli::attach_console();
+    }
+    if let Some(code) = cli_command.as_ref().and_then(cli::run_offline) {
+        std::process::exit(code);
+    }
+    let open_target = match cli_command {
+        Some(cli::CliCommand::Open { target }) => Some(target),
+        _ => None,
+    };
+
+    let builder = tauri::Builder::default();
+
+    // 单实例插件必须最先注册：再次启动（包括系统转交协议链接）时唤起已运行的实例
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+        if cli::handle_second_instance(app, &argv) {
+            return;
+        }
+        if let Some(main_window) = app.get_webview_window("main") {
+            let _ = main_window.show();
+            let _ = main_window.set_focus();
+        }
+    }));
 
-    let builder = tauri::Builder::default()
+    let builder = builder
         .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_store::Builder::new().build());
+        .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init());
 
     // 全局快捷键插件在某些系统上可能失败（权限问题），需要优雅处理
     #[cfg(desktop)]
     let builder = builder.plugin(tauri_plugin_global_shortcut::Builder::new().build());
 
+    // 开机自启（系统登录项），启动参数用于区分开机自启
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_autostart::init(
+        tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+        Some(vec![autostart::LAUNCH_ARG]),
+    ));
+
     builder
-        .setup(|app| {
+        .setup(move |app| {
             // 初始化配置管理器
-            let config_path = app
-                .path()
-                .app_data_dir()
-                .unwrap_or_default()
-                .join("config.json");
-            let config_manager = ConfigManager::new(config_path);
+            let data_dir = app.path().app_data_dir().unwrap_or_default();
+            let config_manager = ConfigManager::new(config_store::open(&data_dir));
             let config = config_manager.read();
             app.manage(config_manager);
 
+            // 命令行指定或之前连续多次启动失败时以安全模式启动
+            let safe_mode_status = safe_mode::start(&data_dir, safe_mode_requested);
+            app.manage(safe_mode::SafeModeState::new(safe_mode_status));
+            safe_mode::mark_stable_later(data_dir.clone());
+            if config.log_level.is_some() {
+                if let Err(e) = logging::apply(config.log_level.as_deref()) {
+                    log::warn!("Invalid log level in config: {}", e);
+                }
+            }
+
+            // 后台网络任务共享的 HTTP 客户端（遵循代理配置和信任的证书）
+            certs::load(app.handle());
+            proxy_auth::load(&config.proxy);
+            app.manage(net::HttpClient::new(&config.proxy, &config.network));
+
             // 初始化窗口管理器，使用配置中的最大窗口数
             let window_manager = WindowManager::new(config.max_active_windows);
             app.manage(window_manager);
+            app.manage(shutdown::ShutdownState::new());
+            app.manage(processes::ProcessManager::new());
+            app.manage(reader::ReaderState::new());
+            app.manage(dock::DockState::new());
+            app.manage(navigation::NavigationState::new());
+            app.manage(speech::SpeechState::new());
+            app.manage(policy::PolicyState::new());
+            app.manage(badges::BadgeState::new());
+            app.manage(recent::RecentlyClosed::new());
+            app.manage(usage::UsageState::load(app.handle()));
+            app.manage(watchdog::WatchdogState::new());
+            app.manage(request_log::RequestLog::new());
+            app.manage(events::EventBus::new());
+            app.manage(proxy_auth::ProxyAuthState::new());
+            app.manage(zoom::ZoomState::new());
+            app.manage(downloads::DownloadManager::new());
+            app.manage(viewstate::ViewStates::load(app.handle()));
+
+            // 初始化系统媒体会话（媒体键控制小程序中的播放）
+            let media_session = MediaSession::start(app);
+            app.manage(media_session);
 
             // 初始化快捷键管理（如果失败只记录日志，不阻止启动）
             if let Err(e) = shortcuts::setup_shortcuts(app) {
@@ -76,12 +275,95 @@ pub fn run() {
                 // 仍然继续启动，只是快捷键功能不可用
             }
 
+            // 启动本地控制 API（默认关闭）
+            let control_api = ControlApi::new();
+            control_api.apply(app.handle(), &config.control_api);
+            app.manage(control_api);
+
+            // 创建系统托盘（失败不阻止启动）
+            let tray_ready = match tray::setup_tray(app) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::error!("Failed to setup tray: {:?}", e);
+                    false
+                }
+            };
+
+            // 恢复主窗口上次的位置、大小和可见性
+            main_window::restore(app.handle(), tray_ready);
+            dnd::start_monitor(app.handle().clone());
+            quiet_hours::start_monitor(app.handle().clone());
+            dock::start_monitor(app.handle().clone());
+            clipboard::start_monitor(app.handle().clone());
+            watchdog::start(app.handle().clone());
+            auto_reload::start(app.handle().clone());
+            config_watch::start(app.handle().clone());
+            memory::start_monitor(app.handle().clone());
+            proxy_routes::start_monitor(app.handle().clone());
+            favicon::start_refresh_job(app.handle().clone());
+
+            // 注册小程序处理的链接协议
+            protocols::setup(app.handle());
+
+            // 让系统登录项与配置中的开机自启设置一致
+            autostart::reconcile(app.handle(), config.auto_start);
+
+            // 错开打开启动小程序并恢复上次会话中的窗口（安全模式下只打开主窗口）
+            if !safe_mode_status.active {
+                session::restore(app.handle().clone());
+            }
+
+            // 命令行启动时只打开指定的小程序，主窗口留在托盘中
+            if let Some(target) = open_target.as_deref() {
+                if tray_ready {
+                    if let Some(main_window) = app.get_webview_window("main") {
+                        let _ = main_window.hide();
+                    }
+                }
+                if let Err(e) = cli::open(app.handle(), target) {
+                    log::error!("Failed to open {} from command line: {}", target, e);
+                }
+            }
+
+            // 启动自动化脚本定时调度
+            if !safe_mode_status.active {
+                scripting::start_scheduler(app.handle().clone());
+            }
+            if safe_mode_status.automatic {
+                safe_mode::prompt(app.handle());
+            }
+
+            // 启动定时配置备份
+            app.manage(backup::BackupState::new());
+            backup::start_scheduler(app.handle().clone());
+            app.manage(maintenance::MaintenanceState::new());
+            maintenance::start_scheduler(app.handle().clone());
+
             Ok(())
         })
         .on_window_event(|window, event| {
+            // 系统外观变化时更新小程序窗口的深色样式（每个窗口都会收到，只处理一次）
+            if let tauri::WindowEvent::ThemeChanged(appearance) = event {
+                theme::handle_theme_changed(window.app_handle(), *appearance);
+            }
+
+            // 窗口销毁时移除事件订阅，小程序窗口同步窗口管理器的记录
+            if let tauri::WindowEvent::Destroyed = event {
+                if let Some(bus) = window.app_handle().try_state::<events::EventBus>() {
+                    bus.forget(window.label());
+                }
+                navigation::forget(window.app_handle(), window.label());
+                if let Some(webapp_id) = bridge::webapp_id_from_label(window.label()) {
+                    if let Some(manager) = window.app_handle().try_state::<WindowManager>() {
+                        manager.handle_window_destroyed(window.app_handle(), webapp_id);
+                    }
+                }
+            }
+
             // 处理窗口关闭事件，清理资源
             if let tauri::WindowEvent::CloseRequested { .. } = event {
                 if window.label() == "main" {
+                    main_window::save(window.app_handle());
                     // 主窗口关闭时，清理所有快捷键
                     if let Some(manager) = window.app_handle().try_state::<shortcuts::ShortcutManager>() {
                         let _ = manager.clear_all(window.app_handle());
@@ -94,18 +376,150 @@ pub fn run() {
             commands::save_config,
             commands::add_webapp,
             commands::update_webapp,
+            commands::bulk_update_webapps,
             commands::delete_webapp,
             commands::open_webapp,
+            commands::open_webapp_at,
+            commands::toggle_webapp,
             commands::close_webapp,
             commands::set_max_active_windows,
             commands::set_proxy_config,
             commands::register_shortcut,
             commands::unregister_shortcut,
+            commands::get_shortcut_failures,
             commands::open_webapp_window,
             commands::close_webapp_window,
             commands::toggle_webapp_window,
+            commands::run_automation,
+            commands::run_automation_source,
+            commands::run_macro,
+            commands::set_dnd,
+            commands::get_dnd_status,
+            commands::bridge_event,
+            commands::set_webapp_muted,
+            commands::set_mute_all,
+            commands::toggle_mute_all,
+            commands::reader_mode,
+            commands::exit_reader_mode,
+            commands::speak_selection,
+            commands::stop_speaking,
+            commands::translate_selection,
+            commands::list_cookie_sources,
+            commands::import_browser_cookies,
+            commands::get_accessibility,
+            commands::set_accessibility,
+            commands::open_settings,
+            commands::complete_onboarding,
+            commands::share_webapp,
+            commands::import_share_code,
+            commands::lint_config,
+            commands::set_staging_mode,
+            commands::get_platform_capabilities,
+            commands::get_ui_prefs,
+            commands::set_ui_prefs,
+            commands::get_backup_status,
+            commands::run_backup_now,
+            commands::get_maintenance_report,
+            commands::run_maintenance_now,
+            commands::get_storage_usage,
+            commands::set_protocol_handler,
+            commands::set_protocol_enabled,
+            commands::remove_protocol_handler,
+            commands::get_share_candidates,
+            commands::share_to_webapp,
+            commands::set_clipboard_watch,
+            commands::get_policy_status,
+            commands::lock_config,
+            commands::unlock_config,
+            commands::relock_config,
+            commands::disable_config_lock,
+            commands::set_blocked_domains,
+            commands::get_managed_policy,
+            commands::get_badges,
+            commands::get_launcher_grid,
+            commands::activate_index,
+            commands::get_recently_closed,
+            commands::reopen_last_closed,
+            commands::get_usage_stats,
+            commands::recover_webapp,
+            commands::get_memory_status,
+            commands::get_request_log,
+            commands::clear_request_log,
+            commands::add_trusted_ca,
+            commands::list_trusted_cas,
+            commands::remove_trusted_ca,
+            commands::submit_proxy_credentials,
+            commands::cancel_proxy_auth,
+            commands::forget_proxy_credentials,
+            commands::set_monitor_zoom,
+            commands::snapshot_state,
+            commands::restore_state,
+            commands::patch_config,
+            commands::wipe_webapp_data,
+            commands::get_webapp_icon,
+            commands::export_config,
+            commands::import_config,
+            commands::set_auto_start,
+            commands::get_auto_start_status,
+            commands::get_user_scripts,
+            commands::save_user_script,
+            commands::delete_user_script,
+            commands::run_user_script,
+            commands::export_diagnostics,
+            commands::clone_window,
+            commands::set_log_level,
+            commands::get_active_webapps,
+            commands::refresh_webapp_icon,
+            commands::open_in_browser,
+            commands::get_groups,
+            commands::save_group,
+            commands::delete_group,
+            commands::open_group,
+            commands::close_group,
+            commands::get_routing_decisions,
+            commands::set_webapp_pin,
+            commands::list_shortcuts,
+            commands::validate_shortcut,
+            commands::set_mute_all_shortcut,
+            commands::set_network_conditions,
+            commands::subscribe,
+            commands::export_proxy_env,
+            commands::import_from,
+            commands::notify,
+            commands::get_webapp_stats,
+            commands::reset_stats,
+            commands::reorder_webapps,
+            commands::bulk_delete,
+            commands::bulk_set_proxy,
+            commands::get_storage_backend,
+            commands::set_storage_backend,
+            commands::set_webapp_zoom,
+            commands::zoom_webapp,
+            commands::run_script,
+            commands::get_recent_script_runs,
+            commands::list_downloads,
+            commands::open_download_folder,
+            commands::search_webapps,
+            commands::activate_switcher_result,
+            commands::export_webapp_shortcut,
+            commands::get_quiet_hours_status,
+            commands::set_quiet_hours_override,
+            commands::query_state,
+            commands::get_safe_mode_status,
+            commands::exit_safe_mode,
+            commands::set_icon_theme,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::ExitRequested { code, api, .. } = &event {
+                // 退出前清理：此时小程序窗口尚未销毁
+                shutdown::handle_exit_requested(app, *code, api);
+            }
+            if let tauri::RunEvent::Exit = event {
+                // 等待后台任务写完配置再退出
+                app.state::<ConfigManager>().flush();
+            }
+        });
 }
 
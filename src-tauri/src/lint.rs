@@ -0,0 +1,364 @@
+use reqwest::Client;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::domains;
+use crate::models::{AppConfig, InjectTiming};
+use crate::proxy::ProxyManager;
+use crate::rewrite;
+use crate::scripting;
+
+/// 可达性检查的超时（比普通后台请求更短，避免整个检查等待太久）
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// 问题严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+/// 配置检查发现的单个问题
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintIssue {
+    pub severity: LintSeverity,
+    /// 稳定的问题类型，如 `duplicate-shortcut`
+    pub code: &'static str,
+    pub message: String,
+    /// 相关的小程序（全局问题为空）
+    pub webapp_id: Option<String>,
+}
+
+impl LintIssue {
+    fn new(
+        severity: LintSeverity,
+        code: &'static str,
+        message: String,
+        webapp_id: Option<&str>,
+    ) -> Self {
+        Self {
+            severity,
+            code,
+            message,
+            webapp_id: webapp_id.map(str::to_string),
+        }
+    }
+}
+
+/// 检查报告
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    pub fn new(issues: Vec<LintIssue>) -> Self {
+        let errors = issues
+            .iter()
+            .filter(|i| i.severity == LintSeverity::Error)
+            .count();
+        Self {
+            errors,
+            warnings: issues.len() - errors,
+            issues,
+        }
+    }
+}
+
+/// 不需要网络的静态检查
+pub fn lint(config: &AppConfig) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    if let Err(e) = ProxyManager::validate_config(&config.proxy) {
+        issues.push(LintIssue::new(LintSeverity::Error, "invalid-proxy", e.to_string(), None));
+    }
+
+    // 快捷键: 规范化后的快捷键 -> 使用者名称
+    let mut shortcuts: HashMap<String, Vec<String>> = HashMap::new();
+    let mut check_shortcut = |issues: &mut Vec<LintIssue>, shortcut: &str, owner: &str, webapp_id: Option<&str>| {
+        if shortcut.is_empty() {
+            return;
+        }
+        if shortcut.parse::<Shortcut>().is_err() {
+            issues.push(LintIssue::new(
+                LintSeverity::Error,
+                "invalid-shortcut",
+                format!("{} 的快捷键无效: {}", owner, shortcut),
+                webapp_id,
+            ));
+        }
+        shortcuts
+            .entry(shortcut.trim().to_ascii_lowercase())
+            .or_default()
+            .push(owner.to_string());
+    };
+
+    for webapp in &config.webapps {
+        match webapp.url.parse::<url::Url>() {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => issues.push(LintIssue::new(
+                LintSeverity::Error,
+                "invalid-url",
+                format!("{} 的网址无效: {}", webapp.name, webapp.url),
+                Some(&webapp.id),
+            )),
+        }
+
+        if let Some(shortcut) = &webapp.shortcut {
+            check_shortcut(&mut issues, shortcut, &webapp.name, Some(&webapp.id));
+        }
+
+        if (webapp.effective_inject_timing() != InjectTiming::Manual || webapp.inject_on_shortcut)
+            && webapp.inject_script.as_deref().unwrap_or_default().trim().is_empty()
+        {
+            issues.push(LintIssue::new(
+                LintSeverity::Warning,
+                "empty-inject-script",
+                format!("{} 开启了脚本注入但没有脚本", webapp.name),
+                Some(&webapp.id),
+            ));
+        }
+    }
+
+    if let Some(shortcut) = &config.main_window_shortcut {
+        check_shortcut(&mut issues, shortcut, "主窗口", None);
+    }
+    if let Some(shortcut) = &config.switcher_shortcut {
+        check_shortcut(&mut issues, shortcut, "快速切换", None);
+    }
+    if let Some(shortcut) = &config.mute_all_shortcut {
+        check_shortcut(&mut issues, shortcut, "全部静音", None);
+    }
+    for binding in config.shortcut_bindings.iter().filter(|b| b.enabled) {
+        let owner = format!("动作 {}", binding.action.target_key());
+        check_shortcut(&mut issues, &binding.shortcut, &owner, None);
+    }
+
+    let mut duplicates: Vec<_> = shortcuts.into_iter().filter(|(_, owners)| owners.len() > 1).collect();
+    duplicates.sort();
+    for (shortcut, owners) in duplicates {
+        issues.push(LintIssue::new(
+            LintSeverity::Error,
+            "duplicate-shortcut",
+            format!("快捷键 {} 被多次使用: {}", shortcut, owners.join(", ")),
+            None,
+        ));
+    }
+
+    // 重复的小程序（规范化网址相同）
+    let mut urls: HashMap<String, &str> = HashMap::new();
+    for webapp in &config.webapps {
+        if let Some(first) = urls.insert(domains::normalize_url(&webapp.url), &webapp.name) {
+            issues.push(LintIssue::new(
+                LintSeverity::Warning,
+                "duplicate-webapp",
+                format!("{} 与 {} 的网址相同", webapp.name, first),
+                Some(&webapp.id),
+            ));
+        }
+    }
+
+    for rule in &config.rewrite_rules {
+        if let Err(e) = rewrite::validate_rule(rule) {
+            issues.push(LintIssue::new(
+                LintSeverity::Error,
+                "invalid-rewrite-rule",
+                format!("{}: {}", rule.pattern, e),
+                None,
+            ));
+        }
+    }
+
+    if let Some(webapp_id) = config.translation.webapp_id.as_deref() {
+        if !config.webapps.iter().any(|w| w.id == webapp_id) {
+            issues.push(LintIssue::new(
+                LintSeverity::Warning,
+                "unknown-translation-webapp",
+                "划词翻译使用的小程序不存在".to_string(),
+                None,
+            ));
+        }
+    }
+
+    // 协议: 协议名 -> 处理该协议的小程序名称
+    let mut protocols: HashMap<String, Vec<String>> = HashMap::new();
+    for webapp in config.webapps.iter().filter(|w| w.enabled) {
+        for handler in webapp.protocols.iter().filter(|p| p.enabled) {
+            protocols
+                .entry(handler.scheme.to_ascii_lowercase())
+                .or_default()
+                .push(webapp.name.clone());
+        }
+    }
+    for (scheme, owners) in protocols.iter().filter(|(_, owners)| owners.len() > 1) {
+        issues.push(LintIssue::new(
+            LintSeverity::Warning,
+            "duplicate-protocol",
+            format!("{} 协议被多个小程序处理（只有 {} 生效）: {}", scheme, owners[0], owners.join(", ")),
+            None,
+        ));
+    }
+
+    for item in &config.macros {
+        for webapp_id in item.steps.iter().filter_map(|step| step.webapp_id()) {
+            if !config.webapps.iter().any(|w| w.id == webapp_id) {
+                issues.push(LintIssue::new(
+                    LintSeverity::Warning,
+                    "unknown-macro-target",
+                    format!("宏 {} 引用了不存在的小程序: {}", item.name, webapp_id),
+                    None,
+                ));
+            }
+        }
+    }
+
+    for automation in &config.automations {
+        if let Some(run_at) = automation.run_at.as_deref().filter(|t| !t.is_empty()) {
+            if scripting::parse_run_at(run_at).is_none() {
+                issues.push(LintIssue::new(
+                    LintSeverity::Error,
+                    "invalid-schedule",
+                    format!("自动化脚本 {} 的运行时间无效: {}", automation.name, run_at),
+                    None,
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// 检查所有小程序网址是否可以访问（并发请求）
+pub async fn check_reachability(client: Client, config: &AppConfig) -> Vec<LintIssue> {
+    let tasks: Vec<_> = config
+        .webapps
+        .iter()
+        .filter(|w| w.enabled)
+        .map(|webapp| {
+            let client = client.clone();
+            let (id, name, url) = (webapp.id.clone(), webapp.name.clone(), webapp.url.clone());
+            tauri::async_runtime::spawn(async move {
+                let result = client.head(&url).timeout(REACHABILITY_TIMEOUT).send().await;
+                match result {
+                    Ok(_) => None,
+                    Err(e) => Some(LintIssue::new(
+                        LintSeverity::Warning,
+                        "unreachable-url",
+                        format!("{} 无法访问: {}", name, e),
+                        Some(&id),
+                    )),
+                }
+            })
+        })
+        .collect();
+
+    let mut issues = Vec::new();
+    for task in tasks {
+        if let Ok(Some(issue)) = task.await {
+            issues.push(issue);
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Automation, Macro, MacroStep, ProtocolHandler, WebApp};
+
+    fn codes(config: &AppConfig) -> Vec<&'static str> {
+        lint(config).into_iter().map(|i| i.code).collect()
+    }
+
+    #[test]
+    fn test_clean_config() {
+        let mut config = AppConfig::default();
+        config.webapps.push(WebApp::new("Mail".to_string(), "https://mail.example.com".to_string()));
+        assert!(codes(&config).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_shortcuts() {
+        let mut config = AppConfig::default();
+        let mut a = WebApp::new("A".to_string(), "https://a.example.com".to_string());
+        a.shortcut = Some("CmdOrCtrl+Shift+A".to_string());
+        let mut b = WebApp::new("B".to_string(), "https://b.example.com".to_string());
+        b.shortcut = Some("cmdorctrl+shift+a".to_string());
+        config.webapps = vec![a, b];
+
+        assert_eq!(codes(&config), vec!["duplicate-shortcut"]);
+    }
+
+    #[test]
+    fn test_invalid_url_and_duplicate_webapp() {
+        let mut config = AppConfig::default();
+        config.webapps.push(WebApp::new("Bad".to_string(), "not a url".to_string()));
+        config.webapps.push(WebApp::new("One".to_string(), "https://example.com/".to_string()));
+        config.webapps.push(WebApp::new("Two".to_string(), "https://www.example.com".to_string()));
+
+        let codes = codes(&config);
+        assert!(codes.contains(&"invalid-url"));
+        assert!(codes.contains(&"duplicate-webapp"));
+    }
+
+    #[test]
+    fn test_unknown_macro_target() {
+        let mut config = AppConfig::default();
+        let webapp = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        config.macros.push(Macro {
+            id: "m1".to_string(),
+            name: "Morning".to_string(),
+            steps: vec![
+                MacroStep::Open {
+                    webapp_id: webapp.id.clone(),
+                },
+                MacroStep::Wait { ms: 500 },
+                MacroStep::Open {
+                    webapp_id: "missing".to_string(),
+                },
+            ],
+        });
+        config.webapps.push(webapp);
+
+        assert_eq!(codes(&config), vec!["unknown-macro-target"]);
+    }
+
+    #[test]
+    fn test_duplicate_protocol() {
+        let mut config = AppConfig::default();
+        for (name, url) in [("Mail", "https://mail.example.com"), ("Outlook", "https://outlook.example.org")] {
+            let mut webapp = WebApp::new(name.to_string(), url.to_string());
+            webapp.protocols.push(ProtocolHandler {
+                scheme: "mailto".to_string(),
+                url_template: format!("{}/?to={{path}}", url),
+                enabled: true,
+            });
+            config.webapps.push(webapp);
+        }
+
+        assert_eq!(codes(&config), vec!["duplicate-protocol"]);
+    }
+
+    #[test]
+    fn test_invalid_schedule() {
+        let mut config = AppConfig::default();
+        for run_at in ["09:05", "9:05", "25:00", ""] {
+            config.automations.push(Automation {
+                id: run_at.to_string(),
+                name: "Backup".to_string(),
+                source: String::new(),
+                run_at: Some(run_at.to_string()),
+                enabled: true,
+            });
+        }
+
+        assert_eq!(codes(&config), vec!["invalid-schedule", "invalid-schedule"]);
+    }
+}
@@ -0,0 +1,108 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use parking_lot::RwLock;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// 本 crate 的模块路径前缀，日志设置中的简写模块名（如 `window`）会补上它
+const CRATE_PREFIX: &str = "webapp_hub_lib";
+
+/// 可在运行时替换过滤规则的日志器（内部仍使用 env_logger 输出）
+struct HubLogger {
+    inner: RwLock<env_logger::Logger>,
+}
+
+impl Log for HubLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.read().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.inner.read().log(record)
+    }
+
+    fn flush(&self) {
+        self.inner.read().flush()
+    }
+}
+
+static LOGGER: OnceLock<HubLogger> = OnceLock::new();
+
+/// 将日志设置转换为 env_logger 的过滤规则
+///
+/// 格式与 `RUST_LOG` 相同，如 `warn,window=debug`；不含 `::` 的模块名视为本应用的模块
+fn normalize(spec: &str) -> Result<String, String> {
+    let mut directives = Vec::new();
+    for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        let normalized = match directive.split_once('=') {
+            Some((module, level)) => {
+                let (module, level) = (module.trim(), level.trim());
+                LevelFilter::from_str(level).map_err(|_| format!("无效的日志级别: {}", level))?;
+                if module.is_empty() {
+                    return Err(format!("无效的日志设置: {}", directive));
+                }
+                let module = if module.contains("::") || module == CRATE_PREFIX {
+                    module.to_string()
+                } else {
+                    format!("{}::{}", CRATE_PREFIX, module)
+                };
+                format!("{}={}", module, level)
+            }
+            None => {
+                LevelFilter::from_str(directive).map_err(|_| format!("无效的日志级别: {}", directive))?;
+                directive.to_string()
+            }
+        };
+        directives.push(normalized);
+    }
+    Ok(directives.join(","))
+}
+
+fn build(spec: Option<&str>) -> env_logger::Logger {
+    match spec {
+        Some(spec) => env_logger::Builder::new().parse_filters(spec).build(),
+        // 未设置时沿用 RUST_LOG
+        None => env_logger::Builder::from_default_env().build(),
+    }
+}
+
+/// 安装日志器（启动时调用一次，此时按 RUST_LOG 过滤）
+pub fn init() {
+    let logger = LOGGER.get_or_init(|| HubLogger {
+        inner: RwLock::new(build(None)),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(logger.inner.read().filter());
+    }
+}
+
+/// 立即应用日志设置（None 时恢复为 RUST_LOG）
+pub fn apply(spec: Option<&str>) -> Result<(), String> {
+    let normalized = spec
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(normalize)
+        .transpose()?;
+    let logger = match LOGGER.get() {
+        Some(logger) => logger,
+        None => return Ok(()),
+    };
+    let inner = build(normalized.as_deref());
+    log::set_max_level(inner.filter());
+    *logger.inner.write() = inner;
+    log::info!("Log filter set to {}", normalized.as_deref().unwrap_or("RUST_LOG"));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_log_spec() {
+        assert_eq!(normalize("warn,window=debug").unwrap(), "warn,webapp_hub_lib::window=debug");
+        assert_eq!(normalize(" info , tao::platform=error ").unwrap(), "info,tao::platform=error");
+        assert!(normalize("loud").is_err());
+        assert!(normalize("window=loud").is_err());
+        assert!(normalize("=debug").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::models::MacroStep;
+use crate::placement;
+use crate::window::{window_label, WindowManager};
+
+/// 单个等待步骤的最长时间，防止误配置让宏长时间挂起
+const MAX_WAIT_MS: u64 = 60_000;
+
+/// 按 ID 运行宏：依次执行每个步骤，任一步骤失败即停止
+pub async fn run_macro(app: AppHandle, macro_id: String) -> AppResult<()> {
+    let item = {
+        let config = app.state::<ConfigManager>().read();
+        config
+            .macros
+            .iter()
+            .find(|m| m.id == macro_id)
+            .cloned()
+            .ok_or_else(|| AppError::not_found(format!("宏不存在: {}", macro_id)))?
+    };
+
+    log::info!("Running macro: {}", item.name);
+
+    for (index, step) in item.steps.iter().enumerate() {
+        run_step(&app, step)
+            .await
+            .map_err(|e| e.context(format!("宏 {} 第 {} 步失败", item.name, index + 1)))?;
+    }
+
+    Ok(())
+}
+
+async fn run_step(app: &AppHandle, step: &MacroStep) -> AppResult<()> {
+    let window_manager = app.state::<WindowManager>();
+
+    match step {
+        MacroStep::Open { webapp_id } => {
+            let config = app.state::<ConfigManager>().read();
+            let webapp = config
+                .webapps
+                .iter()
+                .find(|w| w.id == *webapp_id)
+                .ok_or_else(|| AppError::not_found(format!("小程序不存在: {}", webapp_id)))?;
+            window_manager.open_webapp(app, webapp, config.proxy_url_for(webapp))
+        }
+        MacroStep::Close { webapp_id } => window_manager.close_webapp(app, webapp_id),
+        MacroStep::Wait { ms } => {
+            tokio::time::sleep(Duration::from_millis((*ms).min(MAX_WAIT_MS))).await;
+            Ok(())
+        }
+        MacroStep::Inject { webapp_id, script } => {
+            Ok(window_manager.inject_script(app, webapp_id, script)?)
+        }
+        MacroStep::Snap {
+            webapp_id,
+            position,
+        } => {
+            let window = app
+                .get_webview_window(&window_label(webapp_id))
+                .ok_or_else(|| AppError::not_found(format!("窗口未打开: {}", webapp_id)))?;
+            Ok(placement::snap(&window, *position)?)
+        }
+    }
+}
+
+/// 在后台运行宏（用于快捷键等不等待结果的调用方）
+pub fn spawn_macro(app: &AppHandle, macro_id: &str) {
+    let app = app.clone();
+    let macro_id = macro_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_macro(app, macro_id.clone()).await {
+            log::error!("Macro {} failed: {}", macro_id, e);
+        }
+    });
+}
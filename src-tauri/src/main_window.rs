@@ -0,0 +1,132 @@
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager};
+
+use crate::config::ConfigManager;
+use crate::models::MainWindowState;
+use crate::placement::{self, Rect};
+use crate::platform;
+
+/// 主窗口的最小尺寸（与 tauri.conf.json 一致）
+const MIN_SIZE: (f64, f64) = (800.0, 600.0);
+
+/// 恢复时的窗口大小：不小于最小尺寸，也不超过最大的显示器（分辨率变小后窗口不会大到无法操作）
+fn fit_size(state: &MainWindowState, monitors: &[Rect]) -> (f64, f64) {
+    let largest = monitors
+        .iter()
+        .fold((0.0_f64, 0.0_f64), |(w, h), m| (w.max(m.2), h.max(m.3)));
+    let clamp = |value: f64, min: f64, max: f64| {
+        if max >= min {
+            value.min(max).max(min)
+        } else {
+            value.max(min)
+        }
+    };
+    (
+        clamp(state.width as f64, MIN_SIZE.0, largest.0),
+        clamp(state.height as f64, MIN_SIZE.1, largest.1),
+    )
+}
+
+/// 启动时恢复主窗口的位置、大小和可见性，保存的位置已不在任何显示器上时居中显示
+///
+/// 没有托盘图标时隐藏的主窗口无法再打开，因此只在托盘可用时恢复隐藏状态
+pub fn restore(app: &AppHandle, tray_ready: bool) {
+    let state = match app.state::<ConfigManager>().read().main_window {
+        Some(state) => state,
+        None => return,
+    };
+    let window = match app.get_webview_window("main") {
+        Some(window) => window,
+        None => return,
+    };
+
+    let monitors = placement::monitors(app);
+    let (width, height) = fit_size(&state, &monitors);
+    let _ = window.set_size(LogicalSize::new(width, height));
+
+    // Wayland 不允许指定窗口位置，交给系统摆放
+    if platform::capabilities().window_positioning {
+        let bounds = (state.x as f64, state.y as f64, width, height);
+        if placement::is_reachable(bounds, &monitors) {
+            let _ = window.set_position(LogicalPosition::new(bounds.0, bounds.1));
+        } else {
+            log::info!("Saved main window position is off-screen, centering");
+            let _ = window.center();
+        }
+    }
+
+    if state.maximized {
+        let _ = window.maximize();
+    }
+    if !state.is_visible && tray_ready {
+        let _ = window.hide();
+    }
+}
+
+/// 保存主窗口的位置、大小和可见性（主窗口关闭和退出时调用，没有变化时不写入）
+pub fn save(app: &AppHandle) {
+    let (window, config_manager) = match (app.get_webview_window("main"), app.try_state::<ConfigManager>()) {
+        (Some(window), Some(config_manager)) => (window, config_manager),
+        _ => return,
+    };
+    let previous = config_manager.read().main_window;
+    let maximized = window.is_maximized().unwrap_or(false);
+    let minimized = window.is_minimized().unwrap_or(false);
+
+    let mut state = match previous.clone() {
+        // 最大化或最小化时的位置和大小不是正常窗口的，沿用上次保存的
+        Some(previous) if maximized || minimized => previous,
+        _ => {
+            let (position, size, scale) =
+                match (window.outer_position(), window.inner_size(), window.scale_factor()) {
+                    (Ok(position), Ok(size), Ok(scale)) if scale > 0.0 => (position, size, scale),
+                    _ => return,
+                };
+            let position = position.to_logical::<f64>(scale);
+            let size = size.to_logical::<f64>(scale);
+            if size.width < 1.0 || size.height < 1.0 {
+                return;
+            }
+            MainWindowState {
+                x: position.x.round() as i32,
+                y: position.y.round() as i32,
+                width: size.width.round() as u32,
+                height: size.height.round() as u32,
+                maximized: false,
+                is_visible: true,
+            }
+        }
+    };
+    state.maximized = maximized;
+    state.is_visible = window.is_visible().unwrap_or(true);
+
+    if previous.as_ref() == Some(&state) {
+        return;
+    }
+    if let Err(e) = config_manager.update(|config| config.main_window = Some(state)) {
+        log::warn!("Failed to save main window state: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_size() {
+        let state = |width, height| MainWindowState {
+            x: 0,
+            y: 0,
+            width,
+            height,
+            maximized: false,
+            is_visible: true,
+        };
+        let monitors = [(0.0, 0.0, 1920.0, 1080.0), (1920.0, 0.0, 1280.0, 1440.0)];
+
+        assert_eq!(fit_size(&state(1200, 900), &monitors), (1200.0, 900.0));
+        assert_eq!(fit_size(&state(400, 300), &monitors), (800.0, 600.0));
+        // 上次在更大的显示器上，现在缩小到最大的显示器
+        assert_eq!(fit_size(&state(3840, 2160), &monitors), (1920.0, 1440.0));
+        assert_eq!(fit_size(&state(3840, 2160), &[]), (3840.0, 2160.0));
+    }
+}
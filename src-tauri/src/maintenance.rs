@@ -0,0 +1,324 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::models::AppConfig;
+use crate::profiles;
+use crate::storage;
+
+/// 定时清理的检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 启动后等待一段时间再进行第一次检查，避免拖慢启动
+const STARTUP_DELAY: Duration = Duration::from_secs(10 * 60);
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// 单个清理目标的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetReport {
+    pub name: &'static str,
+    pub removed_files: usize,
+    pub reclaimed_bytes: u64,
+    pub error: Option<String>,
+}
+
+/// 一次清理的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    pub ran_at: u64,
+    pub removed_files: usize,
+    pub reclaimed_bytes: u64,
+    pub targets: Vec<TargetReport>,
+}
+
+/// 定时清理状态
+pub struct MaintenanceState {
+    last: Mutex<Option<MaintenanceReport>>,
+}
+
+impl MaintenanceState {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(None),
+        }
+    }
+
+    pub fn last_report(&self) -> Option<MaintenanceReport> {
+        self.last.lock().clone()
+    }
+}
+
+impl Default for MaintenanceState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FileEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    /// 修改时间（Unix 秒）
+    pub modified: u64,
+}
+
+/// 清理方式
+enum Limit {
+    /// 总大小上限，超出时从最旧的文件开始删除
+    Size(u64),
+    /// 保留天数，删除更早的文件
+    Age(u64),
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 递归列出目录下的文件（不跟随符号链接）
+pub(crate) fn collect_files(dir: &Path, files: &mut Vec<FileEntry>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let metadata = match entry.path().symlink_metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        if metadata.is_dir() {
+            collect_files(&entry.path(), files);
+        } else if metadata.is_file() {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            files.push(FileEntry {
+                path: entry.path(),
+                size: metadata.len(),
+                modified,
+            });
+        }
+    }
+}
+
+/// 超出大小上限时需要删除的文件（最旧的优先）
+fn over_size(mut files: Vec<FileEntry>, max_bytes: u64) -> Vec<FileEntry> {
+    let mut total: u64 = files.iter().map(|f| f.size).sum();
+    files.sort_by_key(|f| f.modified);
+
+    let mut removed = Vec::new();
+    for file in files {
+        if total <= max_bytes {
+            break;
+        }
+        total -= file.size;
+        removed.push(file);
+    }
+    removed
+}
+
+/// 超过保留时间的文件
+fn older_than(files: Vec<FileEntry>, max_age_secs: u64, now: u64) -> Vec<FileEntry> {
+    let cutoff = now.saturating_sub(max_age_secs);
+    files.into_iter().filter(|f| f.modified < cutoff).collect()
+}
+
+fn select(files: Vec<FileEntry>, limit: Limit) -> Vec<FileEntry> {
+    match limit {
+        Limit::Size(max_bytes) => over_size(files, max_bytes),
+        Limit::Age(max_age_secs) => older_than(files, max_age_secs, now_secs()),
+    }
+}
+
+fn remove(report: &mut TargetReport, candidates: Vec<FileEntry>) {
+    for file in candidates {
+        match std::fs::remove_file(&file.path) {
+            Ok(()) => {
+                report.removed_files += 1;
+                report.reclaimed_bytes += file.size;
+            }
+            // 正在使用的文件（如 Windows 上被锁住的缓存）跳过即可
+            Err(e) => report.error = Some(format!("部分文件无法删除: {}", e)),
+        }
+    }
+}
+
+fn empty_report(name: &'static str) -> TargetReport {
+    TargetReport {
+        name,
+        removed_files: 0,
+        reclaimed_bytes: 0,
+        error: None,
+    }
+}
+
+fn clean(name: &'static str, dirs: &[PathBuf], limit: Limit) -> TargetReport {
+    let mut files = Vec::new();
+    for dir in dirs {
+        collect_files(dir, &mut files);
+    }
+
+    let mut report = empty_report(name);
+    remove(&mut report, select(files, limit));
+    report
+}
+
+/// 按各自的上限清理独立配置文件中的缓存（不支持限制缓存大小的平台靠这里兜底）
+fn clean_profile_caches(limits: &BTreeMap<PathBuf, u64>) -> TargetReport {
+    let mut report = empty_report("profileCaches");
+    for (dir, max_mb) in limits {
+        let mut files = Vec::new();
+        collect_files(dir, &mut files);
+        files.retain(|f| storage::is_cache_file(f.path.strip_prefix(dir).unwrap_or(&f.path)));
+        remove(&mut report, select(files, Limit::Size(max_mb.saturating_mul(1024 * 1024))));
+    }
+    report
+}
+
+/// WebView 的缓存目录
+/// Windows 上 WebView2 的缓存和 Cookie 等数据在同一个目录下，只清理其中的缓存子目录
+fn webview_cache_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let profile = match app.path().app_local_data_dir() {
+            Ok(dir) => dir.join("EBWebView").join("Default"),
+            Err(_) => return Vec::new(),
+        };
+        ["Cache", "Code Cache", "GPUCache"]
+            .iter()
+            .map(|name| profile.join(name))
+            .collect()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        app.path().app_cache_dir().into_iter().collect()
+    }
+}
+
+/// 按配置清理缓存、图标和日志
+fn run(app: &AppHandle, config: &AppConfig) -> MaintenanceReport {
+    let maintenance = &config.maintenance;
+    let icon_dirs: Vec<PathBuf> = app
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("icons"))
+        .into_iter()
+        .collect();
+    let log_dirs: Vec<PathBuf> = app.path().app_log_dir().into_iter().collect();
+
+    let targets = vec![
+        clean(
+            "webviewCache",
+            &webview_cache_dirs(app),
+            Limit::Size(maintenance.cache_max_mb.saturating_mul(1024 * 1024)),
+        ),
+        clean_profile_caches(&profiles::cache_limits(app, config)),
+        clean(
+            "icons",
+            &icon_dirs,
+            Limit::Age(maintenance.icon_max_age_days as u64 * DAY_SECS),
+        ),
+        clean(
+            "logs",
+            &log_dirs,
+            Limit::Age(maintenance.log_max_age_days as u64 * DAY_SECS),
+        ),
+    ];
+
+    MaintenanceReport {
+        ran_at: now_secs(),
+        removed_files: targets.iter().map(|t| t.removed_files).sum(),
+        reclaimed_bytes: targets.iter().map(|t| t.reclaimed_bytes).sum(),
+        targets,
+    }
+}
+
+/// 立即执行一次清理（会阻塞，需在后台线程调用）
+pub fn run_now(app: &AppHandle) -> MaintenanceReport {
+    let config = app.state::<ConfigManager>().read();
+    let report = run(app, &config);
+    log::info!(
+        "Maintenance removed {} files, reclaimed {} bytes",
+        report.removed_files,
+        report.reclaimed_bytes
+    );
+
+    if let Some(state) = app.try_state::<MaintenanceState>() {
+        *state.last.lock() = Some(report.clone());
+    }
+    report
+}
+
+/// 启动定时清理：每小时检查一次，距上次清理超过设定间隔时执行
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let config = app.state::<ConfigManager>().read().maintenance;
+            if !config.enabled {
+                continue;
+            }
+            let last_run = app
+                .state::<MaintenanceState>()
+                .last_report()
+                .map(|r| r.ran_at);
+            let interval_secs = config.interval_hours.max(1) as u64 * 60 * 60;
+            let due = last_run.map(|at| at + interval_secs <= now_secs()).unwrap_or(true);
+            if due {
+                let app = app.clone();
+                let _ = tauri::async_runtime::spawn_blocking(move || run_now(&app)).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: u64, modified: u64) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(name),
+            size,
+            modified,
+        }
+    }
+
+    fn names(files: &[FileEntry]) -> Vec<String> {
+        files.iter().map(|f| f.path.display().to_string()).collect()
+    }
+
+    #[test]
+    fn test_over_size_removes_oldest_first() {
+        let files = vec![file("new", 40, 300), file("old", 40, 100), file("mid", 40, 200)];
+
+        assert_eq!(names(&over_size(files.clone(), 50)), vec!["old", "mid"]);
+        assert!(over_size(files, 120).is_empty());
+    }
+
+    #[test]
+    fn test_older_than() {
+        let now = 100 * DAY_SECS;
+        let files = vec![file("recent", 1, now - DAY_SECS), file("stale", 1, now - 30 * DAY_SECS)];
+
+        assert_eq!(names(&older_than(files, 14 * DAY_SECS, now)), vec!["stale"]);
+    }
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::models::{AppConfig, ProxyConfig, WebApp};
+
+/// 管理员部署的小程序
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedWebApp {
+    /// 固定的 ID，用于与用户配置中已有的小程序对应
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+}
+
+/// 系统级策略文件，由管理员统一部署，加载配置时覆盖到用户配置之上
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedPolicy {
+    /// 必须存在的小程序
+    #[serde(default)]
+    pub webapps: Vec<ManagedWebApp>,
+    /// 锁定的代理设置
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+    /// 追加到黑名单的域名
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+}
+
+impl ManagedPolicy {
+    /// 代理设置是否由策略决定
+    pub fn locks_proxy(&self) -> bool {
+        self.proxy.is_some()
+    }
+
+    /// 将策略覆盖到用户配置上（每次加载和修改配置后调用）
+    pub fn apply(&self, config: &mut AppConfig) {
+        if let Some(proxy) = &self.proxy {
+            config.proxy = proxy.clone();
+        }
+
+        for managed in &self.webapps {
+            let webapp = match config.webapps.iter_mut().find(|w| w.id == managed.id) {
+                Some(webapp) => webapp,
+                None => {
+                    let mut webapp = WebApp::new(managed.name.clone(), managed.url.clone());
+                    webapp.id = managed.id.clone();
+                    webapp.order = config.webapps.len() as u32;
+                    config.webapps.push(webapp);
+                    config.webapps.last_mut().expect("just pushed")
+                }
+            };
+            webapp.name = managed.name.clone();
+            webapp.url = managed.url.clone();
+            webapp.allowed_domains = managed.allowed_domains.clone();
+            if managed.icon.is_some() {
+                webapp.icon = managed.icon.clone();
+            }
+            webapp.managed = true;
+            webapp.enabled = true;
+        }
+        // 策略中已移除的小程序恢复为普通小程序
+        for webapp in config.webapps.iter_mut() {
+            if webapp.managed && !self.webapps.iter().any(|m| m.id == webapp.id) {
+                webapp.managed = false;
+            }
+        }
+
+        for domain in &self.blocked_domains {
+            let domain = domain.trim().to_ascii_lowercase();
+            if !domain.is_empty() && !config.policy.blocked_domains.contains(&domain) {
+                config.policy.blocked_domains.push(domain);
+            }
+        }
+    }
+}
+
+/// 系统级策略文件的位置
+fn policy_path() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("ProgramData")
+            .map(|dir| PathBuf::from(dir).join("WebApp Hub").join("policy.json"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Some(PathBuf::from("/Library/Application Support/WebApp Hub/policy.json"))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        Some(PathBuf::from("/etc/webapp-hub/policy.json"))
+    }
+}
+
+/// 读取系统级策略文件，不存在时返回 None；格式错误时记录日志并忽略
+pub fn load() -> Option<ManagedPolicy> {
+    let path = policy_path()?;
+    let content = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<ManagedPolicy>(&content) {
+        Ok(policy) => {
+            log::info!(
+                "Loaded managed policy from {} ({} webapps)",
+                path.display(),
+                policy.webapps.len()
+            );
+            Some(policy)
+        }
+        Err(e) => {
+            log::error!("Invalid managed policy {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ManagedPolicy {
+        serde_json::from_str(
+            r#"{
+                "webapps": [{ "id": "intranet", "name": "Intranet", "url": "https://intranet.example.com", "allowedDomains": ["*.example.com"] }],
+                "proxy": { "enabled": true, "host": "proxy.example.com", "port": 3128 },
+                "blockedDomains": ["Games.Example.org"]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply_adds_and_locks_managed_webapps() {
+        let mut config = AppConfig::default();
+        policy().apply(&mut config);
+
+        let webapp = config.webapps.iter().find(|w| w.id == "intranet").unwrap();
+        assert!(webapp.managed);
+        assert_eq!(webapp.allowed_domains, vec!["*.example.com"]);
+        assert!(config.proxy.enabled);
+        assert_eq!(config.policy.blocked_domains, vec!["games.example.org"]);
+
+        // 用户修改会在下次应用策略时被覆盖，且不会重复添加
+        config.webapps.iter_mut().find(|w| w.id == "intranet").unwrap().url = "https://evil.example".to_string();
+        config.proxy.enabled = false;
+        policy().apply(&mut config);
+        let managed: Vec<&WebApp> = config.webapps.iter().filter(|w| w.id == "intranet").collect();
+        assert_eq!(managed.len(), 1);
+        assert_eq!(managed[0].url, "https://intranet.example.com");
+        assert!(config.proxy.enabled);
+        assert_eq!(config.policy.blocked_domains.len(), 1);
+    }
+
+    #[test]
+    fn test_removed_from_policy_becomes_regular() {
+        let mut config = AppConfig::default();
+        policy().apply(&mut config);
+        ManagedPolicy::default().apply(&mut config);
+
+        assert!(config.webapps.iter().all(|w| !w.managed));
+    }
+}
@@ -0,0 +1,168 @@
+use parking_lot::Mutex;
+use serde::Deserialize;
+use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, PlatformConfig};
+use std::sync::mpsc;
+use tauri::{AppHandle, Manager};
+
+use crate::window::window_label;
+
+/// 页面上报的媒体播放状态
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaState {
+    pub playing: bool,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub artwork: Option<String>,
+}
+
+enum MediaCommand {
+    Update(MediaState),
+    Clear,
+}
+
+/// 系统媒体会话（Linux MPRIS / macOS Now Playing / Windows SMTC）
+/// 最近开始播放的小程序窗口成为媒体键的控制目标
+pub struct MediaSession {
+    sender: Mutex<Option<mpsc::Sender<MediaCommand>>>,
+    /// 当前媒体来源的小程序 ID
+    source: Mutex<Option<String>>,
+}
+
+impl MediaSession {
+    /// 启动媒体会话线程（平台不支持时仅记录日志）
+    pub fn start(app: &tauri::App) -> Self {
+        #[cfg(target_os = "windows")]
+        let hwnd = app
+            .get_webview_window("main")
+            .and_then(|w| w.hwnd().ok())
+            .map(|h| h.0 as usize);
+        #[cfg(not(target_os = "windows"))]
+        let hwnd: Option<usize> = None;
+
+        let (sender, receiver) = mpsc::channel();
+        let handle = app.handle().clone();
+        std::thread::spawn(move || run_controls(handle, hwnd, receiver));
+
+        Self {
+            sender: Mutex::new(Some(sender)),
+            source: Mutex::new(None),
+        }
+    }
+
+    fn send(&self, command: MediaCommand) {
+        let mut sender = self.sender.lock();
+        if let Some(tx) = sender.as_ref() {
+            if tx.send(command).is_err() {
+                // 媒体线程已退出（平台不支持），不再发送
+                *sender = None;
+            }
+        }
+    }
+
+    /// 处理页面上报的播放状态
+    pub fn update(&self, webapp_id: &str, state: MediaState) {
+        let mut source = self.source.lock();
+        if state.playing {
+            *source = Some(webapp_id.to_string());
+        } else if source.as_deref() != Some(webapp_id) {
+            // 非当前来源的暂停事件不影响系统媒体会话
+            return;
+        }
+        drop(source);
+        self.send(MediaCommand::Update(state));
+    }
+
+    /// 小程序窗口关闭时清理媒体会话
+    pub fn forget(&self, webapp_id: &str) {
+        let mut source = self.source.lock();
+        if source.as_deref() == Some(webapp_id) {
+            *source = None;
+            drop(source);
+            self.send(MediaCommand::Clear);
+        }
+    }
+
+    fn source(&self) -> Option<String> {
+        self.source.lock().clone()
+    }
+}
+
+fn run_controls(app: AppHandle, hwnd: Option<usize>, receiver: mpsc::Receiver<MediaCommand>) {
+    let config = PlatformConfig {
+        dbus_name: "webapp_hub",
+        display_name: "WebApp Hub",
+        hwnd: hwnd.map(|h| h as *mut std::ffi::c_void),
+    };
+
+    let mut controls = match MediaControls::new(config) {
+        Ok(controls) => controls,
+        Err(e) => {
+            log::warn!("Media controls unavailable: {:?}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = controls.attach(move |event| handle_control_event(&app, event)) {
+        log::warn!("Failed to attach media controls: {:?}", e);
+        return;
+    }
+
+    for command in receiver {
+        let result = match command {
+            MediaCommand::Update(state) => controls
+                .set_metadata(MediaMetadata {
+                    title: state.title.as_deref(),
+                    artist: state.artist.as_deref(),
+                    album: state.album.as_deref(),
+                    cover_url: state.artwork.as_deref(),
+                    ..Default::default()
+                })
+                .and_then(|_| {
+                    controls.set_playback(if state.playing {
+                        MediaPlayback::Playing { progress: None }
+                    } else {
+                        MediaPlayback::Paused { progress: None }
+                    })
+                }),
+            MediaCommand::Clear => controls.set_playback(MediaPlayback::Stopped),
+        };
+
+        if let Err(e) = result {
+            log::debug!("Failed to update media session: {:?}", e);
+        }
+    }
+}
+
+/// 把系统媒体键转发给当前媒体来源窗口
+fn handle_control_event(app: &AppHandle, event: MediaControlEvent) {
+    let action = match event {
+        MediaControlEvent::Play => "play",
+        MediaControlEvent::Pause => "pause",
+        MediaControlEvent::Toggle => "toggle",
+        MediaControlEvent::Next => "next",
+        MediaControlEvent::Previous => "previous",
+        MediaControlEvent::Stop => "stop",
+        _ => return,
+    };
+
+    let source = match app.try_state::<MediaSession>().and_then(|s| s.source()) {
+        Some(id) => id,
+        None => return,
+    };
+
+    if let Some(window) = app.get_webview_window(&window_label(&source)) {
+        let script = format!(
+            "window.__WEBAPP_HUB__ && window.__WEBAPP_HUB__.media('{}')",
+            action
+        );
+        if let Err(e) = window.eval(&script) {
+            log::debug!("Failed to forward media key to {}: {}", source, e);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use serde::Serialize;
+use std::time::Duration;
+use sysinfo::System;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::events::{self, Channel};
+use crate::window::{window_label, WindowManager};
+
+/// 可用内存检查间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 系统内存状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStatus {
+    pub total_mb: u64,
+    pub available_mb: u64,
+    /// 可用内存是否低于设置的阈值
+    pub under_pressure: bool,
+}
+
+/// 内存不足时关闭窗口的事件（`memory://suspended`）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SuspendedEvent {
+    webapp_ids: Vec<String>,
+    available_mb: u64,
+}
+
+/// 窗口的候选信息（按最近使用排序，最近使用的在前）
+struct Candidate {
+    webapp_id: String,
+    visible: bool,
+    dirty: bool,
+}
+
+/// 选出要关闭的窗口：从最久未使用的开始，只选隐藏且没有未保存内容的窗口，
+/// 关闭后至少保留 keep 个窗口
+fn pick_victims(candidates: &[Candidate], keep: usize) -> Vec<String> {
+    let budget = candidates.len().saturating_sub(keep);
+    candidates
+        .iter()
+        .rev()
+        .filter(|c| !c.visible && !c.dirty)
+        .take(budget)
+        .map(|c| c.webapp_id.clone())
+        .collect()
+}
+
+fn read_status(system: &mut System, min_available_mb: u64) -> MemoryStatus {
+    system.refresh_memory();
+    let available_mb = system.available_memory() / 1024 / 1024;
+    MemoryStatus {
+        total_mb: system.total_memory() / 1024 / 1024,
+        available_mb,
+        // 部分平台读不到可用内存，返回 0 时不作判断
+        under_pressure: available_mb > 0 && available_mb < min_available_mb,
+    }
+}
+
+/// 当前内存状态
+pub fn status(app: &AppHandle) -> MemoryStatus {
+    let min_available_mb = app.state::<ConfigManager>().read().memory_pressure.min_available_mb;
+    read_status(&mut System::new(), min_available_mb)
+}
+
+/// 内存不足时关闭后台窗口，返回关闭的小程序 ID
+fn relieve(app: &AppHandle, status: &MemoryStatus, keep: usize) -> Vec<String> {
+    let window_manager = app.state::<WindowManager>();
+    let candidates: Vec<Candidate> = window_manager
+        .get_active_window_ids()
+        .into_iter()
+        .map(|webapp_id| Candidate {
+            visible: app
+                .get_webview_window(&window_label(&webapp_id))
+                .and_then(|w| w.is_visible().ok())
+                .unwrap_or(false),
+            dirty: window_manager.is_dirty(&webapp_id),
+            webapp_id,
+        })
+        .collect();
+
+    let victims = pick_victims(&candidates, keep);
+    for webapp_id in &victims {
+        if let Err(e) = window_manager.close_webapp(app, webapp_id) {
+            log::warn!("Failed to suspend {} under memory pressure: {}", webapp_id, e);
+        }
+    }
+
+    if !victims.is_empty() {
+        log::warn!(
+            "Low memory ({} MB available), suspended {} background windows",
+            status.available_mb,
+            victims.len()
+        );
+        events::emit(
+            app,
+            Channel::Windows,
+            "memory://suspended",
+            SuspendedEvent {
+                webapp_ids: victims.clone(),
+                available_mb: status.available_mb,
+            },
+        );
+    }
+    victims
+}
+
+/// 启动内存监控
+pub fn start_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut system = System::new();
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let settings = app.state::<ConfigManager>().read().memory_pressure;
+            if !settings.enabled {
+                continue;
+            }
+            let status = read_status(&mut system, settings.min_available_mb);
+            if status.under_pressure {
+                relieve(&app, &status, settings.keep_windows);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, visible: bool, dirty: bool) -> Candidate {
+        Candidate {
+            webapp_id: id.to_string(),
+            visible,
+            dirty,
+        }
+    }
+
+    #[test]
+    fn test_pick_victims_oldest_hidden_first() {
+        // 最近使用的在前
+        let candidates = vec![
+            candidate("recent", false, false),
+            candidate("visible", true, false),
+            candidate("editing", false, true),
+            candidate("older", false, false),
+            candidate("oldest", false, false),
+        ];
+
+        assert_eq!(pick_victims(&candidates, 2), vec!["oldest", "older", "recent"]);
+        assert_eq!(pick_victims(&candidates, 4), vec!["oldest"]);
+        assert!(pick_victims(&candidates, 5).is_empty());
+    }
+}
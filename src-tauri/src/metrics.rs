@@ -0,0 +1,253 @@
+//! 控制 API 的 `/metrics`：以 Prometheus 文本格式输出运行指标，供 Prometheus / Grafana 采集
+//!
+//! 计数器从启动时开始累计；窗口数和内存在每次采集时读取
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use sysinfo::{Pid, Process, System};
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::models::AppConfig;
+use crate::profiles;
+use crate::window::WindowManager;
+
+/// 没有独立配置文件的 WebView 进程归入的标签
+const SHARED_PROFILE: &str = "shared";
+
+/// 向上查找父进程的最大层数（防止进程表中的循环）
+const MAX_PROCESS_DEPTH: usize = 16;
+
+static SHORTCUT_TRIGGERS: AtomicU64 = AtomicU64::new(0);
+static CONFIG_WRITES: AtomicU64 = AtomicU64::new(0);
+static PROXY_UNREACHABLE: AtomicU64 = AtomicU64::new(0);
+static PROXY_AUTH_REQUIRED: AtomicU64 = AtomicU64::new(0);
+
+/// 代理失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyFailure {
+    /// 健康检查连接不上代理
+    Unreachable,
+    /// 代理要求认证（返回 407）
+    AuthRequired,
+}
+
+/// 记录一次快捷键触发
+pub fn shortcut_triggered() {
+    SHORTCUT_TRIGGERS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 记录一次写入配置存储
+pub fn config_written() {
+    CONFIG_WRITES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 记录一次代理失败
+pub fn proxy_failed(failure: ProxyFailure) {
+    let counter = match failure {
+        ProxyFailure::Unreachable => &PROXY_UNREACHABLE,
+        ProxyFailure::AuthRequired => &PROXY_AUTH_REQUIRED,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 一次采集的全部指标
+#[derive(Debug, Default)]
+struct Snapshot {
+    webapps: u64,
+    open_windows: u64,
+    shortcut_triggers: u64,
+    config_writes: u64,
+    proxy_unreachable: u64,
+    proxy_auth_required: u64,
+    /// 本进程的内存（字节）
+    hub_memory: u64,
+    /// 配置文件 -> 其 WebView 进程的内存（字节）
+    webview_memory: BTreeMap<String, u64>,
+}
+
+/// 进程是否是 `root` 的子孙进程
+fn is_descendant(system: &System, process: &Process, root: Pid) -> bool {
+    let mut parent = process.parent();
+    for _ in 0..MAX_PROCESS_DEPTH {
+        match parent {
+            Some(pid) if pid == root => return true,
+            Some(pid) => parent = system.process(pid).and_then(Process::parent),
+            None => return false,
+        }
+    }
+    false
+}
+
+/// 是否是 WebView 的进程（子进程中还有小程序的启动命令和 SSH 隧道，不计入 WebView）
+fn is_webview_process(name: &str) -> bool {
+    name.to_ascii_lowercase().starts_with("msedgewebview2") || name.starts_with("WebKit")
+}
+
+/// 按命令行中的数据目录判断 WebView 进程属于哪个独立配置文件（WebView2 的 `--user-data-dir`）
+fn profile_of<'a>(cmd: &str, profiles: &'a [(String, String)]) -> &'a str {
+    profiles
+        .iter()
+        .find(|(_, dir)| cmd.contains(dir.as_str()))
+        .map_or(SHARED_PROFILE, |(name, _)| name.as_str())
+}
+
+/// 本进程和各 WebView 子进程的内存
+///
+/// 子进程按独立配置文件统计，其他平台上看不出归属的进程计入 shared；
+/// macOS 的 WebContent 进程不是本进程的子进程，只统计本进程
+fn memory(app: &AppHandle, config: &AppConfig) -> (u64, BTreeMap<String, u64>) {
+    let mut webviews = BTreeMap::new();
+    let Ok(own) = sysinfo::get_current_pid() else {
+        return (0, webviews);
+    };
+    let mut system = System::new();
+    system.refresh_processes();
+    let hub = system.process(own).map_or(0, Process::memory);
+
+    let mut profiles: Vec<(String, String)> = Vec::new();
+    for dir in config.webapps.iter().filter_map(|w| profiles::data_directory(app, w)) {
+        let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let dir = dir.to_string_lossy().into_owned();
+        if !profiles.iter().any(|(_, existing)| *existing == dir) {
+            profiles.push((name, dir));
+        }
+    }
+
+    for process in system.processes().values() {
+        if !is_webview_process(process.name()) || !is_descendant(&system, process, own) {
+            continue;
+        }
+        let profile = profile_of(&process.cmd().join(" "), &profiles);
+        *webviews.entry(profile.to_string()).or_insert(0) += process.memory();
+    }
+    (hub, webviews)
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// 输出一组指标（HELP、TYPE 和各个样本），样本的标签最多一个
+fn family(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(Option<(&str, &str)>, u64)]) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+    for (label, value) in samples {
+        match label {
+            Some((key, label)) => out.push_str(&format!("{}{{{}=\"{}\"}} {}\n", name, key, escape_label(label), value)),
+            None => out.push_str(&format!("{} {}\n", name, value)),
+        }
+    }
+}
+
+fn encode(snapshot: &Snapshot) -> String {
+    let mut out = String::new();
+    family(
+        &mut out,
+        "webapp_hub_info",
+        "gauge",
+        "WebApp Hub version.",
+        &[(Some(("version", env!("CARGO_PKG_VERSION"))), 1)],
+    );
+    family(&mut out, "webapp_hub_webapps", "gauge", "Configured webapps.", &[(None, snapshot.webapps)]);
+    family(
+        &mut out,
+        "webapp_hub_open_windows",
+        "gauge",
+        "Open webapp windows.",
+        &[(None, snapshot.open_windows)],
+    );
+    family(
+        &mut out,
+        "webapp_hub_shortcut_triggers_total",
+        "counter",
+        "Global shortcuts triggered.",
+        &[(None, snapshot.shortcut_triggers)],
+    );
+    family(
+        &mut out,
+        "webapp_hub_config_writes_total",
+        "counter",
+        "Config writes to storage.",
+        &[(None, snapshot.config_writes)],
+    );
+    family(
+        &mut out,
+        "webapp_hub_proxy_failures_total",
+        "counter",
+        "Proxy failures by reason.",
+        &[
+            (Some(("reason", "unreachable")), snapshot.proxy_unreachable),
+            (Some(("reason", "auth_required")), snapshot.proxy_auth_required),
+        ],
+    );
+    family(
+        &mut out,
+        "webapp_hub_process_memory_bytes",
+        "gauge",
+        "Resident memory of the hub process.",
+        &[(None, snapshot.hub_memory)],
+    );
+    let webviews: Vec<(Option<(&str, &str)>, u64)> = snapshot
+        .webview_memory
+        .iter()
+        .map(|(profile, bytes)| (Some(("profile", profile.as_str())), *bytes))
+        .collect();
+    family(
+        &mut out,
+        "webapp_hub_webview_memory_bytes",
+        "gauge",
+        "Resident memory of webview processes by profile.",
+        &webviews,
+    );
+    out
+}
+
+/// 采集当前的指标并输出为 Prometheus 文本格式
+pub fn render(app: &AppHandle) -> String {
+    let config = app.state::<ConfigManager>().read();
+    let open_windows = app
+        .try_state::<WindowManager>()
+        .map_or(0, |wm| wm.get_active_window_ids().len());
+    let (hub_memory, webview_memory) = memory(app, &config);
+    encode(&Snapshot {
+        webapps: config.webapps.len() as u64,
+        open_windows: open_windows as u64,
+        shortcut_triggers: SHORTCUT_TRIGGERS.load(Ordering::Relaxed),
+        config_writes: CONFIG_WRITES.load(Ordering::Relaxed),
+        proxy_unreachable: PROXY_UNREACHABLE.load(Ordering::Relaxed),
+        proxy_auth_required: PROXY_AUTH_REQUIRED.load(Ordering::Relaxed),
+        hub_memory,
+        webview_memory,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_metrics() {
+        let profiles = vec![("Mail".to_string(), "/data/profiles/Mail".to_string())];
+        assert_eq!(
+            profile_of("msedgewebview2.exe --user-data-dir=/data/profiles/Mail/EBWebView", &profiles),
+            "Mail"
+        );
+        assert_eq!(profile_of("WebKitWebProcess 13 45", &profiles), SHARED_PROFILE);
+        assert!(is_webview_process("msedgewebview2.exe") && is_webview_process("WebKitNetworkProcess"));
+        assert!(!is_webview_process("ssh"));
+
+        let snapshot = Snapshot {
+            webapps: 4,
+            open_windows: 2,
+            proxy_auth_required: 1,
+            webview_memory: BTreeMap::from([("Mail".to_string(), 2048), ("a\"b".to_string(), 1)]),
+            ..Snapshot::default()
+        };
+        let text = encode(&snapshot);
+        assert!(text.contains("# TYPE webapp_hub_open_windows gauge\nwebapp_hub_open_windows 2\n"));
+        assert!(text.contains("webapp_hub_proxy_failures_total{reason=\"auth_required\"} 1\n"));
+        assert!(text.contains("webapp_hub_webview_memory_bytes{profile=\"Mail\"} 2048\n"));
+        assert!(text.contains("webapp_hub_webview_memory_bytes{profile=\"a\\\"b\"} 1\n"));
+        assert!(text.ends_with('\n'));
+    }
+}
@@ -0,0 +1,201 @@
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::models::AppConfig;
+
+/// 当前的配置格式版本
+///
+/// 修改配置格式（重命名、移动或改变字段含义）时加 1，并在 `MIGRATIONS` 中添加从上一版本升级的步骤；
+/// 只新增带默认值的字段不需要升级
+pub const CONFIG_VERSION: u32 = 2;
+
+/// 一次升级：把上一版本的配置改为当前步骤的版本
+type Migration = fn(&mut Map<String, Value>);
+
+/// 升级步骤（目标版本, 升级函数），按版本顺序逐步执行
+const MIGRATIONS: &[(u32, Migration)] = &[(1, unversioned_to_v1), (2, named_groups_to_v2)];
+
+/// 解析配置文件内容的结果
+#[derive(Debug)]
+pub struct Parsed {
+    pub config: AppConfig,
+    /// 文件中的版本（没有版本号的旧配置为 0）
+    pub from_version: u32,
+}
+
+impl Parsed {
+    /// 是否从旧版本升级（需要写回文件）
+    pub fn upgraded(&self) -> bool {
+        self.from_version < CONFIG_VERSION
+    }
+
+    /// 是否来自更新版本的程序（保存时会丢失本版本不认识的设置）
+    pub fn written_by_newer(&self) -> bool {
+        self.from_version > CONFIG_VERSION
+    }
+}
+
+/// 没有版本号的配置与版本 1 的格式相同，只补上版本号
+fn unversioned_to_v1(_config: &mut Map<String, Value>) {}
+
+/// 版本 2 去掉了小程序的 `group` 名称：同名的小程序移到同名的分组中（没有时新建，成员按 order 排序），
+/// 按名称引用分组的快捷键改为按分组 ID 引用，`openWorkspace` 改为 `openGroup`
+fn named_groups_to_v2(config: &mut Map<String, Value>) {
+    let mut groups: Vec<Value> = config.get("groups").and_then(Value::as_array).cloned().unwrap_or_default();
+    let mut group_id = |name: &str| -> String {
+        let existing = groups
+            .iter()
+            .find(|g| g.get("name").and_then(Value::as_str) == Some(name))
+            .and_then(|g| g.get("id").and_then(Value::as_str));
+        if let Some(id) = existing {
+            return id.to_string();
+        }
+        let id = Uuid::new_v4().to_string();
+        groups.push(json!({ "id": id, "name": name, "webappIds": [] }));
+        id
+    };
+
+    let mut members: Vec<(u64, String, String)> = Vec::new();
+    for webapp in config.get_mut("webapps").and_then(Value::as_array_mut).into_iter().flatten() {
+        let Some(webapp) = webapp.as_object_mut() else {
+            continue;
+        };
+        let name = match webapp.remove("group") {
+            Some(Value::String(name)) if !name.trim().is_empty() => name,
+            _ => continue,
+        };
+        if let Some(id) = webapp.get("id").and_then(Value::as_str) {
+            let order = webapp.get("order").and_then(Value::as_u64).unwrap_or(0);
+            members.push((order, name, id.to_string()));
+        }
+    }
+    members.sort_by_key(|(order, _, _)| *order);
+    let mut added: Vec<(String, String)> = Vec::new();
+    for (_, name, webapp_id) in members {
+        added.push((group_id(&name), webapp_id));
+    }
+
+    for binding in config.get_mut("shortcutBindings").and_then(Value::as_array_mut).into_iter().flatten() {
+        let Some(action) = binding.get_mut("action").and_then(Value::as_object_mut) else {
+            continue;
+        };
+        let kind = match action.get("type").and_then(Value::as_str) {
+            Some("openWorkspace") => "openGroup",
+            Some("cycleGroup") => "cycleGroup",
+            _ => continue,
+        };
+        let name = action.get("group").and_then(Value::as_str).unwrap_or_default().to_string();
+        *action = json!({ "type": kind, "groupId": group_id(&name) }).as_object().cloned().unwrap_or_default();
+    }
+
+    for (id, webapp_id) in added {
+        let group = groups.iter_mut().find(|g| g.get("id").and_then(Value::as_str) == Some(id.as_str()));
+        let ids = group.and_then(|g| g.as_object_mut()).map(|g| g.entry("webappIds").or_insert(json!([])));
+        if let Some(Value::Array(ids)) = ids {
+            if !ids.iter().any(|w| w.as_str() == Some(webapp_id.as_str())) {
+                ids.push(Value::from(webapp_id));
+            }
+        }
+    }
+    if !groups.is_empty() {
+        config.insert("groups".to_string(), Value::Array(groups));
+    }
+}
+
+fn version_of(config: &Map<String, Value>) -> u32 {
+    config
+        .get("version")
+        .and_then(Value::as_u64)
+        .map(|v| v.min(u32::MAX as u64) as u32)
+        .unwrap_or(0)
+}
+
+/// 按顺序执行版本高于配置版本的升级步骤，返回升级前的版本
+fn upgrade(config: &mut Map<String, Value>, migrations: &[(u32, Migration)]) -> u32 {
+    let from = version_of(config);
+    for (version, migration) in migrations.iter().filter(|(version, _)| *version > from) {
+        log::info!("Migrating config to version {}", version);
+        migration(config);
+        config.insert("version".to_string(), Value::from(*version));
+    }
+    from
+}
+
+/// 解析配置文件内容：先升级旧版本的格式，再转换为配置
+pub fn parse(content: &str) -> Result<Parsed, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("配置文件不是有效的 JSON: {}", e))?;
+    let mut map = match value {
+        Value::Object(map) => map,
+        _ => return Err("配置文件的内容不是对象".to_string()),
+    };
+    let from_version = upgrade(&mut map, MIGRATIONS);
+    let config = serde_json::from_value(Value::Object(map)).map_err(|e| format!("配置文件格式错误: {}", e))?;
+    Ok(Parsed { config, from_version })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename_width(config: &mut Map<String, Value>) {
+        if let Some(width) = config.remove("width") {
+            config.insert("windowWidth".to_string(), width);
+        }
+    }
+
+    fn double_width(config: &mut Map<String, Value>) {
+        if let Some(width) = config.get("windowWidth").and_then(Value::as_u64) {
+            config.insert("windowWidth".to_string(), Value::from(width * 2));
+        }
+    }
+
+    #[test]
+    fn test_upgrade_runs_pending_steps_in_order() {
+        let migrations: &[(u32, Migration)] = &[(1, unversioned_to_v1), (2, rename_width), (3, double_width)];
+
+        let mut legacy = serde_json::json!({ "width": 100 }).as_object().unwrap().clone();
+        assert_eq!(upgrade(&mut legacy, migrations), 0);
+        assert_eq!(Value::Object(legacy), serde_json::json!({ "windowWidth": 200, "version": 3 }));
+
+        // 已经是版本 2 的配置只执行之后的步骤
+        let mut v2 = serde_json::json!({ "version": 2, "windowWidth": 100 }).as_object().unwrap().clone();
+        assert_eq!(upgrade(&mut v2, migrations), 2);
+        assert_eq!(v2["windowWidth"], 200);
+
+        let parsed = parse("{}").unwrap();
+        assert!(parsed.upgraded());
+        assert_eq!(parsed.config.version, CONFIG_VERSION);
+        assert!(parse(r#"{ "webapps": 1 }"#).is_err());
+        assert!(parse("[").is_err());
+    }
+
+    #[test]
+    fn test_named_groups_move_to_group_list() {
+        let mut config = serde_json::json!({
+            "version": 1,
+            "webapps": [
+                { "id": "mail", "group": "Work", "order": 2 },
+                { "id": "chat", "group": "Work", "order": 1 },
+                { "id": "music", "group": "" },
+            ],
+            "groups": [{ "id": "existing", "name": "Work", "webappIds": ["wiki"] }],
+            "shortcutBindings": [
+                { "shortcut": "Ctrl+1", "action": { "type": "openWorkspace", "group": "Work" } },
+                { "shortcut": "Ctrl+2", "action": { "type": "cycleGroup", "group": "Home" } },
+            ],
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        named_groups_to_v2(&mut config);
+
+        assert!(config["webapps"].as_array().unwrap().iter().all(|w| w.get("group").is_none()));
+        let groups = config["groups"].as_array().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0]["webappIds"], serde_json::json!(["wiki", "chat", "mail"]));
+        assert_eq!(groups[1]["name"], "Home");
+        let bindings = config["shortcutBindings"].as_array().unwrap();
+        assert_eq!(bindings[0]["action"], serde_json::json!({ "type": "openGroup", "groupId": "existing" }));
+        assert_eq!(bindings[1]["action"]["groupId"], groups[1]["id"]);
+    }
+}
@@ -1,7 +1,19 @@
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use serde::{Deserialize, Serialize};
+use std::net::Ipv6Addr;
 use uuid::Uuid;
 
+use crate::certs::TrustedCa;
+use crate::domains;
+use crate::icons;
+use crate::migrate::CONFIG_VERSION;
+use crate::placement::{SnapPosition, WindowPlacement};
+use crate::proxy::ProxyManager;
+use crate::proxy_auth;
+use crate::proxy_routes;
+use crate::tunnel;
+use crate::zoom::ZoomStep;
+
 /// 网页小程序配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -39,9 +51,351 @@ pub struct WebApp {
     /// 是否在页面加载时注入
     #[serde(default)]
     pub inject_on_load: bool,
+    /// 注入时机，未设置时按 `inject_on_load` 决定（开启时为 `document-end`）
+    #[serde(default)]
+    pub inject_timing: Option<InjectTiming>,
     /// 是否在快捷键显示时注入
     #[serde(default)]
     pub inject_on_shortcut: bool,
+    /// 快捷键注入在每个窗口生命周期内只执行一次
+    #[serde(default)]
+    pub inject_once: bool,
+    /// 自定义样式 (CSS)
+    #[serde(default)]
+    pub custom_css: Option<String>,
+    /// 时区覆盖 (IANA 时区名，如 `America/New_York`)
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// 首选麦克风（设备名称关键字或设备 ID）
+    #[serde(default)]
+    pub preferred_audio_input: Option<String>,
+    /// 首选摄像头（设备名称关键字或设备 ID）
+    #[serde(default)]
+    pub preferred_video_input: Option<String>,
+    /// 是否静音
+    #[serde(default)]
+    pub muted: bool,
+    /// 失去焦点多少秒后自动隐藏窗口（为空或 0 表示不自动隐藏）
+    #[serde(default)]
+    pub auto_hide_seconds: Option<u32>,
+    /// 除主页域名外允许打开的域名模式，如 `*.atlassian.net`
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// 会话恢复时该小程序额外的启动延迟（毫秒）
+    #[serde(default)]
+    pub startup_delay_ms: Option<u32>,
+    /// 应用退出前在窗口中执行的脚本（例如点击保存）
+    #[serde(default)]
+    pub on_close_script: Option<String>,
+    /// 是否启用（停用的小程序不注册快捷键，也不能打开窗口）
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 是否为首次启动时添加的示例小程序
+    #[serde(default)]
+    pub sample: bool,
+    /// 在所有工作区/虚拟桌面上显示（如悬浮聊天窗口）
+    #[serde(default)]
+    pub visible_on_all_workspaces: bool,
+    /// 打开前启动的本地命令（如 `npm run dev`），等待网址端口可连接后再打开窗口
+    #[serde(default)]
+    pub start_command: Option<String>,
+    /// 启动命令的工作目录
+    #[serde(default)]
+    pub start_working_dir: Option<String>,
+    /// 关闭窗口时是否停止启动的进程
+    #[serde(default = "default_true")]
+    pub stop_process_on_close: bool,
+    /// 通过 SSH 端口转发访问内网小程序
+    #[serde(default)]
+    pub ssh_tunnel: Option<SshTunnel>,
+    /// 由该小程序处理的链接协议（如 `mailto`）
+    #[serde(default)]
+    pub protocols: Vec<ProtocolHandler>,
+    /// 作为分享目标时打开的网址模板，`{url}`、`{text}`、`{title}` 为分享的内容
+    #[serde(default)]
+    pub share_url_template: Option<String>,
+    /// 由系统策略文件预置，不能删除，名称、网址和允许的域名由策略决定
+    #[serde(default)]
+    pub managed: bool,
+    /// 页面崩溃或无响应时的处理方式
+    #[serde(default)]
+    pub crash_recovery: CrashRecovery,
+    /// 记录页面发出的 fetch/XHR 请求（用于排查问题，默认关闭）
+    #[serde(default)]
+    pub request_log: bool,
+    /// 页面缩放比例（百分比），为空时为 100%
+    #[serde(default)]
+    pub zoom_percent: Option<u32>,
+    /// 按显示器单独设置的缩放，窗口移到该显示器时使用
+    #[serde(default)]
+    pub monitor_zoom: Vec<MonitorZoom>,
+    /// 启动时总是在后台打开（与会话恢复无关），随时可以用快捷键呼出
+    #[serde(default)]
+    pub launch_on_start: bool,
+    /// 单独使用的代理（启用时优先于全局代理设置）
+    #[serde(default)]
+    pub proxy_override: Option<ProxyConfig>,
+    /// 使用独立的 Cookie、缓存和网站存储（例如在两个小程序中登录不同的账号），重新打开窗口后生效
+    #[serde(default)]
+    pub isolated_session: bool,
+    /// 独立配置文件的目录名（多个小程序填同一名称即共用）或绝对路径，设置后自动隔离
+    #[serde(default)]
+    pub data_dir: Option<String>,
+    /// 是否允许页面运行 JavaScript（只读的参考网站可以关闭以提速和提高安全性），重新打开窗口后生效
+    #[serde(default = "default_true")]
+    pub javascript_enabled: bool,
+    /// 引用的脚本库脚本 ID，按顺序注入，重新打开窗口后生效
+    #[serde(default)]
+    pub script_ids: Vec<String>,
+    /// 独立配置文件的缓存大小上限（MB），未设置时使用全局上限；使用共享配置文件时不生效
+    #[serde(default)]
+    pub cache_max_mb: Option<u64>,
+    /// 允许复制窗口，同时打开多个窗口（例如并排比较两个文档）
+    #[serde(default)]
+    pub multi_instance: bool,
+    /// 关闭窗口（包括 LRU 淘汰）前先弹窗确认，防止误关正在编辑的页面
+    #[serde(default)]
+    pub confirm_close: bool,
+    /// 跳转到主页域名和允许的域名（`allowed_domains`）以外的网页时，在系统浏览器中打开而不是留在小程序窗口中
+    #[serde(default)]
+    pub open_external_in_browser: bool,
+    /// 登录时经过的身份提供方域名模式（如 `accounts.google.com`），开启 `open_external_in_browser` 时也留在窗口中
+    #[serde(default)]
+    pub auth_domains: Vec<String>,
+    /// 开启 `open_external_in_browser` 时，跳转到范围外的网页前先询问（可以允许这一次在窗口中打开）
+    #[serde(default)]
+    pub confirm_external_navigation: bool,
+    /// 自动获取网站图标的时间（Unix 秒），定时刷新只更新自动获取的图标；用户设置图标后为 None
+    #[serde(default)]
+    pub icon_fetched_at: Option<u64>,
+    /// 窗口置顶（如用作悬浮的笔记、翻译面板）
+    #[serde(default)]
+    pub always_on_top: bool,
+    /// 不在任务栏中显示窗口
+    #[serde(default)]
+    pub skip_taskbar: bool,
+    /// 显示窗口标题栏和边框，关闭时为无边框窗口
+    #[serde(default = "default_true")]
+    pub decorations: bool,
+    /// 窗口类（Linux X11 上的 WM_CLASS，Windows 上的 AppUserModelID），
+    /// 设置后任务栏和窗口管理器把小程序当作独立的应用，单独分组并显示自己的图标
+    #[serde(default)]
+    pub window_class: Option<String>,
+    /// 窗口打开期间每隔多少分钟自动刷新页面（用于状态看板），窗口有焦点时推迟
+    #[serde(default)]
+    pub auto_reload_minutes: Option<u32>,
+    /// 开发者选项：模拟的网络条件（离线或限速），未设置时不限制
+    #[serde(default)]
+    pub network_profile: Option<NetworkProfile>,
+    /// 自定义 User-Agent（部分网站按 UA 提供移动版或桌面版页面），未设置时使用系统 WebView 的默认值
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// 页面的网页通知转发为系统通知（窗口隐藏时也能收到）
+    #[serde(default = "default_true")]
+    pub allow_notifications: bool,
+    /// 是否使用硬件加速（GPU），为空时使用全局设置；部分网站在内嵌 WebView 中使用 GPU 时显示异常
+    #[serde(default)]
+    pub hardware_acceleration: Option<bool>,
+    /// 网页通知的过滤规则（关键词、发送者和频率），未设置时显示所有通知
+    #[serde(default)]
+    pub notification_rules: Option<NotificationRules>,
+    /// 下载文件的保存目录，未设置时使用全局下载目录
+    #[serde(default)]
+    pub download_dir: Option<String>,
+    /// 窗口的半透明背景效果（创建窗口时应用，修改后重新打开窗口生效）
+    #[serde(default)]
+    pub window_background: WindowBackground,
+    /// 安全加固预设（同时控制第三方 Cookie、WebRTC、跟踪器拦截和临时存储），重新打开窗口后生效
+    #[serde(default)]
+    pub security_preset: SecurityPreset,
+    /// 系统为深色外观时追加的样式（切换系统外观时实时应用到已打开的窗口）
+    #[serde(default)]
+    pub dark_css: Option<String>,
+    /// 系统为深色外观时用反色滤镜强制深色显示（适合没有深色模式的网站）
+    #[serde(default)]
+    pub force_dark: bool,
+    /// 停靠到屏幕边缘：失去焦点时收起成细边，鼠标移到边缘或按快捷键时滑出
+    #[serde(default)]
+    pub dock_edge: DockEdge,
+}
+
+/// 模拟的网络条件（用于在小程序中测试本地开发的网页）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NetworkProfile {
+    /// 离线：请求直接失败，`navigator.onLine` 为 false
+    Offline,
+    /// 慢速 3G（延迟 2000 毫秒，下载 400 kbps）
+    Slow3g,
+    /// 快速 3G（延迟 560 毫秒，下载 1600 kbps）
+    Fast3g,
+    /// 自定义延迟和下载速度
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        latency_ms: u32,
+        #[serde(default)]
+        download_kbps: Option<u32>,
+    },
+}
+
+/// 页面加载时注入脚本的时机（每次导航都会执行）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InjectTiming {
+    /// 页面脚本执行前（此时 DOM 尚未解析，适合包装 `fetch` 等全局对象）
+    DocumentStart,
+    /// DOMContentLoaded 之后
+    #[default]
+    DocumentEnd,
+    /// 不自动注入，只在快捷键或手动触发时注入
+    Manual,
+}
+
+/// 某台显示器上的页面缩放
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorZoom {
+    /// 显示器名称
+    pub monitor: String,
+    pub zoom_percent: u32,
+}
+
+/// 页面崩溃或无响应时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CrashRecovery {
+    /// 自动重新加载
+    #[default]
+    Reload,
+    /// 询问用户（由主窗口显示提示）
+    Ask,
+    /// 关闭窗口，不再打开
+    LeaveClosed,
+}
+
+/// 小程序窗口的背景效果（macOS 的毛玻璃材质，Windows 的 Acrylic / Mica），适合悬浮的便签类小程序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowBackground {
+    /// 普通的不透明窗口
+    #[default]
+    Opaque,
+    /// macOS 的窗口底层材质，Windows 上为模糊背景
+    Vibrancy,
+    /// 较强的模糊效果（Windows 10 及以上）
+    Acrylic,
+    /// 带桌面色调的背景（Windows 11 及以上），macOS 上为侧边栏材质
+    Mica,
+}
+
+/// 小程序窗口的安全加固预设，创建窗口时由 `presets` 展开为具体的设置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecurityPreset {
+    /// 不做额外限制
+    #[default]
+    Off,
+    /// 拦截第三方 Cookie 和常见跟踪器，不影响网页功能
+    Balanced,
+    /// 在 balanced 的基础上禁用 WebRTC，并使用关闭窗口即清除的临时存储（每次都需要重新登录）
+    Strict,
+}
+
+/// 小程序窗口停靠的屏幕边缘，由 `dock` 模块处理
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DockEdge {
+    /// 不停靠，普通窗口
+    #[default]
+    Off,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// 启动器图标主题：对缓存的内嵌图标做后处理，由 `icon_themes` 模块处理
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IconTheme {
+    /// 原始图标
+    #[default]
+    Original,
+    /// 灰度图标
+    Monochrome,
+    /// 统一的圆角方形
+    Rounded,
+}
+
+/// 协议处理设置：系统中打开该协议的链接时，在小程序窗口中打开模板生成的网址
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolHandler {
+    /// 协议名（不含 `:`），如 `mailto`、`slack`
+    pub scheme: String,
+    /// 网址模板，`{url}` 为完整链接，`{path}` 为协议名之后的部分
+    pub url_template: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// SSH 隧道设置：打开窗口时建立 `-L` 端口转发，窗口指向本地端口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshTunnel {
+    /// SSH 服务器
+    pub host: String,
+    /// SSH 用户名
+    #[serde(default)]
+    pub user: Option<String>,
+    /// SSH 端口，默认 22
+    #[serde(default)]
+    pub ssh_port: Option<u16>,
+    /// 本地监听端口
+    pub local_port: u16,
+    /// 从 SSH 服务器访问的目标主机，默认为小程序网址的主机名
+    #[serde(default)]
+    pub remote_host: Option<String>,
+    /// 目标端口，默认为小程序网址的端口
+    #[serde(default)]
+    pub remote_port: Option<u16>,
+    /// 私钥文件
+    #[serde(default)]
+    pub identity_file: Option<String>,
+}
+
+/// 网页通知的过滤规则，在显示系统通知之前检查（用于减少聊天类网站的通知而不完全关闭）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRules {
+    /// 标题或正文包含其中任一关键词时才显示，为空时不限制（不区分大小写）
+    #[serde(default)]
+    pub allow_keywords: Vec<String>,
+    /// 标题或正文包含其中任一关键词时不显示，优先于 `allow_keywords`
+    #[serde(default)]
+    pub deny_keywords: Vec<String>,
+    /// 不显示这些发送者的通知：与通知标题比较（聊天类网站通常以发送者名称作为标题）
+    #[serde(default)]
+    pub muted_senders: Vec<String>,
+    /// 每分钟最多显示的通知数量，为空时不限制
+    #[serde(default)]
+    pub max_per_minute: Option<u32>,
+}
+
+impl NotificationRules {
+    /// 去掉空白的关键词和发送者，0 表示不限制频率
+    pub fn normalized(mut self) -> Self {
+        for list in [&mut self.allow_keywords, &mut self.deny_keywords, &mut self.muted_senders] {
+            *list = list.iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        }
+        self.max_per_minute = self.max_per_minute.filter(|max| *max > 0);
+        self
+    }
+
+    /// 是否没有任何规则
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 fn default_width() -> u32 {
@@ -74,7 +428,87 @@ impl WebApp {
                 .as_secs(),
             inject_script: None,
             inject_on_load: false,
+            inject_timing: None,
             inject_on_shortcut: false,
+            inject_once: false,
+            custom_css: None,
+            timezone: None,
+            preferred_audio_input: None,
+            preferred_video_input: None,
+            muted: false,
+            auto_hide_seconds: None,
+            allowed_domains: Vec::new(),
+            startup_delay_ms: None,
+            on_close_script: None,
+            enabled: true,
+            sample: false,
+            visible_on_all_workspaces: false,
+            start_command: None,
+            start_working_dir: None,
+            stop_process_on_close: true,
+            ssh_tunnel: None,
+            protocols: Vec::new(),
+            share_url_template: None,
+            managed: false,
+            crash_recovery: CrashRecovery::Reload,
+            request_log: false,
+            zoom_percent: None,
+            monitor_zoom: Vec::new(),
+            launch_on_start: false,
+            proxy_override: None,
+            isolated_session: false,
+            data_dir: None,
+            javascript_enabled: true,
+            script_ids: Vec::new(),
+            cache_max_mb: None,
+            multi_instance: false,
+            confirm_close: false,
+            open_external_in_browser: false,
+            auth_domains: Vec::new(),
+            confirm_external_navigation: false,
+            icon_fetched_at: None,
+            always_on_top: false,
+            skip_taskbar: false,
+            decorations: true,
+            window_class: None,
+            auto_reload_minutes: None,
+            network_profile: None,
+            user_agent: None,
+            allow_notifications: true,
+            hardware_acceleration: None,
+            notification_rules: None,
+            download_dir: None,
+            window_background: WindowBackground::Opaque,
+            security_preset: SecurityPreset::Off,
+            dark_css: None,
+            force_dark: false,
+            dock_edge: DockEdge::Off,
+        }
+    }
+
+    /// 实际使用的注入时机（兼容只设置了 `inject_on_load` 的旧配置）
+    pub fn effective_inject_timing(&self) -> InjectTiming {
+        self.inject_timing.unwrap_or(if self.inject_on_load {
+            InjectTiming::DocumentEnd
+        } else {
+            InjectTiming::Manual
+        })
+    }
+
+    /// 判断 URL 是否属于该小程序（主页域名或允许的域名）
+    pub fn is_url_in_scope(&self, target: &url::Url) -> bool {
+        // 通过隧道访问时页面位于本地端口
+        if let Some(settings) = &self.ssh_tunnel {
+            if target.host_str() == Some(tunnel::LOCAL_HOST)
+                && target.port() == Some(settings.local_port)
+            {
+                return true;
+            }
+        }
+
+        match self.url.parse::<url::Url>() {
+            Ok(home) => domains::url_in_scope(&home, &self.allowed_domains, target),
+            Err(_) => false,
         }
     }
 }
@@ -98,9 +532,35 @@ pub struct ProxyConfig {
     /// 代理密码 (可选)
     #[serde(default)]
     pub password: Option<String>,
+    /// 保存在系统钥匙串中的密码的键（只出现在配置文件中，加载后换成密码）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_ref: Option<String>,
     /// 代理类型 (http/https/socks5)
     #[serde(default = "default_proxy_type")]
     pub proxy_type: String,
+    /// 不经过代理的主机（如 `localhost`、`*.corp.example.com`、`10.0.0.0/8`）
+    #[serde(default)]
+    pub bypass_list: Vec<String>,
+}
+
+/// 按域名选择代理的规则：首选代理不可用时改用备用代理，没有备用代理时直接连接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyRoute {
+    /// 规则名称
+    #[serde(default)]
+    pub name: String,
+    /// 匹配小程序主页的域名模式（如 `*.example.com`）
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// 首选代理
+    pub proxy: ProxyConfig,
+    /// 备用代理，为空时直接连接
+    #[serde(default)]
+    pub fallback: Option<ProxyConfig>,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
 }
 
 fn default_proxy_type() -> String {
@@ -115,7 +575,33 @@ impl ProxyConfig {
             return None;
         }
 
-        let auth = match (&self.username, &self.password) {
+        // 配置中没有密码时，使用认证提示中输入的凭据
+        let stored = if self.password.is_none() {
+            proxy_auth::stored_credentials(self)
+        } else {
+            None
+        };
+        let (username, password) = match &stored {
+            Some((user, pass)) => (Some(user.as_str()), Some(pass.as_str())),
+            None => (self.username.as_deref(), self.password.as_deref()),
+        };
+        self.url_with_auth(username, password)
+    }
+
+    /// 解析代理主机地址：主机名、IPv4 地址或 IPv6 地址（可带方括号）
+    pub fn parsed_host(&self) -> Result<url::Host, String> {
+        let host = self.host.trim();
+        let result = match host.parse::<Ipv6Addr>() {
+            Ok(ip) => Ok(url::Host::Ipv6(ip)),
+            Err(_) => url::Host::parse(host),
+        };
+        result.map_err(|_| format!("无效的代理主机地址: {}", self.host))
+    }
+
+    /// 使用给定的用户名和密码生成代理URL（不检查是否启用），主机地址无效时返回 None
+    pub fn url_with_auth(&self, username: Option<&str>, password: Option<&str>) -> Option<String> {
+        let host = self.parsed_host().ok()?;
+        let auth = match (username, password) {
             (Some(user), Some(pass)) => {
                 let encoded_user = utf8_percent_encode(user, NON_ALPHANUMERIC);
                 let encoded_pass = utf8_percent_encode(pass, NON_ALPHANUMERIC);
@@ -128,10 +614,10 @@ impl ProxyConfig {
             _ => String::new(),
         };
 
-        Some(format!(
-            "{}://{}{}:{}",
-            self.proxy_type, auth, self.host, self.port
-        ))
+        // url::Host 的显示形式会为 IPv6 地址加上方括号；端口总是写出，避免部分工具使用其他默认端口
+        let url = format!("{}://{}{}:{}", self.proxy_type, auth, host, self.port);
+        url::Url::parse(&url).ok()?;
+        Some(url)
     }
 }
 
@@ -148,37 +634,1175 @@ pub struct AppConfig {
     /// 最大同时活跃窗口数量
     #[serde(default = "default_max_windows")]
     pub max_active_windows: usize,
+    /// 活跃窗口达到上限时显示系统通知，提示下次打开小程序会关闭哪个窗口
+    #[serde(default)]
+    pub window_limit_notification: bool,
     /// 主窗口呼出快捷键
     #[serde(default)]
     pub main_window_shortcut: Option<String>,
+    /// 全部静音快捷键
+    #[serde(default)]
+    pub mute_all_shortcut: Option<String>,
+    /// 快速切换窗口呼出快捷键
+    #[serde(default)]
+    pub switcher_shortcut: Option<String>,
+    /// 是否全部静音
+    #[serde(default)]
+    pub mute_all: bool,
     /// 是否开机启动
     #[serde(default)]
     pub auto_start: bool,
     /// 是否最小化到托盘
     #[serde(default = "default_true")]
     pub minimize_to_tray: bool,
+    /// 启动时恢复上次打开的小程序窗口
+    #[serde(default)]
+    pub restore_session: bool,
+    /// 会话恢复时窗口之间的创建间隔（毫秒）
+    #[serde(default = "default_session_restore_delay")]
+    pub session_restore_delay_ms: u64,
+    /// 自动化脚本列表
+    #[serde(default)]
+    pub automations: Vec<Automation>,
+    /// 出站 Webhook 列表
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    /// 本地控制 API
+    #[serde(default)]
+    pub control_api: ControlApiConfig,
+    /// 勿扰模式
+    #[serde(default)]
+    pub dnd: DndConfig,
+    /// 静默时段：自动隐藏未置顶的小程序窗口并忽略它们的快捷键
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+    /// 应用到所有小程序的全局样式 (CSS)
+    #[serde(default)]
+    pub global_css: Option<String>,
+    /// 按域名共享的脚本
+    #[serde(default)]
+    pub domain_scripts: Vec<DomainScript>,
+    /// 脚本库，小程序通过 `script_ids` 引用
+    #[serde(default)]
+    pub scripts: Vec<UserScript>,
+    /// 设置窗口上次关闭时的大小
+    #[serde(default)]
+    pub settings_window: Option<WindowSize>,
+    /// 是否已完成首次启动引导（已有配置文件的用户视为已完成）
+    #[serde(default = "default_true")]
+    pub onboarding_completed: bool,
+    /// 完成引导的时间戳
+    #[serde(default)]
+    pub onboarding_completed_at: Option<u64>,
+    /// 后台网络请求的超时与重试策略
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// 前端界面偏好
+    #[serde(default)]
+    pub ui_preferences: UiPreferences,
+    /// 新窗口（以及快捷键呼出的窗口）的位置策略
+    #[serde(default)]
+    pub window_placement: WindowPlacement,
+    /// 定时备份
+    #[serde(default)]
+    pub backup: BackupConfig,
+    /// 绑定到任意动作的快捷键（小程序、主窗口和全部静音快捷键仍使用各自的字段）
+    #[serde(default)]
+    pub shortcut_bindings: Vec<ShortcutBinding>,
+    /// 窗口操作宏
+    #[serde(default)]
+    pub macros: Vec<Macro>,
+    /// 打开和导航时应用的网址改写规则（按顺序匹配，第一条命中的规则生效）
+    #[serde(default)]
+    pub rewrite_rules: Vec<RewriteRule>,
+    /// 测试环境模式：开启时才应用标记为 `staging_only` 的改写规则
+    #[serde(default)]
+    pub staging_mode: bool,
+    /// 无障碍设置（应用到所有小程序窗口）
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+    /// 划词翻译使用的小程序和网址模板
+    #[serde(default)]
+    pub translation: TranslationConfig,
+    /// 定时清理缓存和日志
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    /// 复制小程序网址时提示在小程序中打开（默认关闭）
+    #[serde(default)]
+    pub clipboard_watch: ClipboardWatchConfig,
+    /// 管理员锁定和网站黑名单（家庭共用或展示机）
+    #[serde(default)]
+    pub policy: PolicyConfig,
+    /// 内存不足时关闭后台窗口
+    #[serde(default)]
+    pub memory_pressure: MemoryPressureConfig,
+    /// 额外信任的根证书（企业 CA），证书文件保存在应用数据目录的 certs 下
+    #[serde(default)]
+    pub trusted_cas: Vec<TrustedCa>,
+    /// 各小程序上次关闭时的窗口位置和大小
+    #[serde(default)]
+    pub window_states: Vec<WindowState>,
+    /// 日志过滤规则，格式同 `RUST_LOG`（如 `warn,window=debug`，简写的模块名指本应用的模块），未设置时沿用 `RUST_LOG`
+    #[serde(default)]
+    pub log_level: Option<String>,
+    /// 主窗口上次退出时的位置、大小和可见性
+    #[serde(default)]
+    pub main_window: Option<MainWindowState>,
+    /// 小程序分组（可整体打开或关闭）
+    #[serde(default)]
+    pub groups: Vec<WebAppGroup>,
+    /// 按域名选择代理的规则（按顺序匹配，优先于全局代理和小程序的代理设置）
+    #[serde(default)]
+    pub proxy_routes: Vec<ProxyRoute>,
+    /// 重新获取自动获取的网站图标的间隔（天），为 0 时不刷新
+    #[serde(default = "default_icon_refresh_days")]
+    pub icon_refresh_days: u32,
+    /// 启动器使用的图标主题
+    #[serde(default)]
+    pub icon_theme: IconTheme,
+    /// 默认禁用小程序窗口的硬件加速（单个小程序可以覆盖）
+    #[serde(default)]
+    pub disable_gpu: bool,
+    /// 小程序下载文件的默认保存目录，未设置时使用系统的下载目录
+    #[serde(default)]
+    pub download_dir: Option<String>,
+    /// 配置格式版本（没有版本号的旧配置为 0），加载时按版本逐步升级
+    #[serde(default)]
+    pub version: u32,
+}
+
+/// 内存压力处理设置：可用内存低于阈值时，从最久未使用的隐藏窗口开始关闭
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryPressureConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 可用内存低于该值（MB）时视为内存不足
+    #[serde(default = "default_min_available_mb")]
+    pub min_available_mb: u64,
+    /// 至少保留的窗口数，不会关闭到比这更少
+    #[serde(default = "default_keep_windows")]
+    pub keep_windows: usize,
+}
+
+fn default_min_available_mb() -> u64 {
+    512
+}
+
+fn default_keep_windows() -> usize {
+    2
+}
+
+impl Default for MemoryPressureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_available_mb: default_min_available_mb(),
+            keep_windows: default_keep_windows(),
+        }
+    }
+}
+
+/// 管理员锁定设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyConfig {
+    /// 锁定后配置只读，需要输入密码解锁才能修改
+    #[serde(default)]
+    pub locked: bool,
+    /// 解锁密码的哈希（PBKDF2-SHA256）
+    #[serde(default)]
+    pub passphrase_hash: Option<String>,
+    #[serde(default)]
+    pub passphrase_salt: Option<String>,
+    /// 禁止访问的域名（支持 `*.example.com`）
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+}
+
+/// 剪贴板监听设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardWatchConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 两次提示之间的最短间隔（秒）
+    #[serde(default = "default_clipboard_interval")]
+    pub min_interval_secs: u64,
+}
+
+fn default_clipboard_interval() -> u64 {
+    30
+}
+
+impl Default for ClipboardWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_secs: default_clipboard_interval(),
+        }
+    }
+}
+
+/// 划词翻译设置：选中的文本按网址模板在指定的翻译小程序中打开
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationConfig {
+    /// 用于翻译的小程序
+    #[serde(default)]
+    pub webapp_id: Option<String>,
+    /// 网址模板，`{text}` 为选中的文本，`{lang}` 为目标语言
+    #[serde(default = "default_translation_template")]
+    pub url_template: String,
+    /// 目标语言
+    #[serde(default = "default_translation_language")]
+    pub target_language: String,
+}
+
+fn default_translation_template() -> String {
+    "https://translate.google.com/?sl=auto&tl={lang}&text={text}&op=translate".to_string()
+}
+
+fn default_translation_language() -> String {
+    "zh-CN".to_string()
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            webapp_id: None,
+            url_template: default_translation_template(),
+            target_language: default_translation_language(),
+        }
+    }
+}
+
+/// 无障碍设置：通过样式注入作用于所有小程序窗口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityConfig {
+    /// 总开关（快捷键切换的就是这个开关，其余选项保持不变）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 字体缩放百分比
+    #[serde(default = "default_font_scale")]
+    pub font_scale: u32,
+    /// 高对比度
+    #[serde(default)]
+    pub high_contrast: bool,
+    /// 减少动画
+    #[serde(default)]
+    pub reduced_motion: bool,
+    /// 详细的读屏播报：除窗口显示和隐藏外，还播报未读数量的变化（不受总开关影响）
+    #[serde(default)]
+    pub verbose_announcements: bool,
+}
+
+fn default_font_scale() -> u32 {
+    100
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            font_scale: default_font_scale(),
+            high_contrast: false,
+            reduced_motion: false,
+            verbose_announcements: false,
+        }
+    }
+}
+
+/// 快捷键可触发的动作
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ShortcutAction {
+    /// 切换小程序窗口
+    #[serde(rename_all = "camelCase")]
+    ToggleWebApp { webapp_id: String },
+    /// 切换主窗口
+    ToggleMainWindow,
+    /// 呼出或隐藏快速切换窗口
+    ToggleSwitcher,
+    /// 运行自动化脚本
+    #[serde(rename_all = "camelCase")]
+    RunScript { automation_id: String },
+    /// 运行宏
+    #[serde(rename_all = "camelCase")]
+    RunMacro { macro_id: String },
+    /// 切换全部静音
+    MuteAll,
+    /// 隐藏所有小程序窗口
+    HideAll,
+    /// 截图请求（由前端处理当前聚焦的小程序窗口）
+    Screenshot,
+    /// 切换无障碍样式
+    ToggleAccessibility,
+    /// 切换当前窗口的阅读模式
+    ToggleReaderMode,
+    /// 朗读当前窗口中选中的文本
+    SpeakSelection,
+    /// 在翻译小程序中翻译当前窗口中选中的文本
+    TranslateSelection,
+    /// 重新打开最近关闭的小程序窗口
+    ReopenClosed,
+    /// 切换分组中的第一个小程序，短时间内连续按下时依次切换到分组中的下一个
+    #[serde(rename_all = "camelCase")]
+    CycleGroup { group_id: String },
+    /// 在系统浏览器中打开小程序当前的页面（未指定小程序时为当前聚焦的小程序窗口）
+    #[serde(rename_all = "camelCase")]
+    OpenInBrowser {
+        #[serde(default)]
+        webapp_id: Option<String>,
+    },
+    /// 打开小程序分组
+    #[serde(rename_all = "camelCase")]
+    OpenGroup { group_id: String },
+    /// 放大、缩小或恢复当前聚焦的小程序窗口的缩放
+    Zoom { step: ZoomStep },
+}
+
+impl ShortcutAction {
+    /// 动作目标的标识：小程序动作为小程序 ID，其余为 `__main__` 等特殊目标
+    pub fn target_key(&self) -> String {
+        match self {
+            ShortcutAction::ToggleWebApp { webapp_id } => webapp_id.clone(),
+            ShortcutAction::ToggleMainWindow => "__main__".to_string(),
+            ShortcutAction::ToggleSwitcher => "__switcher__".to_string(),
+            ShortcutAction::RunScript { automation_id } => format!("__script__:{}", automation_id),
+            ShortcutAction::RunMacro { macro_id } => format!("__macro__:{}", macro_id),
+            ShortcutAction::MuteAll => "__mute_all__".to_string(),
+            ShortcutAction::HideAll => "__hide_all__".to_string(),
+            ShortcutAction::Screenshot => "__screenshot__".to_string(),
+            ShortcutAction::ToggleAccessibility => "__accessibility__".to_string(),
+            ShortcutAction::ToggleReaderMode => "__reader_mode__".to_string(),
+            ShortcutAction::SpeakSelection => "__speak_selection__".to_string(),
+            ShortcutAction::TranslateSelection => "__translate_selection__".to_string(),
+            ShortcutAction::ReopenClosed => "__reopen_closed__".to_string(),
+            ShortcutAction::CycleGroup { group_id } => format!("__cycle_group__:{}", group_id),
+            ShortcutAction::OpenInBrowser { webapp_id } => match webapp_id {
+                Some(webapp_id) => format!("__browser__:{}", webapp_id),
+                None => "__browser__".to_string(),
+            },
+            ShortcutAction::OpenGroup { group_id } => format!("__group__:{}", group_id),
+            ShortcutAction::Zoom { step } => match step {
+                ZoomStep::In => "__zoom_in__".to_string(),
+                ZoomStep::Out => "__zoom_out__".to_string(),
+                ZoomStep::Reset => "__zoom_reset__".to_string(),
+            },
+        }
+    }
+}
+
+/// 快捷键与动作的绑定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub shortcut: String,
+    pub action: ShortcutAction,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 定时备份配置：每天将配置复制到用户选择的目录（如 NAS 或同步文件夹）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupConfig {
+    /// 是否启用定时备份
+    #[serde(default)]
+    pub enabled: bool,
+    /// 备份目录
+    #[serde(default)]
+    pub directory: Option<String>,
+    /// 在备份目录中保留的备份数量
+    #[serde(default = "default_backup_keep")]
+    pub keep: u32,
+}
+
+fn default_backup_keep() -> u32 {
+    14
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            keep: default_backup_keep(),
+        }
+    }
+}
+
+/// 定时清理设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceConfig {
+    /// 是否启用定时清理
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 两次清理之间的间隔（小时）
+    #[serde(default = "default_maintenance_interval")]
+    pub interval_hours: u32,
+    /// WebView 缓存的大小上限（MB），超出时从最旧的文件开始删除；也是独立配置文件的默认上限，
+    /// Windows 上同时作为 WebView2 的磁盘缓存上限
+    #[serde(default = "default_cache_max_mb")]
+    pub cache_max_mb: u64,
+    /// 图标缓存的保留天数
+    #[serde(default = "default_icon_max_age_days")]
+    pub icon_max_age_days: u32,
+    /// 日志文件的保留天数
+    #[serde(default = "default_log_max_age_days")]
+    pub log_max_age_days: u32,
+}
+
+fn default_maintenance_interval() -> u32 {
+    24
+}
+
+fn default_cache_max_mb() -> u64 {
+    512
+}
+
+fn default_icon_max_age_days() -> u32 {
+    90
+}
+
+fn default_log_max_age_days() -> u32 {
+    14
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: default_maintenance_interval(),
+            cache_max_mb: default_cache_max_mb(),
+            icon_max_age_days: default_icon_max_age_days(),
+            log_max_age_days: default_log_max_age_days(),
+        }
+    }
+}
+
+/// 前端界面偏好，随配置一起保存和同步
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiPreferences {
+    /// 主题: system / light / dark
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    /// 图标大小: small / medium / large
+    #[serde(default = "default_tile_size")]
+    pub tile_size: String,
+    /// 排序方式: manual / name / recent
+    #[serde(default = "default_sort_mode")]
+    pub sort_mode: String,
+    /// 界面语言（为空时跟随系统）
+    #[serde(default)]
+    pub language: Option<String>,
+    /// 前端自定义的其他偏好
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+fn default_theme() -> String {
+    "system".to_string()
+}
+
+fn default_tile_size() -> String {
+    "medium".to_string()
+}
+
+fn default_sort_mode() -> String {
+    "manual".to_string()
+}
+
+impl Default for UiPreferences {
+    fn default() -> Self {
+        Self {
+            theme: default_theme(),
+            tile_size: default_tile_size(),
+            sort_mode: default_sort_mode(),
+            language: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// 后台网络请求（图标、目录同步、健康检查等）的超时与重试策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConfig {
+    /// 单个请求的总超时（秒）
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// 建立连接的超时（秒）
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// 失败后的最大重试次数
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// 首次重试的等待时间（毫秒），之后每次翻倍
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    20
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    500
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_timeout_secs(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            retries: default_retries(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+        }
+    }
+}
+
+/// 窗口大小（逻辑像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
 }
 
 fn default_max_windows() -> usize {
     5
 }
 
+fn default_session_restore_delay() -> u64 {
+    800
+}
+
+fn default_icon_refresh_days() -> u32 {
+    7
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             webapps: Vec::new(),
             proxy: ProxyConfig::default(),
             max_active_windows: 5,
+            window_limit_notification: false,
             main_window_shortcut: None,
+            mute_all_shortcut: None,
+            switcher_shortcut: None,
+            mute_all: false,
             auto_start: false,
             minimize_to_tray: true,
+            restore_session: false,
+            session_restore_delay_ms: default_session_restore_delay(),
+            automations: Vec::new(),
+            webhooks: Vec::new(),
+            control_api: ControlApiConfig::default(),
+            dnd: DndConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            global_css: None,
+            domain_scripts: Vec::new(),
+            scripts: Vec::new(),
+            settings_window: None,
+            onboarding_completed: false,
+            onboarding_completed_at: None,
+            network: NetworkConfig::default(),
+            ui_preferences: UiPreferences::default(),
+            window_placement: WindowPlacement::default(),
+            backup: BackupConfig::default(),
+            shortcut_bindings: Vec::new(),
+            macros: Vec::new(),
+            rewrite_rules: Vec::new(),
+            staging_mode: false,
+            accessibility: AccessibilityConfig::default(),
+            translation: TranslationConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            clipboard_watch: ClipboardWatchConfig::default(),
+            policy: PolicyConfig::default(),
+            memory_pressure: MemoryPressureConfig::default(),
+            trusted_cas: Vec::new(),
+            window_states: Vec::new(),
+            log_level: None,
+            main_window: None,
+            groups: Vec::new(),
+            proxy_routes: Vec::new(),
+            icon_refresh_days: default_icon_refresh_days(),
+            icon_theme: IconTheme::default(),
+            disable_gpu: false,
+            download_dir: None,
+            version: CONFIG_VERSION,
+        }
+    }
+}
+
+impl AppConfig {
+    /// 小程序窗口是否使用硬件加速
+    pub fn hardware_acceleration_for(&self, webapp: &WebApp) -> bool {
+        webapp.hardware_acceleration.unwrap_or(!self.disable_gpu)
+    }
+
+    /// 计算小程序实际使用的代理URL（小程序未启用代理、全局代理关闭或主页在绕过列表中时为 None），
+    /// 主页匹配代理路由规则时按代理的健康状况选择
+    pub fn proxy_url_for(&self, webapp: &WebApp) -> Option<String> {
+        if !webapp.use_proxy {
+            return None;
+        }
+        if let Some(routed) = proxy_routes::route(self, webapp) {
+            return routed;
+        }
+        let proxy = match webapp.proxy_override.as_ref().filter(|p| p.enabled) {
+            Some(proxy) => proxy,
+            None if self.proxy.enabled => &self.proxy,
+            None => return None,
+        };
+        // 主页在绕过列表中的小程序（如内网地址）直接连接
+        let host = url::Url::parse(&webapp.url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string));
+        if host.is_some_and(|host| ProxyManager::is_bypassed(&proxy.bypass_list, &host)) {
+            return None;
+        }
+        proxy.get_proxy_url()
+    }
+
+    /// 查找与给定名称或 URL（规范化后比较）重复的小程序
+    pub fn find_duplicate(&self, name: &str, url: &str) -> Option<&WebApp> {
+        let name = name.trim();
+        let url = domains::normalize_url(url);
+        self.webapps.iter().find(|w| {
+            w.name.trim().eq_ignore_ascii_case(name) || domains::normalize_url(&w.url) == url
+        })
+    }
+}
+
+/// 破坏性操作涉及的变更（`dry_run` 时只返回、不执行），用于前端确认对话框
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePreview {
+    /// 是否只是预览
+    pub dry_run: bool,
+    /// 将被删除的小程序 ID
+    pub removed_webapps: Vec<String>,
+    /// 将被替换为新设置的小程序 ID（替换导入配置时 ID 相同的小程序）
+    pub replaced_webapps: Vec<String>,
+    /// 将被关闭的窗口对应的小程序 ID
+    pub closed_windows: Vec<String>,
+    /// 将被注销的快捷键
+    pub released_shortcuts: Vec<String>,
+}
+
+/// 添加或修改小程序的输入（字段为空表示不修改，可清除的文本字段传空字符串表示清除）
+///
+/// `add_webapp`、`update_webapp` 和 `bulk_update_webapps` 共用，统一由 [`WebAppPatch::apply`] 应用
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebAppPatch {
+    /// 要修改的小程序 ID（添加时忽略）
+    pub id: String,
+    pub name: Option<String>,
+    pub url: Option<String>,
+    /// 图标（内联图标的引用会被忽略，避免覆盖原图标）
+    pub icon: Option<String>,
+    pub shortcut: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// 启用或停用
+    pub enabled: Option<bool>,
+    /// 移到指定 ID 的分组（同时移出其他分组，空字符串表示移出所有分组），由 `groups::move_webapp` 应用
+    pub group_id: Option<String>,
+    /// 是否使用全局代理
+    pub use_proxy: Option<bool>,
+    pub order: Option<u32>,
+    pub inject_script: Option<String>,
+    pub inject_on_load: Option<bool>,
+    pub inject_on_shortcut: Option<bool>,
+    pub inject_once: Option<bool>,
+    pub inject_timing: Option<InjectTiming>,
+    pub script_ids: Option<Vec<String>>,
+    pub custom_css: Option<String>,
+    pub dark_css: Option<String>,
+    pub force_dark: Option<bool>,
+    pub timezone: Option<String>,
+    pub preferred_audio_input: Option<String>,
+    pub preferred_video_input: Option<String>,
+    /// 0 表示不自动隐藏
+    pub auto_hide_seconds: Option<u32>,
+    pub allowed_domains: Option<Vec<String>>,
+    pub auth_domains: Option<Vec<String>>,
+    pub confirm_external_navigation: Option<bool>,
+    pub on_close_script: Option<String>,
+    pub visible_on_all_workspaces: Option<bool>,
+    pub start_command: Option<String>,
+    pub start_working_dir: Option<String>,
+    pub stop_process_on_close: Option<bool>,
+    /// 主机为空表示清除
+    pub ssh_tunnel: Option<SshTunnel>,
+    pub share_url_template: Option<String>,
+    pub crash_recovery: Option<CrashRecovery>,
+    pub request_log: Option<bool>,
+    /// 0 表示使用默认缩放
+    pub zoom_percent: Option<u32>,
+    pub launch_on_start: Option<bool>,
+    /// 主机为空表示清除单独的代理，改用全局代理
+    pub proxy_override: Option<ProxyConfig>,
+    pub isolated_session: Option<bool>,
+    pub data_dir: Option<String>,
+    pub javascript_enabled: Option<bool>,
+    /// 0 表示使用全局上限
+    pub cache_max_mb: Option<u64>,
+    pub multi_instance: Option<bool>,
+    pub confirm_close: Option<bool>,
+    pub open_external_in_browser: Option<bool>,
+    pub always_on_top: Option<bool>,
+    pub skip_taskbar: Option<bool>,
+    pub decorations: Option<bool>,
+    pub window_class: Option<String>,
+    /// 0 表示不自动刷新
+    pub auto_reload_minutes: Option<u32>,
+    pub user_agent: Option<String>,
+    pub allow_notifications: Option<bool>,
+    /// 在重新打开窗口后生效
+    pub hardware_acceleration: Option<bool>,
+    /// 没有任何规则表示清除过滤
+    pub notification_rules: Option<NotificationRules>,
+    /// 空字符串表示恢复为全局下载目录
+    pub download_dir: Option<String>,
+    /// 在重新打开窗口后生效
+    pub window_background: Option<WindowBackground>,
+    pub security_preset: Option<SecurityPreset>,
+    pub dock_edge: Option<DockEdge>,
+}
+
+/// 可清除的文本字段：空字符串（或只有空白）表示清除
+fn non_empty(value: &str) -> Option<String> {
+    (!value.trim().is_empty()).then(|| value.to_string())
+}
+
+impl WebAppPatch {
+    /// 将修改应用到小程序（分组保存在 `AppConfig::groups` 中，不在这里修改）
+    pub fn apply(&self, webapp: &mut WebApp) {
+        fn set<T: Clone>(field: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *field = value.clone();
+            }
+        }
+        fn set_text(field: &mut Option<String>, value: &Option<String>) {
+            if let Some(value) = value {
+                *field = non_empty(value);
+            }
+        }
+        fn set_positive<T: Copy + Default + PartialEq>(field: &mut Option<T>, value: Option<T>) {
+            if let Some(value) = value {
+                *field = (value != T::default()).then_some(value);
+            }
+        }
+
+        set(&mut webapp.name, &self.name);
+        set(&mut webapp.url, &self.url);
+        if let Some(icon) = self.icon.as_deref().filter(|icon| !icons::is_reference(icon)) {
+            webapp.icon = non_empty(icon);
+            webapp.icon_fetched_at = None;
+        }
+        set_text(&mut webapp.shortcut, &self.shortcut);
+        set(&mut webapp.width, &self.width);
+        set(&mut webapp.height, &self.height);
+        set(&mut webapp.enabled, &self.enabled);
+        set(&mut webapp.use_proxy, &self.use_proxy);
+        set(&mut webapp.order, &self.order);
+        set_text(&mut webapp.inject_script, &self.inject_script);
+        set(&mut webapp.inject_on_load, &self.inject_on_load);
+        set(&mut webapp.inject_on_shortcut, &self.inject_on_shortcut);
+        set(&mut webapp.inject_once, &self.inject_once);
+        if let Some(timing) = self.inject_timing {
+            webapp.inject_timing = Some(timing);
+        }
+        set(&mut webapp.script_ids, &self.script_ids);
+        set_text(&mut webapp.custom_css, &self.custom_css);
+        set_text(&mut webapp.dark_css, &self.dark_css);
+        set(&mut webapp.force_dark, &self.force_dark);
+        set_text(&mut webapp.timezone, &self.timezone);
+        set_text(&mut webapp.preferred_audio_input, &self.preferred_audio_input);
+        set_text(&mut webapp.preferred_video_input, &self.preferred_video_input);
+        set_positive(&mut webapp.auto_hide_seconds, self.auto_hide_seconds);
+        set(&mut webapp.allowed_domains, &self.allowed_domains);
+        set(&mut webapp.auth_domains, &self.auth_domains);
+        set(&mut webapp.confirm_external_navigation, &self.confirm_external_navigation);
+        set_text(&mut webapp.on_close_script, &self.on_close_script);
+        set(&mut webapp.visible_on_all_workspaces, &self.visible_on_all_workspaces);
+        set_text(&mut webapp.start_command, &self.start_command);
+        set_text(&mut webapp.start_working_dir, &self.start_working_dir);
+        set(&mut webapp.stop_process_on_close, &self.stop_process_on_close);
+        if let Some(tunnel) = &self.ssh_tunnel {
+            webapp.ssh_tunnel = Some(tunnel.clone()).filter(|t| !t.host.is_empty());
+        }
+        set_text(&mut webapp.share_url_template, &self.share_url_template);
+        set(&mut webapp.crash_recovery, &self.crash_recovery);
+        set(&mut webapp.request_log, &self.request_log);
+        set_positive(&mut webapp.zoom_percent, self.zoom_percent);
+        set(&mut webapp.launch_on_start, &self.launch_on_start);
+        if let Some(proxy) = &self.proxy_override {
+            webapp.proxy_override = Some(proxy.clone()).filter(|proxy| !proxy.host.is_empty());
+        }
+        set(&mut webapp.isolated_session, &self.isolated_session);
+        set_text(&mut webapp.data_dir, &self.data_dir);
+        set(&mut webapp.javascript_enabled, &self.javascript_enabled);
+        set_positive(&mut webapp.cache_max_mb, self.cache_max_mb);
+        set(&mut webapp.multi_instance, &self.multi_instance);
+        set(&mut webapp.confirm_close, &self.confirm_close);
+        set(&mut webapp.open_external_in_browser, &self.open_external_in_browser);
+        set(&mut webapp.always_on_top, &self.always_on_top);
+        set(&mut webapp.skip_taskbar, &self.skip_taskbar);
+        set(&mut webapp.decorations, &self.decorations);
+        set_text(&mut webapp.window_class, &self.window_class);
+        set_positive(&mut webapp.auto_reload_minutes, self.auto_reload_minutes);
+        if let Some(ua) = &self.user_agent {
+            webapp.user_agent = non_empty(ua.trim());
+        }
+        set(&mut webapp.allow_notifications, &self.allow_notifications);
+        if let Some(enabled) = self.hardware_acceleration {
+            webapp.hardware_acceleration = Some(enabled);
+        }
+        if let Some(rules) = &self.notification_rules {
+            webapp.notification_rules = Some(rules.clone().normalized()).filter(|r| !r.is_empty());
+        }
+        set_text(&mut webapp.download_dir, &self.download_dir);
+        set(&mut webapp.window_background, &self.window_background);
+        set(&mut webapp.security_preset, &self.security_preset);
+        set(&mut webapp.dock_edge, &self.dock_edge);
+    }
+
+    /// 是否修改了需要实时应用到已打开窗口的窗口级设置
+    pub fn touches_window_settings(&self) -> bool {
+        self.visible_on_all_workspaces.is_some()
+            || self.always_on_top.is_some()
+            || self.skip_taskbar.is_some()
+            || self.decorations.is_some()
+            || self.window_class.is_some()
+    }
+}
+
+/// 自动化脚本 (Rhai)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Automation {
+    /// 唯一标识符
+    pub id: String,
+    /// 脚本名称
+    pub name: String,
+    /// Rhai 脚本源码
+    pub source: String,
+    /// 每日定时运行时间 (HH:MM)，为空则只能手动运行
+    #[serde(default)]
+    pub run_at: Option<String>,
+    /// 是否启用定时运行
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 窗口操作宏：按顺序执行的步骤列表（比自动化脚本更轻量）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Macro {
+    /// 唯一标识符
+    pub id: String,
+    /// 宏名称
+    pub name: String,
+    /// 执行步骤
+    #[serde(default)]
+    pub steps: Vec<MacroStep>,
+}
+
+/// 宏的单个步骤
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MacroStep {
+    /// 打开小程序窗口
+    #[serde(rename_all = "camelCase")]
+    Open { webapp_id: String },
+    /// 关闭小程序窗口
+    #[serde(rename_all = "camelCase")]
+    Close { webapp_id: String },
+    /// 等待指定毫秒
+    #[serde(rename_all = "camelCase")]
+    Wait { ms: u64 },
+    /// 向小程序窗口注入脚本
+    #[serde(rename_all = "camelCase")]
+    Inject { webapp_id: String, script: String },
+    /// 将小程序窗口贴靠到所在显示器的指定位置
+    #[serde(rename_all = "camelCase")]
+    Snap {
+        webapp_id: String,
+        position: SnapPosition,
+    },
+}
+
+impl MacroStep {
+    /// 步骤操作的小程序 ID（等待步骤没有目标）
+    pub fn webapp_id(&self) -> Option<&str> {
+        match self {
+            MacroStep::Open { webapp_id }
+            | MacroStep::Close { webapp_id }
+            | MacroStep::Inject { webapp_id, .. }
+            | MacroStep::Snap { webapp_id, .. } => Some(webapp_id),
+            MacroStep::Wait { .. } => None,
+        }
+    }
+}
+
+/// Webhook 可订阅的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebhookEvent {
+    /// 小程序窗口已打开
+    WebappOpened,
+    /// 小程序窗口已关闭（包括 LRU 自动关闭）
+    WebappClosed,
+    /// 小程序的未读数量变化（勿扰模式期间暂存，结束后发送）
+    BadgeChanged,
+    /// 代理路由中的代理变为无法连接
+    HealthCheckFailed,
+}
+
+/// 出站 Webhook 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    /// 唯一标识符
+    pub id: String,
+    /// 接收 POST 请求的地址
+    pub url: String,
+    /// 订阅的事件，为空表示订阅全部事件
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl Webhook {
+    /// 该 Webhook 是否应接收指定事件
+    pub fn accepts(&self, event: WebhookEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+/// 本地控制 API 配置（仅监听 127.0.0.1）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlApiConfig {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 监听端口
+    #[serde(default = "default_control_port")]
+    pub port: u16,
+    /// 访问令牌 (可选)，设置后请求需携带 `Authorization: Bearer <token>`
+    #[serde(default)]
+    pub token: Option<String>,
+    /// 开放 `/metrics`：以 Prometheus 文本格式输出窗口、快捷键、配置写入、代理和内存指标（默认关闭）
+    #[serde(default)]
+    pub metrics: bool,
+}
+
+fn default_control_port() -> u16 {
+    23580
+}
+
+impl Default for ControlApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_control_port(),
+            token: None,
+            metrics: false,
         }
     }
 }
 
-/// 窗口状态信息
+/// 勿扰模式配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DndConfig {
+    /// 手动开启（托盘或命令切换），开启时无视计划
+    #[serde(default)]
+    pub manual: bool,
+    /// 是否启用计划
+    #[serde(default)]
+    pub schedule_enabled: bool,
+    /// 计划时间段
+    #[serde(default)]
+    pub schedules: Vec<DndSchedule>,
+    /// 临时解除计划到此时间（Unix 秒），在计划时间段内从托盘关闭勿扰时设为时间段结束
+    #[serde(default)]
+    pub override_until: Option<u64>,
+}
+
+/// 静默时段配置（如下班后），置顶的小程序不受影响
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHoursConfig {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 静默时间段，格式与勿扰时间段相同
+    #[serde(default)]
+    pub schedules: Vec<DndSchedule>,
+    /// 临时解除静默到此时间（Unix 秒）
+    #[serde(default)]
+    pub override_until: Option<u64>,
+}
+
+/// 勿扰时间段，结束时间早于开始时间表示跨越午夜
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DndSchedule {
+    /// 开始时间 (HH:MM)
+    pub start: String,
+    /// 结束时间 (HH:MM)
+    pub end: String,
+    /// 生效的星期（0 = 周一 … 6 = 周日），为空表示每天；跨午夜时按开始当天计算
+    #[serde(default)]
+    pub days: Vec<u8>,
+}
+
+/// 按域名共享的脚本，注入到当前页面域名匹配的任意小程序窗口
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainScript {
+    /// 唯一标识符
+    pub id: String,
+    /// 脚本名称
+    pub name: String,
+    /// 域名模式列表，如 `*.google.com`
+    pub domains: Vec<String>,
+    /// JavaScript 源码
+    pub script: String,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 脚本库中的用户脚本，由引用它的小程序注入
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserScript {
+    /// 唯一标识符
+    pub id: String,
+    /// 脚本名称
+    pub name: String,
+    /// JavaScript 源码
+    pub script: String,
+    /// 注入时机
+    #[serde(default)]
+    pub run_at: InjectTiming,
+    /// 网址匹配模式，`*` 匹配任意字符，如 `https://mail.example.com/inbox/*`；为空时匹配所有页面
+    #[serde(default)]
+    pub matches: Vec<String>,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 小程序分组，按顺序整体打开或关闭其中的小程序
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAppGroup {
+    /// 唯一标识符
+    pub id: String,
+    /// 分组名称
+    pub name: String,
+    /// 显示颜色（`#rrggbb`）
+    #[serde(default)]
+    pub color: Option<String>,
+    /// 分组中的小程序 ID（按打开顺序）
+    #[serde(default)]
+    pub webapp_ids: Vec<String>,
+    /// 打开分组的快捷键
+    #[serde(default)]
+    pub shortcut: Option<String>,
+    /// 成员的依赖和就绪检查（没有设置的成员按顺序直接打开）
+    #[serde(default)]
+    pub steps: Vec<GroupStep>,
+}
+
+/// 打开分组时某个成员的步骤设置：先等待依赖的成员就绪再打开（如先打开 VPN 状态页，就绪后再打开内网小程序）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupStep {
+    pub webapp_id: String,
+    /// 需要先就绪的成员，必须排在该成员之前
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// 就绪检查网址，返回 2xx 时就绪；未设置时窗口打开即就绪
+    #[serde(default)]
+    pub health_check_url: Option<String>,
+    /// 等待就绪的最长时间（秒），默认 30 秒
+    #[serde(default)]
+    pub timeout_secs: Option<u32>,
+    /// 未能就绪时的处理方式
+    #[serde(default)]
+    pub on_failure: StepFailurePolicy,
+}
+
+/// 分组成员未能在超时前就绪时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StepFailurePolicy {
+    /// 跳过依赖它的成员，其余成员照常打开
+    #[default]
+    SkipDependents,
+    /// 依赖它的成员照常打开
+    Continue,
+    /// 停止打开分组中剩余的成员
+    Abort,
+}
+
+/// 网址改写规则的匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RewriteKind {
+    /// 网址以 `pattern` 开头时，将该前缀替换为 `replacement`
+    #[default]
+    Prefix,
+    /// 正则表达式匹配，`replacement` 可使用 `$1` 等捕获组
+    Regex,
+}
+
+/// 网址改写规则，例如将 `http://old-intranet/` 改写到新域名
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct RewriteRule {
+    /// 唯一标识符
+    pub id: String,
+    #[serde(default)]
+    pub kind: RewriteKind,
+    pub pattern: String,
+    pub replacement: String,
+    /// 仅在测试环境模式下生效
+    #[serde(default)]
+    pub staging_only: bool,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 窗口状态信息（位置和大小为逻辑像素），窗口关闭时按小程序保存，下次打开时恢复
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct WindowState {
     pub webapp_id: String,
     pub is_visible: bool,
@@ -188,3 +1812,17 @@ pub struct WindowState {
     pub height: u32,
 }
 
+/// 主窗口状态（位置和大小为逻辑像素，最大化时保留最大化之前的位置和大小）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MainWindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default = "default_true")]
+    pub is_visible: bool,
+}
+
@@ -0,0 +1,197 @@
+//! 范围外的跳转：开启 `open_external_in_browser` 时，跳转到主页域名和允许的域名以外的网页默认交给系统浏览器
+//!
+//! 单点登录会经过外部的身份提供方，`auth_domains` 中的域名留在窗口中；
+//! 开启 `confirm_external_navigation` 时先询问，用户可以允许这一次在窗口中打开
+//! （该域名在窗口回到小程序范围内之前都允许，登录流程中的多次跳转只询问一次）
+
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use url::Url;
+
+use crate::browser;
+use crate::domains;
+use crate::models::{AppConfig, WebApp};
+
+/// 对一次跳转的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    /// 留在窗口中
+    Allow,
+    /// 交给系统浏览器
+    OpenInBrowser,
+    /// 询问用户
+    Ask,
+}
+
+/// 各窗口临时允许的域名和正在询问的跳转
+pub struct NavigationState {
+    /// 窗口标签 -> 允许这一次打开的主机名
+    allowed_once: Mutex<HashMap<String, String>>,
+    /// 正在弹窗询问的窗口（询问期间的其他跳转直接拦截）
+    prompting: Mutex<HashSet<String>>,
+}
+
+impl NavigationState {
+    pub fn new() -> Self {
+        Self {
+            allowed_once: Mutex::new(HashMap::new()),
+            prompting: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+impl Default for NavigationState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 整理用户输入的登录域名：去掉空白和空项，转小写并去重
+pub fn normalize_domains(domains: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for domain in domains {
+        let domain = domain.trim().to_ascii_lowercase();
+        if !domain.is_empty() && !normalized.contains(&domain) {
+            normalized.push(domain);
+        }
+    }
+    normalized
+}
+
+fn decide(webapp: &WebApp, url: &Url, allowed_once: Option<&str>) -> Decision {
+    if !webapp.open_external_in_browser || !matches!(url.scheme(), "http" | "https") || webapp.is_url_in_scope(url) {
+        return Decision::Allow;
+    }
+    let host = url.host_str().unwrap_or_default();
+    if domains::host_matches_any(&webapp.auth_domains, host) || allowed_once == Some(host) {
+        return Decision::Allow;
+    }
+    if webapp.confirm_external_navigation {
+        Decision::Ask
+    } else {
+        Decision::OpenInBrowser
+    }
+}
+
+/// 处理小程序窗口的跳转，返回是否留在窗口中（否则已交给浏览器或正在询问）
+pub fn allow(app: &AppHandle, config: &AppConfig, label: &str, webapp_id: &str, url: &Url) -> bool {
+    let Some(webapp) = config.webapps.iter().find(|w| w.id == webapp_id) else {
+        return true;
+    };
+    let Some(state) = app.try_state::<NavigationState>() else {
+        return true;
+    };
+    let allowed_once = state.allowed_once.lock().get(label).cloned();
+
+    match decide(webapp, url, allowed_once.as_deref()) {
+        Decision::Allow => {
+            // 回到小程序范围内后，临时允许的域名失效
+            if allowed_once.is_some() && webapp.is_url_in_scope(url) {
+                state.allowed_once.lock().remove(label);
+            }
+            true
+        }
+        Decision::OpenInBrowser => {
+            open_in_browser(app, webapp_id, url);
+            false
+        }
+        Decision::Ask => {
+            ask(app, label, webapp, url);
+            false
+        }
+    }
+}
+
+fn open_in_browser(app: &AppHandle, webapp_id: &str, url: &Url) {
+    log::info!("Opening external link from {} in system browser: {}", webapp_id, url);
+    if let Err(e) = browser::open_url(app, url) {
+        log::warn!("Failed to open external link {}: {}", url, e);
+    }
+}
+
+/// 询问范围外的跳转：允许这一次在窗口中打开，或在系统浏览器中打开
+fn ask(app: &AppHandle, label: &str, webapp: &WebApp, url: &Url) {
+    let Some(state) = app.try_state::<NavigationState>() else {
+        return;
+    };
+    if !state.prompting.lock().insert(label.to_string()) {
+        log::debug!("Navigation prompt already open for {}, blocking {}", label, url);
+        return;
+    }
+    let Some(window) = app.get_webview_window(label) else {
+        state.prompting.lock().remove(label);
+        return;
+    };
+    let host = url.host_str().unwrap_or_default().to_string();
+
+    let label = label.to_string();
+    let webapp_id = webapp.id.clone();
+    let url = url.clone();
+    app.dialog()
+        .message(format!(
+            "“{}”要跳转到 {}，该网站不在小程序的范围内。\n\n\
+             如果这是登录流程，可以允许这一次在窗口中打开；常用的登录网站可以加入小程序设置中的登录域名。",
+            webapp.name, host
+        ))
+        .title("跳转到其他网站")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "在窗口中打开".to_string(),
+            "在浏览器中打开".to_string(),
+        ))
+        .parent(&window)
+        .show({
+            let app = app.clone();
+            move |allowed| {
+                let state = app.state::<NavigationState>();
+                state.prompting.lock().remove(&label);
+                if !allowed {
+                    open_in_browser(&app, &webapp_id, &url);
+                    return;
+                }
+                log::info!("Allowed navigation of {} to {} once", label, host);
+                state.allowed_once.lock().insert(label.clone(), host);
+                if let Some(window) = app.get_webview_window(&label) {
+                    if let Err(e) = window.navigate(url) {
+                        log::warn!("Failed to navigate {} after allowing: {}", label, e);
+                    }
+                }
+            }
+        });
+}
+
+/// 窗口销毁时移除记录
+pub fn forget(app: &AppHandle, label: &str) {
+    if let Some(state) = app.try_state::<NavigationState>() {
+        state.allowed_once.lock().remove(label);
+        state.prompting.lock().remove(label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide_external_navigation() {
+        let mut webapp = WebApp::new("Jira".to_string(), "https://team.atlassian.net".to_string());
+        let idp: Url = "https://login.microsoftonline.com/common/oauth2".parse().unwrap();
+        let home: Url = "https://team.atlassian.net/browse/A-1".parse().unwrap();
+        assert_eq!(decide(&webapp, &idp, None), Decision::Allow);
+
+        webapp.open_external_in_browser = true;
+        assert_eq!(decide(&webapp, &home, None), Decision::Allow);
+        assert_eq!(decide(&webapp, &idp, None), Decision::OpenInBrowser);
+
+        webapp.confirm_external_navigation = true;
+        assert_eq!(decide(&webapp, &idp, None), Decision::Ask);
+        assert_eq!(decide(&webapp, &idp, Some("login.microsoftonline.com")), Decision::Allow);
+        assert_eq!(decide(&webapp, &idp, Some("example.com")), Decision::Ask);
+
+        webapp.auth_domains = normalize_domains(vec![" Login.MicrosoftOnline.com ".to_string(), String::new()]);
+        assert_eq!(webapp.auth_domains, ["login.microsoftonline.com"]);
+        assert_eq!(decide(&webapp, &idp, None), Decision::Allow);
+    }
+}
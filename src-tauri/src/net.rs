@@ -0,0 +1,149 @@
+use parking_lot::RwLock;
+use reqwest::{Client, NoProxy, Proxy, RequestBuilder, Response, StatusCode};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::certs;
+use crate::models::{NetworkConfig, ProxyConfig};
+use crate::proxy::ProxyManager;
+
+/// 后台请求使用的 User-Agent
+const USER_AGENT: &str = concat!("WebAppHub/", env!("CARGO_PKG_VERSION"));
+
+/// 重试间隔上限
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// 按代理配置和网络策略构建 HTTP 客户端（包括代理认证和绕过列表）
+pub fn build_client(proxy: &ProxyConfig, network: &NetworkConfig) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(network.timeout_secs.max(1)))
+        .connect_timeout(Duration::from_secs(network.connect_timeout_secs.max(1)));
+
+    // 用户添加的企业根证书（如公司的 HTTPS 代理证书）
+    for certificate in certs::root_certificates() {
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    if let Some(proxy_url) = proxy.get_proxy_url() {
+        // 代理 URL 中已包含编码后的用户名和密码
+        let proxy_rule = Proxy::all(&proxy_url)
+            .map_err(|e| format!("无效的代理地址: {}", e))?
+            .no_proxy(NoProxy::from_string(&ProxyManager::no_proxy_value(&proxy.bypass_list)));
+        builder = builder.proxy(proxy_rule);
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// 共享 HTTP 客户端，所有后台网络任务（图标、目录同步、过滤列表等）都通过它发出请求
+pub struct HttpClient {
+    client: RwLock<Client>,
+    /// 当前的超时与重试策略
+    policy: RwLock<NetworkConfig>,
+}
+
+impl HttpClient {
+    pub fn new(proxy: &ProxyConfig, network: &NetworkConfig) -> Self {
+        let client = build_client(proxy, network).unwrap_or_else(|e| {
+            log::error!("Failed to build HTTP client with proxy settings: {}", e);
+            Client::new()
+        });
+
+        Self {
+            client: RwLock::new(client),
+            policy: RwLock::new(network.clone()),
+        }
+    }
+
+    /// 代理或网络策略变化后重建客户端（失败时保留旧客户端）
+    pub fn apply(&self, proxy: &ProxyConfig, network: &NetworkConfig) {
+        match build_client(proxy, network) {
+            Ok(client) => {
+                *self.client.write() = client;
+                *self.policy.write() = network.clone();
+            }
+            Err(e) => log::error!("Failed to rebuild HTTP client: {}", e),
+        }
+    }
+
+    /// 获取客户端（内部为引用计数，克隆开销很小）
+    pub fn get(&self) -> Client {
+        self.client.read().clone()
+    }
+
+    /// 获取当前重试策略
+    pub fn policy(&self) -> NetworkConfig {
+        self.policy.read().clone()
+    }
+}
+
+/// 获取共享 HTTP 客户端及其重试策略
+pub fn client(app: &AppHandle) -> (Client, NetworkConfig) {
+    match app.try_state::<HttpClient>() {
+        Some(http) => (http.get(), http.policy()),
+        None => (Client::new(), NetworkConfig::default()),
+    }
+}
+
+/// 第 `attempt` 次重试（从 1 开始）前的等待时间：指数退避，最长 30 秒
+fn retry_delay(policy: &NetworkConfig, attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis(policy.retry_backoff_ms.saturating_mul(factor)).min(MAX_RETRY_DELAY)
+}
+
+/// 服务端错误和限流值得重试，其余状态码直接返回
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// 按策略发送请求：连接失败、超时、5xx 和 429 时按指数退避重试
+/// `build` 每次尝试都会被调用以构建新的请求
+pub async fn send_with_retry<F>(policy: &NetworkConfig, build: F) -> Result<Response, String>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        let result = build().send().await;
+        let retryable = match &result {
+            Ok(resp) => is_retryable_status(resp.status()),
+            Err(e) => e.is_connect() || e.is_timeout(),
+        };
+
+        if !retryable || attempt >= policy.retries {
+            return result.map_err(|e| e.to_string());
+        }
+
+        attempt += 1;
+        let delay = retry_delay(policy, attempt);
+        log::debug!("Retrying request (attempt {}) in {:?}", attempt, delay);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        let policy = NetworkConfig {
+            retry_backoff_ms: 500,
+            ..Default::default()
+        };
+
+        assert_eq!(retry_delay(&policy, 1), Duration::from_millis(500));
+        assert_eq!(retry_delay(&policy, 2), Duration::from_millis(1000));
+        assert_eq!(retry_delay(&policy, 3), Duration::from_millis(2000));
+        assert_eq!(retry_delay(&policy, 20), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::OK));
+    }
+}
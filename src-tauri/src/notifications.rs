@@ -0,0 +1,230 @@
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::ConfigManager;
+use crate::dnd;
+use crate::error::{AppError, AppResult};
+use crate::models::NotificationRules;
+
+/// 同一小程序两次系统通知的最短间隔，避免页面短时间内连续弹出大量通知
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `max_per_minute` 规则统计的时间窗口
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 通知标题和正文的最大长度（字符数）
+const MAX_TEXT_CHARS: usize = 256;
+
+/// 通知转发脚本：替换页面的 `Notification` 和 `ServiceWorkerRegistration.showNotification`，
+/// 把标题和正文交给后端显示为系统通知（窗口被快捷键隐藏时页面自身的通知不会显示）
+const NOTIFICATION_SCRIPT: &str = r#"(function(allowed) {
+    if (window.__WEBAPP_HUB_NOTIFICATION__) return;
+    window.__WEBAPP_HUB_NOTIFICATION__ = true;
+
+    var permission = allowed ? 'granted' : 'denied';
+
+    function forward(title, options) {
+        var internals = window.__TAURI_INTERNALS__;
+        if (permission !== 'granted' || !internals || !internals.invoke) return Promise.reject();
+        return internals.invoke('notify', {
+            title: String(title),
+            body: options && options.body ? String(options.body) : null
+        });
+    }
+
+    function fire(target, type) {
+        var handler = target['on' + type];
+        if (typeof handler === 'function') {
+            try { handler.call(target, new Event(type)); } catch (e) {}
+        }
+    }
+
+    function HubNotification(title, options) {
+        options = options || {};
+        var self = this;
+        this.title = String(title);
+        this.body = options.body || '';
+        this.tag = options.tag || '';
+        this.icon = options.icon || '';
+        this.data = options.data === undefined ? null : options.data;
+        this.onclick = this.onshow = this.onclose = this.onerror = null;
+        forward(title, options).then(function() { fire(self, 'show'); }, function() { fire(self, 'error'); });
+    }
+    HubNotification.prototype.close = function() { fire(this, 'close'); };
+    HubNotification.prototype.addEventListener = function(type, listener) {
+        if (typeof listener === 'function' && !this['on' + type]) this['on' + type] = listener;
+    };
+    HubNotification.prototype.removeEventListener = function(type, listener) {
+        if (this['on' + type] === listener) this['on' + type] = null;
+    };
+    Object.defineProperty(HubNotification, 'permission', { get: function() { return permission; } });
+    HubNotification.maxActions = 0;
+    HubNotification.requestPermission = function(callback) {
+        if (typeof callback === 'function') callback(permission);
+        return Promise.resolve(permission);
+    };
+    window.Notification = HubNotification;
+
+    if (window.ServiceWorkerRegistration && ServiceWorkerRegistration.prototype.showNotification) {
+        ServiceWorkerRegistration.prototype.showNotification = function(title, options) {
+            return forward(title, options).then(function() {}, function() {
+                throw new TypeError('No notification permission has been granted for this origin.');
+            });
+        };
+    }
+})(__ALLOWED__);"#;
+
+/// 各小程序最近一次显示系统通知的时间
+static LAST_SHOWN: Mutex<Option<HashMap<String, Instant>>> = parking_lot::const_mutex(None);
+
+/// 各小程序最近一分钟内显示系统通知的时间（用于 `max_per_minute` 规则）
+static RECENT: Mutex<Option<HashMap<String, VecDeque<Instant>>>> = parking_lot::const_mutex(None);
+
+/// 生成通知转发脚本（作为 initialization_script 注入）
+pub fn notification_script(allowed: bool) -> String {
+    NOTIFICATION_SCRIPT.replace("__ALLOWED__", if allowed { "true" } else { "false" })
+}
+
+fn truncate(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// 距离上次通知是否已超过最短间隔（允许时记录本次时间）
+fn allow(last_shown: &mut HashMap<String, Instant>, webapp_id: &str, now: Instant) -> bool {
+    if last_shown
+        .get(webapp_id)
+        .is_some_and(|last| now.duration_since(*last) < MIN_INTERVAL)
+    {
+        return false;
+    }
+    last_shown.insert(webapp_id.to_string(), now);
+    true
+}
+
+/// 最近一分钟内的通知是否少于 `max` 条（允许时记录本次时间）
+fn within_rate(recent: &mut HashMap<String, VecDeque<Instant>>, webapp_id: &str, now: Instant, max: u32) -> bool {
+    let shown = recent.entry(webapp_id.to_string()).or_default();
+    while shown.front().is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW) {
+        shown.pop_front();
+    }
+    if shown.len() >= max as usize {
+        return false;
+    }
+    shown.push_back(now);
+    true
+}
+
+/// 通知是否符合关键词和发送者规则（不区分大小写）
+fn passes(rules: &NotificationRules, title: &str, body: Option<&str>) -> bool {
+    let sender = title.trim().to_lowercase();
+    if rules.muted_senders.iter().any(|muted| muted.trim().to_lowercase() == sender) {
+        return false;
+    }
+    let text = format!("{}\n{}", title, body.unwrap_or_default()).to_lowercase();
+    let keywords = |list: &[String]| -> Vec<String> {
+        list.iter()
+            .map(|keyword| keyword.trim().to_lowercase())
+            .filter(|keyword| !keyword.is_empty())
+            .collect()
+    };
+    if keywords(&rules.deny_keywords).iter().any(|keyword| text.contains(keyword.as_str())) {
+        return false;
+    }
+    let allowed = keywords(&rules.allow_keywords);
+    allowed.is_empty() || allowed.iter().any(|keyword| text.contains(keyword.as_str()))
+}
+
+/// 把页面发出的网页通知显示为系统通知，标题带上小程序名称
+///
+/// 勿扰模式下、不符合小程序通知规则和过于频繁的通知会被忽略（不视为错误）
+pub fn show(app: &AppHandle, webapp_id: &str, title: &str, body: Option<&str>) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+    if !webapp.allow_notifications {
+        return Err(AppError::invalid_input("该小程序不允许显示通知"));
+    }
+    let rules = webapp.notification_rules.clone().unwrap_or_default();
+    if !passes(&rules, title, body) {
+        log::debug!("Filtered notification from {} by notification rules", webapp_id);
+        return Ok(());
+    }
+    if dnd::current_status(app).active {
+        log::debug!("Suppressed notification from {} during do not disturb", webapp_id);
+        return Ok(());
+    }
+    if !allow(LAST_SHOWN.lock().get_or_insert_with(HashMap::new), webapp_id, Instant::now()) {
+        log::debug!("Dropped notification from {}: too frequent", webapp_id);
+        return Ok(());
+    }
+    if let Some(max) = rules.max_per_minute.filter(|max| *max > 0) {
+        if !within_rate(RECENT.lock().get_or_insert_with(HashMap::new), webapp_id, Instant::now(), max) {
+            log::debug!("Dropped notification from {}: over {} per minute", webapp_id, max);
+            return Ok(());
+        }
+    }
+
+    let title = truncate(title);
+    let mut builder = app.notification().builder().title(if title.is_empty() {
+        webapp.name.clone()
+    } else {
+        format!("{}：{}", webapp.name, title)
+    });
+    if let Some(body) = body.map(truncate).filter(|body| !body.is_empty()) {
+        builder = builder.body(body);
+    }
+    builder.show().map_err(|e| AppError::Other(format!("无法显示通知: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_and_truncate() {
+        let mut last_shown = HashMap::new();
+        let now = Instant::now();
+        assert!(allow(&mut last_shown, "mail", now));
+        assert!(!allow(&mut last_shown, "mail", now + Duration::from_millis(500)));
+        assert!(allow(&mut last_shown, "chat", now + Duration::from_millis(500)));
+        assert!(allow(&mut last_shown, "mail", now + MIN_INTERVAL));
+
+        assert_eq!(truncate("  新消息 "), "新消息");
+        let long = "字".repeat(MAX_TEXT_CHARS + 10);
+        assert_eq!(truncate(&long).chars().count(), MAX_TEXT_CHARS + 1);
+        assert!(notification_script(false).contains("})(false);"));
+    }
+
+    #[test]
+    fn test_notification_rules() {
+        let rules = NotificationRules {
+            allow_keywords: vec!["@me".to_string(), "Urgent".to_string()],
+            deny_keywords: vec!["giveaway".to_string()],
+            muted_senders: vec!["Promo Bot".to_string()],
+            max_per_minute: Some(2),
+        };
+        assert!(passes(&rules, "Alice", Some("urgent: deploy failed")));
+        assert!(passes(&rules, "Bob", Some("ping @me")));
+        assert!(!passes(&rules, "Bob", Some("lunch?")));
+        assert!(!passes(&rules, "Carol", Some("URGENT giveaway")));
+        assert!(!passes(&rules, " promo bot ", Some("urgent sale")));
+        assert!(passes(&NotificationRules::default(), "Anyone", None));
+
+        let mut recent = HashMap::new();
+        let now = Instant::now();
+        assert!(within_rate(&mut recent, "chat", now, 2));
+        assert!(within_rate(&mut recent, "chat", now + Duration::from_secs(10), 2));
+        assert!(!within_rate(&mut recent, "chat", now + Duration::from_secs(30), 2));
+        assert!(within_rate(&mut recent, "chat", now + RATE_WINDOW, 2));
+    }
+}
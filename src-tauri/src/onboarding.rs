@@ -0,0 +1,51 @@
+use crate::models::{AppConfig, WebApp};
+
+/// 首次启动时提供的示例小程序: (名称, 网址)
+const SAMPLE_WEBAPPS: &[(&str, &str)] = &[
+    ("GitHub", "https://github.com"),
+    ("Google Translate", "https://translate.google.com"),
+    ("YouTube Music", "https://music.youtube.com"),
+    ("Excalidraw", "https://excalidraw.com"),
+];
+
+/// 创建示例小程序（标记为 `sample`，便于统一删除）
+fn sample_webapps() -> Vec<WebApp> {
+    SAMPLE_WEBAPPS
+        .iter()
+        .enumerate()
+        .map(|(index, (name, url))| {
+            let mut webapp = WebApp::new(name.to_string(), url.to_string());
+            webapp.order = index as u32;
+            webapp.sample = true;
+            webapp
+        })
+        .collect()
+}
+
+/// 首次启动（没有配置文件）时使用的配置
+pub fn first_run_config() -> AppConfig {
+    AppConfig {
+        webapps: sample_webapps(),
+        ..AppConfig::default()
+    }
+}
+
+/// 标记引导完成，可选删除所有示例小程序，返回被删除的小程序
+pub fn complete(config: &mut AppConfig, remove_samples: bool) -> Vec<WebApp> {
+    config.onboarding_completed = true;
+    config.onboarding_completed_at = Some(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    );
+
+    if !remove_samples {
+        return Vec::new();
+    }
+
+    let (samples, kept): (Vec<WebApp>, Vec<WebApp>) =
+        std::mem::take(&mut config.webapps).into_iter().partition(|w| w.sample);
+    config.webapps = kept;
+    samples
+}
@@ -0,0 +1,145 @@
+use serde_json::Value;
+
+use crate::error::{AppError, AppResult};
+use crate::models::AppConfig;
+use crate::proxy::ProxyManager;
+use crate::scripting;
+
+/// 不能通过补丁修改的配置（锁定设置只能通过锁定相关的命令修改）
+const PROTECTED: &[&str] = &["/policy"];
+
+/// JSON Pointer 中的单个路径片段（处理 `~1` 和 `~0` 转义）
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// 路径是否是 prefix 本身或其下的路径（按完整的路径片段匹配，`/proxy` 不匹配 `/proxyRoutes`）
+pub fn touches(pointer: &str, prefix: &str) -> bool {
+    pointer == prefix || pointer.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// 追加、替换或改名、改网址的小程序在补丁后的配置中的位置（用于检查重复）
+fn patched_webapp(pointer: &str, patched: &AppConfig) -> Option<usize> {
+    let mut segments = pointer.strip_prefix("/webapps/")?.split('/');
+    let index = match segments.next()? {
+        "-" => patched.webapps.len().checked_sub(1)?,
+        index => index.parse().ok()?,
+    };
+    match segments.next() {
+        None | Some("name") | Some("url") => Some(index),
+        Some(_) => None,
+    }
+}
+
+/// 按 JSON Pointer（RFC 6901，字段名为 camelCase）修改配置中的单个值，返回修改后的配置
+///
+/// 只能替换已有的字段或数组元素，数组可以用 `-` 追加；修改后的配置必须仍然有效
+pub fn apply(config: &AppConfig, pointer: &str, value: Value) -> AppResult<AppConfig> {
+    if pointer.is_empty() || !pointer.starts_with('/') {
+        return Err(AppError::invalid_input("路径必须以 / 开头，整份配置请使用保存配置"));
+    }
+    if PROTECTED.iter().any(|p| touches(pointer, p)) {
+        return Err(AppError::invalid_input(format!("不能通过补丁修改 {}", pointer)));
+    }
+
+    let mut root = serde_json::to_value(config).map_err(|e| AppError::Other(e.to_string()))?;
+    let split = pointer.rfind('/').unwrap_or(0);
+    let (parent_pointer, last) = (&pointer[..split], unescape(&pointer[split + 1..]));
+    let parent = root
+        .pointer_mut(parent_pointer)
+        .ok_or_else(|| AppError::invalid_input(format!("配置中没有 {}", parent_pointer)))?;
+
+    match parent {
+        Value::Object(map) => match map.get_mut(&last) {
+            Some(slot) => *slot = value,
+            None => return Err(AppError::invalid_input(format!("配置中没有 {}", pointer))),
+        },
+        Value::Array(items) if last == "-" => items.push(value),
+        Value::Array(items) => {
+            let slot = last
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get_mut(index))
+                .ok_or_else(|| AppError::invalid_input(format!("配置中没有 {}", pointer)))?;
+            *slot = value;
+        }
+        _ => return Err(AppError::invalid_input(format!("{} 不是对象或数组", parent_pointer))),
+    }
+
+    let mut patched: AppConfig =
+        serde_json::from_value(root).map_err(|e| AppError::invalid_input(format!("修改后的配置无效: {}", e)))?;
+    if touches(pointer, "/proxy") {
+        ProxyManager::validate_config(&patched.proxy)?;
+    }
+    if touches(pointer, "/automations") {
+        scripting::validate_schedules(&mut patched.automations)?;
+    }
+    // 与添加小程序相同，不能通过补丁添加名称或网址重复的小程序
+    if let Some((index, webapp)) =
+        patched_webapp(pointer, &patched).and_then(|index| Some((index, patched.webapps.get(index)?)))
+    {
+        let others = AppConfig {
+            webapps: patched
+                .webapps
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .map(|(_, w)| w.clone())
+                .collect(),
+            ..AppConfig::default()
+        };
+        if let Some(existing) = others.find_duplicate(&webapp.name, &webapp.url) {
+            return Err(AppError::invalid_input(format!("已存在相同名称或网址的小程序: {}", existing.name)));
+        }
+    }
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WebApp;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_patch() {
+        let mut config = AppConfig::default();
+        config
+            .webapps
+            .push(WebApp::new("Mail".to_string(), "https://mail.example.com".to_string()));
+
+        let patched = apply(&config, "/webapps/0/width", json!(1280)).unwrap();
+        assert_eq!(patched.webapps[0].width, 1280);
+        let patched = apply(&config, "/maxActiveWindows", json!(3)).unwrap();
+        assert_eq!(patched.max_active_windows, 3);
+
+        assert!(apply(&config, "/webapps/0/width", json!("wide")).is_err());
+        assert!(apply(&config, "/webapps/5/width", json!(1280)).is_err());
+        assert!(apply(&config, "/noSuchField", json!(1)).is_err());
+        assert!(apply(&config, "/policy", json!(null)).is_err());
+        assert!(apply(&config, "", json!(null)).is_err());
+    }
+
+    #[test]
+    fn test_patch_rejects_duplicates() {
+        let mut config = AppConfig::default();
+        config
+            .webapps
+            .push(WebApp::new("Mail".to_string(), "https://mail.example.com".to_string()));
+        config
+            .webapps
+            .push(WebApp::new("Chat".to_string(), "https://chat.example.com".to_string()));
+
+        let copy = WebApp::new("Mail 2".to_string(), "https://mail.example.com/".to_string());
+        assert!(apply(&config, "/webapps/-", serde_json::to_value(&copy).unwrap()).is_err());
+        assert!(apply(&config, "/webapps/1/name", json!("mail")).is_err());
+        let other = WebApp::new("Docs".to_string(), "https://docs.example.com".to_string());
+        assert_eq!(
+            apply(&config, "/webapps/-", serde_json::to_value(&other).unwrap()).unwrap().webapps.len(),
+            3
+        );
+
+        assert!(touches("/proxy/host", "/proxy"));
+        assert!(!touches("/proxyRoutes/0", "/proxy"));
+    }
+}
@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::models::WindowState;
+use crate::platform;
+
+/// 新窗口的位置策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowPlacement {
+    /// 主显示器居中（默认）
+    #[default]
+    Center,
+    /// 鼠标所在显示器居中
+    CursorMonitor,
+    /// 鼠标附近（不超出显示器范围）
+    NearCursor,
+}
+
+/// 窗口贴靠位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapPosition {
+    /// 左半屏
+    Left,
+    /// 右半屏
+    Right,
+    /// 占满显示器
+    Maximize,
+}
+
+/// 显示器区域: (x, y, width, height)
+pub type Rect = (f64, f64, f64, f64);
+
+/// 计算窗口左上角位置（所有参数使用同一坐标单位），`Center` 返回 None 表示交给系统居中
+fn compute_position(
+    placement: WindowPlacement,
+    cursor: (f64, f64),
+    monitor: Rect,
+    size: (f64, f64),
+) -> Option<(f64, f64)> {
+    let (mx, my, mw, mh) = monitor;
+    let (w, h) = size;
+
+    match placement {
+        WindowPlacement::Center => None,
+        WindowPlacement::CursorMonitor => {
+            Some((mx + ((mw - w) / 2.0).max(0.0), my + ((mh - h) / 2.0).max(0.0)))
+        }
+        WindowPlacement::NearCursor => {
+            // 窗口顶部中点对准鼠标，再限制在显示器范围内
+            let x = (cursor.0 - w / 2.0).min(mx + mw - w).max(mx);
+            let y = cursor.1.min(my + mh - h).max(my);
+            Some((x, y))
+        }
+    }
+}
+
+/// 为即将创建的窗口计算逻辑坐标位置（窗口大小为逻辑像素）
+pub fn logical_position_for_new(
+    app: &AppHandle,
+    placement: WindowPlacement,
+    width: f64,
+    height: f64,
+) -> Option<(f64, f64)> {
+    // Wayland 不允许读取鼠标位置或指定窗口位置
+    if placement == WindowPlacement::Center || !platform::capabilities().window_positioning {
+        return None;
+    }
+
+    let cursor = app.cursor_position().ok()?;
+    let monitor = app.monitor_from_point(cursor.x, cursor.y).ok()??;
+    let scale = monitor.scale_factor();
+    let rect = (
+        monitor.position().x as f64 / scale,
+        monitor.position().y as f64 / scale,
+        monitor.size().width as f64 / scale,
+        monitor.size().height as f64 / scale,
+    );
+
+    compute_position(
+        placement,
+        (cursor.x / scale, cursor.y / scale),
+        rect,
+        (width, height),
+    )
+}
+
+/// 将已存在的窗口移动到鼠标所在位置（按策略）
+pub fn move_existing(app: &AppHandle, window: &WebviewWindow, placement: WindowPlacement) {
+    if placement == WindowPlacement::Center || !platform::capabilities().window_positioning {
+        return;
+    }
+
+    let (cursor, size) = match (app.cursor_position(), window.outer_size()) {
+        (Ok(cursor), Ok(size)) => (cursor, size),
+        _ => return,
+    };
+    let monitor = match app.monitor_from_point(cursor.x, cursor.y) {
+        Ok(Some(monitor)) => monitor,
+        _ => return,
+    };
+    let rect = (
+        monitor.position().x as f64,
+        monitor.position().y as f64,
+        monitor.size().width as f64,
+        monitor.size().height as f64,
+    );
+
+    if let Some((x, y)) = compute_position(
+        placement,
+        (cursor.x, cursor.y),
+        rect,
+        (size.width as f64, size.height as f64),
+    ) {
+        let _ = window.set_position(PhysicalPosition::new(x as i32, y as i32));
+    }
+}
+
+/// 计算贴靠后的窗口区域
+fn snap_rect(position: SnapPosition, monitor: Rect) -> Rect {
+    let (mx, my, mw, mh) = monitor;
+    let half = (mw / 2.0).floor();
+
+    match position {
+        SnapPosition::Left => (mx, my, half, mh),
+        SnapPosition::Right => (mx + half, my, mw - half, mh),
+        SnapPosition::Maximize => monitor,
+    }
+}
+
+/// 将窗口贴靠到其所在显示器的指定位置
+pub fn snap(window: &WebviewWindow, position: SnapPosition) -> Result<(), String> {
+    if !platform::capabilities().window_positioning {
+        return Err("当前桌面环境不支持移动窗口".to_string());
+    }
+
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or("无法获取窗口所在的显示器")?;
+    let rect = (
+        monitor.position().x as f64,
+        monitor.position().y as f64,
+        monitor.size().width as f64,
+        monitor.size().height as f64,
+    );
+    let (x, y, width, height) = snap_rect(position, rect);
+
+    window
+        .set_position(PhysicalPosition::new(x as i32, y as i32))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_size(PhysicalSize::new(width as u32, height as u32))
+        .map_err(|e| e.to_string())
+}
+
+/// 标题栏至少要有这么大的区域（逻辑像素）落在某个显示器内，窗口才算可以拖动
+const MIN_VISIBLE: (f64, f64) = (100.0, 30.0);
+
+/// 窗口顶部的标题栏区域是否落在任一显示器内（显示器断开或分辨率变化后可能不在）
+pub fn is_reachable(window: Rect, monitors: &[Rect]) -> bool {
+    let (x, y, w, _) = window;
+    let title_bar = (x, y, w, MIN_VISIBLE.1);
+    monitors.iter().any(|&(mx, my, mw, mh)| {
+        let overlap_w = (title_bar.0 + title_bar.2).min(mx + mw) - title_bar.0.max(mx);
+        let overlap_h = (title_bar.1 + title_bar.3).min(my + mh) - title_bar.1.max(my);
+        overlap_w >= MIN_VISIBLE.0.min(w) && overlap_h >= MIN_VISIBLE.1
+    })
+}
+
+/// 所有显示器的区域（逻辑坐标）
+pub fn monitors(app: &AppHandle) -> Vec<Rect> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let scale = monitor.scale_factor();
+            (
+                monitor.position().x as f64 / scale,
+                monitor.position().y as f64 / scale,
+                monitor.size().width as f64 / scale,
+                monitor.size().height as f64 / scale,
+            )
+        })
+        .collect()
+}
+
+/// 上次保存的窗口位置（逻辑坐标），平台不支持指定位置或窗口已不在任何显示器上时返回 None
+pub fn restorable_position(app: &AppHandle, state: &WindowState) -> Option<(f64, f64)> {
+    if !platform::capabilities().window_positioning {
+        return None;
+    }
+
+    let window = (state.x as f64, state.y as f64, state.width as f64, state.height as f64);
+    is_reachable(window, &monitors(app)).then_some((window.0, window.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: Rect = (1920.0, 0.0, 2560.0, 1440.0);
+
+    #[test]
+    fn test_center_defers_to_system() {
+        assert_eq!(
+            compute_position(WindowPlacement::Center, (0.0, 0.0), MONITOR, (800.0, 600.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cursor_monitor_centers_on_that_monitor() {
+        let pos = compute_position(
+            WindowPlacement::CursorMonitor,
+            (2000.0, 100.0),
+            MONITOR,
+            (800.0, 600.0),
+        );
+        assert_eq!(pos, Some((1920.0 + 880.0, 420.0)));
+    }
+
+    #[test]
+    fn test_near_cursor_is_clamped_to_monitor() {
+        let place = |cursor| {
+            compute_position(WindowPlacement::NearCursor, cursor, MONITOR, (800.0, 600.0)).unwrap()
+        };
+
+        assert_eq!(place((3000.0, 200.0)), (2600.0, 200.0));
+        assert_eq!(place((1950.0, 200.0)), (1920.0, 200.0));
+        assert_eq!(place((4470.0, 1400.0)), (3680.0, 840.0));
+    }
+
+    #[test]
+    fn test_snap_halves_cover_monitor() {
+        let left = snap_rect(SnapPosition::Left, (0.0, 0.0, 1921.0, 1080.0));
+        let right = snap_rect(SnapPosition::Right, (0.0, 0.0, 1921.0, 1080.0));
+
+        assert_eq!(left, (0.0, 0.0, 960.0, 1080.0));
+        assert_eq!(right, (960.0, 0.0, 961.0, 1080.0));
+        assert_eq!(snap_rect(SnapPosition::Maximize, MONITOR), MONITOR);
+    }
+
+    #[test]
+    fn test_is_reachable() {
+        let monitors = [(0.0, 0.0, 1920.0, 1080.0), MONITOR];
+
+        assert!(is_reachable((100.0, 100.0, 800.0, 600.0), &monitors));
+        assert!(is_reachable((1800.0, 50.0, 800.0, 600.0), &monitors));
+        // 标题栏只露出一小段或在显示器之外
+        assert!(!is_reachable((4450.0, 100.0, 800.0, 600.0), &monitors));
+        assert!(!is_reachable((100.0, 1070.0, 800.0, 600.0), &monitors));
+        assert!(!is_reachable((100.0, -500.0, 800.0, 600.0), &monitors));
+        assert!(!is_reachable((100.0, 100.0, 800.0, 600.0), &[]));
+    }
+}
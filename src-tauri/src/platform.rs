@@ -0,0 +1,138 @@
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// Linux 上的显示服务器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisplayServer {
+    X11,
+    Wayland,
+    /// 非 Linux 平台
+    Other,
+}
+
+/// 全局快捷键的实现方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutBackend {
+    /// 系统原生注册（Windows、macOS、X11）
+    Native,
+    /// Wayland 上通过 XDG GlobalShortcuts 门户（由桌面环境弹窗确认）
+    Portal,
+}
+
+/// 当前平台支持的窗口和快捷键能力，前端据此隐藏不可用的设置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformCapabilities {
+    pub os: &'static str,
+    pub display_server: DisplayServer,
+    pub shortcut_backend: ShortcutBackend,
+    /// 置顶窗口（Wayland 由合成器决定）
+    pub always_on_top: bool,
+    /// 程序设置窗口位置（Wayland 不允许）
+    pub window_positioning: bool,
+    /// 读取鼠标位置（Wayland 不允许）
+    pub cursor_position: bool,
+    /// 在所有工作区显示
+    pub visible_on_all_workspaces: bool,
+    /// 按小程序设置窗口类（X11 的 WM_CLASS、Windows 的 AppUserModelID；Wayland 的 app_id 无法按窗口设置）
+    pub window_class: bool,
+    /// 按小程序关闭硬件加速（macOS 的 WKWebView 不支持）
+    pub hardware_acceleration: bool,
+    /// 窗口的半透明背景效果（macOS 和 Windows）
+    pub window_effects: bool,
+}
+
+/// 根据会话环境变量判断显示服务器
+/// `GDK_BACKEND=x11` 强制通过 XWayland 运行时按 X11 处理
+fn detect(
+    session_type: Option<&str>,
+    wayland_display: Option<&str>,
+    gdk_backend: Option<&str>,
+) -> DisplayServer {
+    if gdk_backend.is_some_and(|b| b.split(',').next() == Some("x11")) {
+        return DisplayServer::X11;
+    }
+
+    match session_type.map(str::to_ascii_lowercase).as_deref() {
+        Some("wayland") => DisplayServer::Wayland,
+        Some("x11") => DisplayServer::X11,
+        _ if wayland_display.is_some_and(|d| !d.is_empty()) => DisplayServer::Wayland,
+        _ => DisplayServer::X11,
+    }
+}
+
+/// 当前的显示服务器（启动后不会变化，结果会被缓存）
+pub fn display_server() -> DisplayServer {
+    static DISPLAY_SERVER: OnceLock<DisplayServer> = OnceLock::new();
+
+    *DISPLAY_SERVER.get_or_init(|| {
+        if !cfg!(target_os = "linux") {
+            return DisplayServer::Other;
+        }
+
+        let var = |name| std::env::var(name).ok();
+        let server = detect(
+            var("XDG_SESSION_TYPE").as_deref(),
+            var("WAYLAND_DISPLAY").as_deref(),
+            var("GDK_BACKEND").as_deref(),
+        );
+        log::info!("Detected display server: {:?}", server);
+        server
+    })
+}
+
+/// 是否运行在 Wayland 会话中
+pub fn is_wayland() -> bool {
+    display_server() == DisplayServer::Wayland
+}
+
+/// 全局快捷键的实现方式
+pub fn shortcut_backend() -> ShortcutBackend {
+    if is_wayland() {
+        ShortcutBackend::Portal
+    } else {
+        ShortcutBackend::Native
+    }
+}
+
+/// 汇总当前平台能力
+pub fn capabilities() -> PlatformCapabilities {
+    let wayland = is_wayland();
+
+    PlatformCapabilities {
+        os: std::env::consts::OS,
+        display_server: display_server(),
+        shortcut_backend: shortcut_backend(),
+        always_on_top: !wayland,
+        window_positioning: !wayland,
+        cursor_position: !wayland,
+        visible_on_all_workspaces: !wayland,
+        window_class: cfg!(target_os = "windows") || (cfg!(target_os = "linux") && !wayland),
+        hardware_acceleration: cfg!(any(target_os = "windows", target_os = "linux")),
+        window_effects: cfg!(any(target_os = "macos", target_os = "windows")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_display_server() {
+        assert_eq!(detect(Some("wayland"), Some("wayland-0"), None), DisplayServer::Wayland);
+        assert_eq!(detect(Some("x11"), None, None), DisplayServer::X11);
+        assert_eq!(detect(None, Some("wayland-1"), None), DisplayServer::Wayland);
+        assert_eq!(detect(Some("tty"), None, None), DisplayServer::X11);
+    }
+
+    #[test]
+    fn test_forced_x11_backend_on_wayland() {
+        assert_eq!(detect(Some("wayland"), Some("wayland-0"), Some("x11")), DisplayServer::X11);
+        assert_eq!(
+            detect(Some("wayland"), Some("wayland-0"), Some("wayland,x11")),
+            DisplayServer::Wayland
+        );
+    }
+}
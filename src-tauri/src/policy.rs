@@ -0,0 +1,384 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::config::ConfigManager;
+use crate::domains;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Channel};
+use crate::models::{AppConfig, PolicyConfig};
+
+/// 密码哈希的迭代次数
+const HASH_ITERATIONS: u32 = 100_000;
+
+/// 密码最短长度
+const MIN_PASSPHRASE_LEN: usize = 4;
+
+/// 输错密码后需等待的时间，防止反复尝试
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// 打开黑名单网站时返回的错误
+pub const BLOCKED_MESSAGE: &str = "该网站已被管理员禁止访问";
+
+/// 锁定状态，供前端显示（不包含密码哈希）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyStatus {
+    /// 是否启用了管理员锁定
+    pub locked: bool,
+    /// 本次运行中是否已临时解锁
+    pub unlocked: bool,
+    pub blocked_domains: Vec<String>,
+}
+
+/// 阻止访问事件（`policy://blocked`）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockedEvent<'a> {
+    label: &'a str,
+    host: &'a str,
+}
+
+#[derive(Default)]
+struct Session {
+    /// 已输入密码临时解锁（重启后恢复锁定）
+    unlocked: bool,
+    last_failure: Option<Instant>,
+}
+
+/// 管理员锁定的运行时状态
+pub struct PolicyState {
+    session: Mutex<Session>,
+}
+
+impl PolicyState {
+    pub fn new() -> Self {
+        Self {
+            session: Mutex::new(Session::default()),
+        }
+    }
+}
+
+impl Default for PolicyState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hash_passphrase(passphrase: &str, salt: &str) -> String {
+    let mut hash = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt.as_bytes(), HASH_ITERATIONS, &mut hash);
+    STANDARD.encode(hash)
+}
+
+/// 校验密码（逐字节比较全部内容，不提前返回）
+fn verify_passphrase(policy: &PolicyConfig, passphrase: &str) -> bool {
+    let (hash, salt) = match (&policy.passphrase_hash, &policy.passphrase_salt) {
+        (Some(hash), Some(salt)) => (hash, salt),
+        _ => return false,
+    };
+    let computed = hash_passphrase(passphrase, salt);
+    computed.len() == hash.len()
+        && computed
+            .bytes()
+            .zip(hash.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// 网址是否在管理员设置的黑名单中
+/// 黑名单中的 `example.com` 同时禁止它的子域名
+pub fn is_blocked(config: &AppConfig, url: &Url) -> bool {
+    let host = match url.host_str() {
+        Some(host) => host,
+        None => return false,
+    };
+
+    config.policy.blocked_domains.iter().any(|pattern| {
+        let pattern = pattern.trim();
+        if pattern.starts_with("*.") || pattern == "*" {
+            domains::host_matches(pattern, host)
+        } else {
+            domains::host_matches(&format!("*.{}", pattern), host)
+        }
+    })
+}
+
+/// 记录并通知被阻止的访问
+pub fn report_blocked(app: &AppHandle, label: &str, url: &Url) {
+    let host = url.host_str().unwrap_or_default();
+    log::info!("Blocked navigation of {} to {}", label, host);
+    events::emit(app, Channel::Prompts, "policy://blocked", BlockedEvent { label, host });
+}
+
+/// 配置当前是否只读
+pub fn is_locked(app: &AppHandle) -> bool {
+    let locked = app.state::<ConfigManager>().read().policy.locked;
+    locked
+        && !app
+            .try_state::<PolicyState>()
+            .is_some_and(|state| state.session.lock().unlocked)
+}
+
+/// 修改配置前调用：锁定且未解锁时返回错误
+///
+/// 所有会写入配置的命令都必须先调用它（测试会逐个检查 `commands.rs` 中的命令）。不检查的命令只有：
+/// 只读的查询、打开关闭窗口等不写入配置的操作、在 `policy` 内自行检查的锁定相关命令，
+/// 以及锁定时只作用于打开的窗口、不保存到配置的键盘缩放（`zoom_webapp`）
+pub fn ensure_unlocked(app: &AppHandle) -> AppResult<()> {
+    if is_locked(app) {
+        return Err(AppError::Locked("配置已被管理员锁定，请先输入密码解锁".to_string()));
+    }
+    Ok(())
+}
+
+/// 获取锁定状态
+pub fn status(app: &AppHandle) -> PolicyStatus {
+    let policy = app.state::<ConfigManager>().read().policy;
+    let unlocked = app
+        .try_state::<PolicyState>()
+        .is_some_and(|state| state.session.lock().unlocked);
+    PolicyStatus {
+        locked: policy.locked,
+        unlocked: policy.locked && unlocked,
+        blocked_domains: policy.blocked_domains,
+    }
+}
+
+/// 启用管理员锁定并设置密码（已锁定时需要先解锁）
+pub fn lock(app: &AppHandle, passphrase: &str) -> AppResult<PolicyStatus> {
+    ensure_unlocked(app)?;
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(AppError::invalid_input(format!("密码至少需要 {} 个字符", MIN_PASSPHRASE_LEN)));
+    }
+
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    let hash = hash_passphrase(passphrase, &salt);
+    app.state::<ConfigManager>().update(|config| {
+        config.policy.locked = true;
+        config.policy.passphrase_hash = Some(hash);
+        config.policy.passphrase_salt = Some(salt);
+    })?;
+    app.state::<PolicyState>().session.lock().unlocked = false;
+
+    log::info!("Configuration locked by policy");
+    Ok(status(app))
+}
+
+/// 输入密码临时解锁，直到调用 relock 或应用重启
+pub fn unlock(app: &AppHandle, passphrase: &str) -> AppResult<PolicyStatus> {
+    let policy = app.state::<ConfigManager>().read().policy;
+    let state = app.state::<PolicyState>();
+    {
+        let mut session = state.session.lock();
+        if session.last_failure.is_some_and(|at| at.elapsed() < RETRY_DELAY) {
+            return Err(AppError::Other("尝试过于频繁，请稍后再试".to_string()));
+        }
+        if !verify_passphrase(&policy, passphrase) {
+            session.last_failure = Some(Instant::now());
+            log::warn!("Policy unlock failed: wrong passphrase");
+            return Err(AppError::invalid_input("密码错误"));
+        }
+        session.unlocked = true;
+        session.last_failure = None;
+    }
+
+    log::info!("Configuration temporarily unlocked");
+    Ok(status(app))
+}
+
+/// 结束临时解锁
+pub fn relock(app: &AppHandle) -> PolicyStatus {
+    app.state::<PolicyState>().session.lock().unlocked = false;
+    status(app)
+}
+
+/// 关闭管理员锁定（需要已解锁）
+pub fn disable(app: &AppHandle) -> AppResult<PolicyStatus> {
+    ensure_unlocked(app)?;
+    app.state::<ConfigManager>().update(|config| {
+        config.policy.locked = false;
+        config.policy.passphrase_hash = None;
+        config.policy.passphrase_salt = None;
+    })?;
+    app.state::<PolicyState>().session.lock().unlocked = false;
+
+    log::info!("Policy lock disabled");
+    Ok(status(app))
+}
+
+/// 设置禁止访问的域名（需要已解锁）
+pub fn set_blocked_domains(app: &AppHandle, blocked_domains: Vec<String>) -> AppResult<PolicyStatus> {
+    ensure_unlocked(app)?;
+    let blocked_domains: Vec<String> = blocked_domains
+        .into_iter()
+        .map(|d| d.trim().to_ascii_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect();
+    app.state::<ConfigManager>()
+        .update(|config| config.policy.blocked_domains = blocked_domains)?;
+    Ok(status(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_passphrase() {
+        let salt = "salt".to_string();
+        let policy = PolicyConfig {
+            locked: true,
+            passphrase_hash: Some(hash_passphrase("family-pc", &salt)),
+            passphrase_salt: Some(salt),
+            blocked_domains: Vec::new(),
+        };
+
+        assert!(verify_passphrase(&policy, "family-pc"));
+        assert!(!verify_passphrase(&policy, "family-PC"));
+        assert!(!verify_passphrase(&PolicyConfig::default(), ""));
+    }
+
+    /// 不调用 `ensure_unlocked` 的命令：新增的命令如果会写入配置，必须检查锁定而不是加到这里
+    const UNGUARDED_COMMANDS: &[&str] = &[
+        // 只读查询
+        "get_config",
+        "get_shortcut_failures",
+        "list_shortcuts",
+        "validate_shortcut",
+        "get_dnd_status",
+        "get_accessibility",
+        "share_webapp",
+        "lint_config",
+        "get_platform_capabilities",
+        "get_ui_prefs",
+        "get_backup_status",
+        "get_maintenance_report",
+        "get_storage_usage",
+        "list_cookie_sources",
+        "get_share_candidates",
+        "get_policy_status",
+        "get_managed_policy",
+        "get_badges",
+        "get_launcher_grid",
+        "get_recently_closed",
+        "get_usage_stats",
+        "get_memory_status",
+        "get_request_log",
+        "list_trusted_cas",
+        "get_webapp_icon",
+        "export_config",
+        "get_auto_start_status",
+        "get_user_scripts",
+        "export_diagnostics",
+        "get_active_webapps",
+        "get_groups",
+        "get_routing_decisions",
+        "export_proxy_env",
+        "get_webapp_stats",
+        "get_storage_backend",
+        "get_recent_script_runs",
+        "list_downloads",
+        "search_webapps",
+        "export_webapp_shortcut",
+        "get_quiet_hours_status",
+        "query_state",
+        "get_safe_mode_status",
+        "snapshot_state",
+        "subscribe",
+        // 窗口和运行时操作，不写入配置
+        "open_webapp",
+        "open_webapp_at",
+        "toggle_webapp",
+        "close_webapp",
+        "open_webapp_window",
+        "close_webapp_window",
+        "toggle_webapp_window",
+        "clone_window",
+        "open_in_browser",
+        "open_group",
+        "close_group",
+        "set_webapp_pin",
+        "activate_index",
+        "activate_switcher_result",
+        "reopen_last_closed",
+        "recover_webapp",
+        "reader_mode",
+        "exit_reader_mode",
+        "speak_selection",
+        "translate_selection",
+        "stop_speaking",
+        "open_settings",
+        "open_download_folder",
+        "share_to_webapp",
+        "run_automation",
+        "run_macro",
+        "run_script",
+        "run_user_script",
+        "run_backup_now",
+        "run_maintenance_now",
+        "clear_request_log",
+        "reset_stats",
+        "submit_proxy_credentials",
+        "cancel_proxy_auth",
+        "forget_proxy_credentials",
+        "exit_safe_mode",
+        "bridge_event",
+        "notify",
+        // 锁定时只作用于打开的窗口，不保存
+        "zoom_webapp",
+        // 由 policy 自行检查
+        "lock_config",
+        "unlock_config",
+        "relock_config",
+        "disable_config_lock",
+        "set_blocked_domains",
+    ];
+
+    #[test]
+    fn test_commands_check_lock() {
+        let source = include_str!("commands.rs");
+        let mut commands = Vec::new();
+        for chunk in source.split("#[tauri::command]").skip(1) {
+            let name = chunk
+                .split("pub async fn ")
+                .nth(1)
+                .and_then(|rest| rest.split('(').next())
+                .expect("命令应为 pub async fn");
+            // 函数体到下一个文档注释为止
+            let body = chunk.split("\n///").next().unwrap_or(chunk);
+            let guarded = body.contains("policy::ensure_unlocked(&app)?");
+            assert!(
+                guarded || UNGUARDED_COMMANDS.contains(&name),
+                "命令 {} 没有检查管理员锁定",
+                name
+            );
+            assert!(
+                !(guarded && UNGUARDED_COMMANDS.contains(&name)),
+                "命令 {} 已检查管理员锁定，应从列表中删除",
+                name
+            );
+            commands.push(name);
+        }
+        for name in UNGUARDED_COMMANDS {
+            assert!(commands.contains(name), "命令 {} 不存在", name);
+        }
+    }
+
+    #[test]
+    fn test_is_blocked() {
+        let mut config = AppConfig::default();
+        config.policy.blocked_domains = vec!["games.example.com".to_string(), "*.video.example".to_string()];
+
+        let blocked = |url: &str| is_blocked(&config, &Url::parse(url).unwrap());
+        assert!(blocked("https://games.example.com/play"));
+        assert!(blocked("https://m.games.example.com/"));
+        assert!(blocked("https://video.example/watch"));
+        assert!(!blocked("https://example.com/"));
+        assert!(!blocked("https://notgames.example.com/"));
+    }
+}
@@ -0,0 +1,139 @@
+use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+use ashpd::WindowIdentifier;
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+
+use crate::models::ShortcutAction;
+use crate::shortcuts::{self, ShortcutManager};
+
+/// 门户快捷键 ID 前缀，后接绑定在列表中的序号
+const ID_PREFIX: &str = "binding-";
+
+/// Wayland GlobalShortcuts 门户会话
+/// 门户不支持逐个注销，每次绑定变化时重新创建会话并绑定全部快捷键
+pub struct PortalShortcuts {
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl PortalShortcuts {
+    pub fn new() -> Self {
+        Self {
+            task: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for PortalShortcuts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 将 Tauri 快捷键格式转换为门户使用的 XDG 快捷键格式，如 `CmdOrCtrl+Shift+K` -> `CTRL+SHIFT+k`
+fn portal_trigger(shortcut: &str) -> String {
+    let parts: Vec<&str> = shortcut.split('+').map(str::trim).collect();
+    let (key, modifiers) = match parts.split_last() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+
+    let mut trigger: Vec<String> = modifiers
+        .iter()
+        .map(|m| match m.to_ascii_lowercase().as_str() {
+            "commandorcontrol" | "cmdorctrl" | "control" | "ctrl" => "CTRL".to_string(),
+            "shift" => "SHIFT".to_string(),
+            "alt" | "option" => "ALT".to_string(),
+            "super" | "meta" | "command" | "cmd" => "LOGO".to_string(),
+            other => other.to_ascii_uppercase(),
+        })
+        .collect();
+
+    let key = match key.strip_prefix("Key").or_else(|| key.strip_prefix("Digit")) {
+        Some(stripped) if stripped.len() == 1 => stripped,
+        _ => key,
+    };
+    // 单个字母使用小写的 keysym 名称，其余按键（F5、Return 等）保持原样
+    if key.len() == 1 {
+        trigger.push(key.to_ascii_lowercase());
+    } else {
+        trigger.push(key.to_string());
+    }
+    trigger.join("+")
+}
+
+async fn run_session(app: AppHandle, bindings: Vec<(String, ShortcutAction)>) -> ashpd::Result<()> {
+    let portal = GlobalShortcuts::new().await?;
+    let session = portal.create_session().await?;
+
+    let shortcuts: Vec<NewShortcut> = bindings
+        .iter()
+        .enumerate()
+        .map(|(index, (shortcut, action))| {
+            NewShortcut::new(format!("{}{}", ID_PREFIX, index), action.target_key())
+                .preferred_trigger(portal_trigger(shortcut).as_str())
+        })
+        .collect();
+
+    portal
+        .bind_shortcuts(&session, &shortcuts, &WindowIdentifier::default())
+        .await?
+        .response()?;
+    log::info!("Bound {} shortcuts through the GlobalShortcuts portal", bindings.len());
+
+    let mut activated = portal.receive_activated().await?;
+    while let Some(event) = activated.next().await {
+        let index = event
+            .shortcut_id()
+            .strip_prefix(ID_PREFIX)
+            .and_then(|i| i.parse::<usize>().ok());
+        if let Some((_, action)) = index.and_then(|i| bindings.get(i)) {
+            shortcuts::handle_shortcut_trigger(&app, action);
+        }
+    }
+
+    Ok(())
+}
+
+/// 重新绑定全部快捷键（替换之前的门户会话）
+pub fn bind(app: &AppHandle, bindings: Vec<(String, ShortcutAction)>) {
+    let state = match app.try_state::<PortalShortcuts>() {
+        Some(state) => state,
+        None => return,
+    };
+
+    let mut task = state.task.lock();
+    if let Some(previous) = task.take() {
+        previous.abort();
+    }
+    if bindings.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    *task = Some(tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_session(app.clone(), bindings.clone()).await {
+            log::warn!("GlobalShortcuts portal unavailable: {}", e);
+            if let Some(manager) = app.try_state::<ShortcutManager>() {
+                let reason = format!("桌面环境不支持全局快捷键门户: {}", e);
+                for (shortcut, action) in bindings {
+                    manager.record_failure(&app, &shortcut, action, reason.clone());
+                }
+            }
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portal_trigger() {
+        assert_eq!(portal_trigger("CmdOrCtrl+Shift+K"), "CTRL+SHIFT+k");
+        assert_eq!(portal_trigger("Alt+Super+KeyA"), "ALT+LOGO+a");
+        assert_eq!(portal_trigger("Control+Digit1"), "CTRL+1");
+        assert_eq!(portal_trigger("Shift+F5"), "SHIFT+F5");
+    }
+}
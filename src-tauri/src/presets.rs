@@ -0,0 +1,218 @@
+//! 安全加固预设：把 `security_preset` 展开为第三方 Cookie、WebRTC、跟踪器拦截和临时存储的设置，
+//! 在创建小程序窗口时应用
+
+use tauri::{AppHandle, WebviewWindow, WebviewWindowBuilder};
+
+use crate::models::{SecurityPreset, WebApp};
+
+/// 常见的广告和统计域名（包括子域名），只在页面脚本发起的请求中拦截
+const TRACKER_HOSTS: &[&str] = &[
+    "google-analytics.com",
+    "googletagmanager.com",
+    "googleadservices.com",
+    "googlesyndication.com",
+    "doubleclick.net",
+    "connect.facebook.net",
+    "analytics.tiktok.com",
+    "bat.bing.com",
+    "clarity.ms",
+    "hotjar.com",
+    "mixpanel.com",
+    "segment.io",
+    "cdn.segment.com",
+    "amplitude.com",
+    "fullstory.com",
+    "scorecardresearch.com",
+    "quantserve.com",
+    "criteo.com",
+    "taboola.com",
+    "outbrain.com",
+];
+
+/// 预设展开后的设置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Hardening {
+    /// 拦截第三方 Cookie
+    pub block_third_party_cookies: bool,
+    /// 移除 WebRTC 接口，防止泄露本机 IP
+    pub disable_webrtc: bool,
+    /// 拦截发往跟踪器域名的请求
+    pub block_trackers: bool,
+    /// 使用临时存储（Cookie 和网站数据在窗口关闭后清除）
+    pub ephemeral_storage: bool,
+}
+
+impl Hardening {
+    pub fn is_enabled(&self) -> bool {
+        *self != Hardening::default()
+    }
+}
+
+/// 展开预设
+pub fn expand(preset: SecurityPreset) -> Hardening {
+    match preset {
+        SecurityPreset::Off => Hardening::default(),
+        SecurityPreset::Balanced => Hardening {
+            block_third_party_cookies: true,
+            block_trackers: true,
+            ..Hardening::default()
+        },
+        SecurityPreset::Strict => Hardening {
+            block_third_party_cookies: true,
+            disable_webrtc: true,
+            block_trackers: true,
+            ephemeral_storage: true,
+        },
+    }
+}
+
+const WEBRTC_SCRIPT: &str = r#"(function() {
+    ['RTCPeerConnection', 'webkitRTCPeerConnection', 'RTCDataChannel', 'RTCSessionDescription', 'RTCIceCandidate'].forEach(function(name) {
+        try {
+            Object.defineProperty(window, name, { value: undefined, writable: false, configurable: false });
+        } catch (e) {}
+    });
+})();"#;
+
+/// 跟踪器拦截：包装 fetch、XHR、sendBeacon 和脚本/图片/iframe 的 src
+const TRACKER_SCRIPT: &str = r#"(function() {
+    var hosts = __HOSTS__;
+    function blocked(url) {
+        var host;
+        try {
+            host = new URL(String(url), location.href).hostname;
+        } catch (e) {
+            return false;
+        }
+        for (var i = 0; i < hosts.length; i++) {
+            if (host === hosts[i] || host.endsWith('.' + hosts[i])) return true;
+        }
+        return false;
+    }
+
+    var nativeFetch = window.fetch;
+    window.fetch = function(input, init) {
+        var url = input instanceof Request ? input.url : input;
+        if (blocked(url)) return Promise.reject(new TypeError('Blocked by WebApp Hub'));
+        return nativeFetch.apply(this, arguments);
+    };
+
+    var nativeOpen = XMLHttpRequest.prototype.open;
+    var nativeSend = XMLHttpRequest.prototype.send;
+    XMLHttpRequest.prototype.open = function(method, url) {
+        this.__webappHubBlocked = blocked(url);
+        return nativeOpen.apply(this, arguments);
+    };
+    XMLHttpRequest.prototype.send = function() {
+        if (this.__webappHubBlocked) {
+            var xhr = this;
+            setTimeout(function() { xhr.dispatchEvent(new ProgressEvent('error')); }, 0);
+            return;
+        }
+        return nativeSend.apply(this, arguments);
+    };
+
+    if (navigator.sendBeacon) {
+        var nativeBeacon = navigator.sendBeacon.bind(navigator);
+        navigator.sendBeacon = function(url, data) {
+            return blocked(url) ? false : nativeBeacon(url, data);
+        };
+    }
+
+    [HTMLScriptElement, HTMLImageElement, HTMLIFrameElement].forEach(function(Element) {
+        var descriptor = Object.getOwnPropertyDescriptor(Element.prototype, 'src');
+        if (!descriptor || !descriptor.set) return;
+        Object.defineProperty(Element.prototype, 'src', {
+            get: descriptor.get,
+            set: function(value) {
+                if (!blocked(value)) descriptor.set.call(this, value);
+            },
+            configurable: true
+        });
+    });
+    var nativeSetAttribute = Element.prototype.setAttribute;
+    Element.prototype.setAttribute = function(name, value) {
+        if (String(name).toLowerCase() === 'src' && blocked(value)) return;
+        return nativeSetAttribute.apply(this, arguments);
+    };
+})();"#;
+
+fn tracker_script() -> String {
+    TRACKER_SCRIPT.replace("__HOSTS__", &serde_json::to_string(TRACKER_HOSTS).unwrap_or_default())
+}
+
+/// 创建窗口时应用预设：临时存储和需要在页面脚本之前运行的脚本
+///
+/// 第三方 Cookie 在 Windows 上由 WebView2 参数拦截（见 `profiles::browser_args`），
+/// Linux 上在窗口创建后设置（见 [`apply_to_window`]），macOS 的 WKWebView 默认已拦截
+pub fn apply<'a>(
+    builder: WebviewWindowBuilder<'a, tauri::Wry, AppHandle>,
+    webapp: &WebApp,
+) -> WebviewWindowBuilder<'a, tauri::Wry, AppHandle> {
+    let hardening = expand(webapp.security_preset);
+    if !hardening.is_enabled() {
+        return builder;
+    }
+    log::debug!("Using {:?} security preset for {}", webapp.security_preset, webapp.id);
+
+    let mut builder = builder;
+    // 临时存储优先于独立配置文件
+    if hardening.ephemeral_storage {
+        builder = builder.incognito(true);
+    }
+    if hardening.disable_webrtc {
+        builder = builder.initialization_script(WEBRTC_SCRIPT);
+    }
+    if hardening.block_trackers {
+        builder = builder.initialization_script(tracker_script());
+    }
+    builder
+}
+
+/// 窗口创建后应用预设（Linux 上修改 WebKitGTK 的 Cookie 策略，作用于同一配置文件的所有窗口）
+pub fn apply_to_window(window: &WebviewWindow, webapp: &WebApp) {
+    if !expand(webapp.security_preset).block_third_party_cookies {
+        return;
+    }
+    if let Err(e) = block_third_party_cookies(window) {
+        log::warn!("Failed to block third-party cookies for {}: {}", webapp.id, e);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn block_third_party_cookies(window: &WebviewWindow) -> Result<(), String> {
+    window
+        .with_webview(|webview| {
+            use webkit2gtk::{CookieAcceptPolicy, CookieManagerExt, WebContextExt, WebViewExt};
+            if let Some(cookies) = webview.inner().context().and_then(|context| context.cookie_manager()) {
+                cookies.set_accept_policy(CookieAcceptPolicy::NoThirdParty);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn block_third_party_cookies(_window: &WebviewWindow) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_presets() {
+        assert!(!expand(SecurityPreset::Off).is_enabled());
+
+        let balanced = expand(SecurityPreset::Balanced);
+        assert!(balanced.block_third_party_cookies && balanced.block_trackers);
+        assert!(!balanced.disable_webrtc && !balanced.ephemeral_storage);
+
+        let strict = expand(SecurityPreset::Strict);
+        assert!(strict.disable_webrtc && strict.ephemeral_storage && strict.block_trackers);
+
+        let parsed: SecurityPreset = serde_json::from_str("\"strict\"").unwrap();
+        assert_eq!(parsed, SecurityPreset::Strict);
+        assert!(tracker_script().contains("\"doubleclick.net\""));
+    }
+}
@@ -0,0 +1,454 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::events::{self, Channel};
+use crate::models::WebApp;
+use crate::tunnel;
+use crate::window::{OpenOptions, WindowManager};
+
+/// 等待端口可连接的最长时间
+const PORT_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 端口检查间隔
+const PORT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 发送终止信号后等待进程退出的时间，超时后强制结束
+const STOP_GRACE: Duration = Duration::from_secs(3);
+
+/// 小程序关联进程的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessKind {
+    /// 启动命令（如 `npm run dev`）
+    StartCommand,
+    /// SSH 端口转发
+    Tunnel,
+}
+
+/// 关联进程的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessState {
+    /// 进程已启动，正在等待端口
+    Starting,
+    /// 端口可连接，可以打开窗口
+    Ready,
+    /// 未运行
+    Stopped,
+}
+
+/// 进程状态变化事件（`process://status`）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessStatusEvent<'a> {
+    webapp_id: &'a str,
+    kind: ProcessKind,
+    state: ProcessState,
+    error: Option<String>,
+}
+
+struct ManagedProcess {
+    /// 由本应用启动的进程；尚未启动或端口已被外部服务占用时为 None
+    child: Option<Child>,
+    ready: bool,
+    stop_on_close: bool,
+}
+
+type ProcessKey = (String, ProcessKind);
+
+/// 关联进程管理器 - 跟踪每个小程序启动的本地进程和 SSH 隧道
+pub struct ProcessManager {
+    processes: Mutex<HashMap<ProcessKey, ManagedProcess>>,
+}
+
+impl ProcessManager {
+    pub fn new() -> Self {
+        Self {
+            processes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取小程序关联进程的状态（已退出的进程会被移除）
+    pub fn state(&self, webapp_id: &str, kind: ProcessKind) -> ProcessState {
+        let key = (webapp_id.to_string(), kind);
+        let mut processes = self.processes.lock();
+        let exited = match processes.get_mut(&key) {
+            None => return ProcessState::Stopped,
+            Some(process) => match process.child.as_mut() {
+                Some(child) => !matches!(child.try_wait(), Ok(None)),
+                None => false,
+            },
+        };
+
+        if exited {
+            processes.remove(&key);
+            log::info!("{:?} process for {} has exited", kind, webapp_id);
+            return ProcessState::Stopped;
+        }
+
+        if processes[&key].ready {
+            ProcessState::Ready
+        } else {
+            ProcessState::Starting
+        }
+    }
+
+    /// 记录正在启动的进程，避免重复打开时再次启动
+    fn begin(&self, webapp_id: &str, kind: ProcessKind, stop_on_close: bool) {
+        self.processes.lock().insert(
+            (webapp_id.to_string(), kind),
+            ManagedProcess {
+                child: None,
+                ready: false,
+                stop_on_close,
+            },
+        );
+    }
+
+    fn attach(&self, webapp_id: &str, kind: ProcessKind, child: Child) {
+        match self.processes.lock().get_mut(&(webapp_id.to_string(), kind)) {
+            Some(process) => process.child = Some(child),
+            // 启动期间已被停止
+            None => terminate(child),
+        }
+    }
+
+    fn mark_ready(&self, webapp_id: &str, kind: ProcessKind) {
+        if let Some(process) = self.processes.lock().get_mut(&(webapp_id.to_string(), kind)) {
+            process.ready = true;
+        }
+    }
+
+    /// 停止小程序的关联进程
+    pub fn stop(&self, webapp_id: &str, kind: ProcessKind) {
+        let removed = self.processes.lock().remove(&(webapp_id.to_string(), kind));
+        if let Some(child) = removed.and_then(|p| p.child) {
+            log::info!("Stopping {:?} process for {}", kind, webapp_id);
+            terminate(child);
+        }
+    }
+
+    /// 窗口关闭时停止需要随窗口结束的进程
+    pub fn handle_window_closed(&self, webapp_id: &str) {
+        for kind in [ProcessKind::StartCommand, ProcessKind::Tunnel] {
+            let stop = self
+                .processes
+                .lock()
+                .get(&(webapp_id.to_string(), kind))
+                .map(|p| p.stop_on_close)
+                .unwrap_or(false);
+            if stop {
+                self.stop(webapp_id, kind);
+            }
+        }
+    }
+
+    /// 停止所有进程（应用退出时）
+    pub fn stop_all(&self) {
+        let keys: Vec<ProcessKey> = self.processes.lock().keys().cloned().collect();
+        for (webapp_id, kind) in keys {
+            self.stop(&webapp_id, kind);
+        }
+    }
+}
+
+impl Default for ProcessManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 网址对应的需要等待的地址 (host, port)
+fn target_address(url: &str) -> Result<(String, u16), String> {
+    let parsed = url::Url::parse(url).map_err(|e| e.to_string())?;
+    let host = parsed.host_str().ok_or("网址缺少主机名")?;
+    let port = parsed.port_or_known_default().ok_or("无法确定网址端口")?;
+    Ok((host.trim_matches(|c| c == '[' || c == ']').to_string(), port))
+}
+
+async fn is_port_open(host: &str, port: u16) -> bool {
+    matches!(
+        tokio::time::timeout(PORT_POLL_INTERVAL, tokio::net::TcpStream::connect((host, port))).await,
+        Ok(Ok(_))
+    )
+}
+
+/// 构建通过系统 shell 执行的命令
+fn shell_command(command: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+/// 让子进程在后台运行：不继承标准输入输出，不弹出控制台窗口，
+/// 在 Unix 上使用独立进程组，停止时连同其子进程一起结束
+fn detach(cmd: &mut Command) {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // 不为子进程创建控制台窗口
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+}
+
+/// 结束进程及其子进程
+fn terminate(mut child: Child) {
+    let pid = child.id().to_string();
+
+    #[cfg(target_os = "windows")]
+    let _ = Command::new("taskkill")
+        .args(["/PID", &pid, "/T", "/F"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = Command::new("kill")
+        .arg("-TERM")
+        .arg(format!("-{}", pid))
+        .status();
+
+    // 在后台回收进程，未按时退出则强制结束
+    std::thread::spawn(move || {
+        let deadline = Instant::now() + STOP_GRACE;
+        while Instant::now() < deadline {
+            if !matches!(child.try_wait(), Ok(None)) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    });
+}
+
+fn emit_status(
+    app: &AppHandle,
+    webapp_id: &str,
+    kind: ProcessKind,
+    state: ProcessState,
+    error: Option<String>,
+) {
+    events::emit(
+        app,
+        Channel::Windows,
+        "process://status",
+        ProcessStatusEvent {
+            webapp_id,
+            kind,
+            state,
+            error,
+        },
+    );
+}
+
+/// 打开窗口前需要启动的进程
+struct Launch {
+    kind: ProcessKind,
+    command: Command,
+    /// 就绪后可连接的地址
+    host: String,
+    port: u16,
+    /// 端口已可连接时直接复用（服务已在运行），否则视为端口被占用
+    reuse_open_port: bool,
+    stop_on_close: bool,
+}
+
+/// 启动进程，等待端口可连接后重新打开窗口
+fn launch_then_open(
+    app: &AppHandle,
+    webapp: &WebApp,
+    launch: Launch,
+    proxy_url: Option<String>,
+    options: OpenOptions,
+) -> Result<(), String> {
+    let Launch {
+        kind,
+        mut command,
+        host,
+        port,
+        reuse_open_port,
+        stop_on_close,
+    } = launch;
+    let app = app.clone();
+    let webapp = webapp.clone();
+
+    app.state::<ProcessManager>()
+        .begin(&webapp.id, kind, stop_on_close);
+
+    tauri::async_runtime::spawn(async move {
+        let processes = app.state::<ProcessManager>();
+        let fail = |error: String| {
+            processes.stop(&webapp.id, kind);
+            emit_status(&app, &webapp.id, kind, ProcessState::Stopped, Some(error));
+        };
+
+        if is_port_open(&host, port).await {
+            if !reuse_open_port {
+                return fail(format!("本地端口 {} 已被占用", port));
+            }
+            log::info!("Port {} already open for {}, skipping {:?}", port, webapp.id, kind);
+        } else {
+            detach(&mut command);
+            match command.spawn() {
+                Ok(child) => {
+                    log::info!("Started {:?} process for {} (pid {})", kind, webapp.id, child.id());
+                    processes.attach(&webapp.id, kind, child);
+                }
+                Err(e) => {
+                    log::error!("Failed to start {:?} process for {}: {}", kind, webapp.id, e);
+                    return fail(format!("启动进程失败: {}", e));
+                }
+            }
+            emit_status(&app, &webapp.id, kind, ProcessState::Starting, None);
+
+            if let Err(error) = wait_until_ready(&processes, &webapp.id, kind, &host, port).await {
+                return fail(error);
+            }
+        }
+
+        processes.mark_ready(&webapp.id, kind);
+        emit_status(&app, &webapp.id, kind, ProcessState::Ready, None);
+
+        if let Err(e) = app
+            .state::<WindowManager>()
+            .open_webapp_with(&app, &webapp, proxy_url, options)
+        {
+            log::error!("Failed to open {} after {:?} became ready: {}", webapp.id, kind, e);
+        }
+    });
+
+    Ok(())
+}
+
+/// 等待端口可连接；进程提前退出或超时时返回错误
+async fn wait_until_ready(
+    processes: &ProcessManager,
+    webapp_id: &str,
+    kind: ProcessKind,
+    host: &str,
+    port: u16,
+) -> Result<(), String> {
+    let deadline = Instant::now() + PORT_WAIT_TIMEOUT;
+
+    loop {
+        if is_port_open(host, port).await {
+            return Ok(());
+        }
+        if processes.state(webapp_id, kind) == ProcessState::Stopped {
+            return Err("进程已退出".to_string());
+        }
+        if Instant::now() >= deadline {
+            log::warn!("Timed out waiting for port {} of {}", port, webapp_id);
+            return Err(format!("等待端口 {} 超时", port));
+        }
+        tokio::time::sleep(PORT_POLL_INTERVAL).await;
+    }
+}
+
+/// 打开窗口前确保关联进程（SSH 隧道、启动命令）已就绪
+/// 返回 true 表示可以立即创建窗口；否则进程正在启动，就绪后会自动重新打开
+pub fn ensure_ready(
+    app: &AppHandle,
+    webapp: &WebApp,
+    proxy_url: &Option<String>,
+    options: &OpenOptions,
+) -> Result<bool, String> {
+    let processes = match app.try_state::<ProcessManager>() {
+        Some(processes) => processes,
+        None => return Ok(true),
+    };
+
+    // 先建立隧道，启动命令（如果有）在隧道就绪后的下一次打开中处理
+    if let Some(settings) = &webapp.ssh_tunnel {
+        match processes.state(&webapp.id, ProcessKind::Tunnel) {
+            ProcessState::Ready => {}
+            ProcessState::Starting => return Ok(false),
+            ProcessState::Stopped => {
+                let launch = Launch {
+                    kind: ProcessKind::Tunnel,
+                    command: tunnel::ssh_command(settings, &webapp.url)?,
+                    host: tunnel::LOCAL_HOST.to_string(),
+                    port: settings.local_port,
+                    reuse_open_port: false,
+                    stop_on_close: true,
+                };
+                launch_then_open(app, webapp, launch, proxy_url.clone(), options.clone())?;
+                return Ok(false);
+            }
+        }
+    }
+
+    if let Some(start_command) = webapp.start_command.as_deref() {
+        match processes.state(&webapp.id, ProcessKind::StartCommand) {
+            ProcessState::Ready => {}
+            ProcessState::Starting => return Ok(false),
+            ProcessState::Stopped => {
+                let (host, port) = target_address(&webapp.url)?;
+                let mut command = shell_command(start_command);
+                if let Some(dir) = webapp.start_working_dir.as_deref() {
+                    command.current_dir(dir);
+                }
+                let launch = Launch {
+                    kind: ProcessKind::StartCommand,
+                    command,
+                    host,
+                    port,
+                    reuse_open_port: true,
+                    stop_on_close: webapp.stop_process_on_close,
+                };
+                launch_then_open(app, webapp, launch, proxy_url.clone(), options.clone())?;
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_address() {
+        assert_eq!(
+            target_address("http://localhost:5173/app").unwrap(),
+            ("localhost".to_string(), 5173)
+        );
+        assert_eq!(
+            target_address("https://dev.internal").unwrap(),
+            ("dev.internal".to_string(), 443)
+        );
+        assert_eq!(
+            target_address("http://[::1]:3000").unwrap(),
+            ("::1".to_string(), 3000)
+        );
+        assert!(target_address("not a url").is_err());
+    }
+}
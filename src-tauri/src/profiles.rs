@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::models::{AppConfig, WebApp};
+#[cfg(any(target_os = "windows", test))]
+use crate::presets;
+use crate::window::{window_label, WindowManager};
+
+/// 独立配置文件的根目录
+pub fn profiles_root(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_local_data_dir()
+        .unwrap_or_default()
+        .join("profiles")
+}
+
+/// 小程序使用的配置文件目录，使用共享配置文件时返回 None
+///
+/// `data_dir` 为绝对路径时直接使用，为名称时放在根目录下（多个小程序填同一名称即共用）；
+/// 只开启 `isolated_session` 时按小程序 ID 创建
+fn profile_dir(root: &Path, webapp: &WebApp) -> Option<PathBuf> {
+    if let Some(dir) = webapp.data_dir.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        let path = Path::new(dir);
+        if path.is_absolute() {
+            return Some(path.to_path_buf());
+        }
+        // 名称只能是单个目录名，不能跳出根目录
+        let mut components = path.components();
+        if let (Some(Component::Normal(name)), None) = (components.next(), components.next()) {
+            return Some(root.join(name));
+        }
+        return Some(root.join(&webapp.id));
+    }
+    webapp.isolated_session.then(|| root.join(&webapp.id))
+}
+
+/// 小程序的独立配置文件目录
+pub fn data_directory(app: &AppHandle, webapp: &WebApp) -> Option<PathBuf> {
+    profile_dir(&profiles_root(app), webapp)
+}
+
+/// 各独立配置文件的缓存上限（MB）：共用同一配置文件的小程序取最小值，未设置时使用全局上限
+fn cache_limits_in(root: &Path, config: &AppConfig) -> BTreeMap<PathBuf, u64> {
+    let mut limits = BTreeMap::new();
+    for webapp in &config.webapps {
+        if let Some(dir) = profile_dir(root, webapp) {
+            let limit = webapp.cache_max_mb.unwrap_or(config.maintenance.cache_max_mb);
+            limits
+                .entry(dir)
+                .and_modify(|existing: &mut u64| *existing = (*existing).min(limit))
+                .or_insert(limit);
+        }
+    }
+    limits
+}
+
+/// 各独立配置文件目录及其缓存上限（MB）
+pub fn cache_limits(app: &AppHandle, config: &AppConfig) -> BTreeMap<PathBuf, u64> {
+    cache_limits_in(&profiles_root(app), config)
+}
+
+/// 各配置文件（None 为共享配置文件）是否禁用硬件加速：其中任一小程序禁用时即禁用
+#[cfg(any(target_os = "windows", test))]
+fn gpu_disabled_in(root: &Path, config: &AppConfig) -> BTreeMap<Option<PathBuf>, bool> {
+    let mut disabled = BTreeMap::new();
+    for webapp in &config.webapps {
+        let off = !config.hardware_acceleration_for(webapp);
+        *disabled.entry(profile_dir(root, webapp)).or_insert(false) |= off;
+    }
+    disabled
+}
+
+/// 各配置文件是否拦截第三方 Cookie：其中任一小程序的安全预设拦截时即拦截
+#[cfg(any(target_os = "windows", test))]
+fn third_party_cookies_blocked_in(root: &Path, config: &AppConfig) -> BTreeMap<Option<PathBuf>, bool> {
+    let mut blocked = BTreeMap::new();
+    for webapp in &config.webapps {
+        let block = presets::expand(webapp.security_preset).block_third_party_cookies;
+        *blocked.entry(profile_dir(root, webapp)).or_insert(false) |= block;
+    }
+    blocked
+}
+
+/// WebView2 的浏览器参数：默认参数加上磁盘缓存上限，配置文件禁用硬件加速时加上 `--disable-gpu`，
+/// 拦截第三方 Cookie 时加上 `--test-third-party-cookie-phaseout`
+///
+/// 同一配置文件的窗口必须使用相同的参数，因此每个配置文件的参数在本次运行中第一次打开窗口时确定
+#[cfg(target_os = "windows")]
+pub fn browser_args(app: &AppHandle, config: &AppConfig, webapp: &WebApp) -> String {
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+
+    static PROFILE_ARGS: Mutex<Option<HashMap<Option<PathBuf>, String>>> = parking_lot::const_mutex(None);
+
+    let root = profiles_root(app);
+    let dir = profile_dir(&root, webapp);
+    PROFILE_ARGS
+        .lock()
+        .get_or_insert_with(HashMap::new)
+        .entry(dir.clone())
+        .or_insert_with(|| {
+            let limit = dir
+                .as_ref()
+                .and_then(|dir| cache_limits_in(&root, config).get(dir).copied())
+                .unwrap_or(config.maintenance.cache_max_mb);
+            let gpu_disabled = gpu_disabled_in(&root, config).get(&dir).copied().unwrap_or(false);
+            let cookies_blocked = third_party_cookies_blocked_in(&root, config)
+                .get(&dir)
+                .copied()
+                .unwrap_or(false);
+            format!(
+                "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection --disk-cache-size={}{}{}",
+                limit.saturating_mul(1024 * 1024),
+                if gpu_disabled { " --disable-gpu" } else { "" },
+                if cookies_blocked { " --test-third-party-cookie-phaseout" } else { "" }
+            )
+        })
+        .clone()
+}
+
+/// macOS 的 WKWebView 按标识区分数据存储，由配置文件目录生成
+#[cfg(target_os = "macos")]
+pub fn data_store_identifier(dir: &Path) -> [u8; 16] {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(dir.to_string_lossy().as_bytes());
+    let mut identifier = [0u8; 16];
+    identifier.copy_from_slice(&digest[..16]);
+    identifier
+}
+
+/// 等待窗口销毁（关闭是异步的）
+async fn wait_until_closed(app: &AppHandle, label: &str) {
+    for _ in 0..40 {
+        if app.get_webview_window(label).is_none() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// 清除使用独立配置文件的小程序保存的 Cookie、缓存和网站存储（会关闭使用该配置文件的窗口）
+pub async fn wipe(app: &AppHandle, webapp_id: &str) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+    let dir = data_directory(app, webapp)
+        .ok_or_else(|| AppError::invalid_input("该小程序使用共享的配置文件，不能单独清除数据"))?;
+    let label = window_label(webapp_id);
+    let window_manager = app.state::<WindowManager>();
+
+    // macOS 的数据存储只能通过 WebView 清除，窗口未打开时先在后台打开
+    #[cfg(target_os = "macos")]
+    {
+        if app.get_webview_window(&label).is_none() {
+            let options = crate::window::OpenOptions {
+                hidden: true,
+                ..Default::default()
+            };
+            window_manager.open_webapp_with(app, webapp, None, options)?;
+        }
+    }
+
+    if let Some(window) = app.get_webview_window(&label) {
+        if let Err(e) = window.clear_all_browsing_data() {
+            log::warn!("Failed to clear browsing data for {}: {}", webapp_id, e);
+        }
+    }
+    // 共用同一配置文件的小程序窗口也要关闭，才能删除目录
+    for other in config
+        .webapps
+        .iter()
+        .filter(|w| data_directory(app, w).as_ref() == Some(&dir))
+    {
+        let label = window_label(&other.id);
+        if app.get_webview_window(&label).is_some() {
+            window_manager.close_webapp(app, &other.id)?;
+            wait_until_closed(app, &label).await;
+        }
+    }
+
+    // WebView 进程退出前可能仍占用文件，稍后重试
+    let mut attempts = 0;
+    while dir.exists() {
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => break,
+            Err(e) if attempts < 10 => {
+                attempts += 1;
+                log::debug!("Retrying removal of {:?}: {}", dir, e);
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+            Err(e) => return Err(AppError::Io(format!("无法删除配置文件目录: {}", e))),
+        }
+    }
+
+    log::info!("Wiped stored data for webapp {}", webapp_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SecurityPreset;
+
+    #[test]
+    fn test_profile_dir() {
+        let root = Path::new("/data/profiles");
+        let mut webapp = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        webapp.id = "mail".to_string();
+        assert_eq!(profile_dir(root, &webapp), None);
+
+        webapp.isolated_session = true;
+        assert_eq!(profile_dir(root, &webapp), Some(root.join("mail")));
+
+        webapp.data_dir = Some("work".to_string());
+        assert_eq!(profile_dir(root, &webapp), Some(root.join("work")));
+        webapp.data_dir = Some("../escape".to_string());
+        assert_eq!(profile_dir(root, &webapp), Some(root.join("mail")));
+    }
+
+    #[test]
+    fn test_cache_limits_use_smallest_cap_per_profile() {
+        let root = Path::new("/data/profiles");
+        let mut config = AppConfig::default();
+        config.maintenance.cache_max_mb = 512;
+
+        let mut shared = WebApp::new("News".to_string(), "https://news.example.com".to_string());
+        shared.cache_max_mb = Some(64);
+        let mut mail = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        mail.data_dir = Some("work".to_string());
+        let mut chat = WebApp::new("Chat".to_string(), "https://chat.example.com".to_string());
+        chat.data_dir = Some("work".to_string());
+        chat.cache_max_mb = Some(128);
+        let mut docs = WebApp::new("Docs".to_string(), "https://docs.example.com".to_string());
+        docs.isolated_session = true;
+        config.webapps = vec![shared, mail, chat, docs.clone()];
+
+        let limits = cache_limits_in(root, &config);
+        assert_eq!(limits.len(), 2);
+        assert_eq!(limits[&root.join("work")], 128);
+        assert_eq!(limits[&root.join(&docs.id)], 512);
+    }
+
+    #[test]
+    fn test_gpu_disabled_per_profile() {
+        let root = Path::new("/data/profiles");
+        let mut config = AppConfig::default();
+        let mut mail = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        mail.data_dir = Some("work".to_string());
+        mail.hardware_acceleration = Some(false);
+        let mut chat = WebApp::new("Chat".to_string(), "https://chat.example.com".to_string());
+        chat.data_dir = Some("work".to_string());
+        let news = WebApp::new("News".to_string(), "https://news.example.com".to_string());
+        config.webapps = vec![mail, chat, news];
+
+        let disabled = gpu_disabled_in(root, &config);
+        assert!(disabled[&Some(root.join("work"))]);
+        assert!(!disabled[&None]);
+
+        config.disable_gpu = true;
+        config.webapps[2].hardware_acceleration = Some(true);
+        assert!(!gpu_disabled_in(root, &config)[&None]);
+        config.webapps.push(WebApp::new("Docs".to_string(), "https://docs.example.com".to_string()));
+        assert!(gpu_disabled_in(root, &config)[&None]);
+    }
+
+    #[test]
+    fn test_third_party_cookies_blocked_per_profile() {
+        let root = Path::new("/data/profiles");
+        let mut mail = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        mail.isolated_session = true;
+        mail.security_preset = SecurityPreset::Balanced;
+        let news = WebApp::new("News".to_string(), "https://news.example.com".to_string());
+        let config = AppConfig {
+            webapps: vec![mail.clone(), news],
+            ..Default::default()
+        };
+
+        let blocked = third_party_cookies_blocked_in(root, &config);
+        assert!(blocked[&Some(root.join(&mail.id))]);
+        assert!(!blocked[&None]);
+    }
+}
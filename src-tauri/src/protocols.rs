@@ -0,0 +1,351 @@
+use percent_encoding::percent_decode_str;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+use url::Url;
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::models::{AppConfig, ProtocolHandler, WebApp};
+use crate::share_target::{self, SharePayload, SHARE_SCHEME};
+use crate::template;
+use crate::window::{OpenOptions, WindowManager};
+
+/// 供其他工具打开小程序的协议：`webapphub://open/<ID 或名称>`、`webapphub://toggle/<ID>`
+pub const HUB_SCHEME: &str = "webapphub";
+
+/// 不允许小程序接管的协议
+const RESERVED_SCHEMES: &[&str] = &[
+    "http", "https", "file", "about", "data", "javascript", "blob", "ws", "wss", "tauri", "asset", "ipc",
+    SHARE_SCHEME, HUB_SCHEME,
+];
+
+/// `webapphub://` 链接的动作
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HubLink {
+    /// 打开（或聚焦）小程序，按 ID 或名称查找
+    Open(String),
+    /// 切换小程序窗口
+    Toggle(String),
+}
+
+impl HubLink {
+    fn parse(link: &Url) -> Option<Self> {
+        if link.scheme() != HUB_SCHEME {
+            return None;
+        }
+        let key = percent_decode_str(link.path().trim_matches('/'))
+            .decode_utf8_lossy()
+            .into_owned();
+        if key.is_empty() {
+            return None;
+        }
+        match link.host_str()? {
+            "open" => Some(HubLink::Open(key)),
+            "toggle" => Some(HubLink::Toggle(key)),
+            _ => None,
+        }
+    }
+}
+
+/// 按 ID 查找已启用的小程序，找不到时按名称（不区分大小写）查找
+fn find_webapp<'a>(config: &'a AppConfig, key: &str) -> Option<&'a WebApp> {
+    let enabled = || config.webapps.iter().filter(|w| w.enabled);
+    enabled()
+        .find(|w| w.id == key)
+        .or_else(|| enabled().find(|w| w.name.eq_ignore_ascii_case(key)))
+}
+
+/// 处理 `webapphub://` 链接
+fn open_hub_link(app: &AppHandle, config: &AppConfig, link: HubLink) -> AppResult<()> {
+    let window_manager = app.state::<WindowManager>();
+    match link {
+        HubLink::Open(key) => {
+            let webapp = find_webapp(config, &key).ok_or_else(|| AppError::not_found(format!("小程序不存在: {}", key)))?;
+            log::info!("Opening {} from {} link", webapp.id, HUB_SCHEME);
+            Ok(window_manager.open_webapp(app, webapp, config.proxy_url_for(webapp))?)
+        }
+        HubLink::Toggle(id) => {
+            let webapp = config
+                .webapps
+                .iter()
+                .find(|w| w.enabled && w.id == id)
+                .ok_or_else(|| AppError::not_found(format!("小程序不存在: {}", id)))?;
+            log::info!("Toggling {} from {} link", webapp.id, HUB_SCHEME);
+            window_manager.toggle_webapp(app, webapp, config.proxy_url_for(webapp))?;
+            Ok(())
+        }
+    }
+}
+
+/// 规范化并检查协议名（允许 `mailto:`、`slack://` 等写法）
+pub fn normalize_scheme(scheme: &str) -> AppResult<String> {
+    let scheme = scheme.trim().trim_end_matches('/').trim_end_matches(':').to_ascii_lowercase();
+
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '-' | '.'));
+    if !valid {
+        return Err(AppError::invalid_input(format!("无效的协议名: {}", scheme)));
+    }
+    if RESERVED_SCHEMES.contains(&scheme.as_str()) {
+        return Err(AppError::invalid_input(format!("不能接管 {} 协议", scheme)));
+    }
+    Ok(scheme)
+}
+
+/// 处理该协议的小程序（第一个启用的）
+fn find_handler<'a>(config: &'a AppConfig, scheme: &str) -> Option<(&'a WebApp, &'a ProtocolHandler)> {
+    config
+        .webapps
+        .iter()
+        .filter(|w| w.enabled)
+        .find_map(|w| {
+            w.protocols
+                .iter()
+                .find(|p| p.enabled && p.scheme.eq_ignore_ascii_case(scheme))
+                .map(|p| (w, p))
+        })
+}
+
+/// 按模板生成链接在小程序中打开的网址
+fn target_url(handler: &ProtocolHandler, link: &Url) -> String {
+    let url = percent_decode_str(link.as_str()).decode_utf8_lossy();
+    let path = url
+        .split_once(':')
+        .map(|(_, rest)| rest.trim_start_matches("//"))
+        .unwrap_or_default();
+    template::render_url(&handler.url_template, &[("url", &url), ("path", path)])
+}
+
+/// 在系统中注册或注销协议
+/// macOS 只能在应用的 Info.plist 中静态声明协议，运行时无法修改
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn register_scheme(app: &AppHandle, scheme: &str, enabled: bool) -> Result<(), String> {
+    let deep_link = app.deep_link();
+    let result = if enabled {
+        deep_link.register(scheme)
+    } else {
+        deep_link.unregister(scheme)
+    };
+    result.map_err(|e| format!("无法注册 {} 协议: {}", scheme, e))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn register_scheme(_app: &AppHandle, scheme: &str, enabled: bool) -> Result<(), String> {
+    log::info!("Protocol {} registration ({}) is not supported at runtime on this platform", scheme, enabled);
+    Ok(())
+}
+
+/// 在小程序窗口中打开系统转交的链接
+fn open_link(app: &AppHandle, config: &AppConfig, link: &Url) -> AppResult<()> {
+    let (webapp, handler) = find_handler(config, link.scheme())
+        .ok_or_else(|| AppError::not_found(format!("没有处理 {} 链接的小程序", link.scheme())))?;
+    let target = Url::parse(&target_url(handler, link))
+        .map_err(|e| AppError::invalid_input(format!("无效的协议网址模板: {}", e)))?;
+
+    log::info!("Opening {} link in {}", link.scheme(), webapp.id);
+    app.state::<WindowManager>().open_webapp_with(
+        app,
+        webapp,
+        config.proxy_url_for(webapp),
+        OpenOptions {
+            target_url: Some(target),
+            ..OpenOptions::default()
+        },
+    )?;
+    Ok(())
+}
+
+/// 处理系统转交的链接（冷启动参数或运行中收到的链接）
+pub fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    let config = app.state::<ConfigManager>().read();
+    for link in urls {
+        if let Some(payload) = SharePayload::from_link(&link) {
+            share_target::handle(app, payload);
+            continue;
+        }
+        if link.scheme() == HUB_SCHEME {
+            let result = match HubLink::parse(&link) {
+                Some(hub_link) => open_hub_link(app, &config, hub_link),
+                None => Err(AppError::invalid_input(format!("无效的链接: {}", link))),
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to handle {} link: {}", HUB_SCHEME, e);
+            }
+            continue;
+        }
+        if let Err(e) = open_link(app, &config, &link) {
+            log::warn!("Failed to handle {} link: {}", link.scheme(), e);
+        }
+    }
+}
+
+/// 注册已启用的协议并监听系统转交的链接
+pub fn setup(app: &AppHandle) {
+    // 本应用的分享和打开协议在 tauri.conf.json 中声明，开发环境下也需要运行时注册
+    for scheme in [SHARE_SCHEME, HUB_SCHEME] {
+        if let Err(e) = register_scheme(app, scheme, true) {
+            log::warn!("{}", e);
+        }
+    }
+
+    let config = app.state::<ConfigManager>().read();
+    for webapp in config.webapps.iter().filter(|w| w.enabled) {
+        for handler in webapp.protocols.iter().filter(|p| p.enabled) {
+            if let Err(e) = register_scheme(app, &handler.scheme, true) {
+                log::warn!("{}", e);
+            }
+        }
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| handle_urls(&handle, event.urls()));
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        handle_urls(app, urls);
+    }
+}
+
+/// 协议修改后同步系统注册：仍有小程序处理时保持注册，否则注销
+fn sync_scheme(app: &AppHandle, scheme: &str) -> Result<(), String> {
+    let config = app.state::<ConfigManager>().read();
+    register_scheme(app, scheme, find_handler(&config, scheme).is_some())
+}
+
+/// 添加或修改小程序的协议处理
+pub fn set_handler(app: &AppHandle, webapp_id: &str, mut handler: ProtocolHandler) -> AppResult<()> {
+    handler.scheme = normalize_scheme(&handler.scheme)?;
+    if !handler.url_template.contains("{url}") && !handler.url_template.contains("{path}") {
+        return Err(AppError::invalid_input("网址模板中需要包含 {url} 或 {path}"));
+    }
+    let sample = Url::parse(&format!("{}:test", handler.scheme)).map_err(|e| AppError::invalid_input(e.to_string()))?;
+    Url::parse(&target_url(&handler, &sample))
+        .map_err(|e| AppError::invalid_input(format!("无效的协议网址模板: {}", e)))?;
+
+    let scheme = handler.scheme.clone();
+    app.state::<ConfigManager>().update(|config| {
+        let webapp = config
+            .webapps
+            .iter_mut()
+            .find(|w| w.id == webapp_id)
+            .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+        match webapp.protocols.iter_mut().find(|p| p.scheme == handler.scheme) {
+            Some(existing) => *existing = handler,
+            None => webapp.protocols.push(handler),
+        }
+        Ok::<_, AppError>(())
+    })??;
+
+    Ok(sync_scheme(app, &scheme)?)
+}
+
+/// 启用或停用小程序的某个协议
+pub fn set_enabled(app: &AppHandle, webapp_id: &str, scheme: &str, enabled: bool) -> AppResult<()> {
+    let scheme = normalize_scheme(scheme)?;
+    app.state::<ConfigManager>().update(|config| {
+        let handler = config
+            .webapps
+            .iter_mut()
+            .find(|w| w.id == webapp_id)
+            .ok_or_else(|| AppError::not_found("小程序不存在"))?
+            .protocols
+            .iter_mut()
+            .find(|p| p.scheme == scheme)
+            .ok_or_else(|| AppError::not_found("小程序没有处理该协议"))?;
+        handler.enabled = enabled;
+        Ok::<_, AppError>(())
+    })??;
+
+    Ok(sync_scheme(app, &scheme)?)
+}
+
+/// 移除小程序的协议处理
+pub fn remove_handler(app: &AppHandle, webapp_id: &str, scheme: &str) -> AppResult<()> {
+    let scheme = normalize_scheme(scheme)?;
+    app.state::<ConfigManager>().update(|config| {
+        let webapp = config
+            .webapps
+            .iter_mut()
+            .find(|w| w.id == webapp_id)
+            .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+        webapp.protocols.retain(|p| p.scheme != scheme);
+        Ok::<_, AppError>(())
+    })??;
+
+    Ok(sync_scheme(app, &scheme)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler(scheme: &str, template: &str) -> ProtocolHandler {
+        ProtocolHandler {
+            scheme: scheme.to_string(),
+            url_template: template.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_normalize_scheme() {
+        assert_eq!(normalize_scheme("mailto:").unwrap(), "mailto");
+        assert_eq!(normalize_scheme("Slack://").unwrap(), "slack");
+        assert_eq!(normalize_scheme("web+app").unwrap(), "web+app");
+        assert!(normalize_scheme("https").is_err());
+        assert!(normalize_scheme("1abc").is_err());
+        assert!(normalize_scheme("").is_err());
+    }
+
+    #[test]
+    fn test_target_url() {
+        let mailto = handler("mailto", "https://mail.google.com/mail/?view=cm&fs=1&to={path}");
+        let link = Url::parse("mailto:someone@example.com").unwrap();
+        assert_eq!(
+            target_url(&mailto, &link),
+            "https://mail.google.com/mail/?view=cm&fs=1&to=someone%40example%2Ecom"
+        );
+
+        let slack = handler("slack", "https://app.slack.com/open?link={url}");
+        let link = Url::parse("slack://channel?team=T1").unwrap();
+        assert_eq!(
+            target_url(&slack, &link),
+            "https://app.slack.com/open?link=slack%3A%2F%2Fchannel%3Fteam%3DT1"
+        );
+    }
+
+    #[test]
+    fn test_hub_links() {
+        let parse = |link: &str| HubLink::parse(&Url::parse(link).unwrap());
+        assert_eq!(parse("webapphub://open/abc-123"), Some(HubLink::Open("abc-123".to_string())));
+        assert_eq!(parse("webapphub://open/My%20Mail/"), Some(HubLink::Open("My Mail".to_string())));
+        assert_eq!(parse("webapphub://toggle/abc-123"), Some(HubLink::Toggle("abc-123".to_string())));
+        assert_eq!(parse("webapphub://open/"), None);
+        assert_eq!(parse("webapphub://close/abc-123"), None);
+        assert_eq!(parse("webapp-hub://open/abc-123"), None);
+
+        let mut config = AppConfig::default();
+        let mail = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        config.webapps = vec![mail.clone()];
+        assert_eq!(find_webapp(&config, &mail.id).map(|w| &w.id), Some(&mail.id));
+        assert_eq!(find_webapp(&config, "mail").map(|w| &w.id), Some(&mail.id));
+        config.webapps[0].enabled = false;
+        assert!(find_webapp(&config, &mail.id).is_none());
+    }
+
+    #[test]
+    fn test_find_handler_skips_disabled() {
+        let mut config = AppConfig::default();
+        let mut first = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        let mut disabled = handler("mailto", "https://mail.example.com/?to={path}");
+        disabled.enabled = false;
+        first.protocols.push(disabled);
+        let mut second = WebApp::new("Other".to_string(), "https://other.example.com".to_string());
+        second.protocols.push(handler("mailto", "https://other.example.com/?to={path}"));
+        config.webapps = vec![first, second.clone()];
+
+        let (webapp, _) = find_handler(&config, "MAILTO").unwrap();
+        assert_eq!(webapp.id, second.id);
+        assert!(find_handler(&config, "slack").is_none());
+    }
+}
@@ -1,5 +1,46 @@
+use std::net::IpAddr;
+
+use crate::domains;
+use crate::error::{AppError, AppResult};
 use crate::models::ProxyConfig;
 
+/// 绕过列表中的 CIDR 网段，如 `10.0.0.0/8`
+fn parse_cidr(entry: &str) -> Option<(IpAddr, u8)> {
+    let (addr, prefix) = entry.split_once('/')?;
+    let addr: IpAddr = addr.parse().ok()?;
+    let prefix: u8 = prefix.parse().ok()?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    (prefix <= max).then_some((addr, prefix))
+}
+
+fn in_cidr(ip: IpAddr, (network, prefix): (IpAddr, u8)) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// 绕过列表中的一项是否匹配主机名：域名模式（`*.corp.example.com`，开头的 `.` 等同于 `*.`）、IP 地址或 CIDR 网段
+fn bypass_matches(entry: &str, host: &str) -> bool {
+    let entry = entry.trim();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Some(cidr) = parse_cidr(entry) {
+        return host.parse().is_ok_and(|ip| in_cidr(ip, cidr));
+    }
+    let pattern = match entry.strip_prefix('.') {
+        Some(rest) => format!("*.{}", rest),
+        None => entry.to_string(),
+    };
+    domains::host_matches(&pattern, host)
+}
+
 /// 代理管理器
 pub struct ProxyManager;
 
@@ -17,16 +58,48 @@ impl ProxyManager {
             std::env::set_var("http_proxy", &proxy_url);
             std::env::set_var("https_proxy", &proxy_url);
 
-            log::info!("Applied proxy configuration: {}", proxy_url);
+            let no_proxy = Self::no_proxy_value(&config.bypass_list);
+            if no_proxy.is_empty() {
+                std::env::remove_var("NO_PROXY");
+                std::env::remove_var("no_proxy");
+            } else {
+                std::env::set_var("NO_PROXY", &no_proxy);
+                std::env::set_var("no_proxy", &no_proxy);
+            }
+
+            log::info!("Applied proxy configuration: {}:{}", config.host, config.port);
         }
     }
 
+    /// 主机是否在绕过列表中（不经过代理）
+    pub fn is_bypassed(bypass_list: &[String], host: &str) -> bool {
+        bypass_list.iter().any(|entry| bypass_matches(entry, host))
+    }
+
+    /// 转换为 `NO_PROXY` 格式：`*.example.com` 写作 `example.com`（同样匹配子域名）
+    pub fn no_proxy_value(bypass_list: &[String]) -> String {
+        bypass_list
+            .iter()
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .strip_prefix("*.")
+                    .or_else(|| entry.strip_prefix('.'))
+                    .unwrap_or(entry)
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
     /// 清除代理配置
     pub fn clear_proxy() {
         std::env::remove_var("HTTP_PROXY");
         std::env::remove_var("HTTPS_PROXY");
         std::env::remove_var("http_proxy");
         std::env::remove_var("https_proxy");
+        std::env::remove_var("NO_PROXY");
+        std::env::remove_var("no_proxy");
 
         log::info!("Cleared proxy configuration");
     }
@@ -39,25 +112,47 @@ impl ProxyManager {
     }
 
     /// 验证代理配置是否有效
-    pub fn validate_config(config: &ProxyConfig) -> Result<(), String> {
+    pub fn validate_config(config: &ProxyConfig) -> AppResult<()> {
         if !config.enabled {
             return Ok(());
         }
 
-        if config.host.is_empty() {
-            return Err("代理主机地址不能为空".to_string());
+        if config.host.trim().is_empty() {
+            return Err(AppError::InvalidProxy("代理主机地址不能为空".to_string()));
         }
+        config.parsed_host().map_err(AppError::InvalidProxy)?;
 
         if config.port == 0 {
-            return Err("代理端口无效".to_string());
+            return Err(AppError::InvalidProxy("代理端口无效".to_string()));
         }
 
         let valid_types = ["http", "https", "socks5"];
         if !valid_types.contains(&config.proxy_type.as_str()) {
-            return Err(format!(
+            return Err(AppError::InvalidProxy(format!(
                 "不支持的代理类型: {}，支持: {:?}",
                 config.proxy_type, valid_types
-            ));
+            )));
+        }
+
+        if config.url_with_auth(config.username.as_deref(), config.password.as_deref()).is_none() {
+            return Err(AppError::InvalidProxy(format!("无效的代理地址: {}:{}", config.host, config.port)));
+        }
+
+        for entry in config.bypass_list.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            let valid = if entry.contains('/') {
+                parse_cidr(entry).is_some()
+            } else {
+                let host = entry.strip_prefix("*.").or_else(|| entry.strip_prefix('.')).unwrap_or(entry);
+                entry == "*"
+                    || host.parse::<IpAddr>().is_ok()
+                    || (!host.is_empty()
+                        && host
+                            .chars()
+                            .all(|c| c.is_alphanumeric() || c == '-' || c == '.' || c == '_'))
+            };
+            if !valid {
+                return Err(AppError::InvalidProxy(format!("无效的代理绕过规则: {}", entry)));
+            }
         }
 
         Ok(())
@@ -76,7 +171,9 @@ mod tests {
             port: 7890,
             username: None,
             password: None,
+            password_ref: None,
             proxy_type: "http".to_string(),
+            bypass_list: Vec::new(),
         };
 
         assert_eq!(
@@ -93,7 +190,9 @@ mod tests {
             port: 7890,
             username: Some("user".to_string()),
             password: Some("pass".to_string()),
+            password_ref: None,
             proxy_type: "http".to_string(),
+            bypass_list: Vec::new(),
         };
 
         assert_eq!(
@@ -102,6 +201,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_proxy_url_hosts() {
+        let proxy = |host: &str| ProxyConfig {
+            enabled: true,
+            host: host.to_string(),
+            port: 1080,
+            proxy_type: "socks5".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            proxy("Proxy.Example.com").get_proxy_url(),
+            Some("socks5://proxy.example.com:1080".to_string())
+        );
+        assert_eq!(proxy("10.0.0.1").get_proxy_url(), Some("socks5://10.0.0.1:1080".to_string()));
+        assert_eq!(proxy("::1").get_proxy_url(), Some("socks5://[::1]:1080".to_string()));
+        assert_eq!(
+            proxy("[2001:db8::1]").url_with_auth(Some("user"), Some("p@ss")),
+            Some("socks5://user:p%40ss@[2001:db8::1]:1080".to_string())
+        );
+        for host in ["::1", "[fe80::1]", "10.0.0.1", "proxy.example.com"] {
+            assert!(ProxyManager::validate_config(&proxy(host)).is_ok(), "{}", host);
+        }
+
+        // 主机地址中不能包含端口、路径或空格
+        for host in ["proxy.example.com:8080", "proxy/path", "two words", "[::1", "1.2.3.4.5:1"] {
+            assert_eq!(proxy(host).get_proxy_url(), None, "{}", host);
+            assert!(ProxyManager::validate_config(&proxy(host)).is_err(), "{}", host);
+        }
+        assert!(ProxyManager::validate_config(&proxy("  ")).is_err());
+    }
+
     #[test]
     fn test_disabled_proxy() {
         let config = ProxyConfig {
@@ -113,5 +244,59 @@ mod tests {
 
         assert_eq!(config.get_proxy_url(), None);
     }
-}
 
+    #[test]
+    fn test_bypass_list() {
+        let list: Vec<String> = ["localhost", "*.internal.corp", ".lan", "10.0.0.0/8", "::1"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert!(ProxyManager::is_bypassed(&list, "localhost"));
+        assert!(ProxyManager::is_bypassed(&list, "wiki.internal.corp"));
+        assert!(ProxyManager::is_bypassed(&list, "internal.corp"));
+        assert!(ProxyManager::is_bypassed(&list, "nas.lan"));
+        assert!(ProxyManager::is_bypassed(&list, "10.20.30.40"));
+        assert!(ProxyManager::is_bypassed(&list, "[::1]"));
+        assert!(!ProxyManager::is_bypassed(&list, "11.0.0.1"));
+        assert!(!ProxyManager::is_bypassed(&list, "example.com"));
+        assert_eq!(
+            ProxyManager::no_proxy_value(&list),
+            "localhost,internal.corp,lan,10.0.0.0/8,::1"
+        );
+
+        let mut config = ProxyConfig {
+            enabled: true,
+            host: "127.0.0.1".to_string(),
+            port: 7890,
+            bypass_list: list,
+            ..Default::default()
+        };
+        assert!(ProxyManager::validate_config(&config).is_ok());
+        config.bypass_list.push("10.0.0.0/40".to_string());
+        assert!(ProxyManager::validate_config(&config).is_err());
+        config.bypass_list = vec!["bad host".to_string()];
+        assert!(ProxyManager::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_bypassed_webapp_connects_directly() {
+        use crate::models::{AppConfig, WebApp};
+
+        let app_config = AppConfig {
+            proxy: ProxyConfig {
+                enabled: true,
+                host: "127.0.0.1".to_string(),
+                port: 7890,
+                bypass_list: vec!["*.internal.corp".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let intranet = WebApp::new("Wiki".to_string(), "https://wiki.internal.corp".to_string());
+        let public = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+
+        assert_eq!(app_config.proxy_url_for(&intranet), None);
+        assert_eq!(app_config.proxy_url_for(&public), Some("http://127.0.0.1:7890".to_string()));
+    }
+}
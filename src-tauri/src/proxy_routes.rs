@@ -0,0 +1,241 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tokio::net::TcpStream;
+
+use crate::config::ConfigManager;
+use crate::domains;
+use crate::metrics::{self, ProxyFailure};
+use crate::models::{AppConfig, ProxyConfig, ProxyRoute, WebApp, WebhookEvent};
+use crate::webhooks;
+
+/// 代理健康检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 连接代理的超时，超时视为不可用
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 保留的路由决策记录条数
+const MAX_DECISIONS: usize = 100;
+
+/// 各代理（`主机:端口`）最近一次检查是否可用，未检查过的代理视为可用
+static HEALTH: Mutex<Option<HashMap<String, bool>>> = parking_lot::const_mutex(None);
+
+/// 最近的路由决策（最新的在前），以及各小程序上一次的选择（只在选择变化时记录）
+static DECISIONS: Mutex<VecDeque<RoutingDecision>> = parking_lot::const_mutex(VecDeque::new());
+static LAST_TARGET: Mutex<Option<HashMap<String, Choice>>> = parking_lot::const_mutex(None);
+
+/// 一次路由选择：连接方式和使用的代理地址（直接连接时为 None）
+type Choice = (RouteTarget, Option<String>);
+
+/// 路由选择的连接方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RouteTarget {
+    /// 首选代理
+    Preferred,
+    /// 备用代理
+    Fallback,
+    /// 直接连接
+    Direct,
+}
+
+/// 一次路由决策（代理只记录地址，不含凭据）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingDecision {
+    pub webapp_id: String,
+    pub host: String,
+    pub route: String,
+    pub target: RouteTarget,
+    pub proxy: Option<String>,
+    pub reason: String,
+    pub timestamp: u64,
+}
+
+fn endpoint(proxy: &ProxyConfig) -> String {
+    format!("{}:{}", proxy.host, proxy.port)
+}
+
+fn is_healthy_endpoint(address: &str) -> bool {
+    HEALTH
+        .lock()
+        .as_ref()
+        .and_then(|health| health.get(address).copied())
+        .unwrap_or(true)
+}
+
+fn is_healthy(proxy: &ProxyConfig) -> bool {
+    is_healthy_endpoint(&endpoint(proxy))
+}
+
+/// 按健康状况选择连接方式：首选代理可用时使用首选代理，否则使用可用的备用代理，都不可用时直接连接
+fn decide(
+    route: &ProxyRoute,
+    healthy: impl Fn(&ProxyConfig) -> bool,
+) -> (RouteTarget, Option<&ProxyConfig>, &'static str) {
+    if healthy(&route.proxy) {
+        return (RouteTarget::Preferred, Some(&route.proxy), "preferred proxy is healthy");
+    }
+    match &route.fallback {
+        Some(fallback) if healthy(fallback) => {
+            (RouteTarget::Fallback, Some(fallback), "preferred proxy is unhealthy")
+        }
+        Some(_) => (RouteTarget::Direct, None, "preferred and fallback proxies are unhealthy"),
+        None => (RouteTarget::Direct, None, "preferred proxy is unhealthy, no fallback"),
+    }
+}
+
+/// 主页匹配的第一条已启用的路由规则
+fn matching_route<'a>(config: &'a AppConfig, host: &str) -> Option<&'a ProxyRoute> {
+    config
+        .proxy_routes
+        .iter()
+        .find(|r| r.enabled && domains::host_matches_any(&r.domains, host))
+}
+
+fn record(decision: RoutingDecision) {
+    let key = (decision.target, decision.proxy.clone());
+    let previous = LAST_TARGET
+        .lock()
+        .get_or_insert_with(HashMap::new)
+        .insert(decision.webapp_id.clone(), key.clone());
+    if previous.as_ref() == Some(&key) {
+        return;
+    }
+
+    log::info!(
+        "Routing {} ({}) via {:?} {}: {}",
+        decision.webapp_id,
+        decision.host,
+        decision.target,
+        decision.proxy.as_deref().unwrap_or("direct"),
+        decision.reason
+    );
+    let mut decisions = DECISIONS.lock();
+    decisions.push_front(decision);
+    decisions.truncate(MAX_DECISIONS);
+}
+
+/// 小程序主页匹配路由规则时返回选择的代理（`Some(None)` 表示直接连接），不匹配时返回 None
+///
+/// 已打开的窗口在重新打开后才会使用新的选择
+pub fn route(config: &AppConfig, webapp: &WebApp) -> Option<Option<String>> {
+    let host = url::Url::parse(&webapp.url).ok()?.host_str()?.to_string();
+    let route = matching_route(config, &host)?;
+    let (target, proxy, reason) = decide(route, is_healthy);
+
+    record(RoutingDecision {
+        webapp_id: webapp.id.clone(),
+        host,
+        route: route.name.clone(),
+        target,
+        proxy: proxy.map(endpoint),
+        reason: reason.to_string(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    });
+    Some(proxy.and_then(|proxy| ProxyConfig { enabled: true, ..proxy.clone() }.get_proxy_url()))
+}
+
+/// 最近的路由决策（最新的在前）
+pub fn decisions() -> Vec<RoutingDecision> {
+    DECISIONS.lock().iter().cloned().collect()
+}
+
+/// 检查路由规则中的所有代理能否连接，代理变为无法连接时通知订阅了 `health-check-failed` 的 Webhook
+async fn check_all(app: &AppHandle, config: &AppConfig) {
+    let mut endpoints: Vec<String> = config
+        .proxy_routes
+        .iter()
+        .filter(|r| r.enabled)
+        .flat_map(|r| std::iter::once(&r.proxy).chain(r.fallback.as_ref()))
+        .map(endpoint)
+        .collect();
+    endpoints.sort();
+    endpoints.dedup();
+
+    let mut results = HashMap::new();
+    for address in endpoints {
+        let healthy = matches!(
+            tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address.as_str())).await,
+            Ok(Ok(_))
+        );
+        let previous = is_healthy_endpoint(&address);
+        if previous != healthy {
+            log::warn!("Proxy {} is now {}", address, if healthy { "healthy" } else { "unhealthy" });
+            if !healthy {
+                webhooks::dispatch(app, WebhookEvent::HealthCheckFailed, serde_json::json!({ "proxy": address }));
+            }
+        }
+        if !healthy {
+            metrics::proxy_failed(ProxyFailure::Unreachable);
+        }
+        results.insert(address, healthy);
+    }
+    *HEALTH.lock() = Some(results);
+}
+
+/// 定时检查路由规则中代理的健康状况
+pub fn start_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let config = app.state::<ConfigManager>().read();
+            if !config.proxy_routes.is_empty() {
+                check_all(&app, &config).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy(host: &str) -> ProxyConfig {
+        ProxyConfig {
+            enabled: true,
+            host: host.to_string(),
+            port: 3128,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decide_falls_back_when_unhealthy() {
+        let mut route = ProxyRoute {
+            name: "corp".to_string(),
+            domains: vec!["*.corp.example.com".to_string()],
+            proxy: proxy("primary.test"),
+            fallback: Some(proxy("backup.test")),
+            enabled: true,
+        };
+        let down =
+            |names: &'static [&'static str]| move |p: &ProxyConfig| !names.contains(&p.host.as_str());
+
+        let (target, chosen, _) = decide(&route, down(&[]));
+        assert_eq!(target, RouteTarget::Preferred);
+        assert_eq!(chosen.map(endpoint).as_deref(), Some("primary.test:3128"));
+        let (target, chosen, _) = decide(&route, down(&["primary.test"]));
+        assert_eq!(target, RouteTarget::Fallback);
+        assert_eq!(chosen.map(endpoint).as_deref(), Some("backup.test:3128"));
+        let (target, chosen, _) = decide(&route, down(&["primary.test", "backup.test"]));
+        assert_eq!((target, chosen.is_none()), (RouteTarget::Direct, true));
+        route.fallback = None;
+        let (target, chosen, _) = decide(&route, down(&["primary.test"]));
+        assert_eq!((target, chosen.is_none()), (RouteTarget::Direct, true));
+
+        let config = AppConfig {
+            proxy_routes: vec![route],
+            ..Default::default()
+        };
+        assert!(matching_route(&config, "wiki.corp.example.com").is_some());
+        assert!(matching_route(&config, "example.com").is_none());
+    }
+}
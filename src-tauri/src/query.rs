@@ -0,0 +1,334 @@
+//! 只读的状态查询：把配置、已打开的窗口、使用统计和角标合成一个 JSON 文档，
+//! 用简化的 JSONPath 选取需要的部分（`query_state` 命令和控制 API 的 `/state`）
+//!
+//! 支持的语法：`$` 根节点，`.name` / `['name']` 字段，`[0]` 下标，`*` / `[*]` 所有子节点，
+//! `[?(@.enabled)]`、`[?(@.badge > 0)]` 过滤（`==`、`!=`、`>`、`>=`、`<`、`<=`），
+//! `.{name,url}` 只保留对象的部分字段
+
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use tauri::{AppHandle, Manager};
+
+use crate::badges;
+use crate::config::ConfigManager;
+use crate::redact;
+use crate::usage;
+use crate::window::WindowManager;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// 过滤条件：`@` 之后的字段路径，没有比较时判断字段是否为真值
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    path: Vec<String>,
+    comparison: Option<(Op, Value)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+    Filter(Filter),
+    Select(Vec<String>),
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+fn syntax_error(query: &str) -> String {
+    format!("查询语法错误: {}", query)
+}
+
+/// 读取字段名，返回字段名和剩余部分
+fn take_name(input: &str) -> (&str, &str) {
+    let end = input.find(|c: char| !is_name_char(c)).unwrap_or(input.len());
+    input.split_at(end)
+}
+
+/// 解析过滤条件中的值：JSON 字面量，或单引号字符串
+fn parse_literal(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if let Some(inner) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+        return Some(Value::String(inner.to_string()));
+    }
+    serde_json::from_str(text).ok()
+}
+
+fn parse_filter(expr: &str) -> Option<Filter> {
+    let rest = expr.trim().strip_prefix('@')?;
+    let mut path = Vec::new();
+    let mut rest = rest;
+    while let Some(after_dot) = rest.strip_prefix('.') {
+        let (name, after) = take_name(after_dot);
+        if name.is_empty() {
+            return None;
+        }
+        path.push(name.to_string());
+        rest = after;
+    }
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return Some(Filter { path, comparison: None });
+    }
+    // 两个字符的运算符先匹配
+    let ops = [
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        (">=", Op::Ge),
+        ("<=", Op::Le),
+        (">", Op::Gt),
+        ("<", Op::Lt),
+    ];
+    let (op, literal) = ops
+        .iter()
+        .find_map(|(token, op)| rest.strip_prefix(token).map(|literal| (*op, literal)))?;
+    Some(Filter {
+        path,
+        comparison: Some((op, parse_literal(literal)?)),
+    })
+}
+
+fn parse(query: &str) -> Result<Vec<Segment>, String> {
+    let trimmed = query.trim();
+    let mut rest = trimmed.strip_prefix('$').unwrap_or(trimmed);
+    let mut segments = Vec::new();
+    // 开头可以省略 `$.`
+    if rest.starts_with(is_name_char) {
+        let (name, after) = take_name(rest);
+        segments.push(Segment::Field(name.to_string()));
+        rest = after;
+    }
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            if let Some(after) = after.strip_prefix('*') {
+                segments.push(Segment::Wildcard);
+                rest = after;
+            } else if let Some(after) = after.strip_prefix('{') {
+                let (fields, after) = after.split_once('}').ok_or_else(|| syntax_error(query))?;
+                let fields: Vec<String> = fields
+                    .split(',')
+                    .map(|f| f.trim().to_string())
+                    .filter(|f| !f.is_empty())
+                    .collect();
+                if fields.is_empty() {
+                    return Err(syntax_error(query));
+                }
+                segments.push(Segment::Select(fields));
+                rest = after;
+            } else {
+                let (name, after) = take_name(after);
+                if name.is_empty() {
+                    return Err(syntax_error(query));
+                }
+                segments.push(Segment::Field(name.to_string()));
+                rest = after;
+            }
+        } else if let Some(after) = rest.strip_prefix('[') {
+            if let Some(expr) = after.strip_prefix("?(") {
+                let (expr, after) = expr.split_once(")]").ok_or_else(|| syntax_error(query))?;
+                segments.push(Segment::Filter(parse_filter(expr).ok_or_else(|| syntax_error(query))?));
+                rest = after;
+            } else {
+                let (inner, after) = after.split_once(']').ok_or_else(|| syntax_error(query))?;
+                let inner = inner.trim();
+                let segment = if inner == "*" {
+                    Segment::Wildcard
+                } else if let Ok(index) = inner.parse::<usize>() {
+                    Segment::Index(index)
+                } else {
+                    let name = inner
+                        .strip_prefix('\'')
+                        .and_then(|n| n.strip_suffix('\''))
+                        .or_else(|| inner.strip_prefix('"').and_then(|n| n.strip_suffix('"')))
+                        .ok_or_else(|| syntax_error(query))?;
+                    Segment::Field(name.to_string())
+                };
+                segments.push(segment);
+                rest = after;
+            }
+        } else {
+            return Err(syntax_error(query));
+        }
+    }
+    Ok(segments)
+}
+
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+impl Filter {
+    fn matches(&self, node: &Value) -> bool {
+        let value = self.path.iter().try_fold(node, |value, name| value.get(name));
+        match (&self.comparison, value) {
+            (None, value) => value.is_some_and(is_truthy),
+            (Some((op, expected)), value) => {
+                // 不存在的字段按 null 比较
+                let ordering = compare(value.unwrap_or(&Value::Null), expected);
+                match op {
+                    Op::Eq => ordering == Some(Ordering::Equal),
+                    Op::Ne => ordering != Some(Ordering::Equal),
+                    Op::Gt => ordering == Some(Ordering::Greater),
+                    Op::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    Op::Lt => ordering == Some(Ordering::Less),
+                    Op::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                }
+            }
+        }
+    }
+}
+
+fn children(node: &Value) -> Vec<&Value> {
+    match node {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 在文档上执行查询，返回所有匹配的节点
+fn evaluate(document: &Value, segments: &[Segment]) -> Vec<Value> {
+    let mut nodes = vec![document.clone()];
+    for segment in segments {
+        nodes = nodes
+            .iter()
+            .flat_map(|node| -> Vec<Value> {
+                match segment {
+                    Segment::Field(name) => node.get(name).cloned().into_iter().collect(),
+                    Segment::Index(index) => node.get(index).cloned().into_iter().collect(),
+                    Segment::Wildcard => children(node).into_iter().cloned().collect(),
+                    Segment::Filter(filter) => children(node)
+                        .into_iter()
+                        .filter(|child| filter.matches(child))
+                        .cloned()
+                        .collect(),
+                    Segment::Select(fields) => match node {
+                        Value::Object(map) => {
+                            let selected: Map<String, Value> = fields
+                                .iter()
+                                .filter_map(|f| map.get(f).map(|v| (f.clone(), v.clone())))
+                                .collect();
+                            vec![Value::Object(selected)]
+                        }
+                        _ => Vec::new(),
+                    },
+                }
+            })
+            .collect();
+    }
+    nodes
+}
+
+/// 查询的文档：`config`（已去掉凭据）、`windows`、`usage`、`badges`，
+/// 以及合并了运行状态、角标和使用统计的 `webapps`
+fn document(app: &AppHandle) -> Value {
+    let mut config = app.state::<ConfigManager>().read();
+    redact::credentials(&mut config);
+    let windows = app.state::<WindowManager>().active_webapps(app);
+    let usage = usage::snapshot(app);
+    let badges = badges::snapshot(app);
+
+    let webapps: Vec<Value> = config
+        .webapps
+        .iter()
+        .map(|webapp| {
+            let mut value = serde_json::to_value(webapp).unwrap_or(Value::Null);
+            if let Value::Object(map) = &mut value {
+                let window = windows.iter().find(|w| w.webapp_id == webapp.id);
+                map.insert("running".to_string(), Value::Bool(window.is_some()));
+                map.insert("visible".to_string(), Value::Bool(window.is_some_and(|w| w.visible)));
+                map.insert("focused".to_string(), Value::Bool(window.is_some_and(|w| w.focused)));
+                map.insert(
+                    "badge".to_string(),
+                    badges
+                        .iter()
+                        .find(|b| b.webapp_id == webapp.id)
+                        .and_then(|b| b.count)
+                        .map_or(Value::Null, Value::from),
+                );
+                map.insert(
+                    "usage".to_string(),
+                    serde_json::to_value(usage.webapps.get(&webapp.id).cloned().unwrap_or_default())
+                        .unwrap_or(Value::Null),
+                );
+            }
+            value
+        })
+        .collect();
+
+    serde_json::json!({
+        "config": config,
+        "windows": windows,
+        "usage": usage.webapps,
+        "badges": badges,
+        "webapps": webapps,
+    })
+}
+
+/// 执行查询，返回匹配节点的数组
+pub fn run(app: &AppHandle, query: &str) -> Result<Vec<Value>, String> {
+    let segments = parse(query)?;
+    Ok(evaluate(&document(app), &segments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_paths_and_filters() {
+        let document = serde_json::json!({
+            "webapps": [
+                { "name": "Mail", "enabled": true, "badge": 3, "usage": { "openCount": 10 } },
+                { "name": "Chat", "enabled": false, "badge": null, "usage": { "openCount": 2 } },
+            ],
+            "config": { "maxActiveWindows": 5 },
+        });
+        let query = |q: &str| evaluate(&document, &parse(q).unwrap());
+
+        assert_eq!(query("$.config.maxActiveWindows"), [serde_json::json!(5)]);
+        assert_eq!(query("config['maxActiveWindows']"), [serde_json::json!(5)]);
+        assert_eq!(query("$.webapps[1].name"), [serde_json::json!("Chat")]);
+        assert_eq!(query("$.webapps[*].name"), [serde_json::json!("Mail"), serde_json::json!("Chat")]);
+        assert_eq!(query("$.webapps[?(@.enabled)].name"), [serde_json::json!("Mail")]);
+        assert_eq!(query("$.webapps[?(@.badge > 0)].name"), [serde_json::json!("Mail")]);
+        assert_eq!(query("$.webapps[?(@.usage.openCount < 5)].name"), [serde_json::json!("Chat")]);
+        assert_eq!(query("$.webapps[?(@.name == 'Chat')].enabled"), [serde_json::json!(false)]);
+        assert_eq!(
+            query("$.webapps[0].{name,badge,missing}"),
+            [serde_json::json!({ "name": "Mail", "badge": 3 })]
+        );
+        assert!(query("$.nothing.here").is_empty());
+
+        assert!(parse("$.webapps[").is_err());
+        assert!(parse("$.webapps[?(@.badge ~ 1)]").is_err());
+        assert!(parse("$..name").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::{Datelike, Timelike};
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::events::{self, Channel};
+use crate::models::{QuietHoursConfig, WebApp};
+use crate::window::{window_label, WindowManager};
+
+/// 计划状态检查间隔
+const MONITOR_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 静默时段状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHoursStatus {
+    /// 当前是否处于静默状态（在时间段内且未临时解除）
+    pub active: bool,
+    /// 是否处于计划时间段内
+    pub scheduled: bool,
+    /// 临时解除到此时间（Unix 秒），已过期时为 None
+    pub override_until: Option<u64>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl QuietHoursConfig {
+    /// 计算指定时刻的静默状态（`now` 为 Unix 秒，用于判断临时解除是否过期）
+    pub fn status_at(&self, weekday: u8, minutes: u32, now: u64) -> QuietHoursStatus {
+        let scheduled = self.enabled && self.schedules.iter().any(|s| s.contains(weekday, minutes));
+        let override_until = self.override_until.filter(|until| *until > now);
+        QuietHoursStatus {
+            active: scheduled && override_until.is_none(),
+            scheduled,
+            override_until,
+        }
+    }
+
+    /// 计算当前本地时间的静默状态
+    pub fn status_now(&self) -> QuietHoursStatus {
+        let now = chrono::Local::now();
+        let weekday = now.weekday().num_days_from_monday() as u8;
+        self.status_at(weekday, now.hour() * 60 + now.minute(), now_secs())
+    }
+}
+
+/// 读取当前静默状态
+pub fn current_status(app: &AppHandle) -> QuietHoursStatus {
+    app.state::<ConfigManager>().read().quiet_hours.status_now()
+}
+
+/// 小程序是否置顶（设置中置顶，或窗口被临时置顶），置顶的小程序不受静默时段影响
+fn is_pinned(app: &AppHandle, webapp: &WebApp) -> bool {
+    webapp.always_on_top
+        || app
+            .get_webview_window(&window_label(&webapp.id))
+            .is_some_and(|window| window.is_always_on_top().unwrap_or(false))
+}
+
+/// 静默时段内忽略未置顶小程序的快捷键
+pub fn blocks_shortcut(app: &AppHandle, webapp: &WebApp) -> bool {
+    current_status(app).active && !is_pinned(app, webapp)
+}
+
+/// 临时解除静默 `minutes` 分钟，None 表示取消临时解除（时间段内会立即重新隐藏窗口）
+pub fn set_override(app: &AppHandle, minutes: Option<u32>) -> AppResult<QuietHoursStatus> {
+    let until = minutes.map(|m| now_secs() + u64::from(m) * 60);
+    let status = app.state::<ConfigManager>().update(|config| {
+        config.quiet_hours.override_until = until;
+        config.quiet_hours.status_now()
+    })?;
+
+    if status.active {
+        suspend_windows(app);
+    }
+    notify_changed(app, status);
+    log::info!("Quiet hours override set to: {:?}", until);
+    Ok(status)
+}
+
+/// 隐藏所有可见且未置顶的小程序窗口（窗口保留在后台，不关闭页面）
+fn suspend_windows(app: &AppHandle) {
+    let config = app.state::<ConfigManager>().read();
+    for webapp_id in app.state::<WindowManager>().get_active_window_ids() {
+        let Some(webapp) = config.webapps.iter().find(|w| w.id == webapp_id) else {
+            continue;
+        };
+        if is_pinned(app, webapp) {
+            continue;
+        }
+        if let Some(window) = app.get_webview_window(&window_label(&webapp_id)) {
+            if window.is_visible().unwrap_or(false) {
+                let _ = window.hide();
+                log::info!("Hid webapp {} for quiet hours", webapp_id);
+            }
+        }
+    }
+}
+
+fn notify_changed(app: &AppHandle, status: QuietHoursStatus) {
+    events::emit(app, Channel::Config, "quiet-hours://changed", status);
+}
+
+/// 监听静默时段的开始和结束：开始时（包括临时解除过期）隐藏未置顶的小程序窗口，并通知前端
+pub fn start_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = current_status(&app);
+        if last.active {
+            suspend_windows(&app);
+        }
+        let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            let status = current_status(&app);
+            if status != last {
+                log::info!("Quiet hours are now {}", if status.active { "on" } else { "off" });
+                if status.active && !last.active {
+                    suspend_windows(&app);
+                }
+                notify_changed(&app, status);
+                last = status;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DndSchedule;
+
+    #[test]
+    fn test_quiet_hours_status() {
+        let mut config = QuietHoursConfig {
+            enabled: false,
+            schedules: vec![DndSchedule {
+                start: "18:00".to_string(),
+                end: "09:00".to_string(),
+                days: vec![0, 1, 2, 3, 4],
+            }],
+            override_until: None,
+        };
+        assert!(!config.status_at(0, 19 * 60, 1000).active);
+
+        config.enabled = true;
+        assert!(config.status_at(0, 19 * 60, 1000).active);
+        assert!(!config.status_at(0, 12 * 60, 1000).active);
+        assert!(config.status_at(5, 8 * 60, 1000).active); // 周六早上，属于周五开始的时间段
+
+        config.override_until = Some(2000);
+        let status = config.status_at(0, 19 * 60, 1000);
+        assert!(status.scheduled && !status.active);
+        assert_eq!(status.override_until, Some(2000));
+
+        // 临时解除过期后重新生效
+        let status = config.status_at(0, 19 * 60, 2000);
+        assert!(status.active);
+        assert_eq!(status.override_until, None);
+    }
+}
@@ -0,0 +1,189 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager};
+
+use crate::events::{self, Channel};
+use crate::window::window_label;
+
+/// 阅读模式脚本：找出正文所在的元素（按段落文本长度和链接密度打分），
+/// 清理脚本、导航和广告等元素后，用简洁的本地页面替换当前文档。
+/// 结果通过桥接上报 `reader` 事件：`{ active, error }`
+const READER_SCRIPT: &str = r#"(function() {
+    var hub = window.__WEBAPP_HUB__;
+    function report(active, error) {
+        if (hub) hub.emit('reader', { active: active, error: error || null });
+    }
+
+    function textLength(el) {
+        return (el.innerText || el.textContent || '').trim().length;
+    }
+
+    function linkDensity(el) {
+        var total = textLength(el) || 1;
+        var links = 0;
+        el.querySelectorAll('a').forEach(function(a) { links += textLength(a); });
+        return links / total;
+    }
+
+    function score(el) {
+        var paragraphs = el.querySelectorAll('p');
+        var text = 0;
+        paragraphs.forEach(function(p) { text += textLength(p); });
+        var bonus = /article|content|post|entry|story|main/i.test((el.className || '') + ' ' + (el.id || '')) ? 1.25 : 1;
+        return text * bonus * (1 - linkDensity(el));
+    }
+
+    var candidates = Array.prototype.slice.call(
+        document.querySelectorAll('article, main, [role="main"], section, div')
+    );
+    var best = null;
+    var bestScore = 0;
+    candidates.forEach(function(el) {
+        var s = score(el);
+        if (s > bestScore) { best = el; bestScore = s; }
+    });
+
+    if (!best || bestScore < 500) {
+        report(false, 'no-article');
+        return;
+    }
+
+    var content = best.cloneNode(true);
+    content.querySelectorAll(
+        'script, style, noscript, iframe, form, button, input, select, textarea, nav, aside, footer, header, ' +
+        '[role="navigation"], [role="complementary"], [aria-hidden="true"], .ad, .ads, .advert, .share, .social'
+    ).forEach(function(el) { el.remove(); });
+    content.querySelectorAll('*').forEach(function(el) {
+        el.removeAttribute('style');
+        el.removeAttribute('class');
+        Array.prototype.slice.call(el.attributes).forEach(function(attr) {
+            if (attr.name.indexOf('on') === 0) el.removeAttribute(attr.name);
+        });
+    });
+    content.querySelectorAll('img[data-src]').forEach(function(img) {
+        if (!img.getAttribute('src')) img.setAttribute('src', img.getAttribute('data-src'));
+    });
+
+    var title = document.title;
+    var heading = document.querySelector('h1');
+    if (heading && textLength(heading) > 0) title = heading.innerText.trim();
+
+    var escape = function(s) {
+        return s.replace(/[&<>"]/g, function(c) {
+            return { '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;' }[c];
+        });
+    };
+
+    var html = '<!DOCTYPE html><html><head><meta charset="utf-8">' +
+        '<meta name="viewport" content="width=device-width, initial-scale=1">' +
+        '<title>' + escape(title) + '</title><style>' +
+        'body{max-width:42em;margin:2em auto;padding:0 1.25em;font:1.15em/1.7 Georgia,"Songti SC","Noto Serif CJK SC",serif;color:#222;background:#fbfaf7}' +
+        'h1{font-size:1.8em;line-height:1.3}img,video,figure{max-width:100%;height:auto}' +
+        'pre,code{white-space:pre-wrap;font-size:.9em}a{color:#1a5fb4}' +
+        '@media (prefers-color-scheme:dark){body{color:#ddd;background:#1e1e1e}a{color:#78aeed}}' +
+        '</style></head><body><h1>' + escape(title) + '</h1>' + content.innerHTML + '</body></html>';
+
+    document.open();
+    document.write(html);
+    document.close();
+    report(true);
+})();"#;
+
+/// 退出阅读模式：重新加载原页面
+const EXIT_SCRIPT: &str = "location.reload()";
+
+/// 阅读模式状态变化事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReaderChanged<'a> {
+    webapp_id: &'a str,
+    active: bool,
+    error: Option<String>,
+}
+
+/// 处于阅读模式的小程序窗口
+pub struct ReaderState {
+    active: Mutex<HashSet<String>>,
+}
+
+impl ReaderState {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn is_active(&self, webapp_id: &str) -> bool {
+        self.active.lock().contains(webapp_id)
+    }
+}
+
+impl Default for ReaderState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 进入阅读模式（提取结果通过桥接事件异步返回）
+pub fn enter(app: &AppHandle, webapp_id: &str) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&window_label(webapp_id))
+        .ok_or("窗口未打开")?;
+    window.eval(READER_SCRIPT).map_err(|e| e.to_string())
+}
+
+/// 退出阅读模式，恢复原页面
+pub fn exit(app: &AppHandle, webapp_id: &str) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&window_label(webapp_id))
+        .ok_or("窗口未打开")?;
+    window.eval(EXIT_SCRIPT).map_err(|e| e.to_string())?;
+    set_active(app, webapp_id, false, None);
+    Ok(())
+}
+
+/// 切换阅读模式
+pub fn toggle(app: &AppHandle, webapp_id: &str) -> Result<(), String> {
+    let active = app
+        .try_state::<ReaderState>()
+        .map(|state| state.is_active(webapp_id))
+        .unwrap_or(false);
+
+    if active {
+        exit(app, webapp_id)
+    } else {
+        enter(app, webapp_id)
+    }
+}
+
+/// 记录阅读模式状态（来自桥接事件，或页面重新加载时清除）
+pub fn set_active(app: &AppHandle, webapp_id: &str, active: bool, error: Option<String>) {
+    let changed = match app.try_state::<ReaderState>() {
+        Some(state) => {
+            let mut set = state.active.lock();
+            if active {
+                set.insert(webapp_id.to_string())
+            } else {
+                set.remove(webapp_id)
+            }
+        }
+        None => return,
+    };
+
+    if changed || error.is_some() {
+        if let Some(error) = &error {
+            log::info!("Reader mode unavailable for {}: {}", webapp_id, error);
+        }
+        events::emit(
+            app,
+            Channel::Windows,
+            "reader://changed",
+            ReaderChanged {
+                webapp_id,
+                active,
+                error,
+            },
+        );
+    }
+}
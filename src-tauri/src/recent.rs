@@ -0,0 +1,170 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::events::{self, Channel};
+use crate::shutdown;
+use crate::window::{window_label, OpenOptions, WindowManager};
+
+/// 最多记录的关闭窗口数
+const MAX_ENTRIES: usize = 20;
+
+/// 最近关闭的小程序窗口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosedWebApp {
+    pub webapp_id: String,
+    pub name: String,
+    /// 关闭时页面的网址
+    pub url: Option<String>,
+    /// 关闭时间（Unix 秒）
+    pub closed_at: u64,
+}
+
+/// 最近关闭的窗口，新的在前（仅在本次运行中保留）
+pub struct RecentlyClosed {
+    entries: Mutex<VecDeque<ClosedWebApp>>,
+}
+
+impl RecentlyClosed {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl Default for RecentlyClosed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 记录一次关闭：同一个小程序只保留最近的一条
+fn push(entries: &mut VecDeque<ClosedWebApp>, entry: ClosedWebApp) {
+    entries.retain(|e| e.webapp_id != entry.webapp_id);
+    entries.push_front(entry);
+    entries.truncate(MAX_ENTRIES);
+}
+
+/// 小程序窗口即将关闭时调用，记录窗口当前的网址
+pub fn record(app: &AppHandle, webapp_id: &str) {
+    if shutdown::in_progress(app) {
+        return;
+    }
+    let state = match app.try_state::<RecentlyClosed>() {
+        Some(state) => state,
+        None => return,
+    };
+    let name = match app
+        .state::<ConfigManager>()
+        .read()
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+    {
+        Some(webapp) => webapp.name.clone(),
+        None => return,
+    };
+    let url = app
+        .get_webview_window(&window_label(webapp_id))
+        .and_then(|window| window.url().ok())
+        .map(|url| url.to_string());
+    let closed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    push(
+        &mut state.entries.lock(),
+        ClosedWebApp {
+            webapp_id: webapp_id.to_string(),
+            name,
+            url,
+            closed_at,
+        },
+    );
+    events::emit(app, Channel::Windows, "recent://changed", list(app));
+}
+
+/// 最近关闭的窗口列表
+pub fn list(app: &AppHandle) -> Vec<ClosedWebApp> {
+    app.try_state::<RecentlyClosed>()
+        .map(|state| state.entries.lock().iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// 重新打开最近关闭的窗口（跳过已删除、已停用或已重新打开的小程序），
+/// 网址仍在小程序范围内时恢复到关闭时的页面
+pub fn reopen_last(app: &AppHandle) -> AppResult<ClosedWebApp> {
+    let config = app.state::<ConfigManager>().read();
+    let window_manager = app.state::<WindowManager>();
+    let state = app.state::<RecentlyClosed>();
+
+    let (entry, webapp) = {
+        let mut entries = state.entries.lock();
+        loop {
+            let entry = entries.pop_front().ok_or("没有最近关闭的小程序")?;
+            if window_manager.is_window_active(&entry.webapp_id) {
+                continue;
+            }
+            if let Some(webapp) = config.webapps.iter().find(|w| w.id == entry.webapp_id && w.enabled) {
+                break (entry, webapp);
+            }
+        }
+    };
+
+    let target_url = entry
+        .url
+        .as_deref()
+        .and_then(|u| Url::parse(u).ok())
+        .filter(|u| webapp.is_url_in_scope(u));
+    window_manager.open_webapp_with(
+        app,
+        webapp,
+        config.proxy_url_for(webapp),
+        OpenOptions {
+            target_url,
+            ..OpenOptions::default()
+        },
+    )?;
+
+    events::emit(app, Channel::Windows, "recent://changed", list(app));
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, closed_at: u64) -> ClosedWebApp {
+        ClosedWebApp {
+            webapp_id: id.to_string(),
+            name: id.to_string(),
+            url: None,
+            closed_at,
+        }
+    }
+
+    #[test]
+    fn test_push_dedupes_and_caps() {
+        let mut entries = VecDeque::new();
+        push(&mut entries, entry("a", 1));
+        push(&mut entries, entry("b", 2));
+        push(&mut entries, entry("a", 3));
+
+        let ids: Vec<&str> = entries.iter().map(|e| e.webapp_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(entries[0].closed_at, 3);
+
+        for i in 0..MAX_ENTRIES + 5 {
+            push(&mut entries, entry(&format!("w{}", i), i as u64));
+        }
+        assert_eq!(entries.len(), MAX_ENTRIES);
+    }
+}
@@ -0,0 +1,197 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use tauri::{AppHandle, Manager};
+
+use crate::events::{self, Channel};
+
+/// 每个小程序最多保留的请求记录数
+const MAX_ENTRIES: usize = 500;
+
+/// 记录的网址最长长度，超出部分截断
+const MAX_URL_LEN: usize = 2048;
+
+/// 请求记录脚本：包装 fetch 和 XMLHttpRequest，请求结束后通过桥接上报 `request` 事件
+/// （只记录方法、网址、状态码和耗时，不记录请求和响应内容）
+const REQUEST_LOG_SCRIPT: &str = r#"(function() {
+    var hub = window.__WEBAPP_HUB__;
+    if (!hub || hub.requestLogInstalled) return;
+    hub.requestLogInstalled = true;
+
+    function report(kind, method, url, startedAt, status, error) {
+        hub.emit('request', {
+            kind: kind,
+            method: (method || 'GET').toUpperCase(),
+            url: String(url),
+            status: status || null,
+            startedAt: startedAt,
+            durationMs: Date.now() - startedAt,
+            error: error || null
+        });
+    }
+
+    if (window.fetch) {
+        var nativeFetch = window.fetch;
+        window.fetch = function(input, init) {
+            var startedAt = Date.now();
+            var url = input && input.url ? input.url : input;
+            var method = (init && init.method) || (input && input.method) || 'GET';
+            return nativeFetch.apply(this, arguments).then(function(response) {
+                report('fetch', method, response.url || url, startedAt, response.status, null);
+                return response;
+            }, function(e) {
+                report('fetch', method, url, startedAt, null, String(e && e.message || e));
+                throw e;
+            });
+        };
+    }
+
+    var nativeOpen = XMLHttpRequest.prototype.open;
+    var nativeSend = XMLHttpRequest.prototype.send;
+    XMLHttpRequest.prototype.open = function(method, url) {
+        this.__hubRequest = { method: method, url: url };
+        return nativeOpen.apply(this, arguments);
+    };
+    XMLHttpRequest.prototype.send = function() {
+        var xhr = this;
+        var info = xhr.__hubRequest || {};
+        var startedAt = Date.now();
+        xhr.addEventListener('loadend', function() {
+            var error = xhr.status === 0 ? 'network error' : null;
+            report('xhr', info.method, xhr.responseURL || info.url, startedAt, xhr.status || null, error);
+        });
+        return nativeSend.apply(this, arguments);
+    };
+})();"#;
+
+/// 一条请求记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestEntry {
+    /// `fetch` 或 `xhr`
+    pub kind: String,
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// 开始时间（Unix 毫秒）
+    pub started_at: u64,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 各小程序的请求记录（只保存在内存中）
+pub struct RequestLog {
+    entries: Mutex<HashMap<String, VecDeque<RequestEntry>>>,
+}
+
+impl RequestLog {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for RequestLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 获取请求记录脚本（作为 initialization_script 注入，依赖桥接脚本）
+pub fn request_log_script() -> &'static str {
+    REQUEST_LOG_SCRIPT
+}
+
+fn push(log: &mut VecDeque<RequestEntry>, mut entry: RequestEntry) {
+    if entry.url.len() > MAX_URL_LEN {
+        let mut end = MAX_URL_LEN;
+        while !entry.url.is_char_boundary(end) {
+            end -= 1;
+        }
+        entry.url.truncate(end);
+    }
+    log.push_back(entry);
+    while log.len() > MAX_ENTRIES {
+        log.pop_front();
+    }
+}
+
+/// 记录页面上报的请求
+pub fn record(app: &AppHandle, webapp_id: &str, entry: RequestEntry) {
+    let state = match app.try_state::<RequestLog>() {
+        Some(state) => state,
+        None => return,
+    };
+    push(
+        state.entries.lock().entry(webapp_id.to_string()).or_default(),
+        entry.clone(),
+    );
+    events::emit(
+        app,
+        Channel::Requests,
+        "request-log://entry",
+        serde_json::json!({ "webappId": webapp_id, "entry": entry }),
+    );
+}
+
+/// 某个小程序的请求记录（旧的在前）
+pub fn get(app: &AppHandle, webapp_id: &str) -> Vec<RequestEntry> {
+    app.try_state::<RequestLog>()
+        .and_then(|state| state.entries.lock().get(webapp_id).map(|log| log.iter().cloned().collect()))
+        .unwrap_or_default()
+}
+
+/// 所有小程序的请求记录
+pub fn all(app: &AppHandle) -> HashMap<String, Vec<RequestEntry>> {
+    app.try_state::<RequestLog>()
+        .map(|state| {
+            state
+                .entries
+                .lock()
+                .iter()
+                .map(|(id, log)| (id.clone(), log.iter().cloned().collect()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 清空某个小程序的请求记录
+pub fn clear(app: &AppHandle, webapp_id: &str) {
+    if let Some(state) = app.try_state::<RequestLog>() {
+        state.entries.lock().remove(webapp_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: String) -> RequestEntry {
+        RequestEntry {
+            kind: "fetch".to_string(),
+            method: "GET".to_string(),
+            url,
+            status: Some(200),
+            started_at: 0,
+            duration_ms: 12,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_push_caps_and_truncates() {
+        let mut log = VecDeque::new();
+        for i in 0..MAX_ENTRIES + 3 {
+            push(&mut log, entry(format!("https://example.com/{}", i)));
+        }
+        assert_eq!(log.len(), MAX_ENTRIES);
+        assert_eq!(log[0].url, "https://example.com/3");
+
+        push(&mut log, entry(format!("https://example.com/{}", "é".repeat(MAX_URL_LEN))));
+        let last = log.back().unwrap();
+        assert!(last.url.len() <= MAX_URL_LEN);
+    }
+}
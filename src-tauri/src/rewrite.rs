@@ -0,0 +1,139 @@
+use regex::Regex;
+use url::Url;
+
+use crate::models::{AppConfig, RewriteKind, RewriteRule};
+
+/// 按单条规则改写网址，不匹配或结果无效时返回 None
+fn apply_rule(rule: &RewriteRule, url: &str) -> Option<String> {
+    match rule.kind {
+        RewriteKind::Prefix => {
+            if rule.pattern.is_empty() {
+                return None;
+            }
+            url.strip_prefix(rule.pattern.as_str())
+                .map(|rest| format!("{}{}", rule.replacement, rest))
+        }
+        RewriteKind::Regex => {
+            let re = Regex::new(&rule.pattern).ok()?;
+            if !re.is_match(url) {
+                return None;
+            }
+            Some(re.replace(url, rule.replacement.as_str()).into_owned())
+        }
+    }
+}
+
+/// 当前生效的规则（测试环境规则只在测试环境模式下生效）
+fn active_rules(config: &AppConfig) -> impl Iterator<Item = &RewriteRule> {
+    config
+        .rewrite_rules
+        .iter()
+        .filter(move |r| r.enabled && (!r.staging_only || config.staging_mode))
+}
+
+/// 按第一条命中的规则改写网址
+fn rewrite_once(config: &AppConfig, url: &Url) -> Option<Url> {
+    let rewritten = active_rules(config).find_map(|rule| apply_rule(rule, url.as_str()))?;
+    match Url::parse(&rewritten) {
+        Ok(parsed) if parsed != *url => Some(parsed),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Rewrite of {} produced an invalid URL {}: {}", url, rewritten, e);
+            None
+        }
+    }
+}
+
+/// 改写网址；返回 None 表示无需改写
+/// 改写结果会再次命中其他规则（会导致导航循环）时放弃改写
+pub fn rewrite(config: &AppConfig, url: &Url) -> Option<Url> {
+    let rewritten = rewrite_once(config, url)?;
+    if rewrite_once(config, &rewritten).is_some() {
+        log::warn!("Rewrite rules loop on {}, leaving it unchanged", url);
+        return None;
+    }
+    Some(rewritten)
+}
+
+/// 检查规则是否有效，返回错误信息
+pub fn validate_rule(rule: &RewriteRule) -> Result<(), String> {
+    if rule.pattern.is_empty() {
+        return Err("改写规则的匹配内容为空".to_string());
+    }
+    if rule.kind == RewriteKind::Regex {
+        Regex::new(&rule.pattern).map_err(|e| format!("无效的正则表达式: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: RewriteKind, pattern: &str, replacement: &str, staging_only: bool) -> RewriteRule {
+        RewriteRule {
+            id: pattern.to_string(),
+            kind,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            staging_only,
+            enabled: true,
+        }
+    }
+
+    fn rewritten(config: &AppConfig, url: &str) -> Option<String> {
+        rewrite(config, &Url::parse(url).unwrap()).map(|u| u.to_string())
+    }
+
+    #[test]
+    fn test_prefix_rule() {
+        let mut config = AppConfig::default();
+        config.rewrite_rules.push(rule(
+            RewriteKind::Prefix,
+            "http://old-intranet/",
+            "https://intranet.example.com/",
+            false,
+        ));
+
+        assert_eq!(
+            rewritten(&config, "http://old-intranet/wiki/Home?x=1").as_deref(),
+            Some("https://intranet.example.com/wiki/Home?x=1")
+        );
+        assert_eq!(rewritten(&config, "https://example.com/"), None);
+    }
+
+    #[test]
+    fn test_regex_rule_only_in_staging_mode() {
+        let mut config = AppConfig::default();
+        config.rewrite_rules.push(rule(
+            RewriteKind::Regex,
+            r"^https://app\.example\.com/(.*)$",
+            "https://staging.example.com/$1",
+            true,
+        ));
+
+        assert_eq!(rewritten(&config, "https://app.example.com/orders"), None);
+
+        config.staging_mode = true;
+        assert_eq!(
+            rewritten(&config, "https://app.example.com/orders").as_deref(),
+            Some("https://staging.example.com/orders")
+        );
+    }
+
+    #[test]
+    fn test_looping_rules_are_ignored() {
+        let mut config = AppConfig::default();
+        config.rewrite_rules.push(rule(RewriteKind::Prefix, "https://a.example.com/", "https://b.example.com/", false));
+        config.rewrite_rules.push(rule(RewriteKind::Prefix, "https://b.example.com/", "https://a.example.com/", false));
+
+        assert_eq!(rewritten(&config, "https://a.example.com/page"), None);
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(validate_rule(&rule(RewriteKind::Regex, "(", "x", false)).is_err());
+        assert!(validate_rule(&rule(RewriteKind::Prefix, "", "x", false)).is_err());
+        assert!(validate_rule(&rule(RewriteKind::Prefix, "http://a/", "http://b/", false)).is_ok());
+    }
+}
@@ -0,0 +1,198 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::events::{self, Channel};
+use crate::scripting;
+use crate::session;
+use crate::shortcuts::load_shortcuts_from_config;
+
+/// 以安全模式启动的命令行参数
+pub const SAFE_MODE_ARG: &str = "--safe-mode";
+
+/// 记录启动状态的文件（应用数据目录下）
+const STARTUP_FILE: &str = "startup.json";
+
+/// 连续多少次启动后没能稳定运行时自动进入安全模式
+const FAILED_START_THRESHOLD: u32 = 3;
+
+/// 启动后稳定运行多久才算启动成功
+const STABLE_AFTER: Duration = Duration::from_secs(30);
+
+/// 启动记录：启动时加一，稳定运行后清零；启动中崩溃（包括 panic）时留下未清零的次数
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartupRecord {
+    #[serde(default)]
+    unfinished_starts: u32,
+}
+
+/// 安全模式状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeStatus {
+    /// 是否处于安全模式（全局快捷键、脚本注入、自动化定时任务和会话恢复均不启用）
+    pub active: bool,
+    /// 是否因为连续启动失败而自动进入
+    pub automatic: bool,
+}
+
+pub struct SafeModeState {
+    status: Mutex<SafeModeStatus>,
+}
+
+impl SafeModeState {
+    pub fn new(status: SafeModeStatus) -> Self {
+        Self {
+            status: Mutex::new(status),
+        }
+    }
+}
+
+/// 从启动参数中取出 `--safe-mode`，返回是否指定以及剩余参数
+pub fn take_flag(args: Vec<String>) -> (bool, Vec<String>) {
+    let requested = args.iter().any(|arg| arg == SAFE_MODE_ARG);
+    (requested, args.into_iter().filter(|arg| arg != SAFE_MODE_ARG).collect())
+}
+
+fn read_record(dir: &Path) -> StartupRecord {
+    std::fs::read_to_string(dir.join(STARTUP_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_record(dir: &Path, record: &StartupRecord) {
+    let result = std::fs::create_dir_all(dir).and_then(|_| {
+        let content = serde_json::to_string(record).unwrap_or_default();
+        std::fs::write(dir.join(STARTUP_FILE), content)
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to write startup record: {}", e);
+    }
+}
+
+/// 记录一次启动，返回之前连续未能稳定运行的次数
+fn record_start(dir: &Path) -> u32 {
+    let mut record = read_record(dir);
+    let previous = record.unfinished_starts;
+    record.unfinished_starts = previous.saturating_add(1);
+    write_record(dir, &record);
+    previous
+}
+
+/// 启动时决定是否进入安全模式（命令行指定，或之前连续多次启动失败）
+pub fn start(dir: &Path, requested: bool) -> SafeModeStatus {
+    let failed_starts = record_start(dir);
+    let automatic = !requested && failed_starts >= FAILED_START_THRESHOLD;
+    if automatic {
+        log::warn!("{} consecutive startups did not finish, starting in safe mode", failed_starts);
+    } else if requested {
+        log::info!("Starting in safe mode");
+    }
+    SafeModeStatus {
+        active: requested || automatic,
+        automatic,
+    }
+}
+
+/// 稳定运行一段时间后把启动记录清零
+pub fn mark_stable_later(dir: PathBuf) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STABLE_AFTER).await;
+        write_record(&dir, &StartupRecord::default());
+        log::debug!("Startup finished, cleared startup record");
+    });
+}
+
+/// 当前是否处于安全模式
+pub fn is_active(app: &AppHandle) -> bool {
+    app.try_state::<SafeModeState>().is_some_and(|state| state.status.lock().active)
+}
+
+/// 当前的安全模式状态
+pub fn status(app: &AppHandle) -> SafeModeStatus {
+    app.try_state::<SafeModeState>()
+        .map(|state| *state.status.lock())
+        .unwrap_or_default()
+}
+
+/// 自动进入安全模式时提示用户：可以保持安全模式排查问题，也可以立即恢复正常
+pub fn prompt(app: &AppHandle) {
+    let mut dialog = app
+        .dialog()
+        .message(
+            "WebApp Hub 最近连续多次启动失败，已以安全模式启动：全局快捷键、脚本注入和会话恢复暂不启用。\
+             可以在设置中停用出问题的脚本或快捷键后恢复正常。",
+        )
+        .title("安全模式")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "保持安全模式".to_string(),
+            "恢复正常".to_string(),
+        ));
+    if let Some(main_window) = app.get_webview_window("main") {
+        dialog = dialog.parent(&main_window);
+    }
+    let app = app.clone();
+    dialog.show(move |keep| {
+        if !keep {
+            if let Err(e) = exit(&app) {
+                log::error!("Failed to leave safe mode: {}", e);
+            }
+        }
+    });
+}
+
+/// 退出安全模式：注册快捷键、启动自动化定时任务并恢复会话（之后打开的窗口照常注入脚本）
+pub fn exit(app: &AppHandle) -> AppResult<SafeModeStatus> {
+    let state = app.try_state::<SafeModeState>().ok_or("安全模式状态未初始化")?;
+    {
+        let mut status = state.status.lock();
+        if !status.active {
+            return Ok(*status);
+        }
+        *status = SafeModeStatus::default();
+    }
+    if let Ok(dir) = app.path().app_data_dir() {
+        write_record(&dir, &StartupRecord::default());
+    }
+
+    load_shortcuts_from_config(app, &app.state::<ConfigManager>().read())?;
+    scripting::start_scheduler(app.clone());
+    session::restore(app.clone());
+
+    let status = SafeModeStatus::default();
+    events::emit(app, Channel::Config, "safe-mode://changed", status);
+    log::info!("Left safe mode");
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_mode_after_repeated_failed_starts() {
+        let dir = std::env::temp_dir().join(format!("webapp-hub-safe-mode-{}", uuid::Uuid::new_v4()));
+        for _ in 0..FAILED_START_THRESHOLD {
+            assert!(!start(&dir, false).active);
+        }
+        let status = start(&dir, false);
+        assert!(status.active && status.automatic);
+
+        write_record(&dir, &StartupRecord::default());
+        assert_eq!(start(&dir, false), SafeModeStatus::default());
+        assert!(!start(&dir, true).automatic);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let (requested, rest) = take_flag(vec!["--safe-mode".to_string(), "open".to_string(), "Mail".to_string()]);
+        assert!(requested);
+        assert_eq!(rest, ["open", "Mail"]);
+    }
+}
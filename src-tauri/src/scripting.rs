@@ -0,0 +1,263 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::models::{AppConfig, Automation, WebApp};
+use crate::window::WindowManager;
+
+/// 单次脚本运行允许的最大操作数，防止死循环占满后台线程
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// 定时任务检查间隔
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 按 ID 或名称查找小程序
+fn find_webapp(config: &AppConfig, key: &str) -> Option<WebApp> {
+    config
+        .webapps
+        .iter()
+        .find(|w| w.id == key || w.name == key)
+        .cloned()
+}
+
+fn resolve_webapp(app: &AppHandle, key: &str) -> Result<(WebApp, Option<String>), String> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = find_webapp(&config, key).ok_or_else(|| format!("小程序不存在: {}", key))?;
+    let proxy_url = config.proxy_url_for(&webapp);
+    Ok((webapp, proxy_url))
+}
+
+fn open_webapp(app: &AppHandle, key: &str) -> Result<(), String> {
+    let (webapp, proxy_url) = resolve_webapp(app, key)?;
+    app.state::<WindowManager>().open_webapp(app, &webapp, proxy_url).map_err(|e| e.to_string())
+}
+
+fn close_webapp(app: &AppHandle, key: &str) -> Result<(), String> {
+    let (webapp, _) = resolve_webapp(app, key)?;
+    app.state::<WindowManager>().close_webapp(app, &webapp.id).map_err(|e| e.to_string())
+}
+
+fn toggle_webapp(app: &AppHandle, key: &str) -> Result<(), String> {
+    let (webapp, proxy_url) = resolve_webapp(app, key)?;
+    app.state::<WindowManager>()
+        .toggle_webapp(app, &webapp, proxy_url)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn inject_script(app: &AppHandle, key: &str, script: &str) -> Result<(), String> {
+    let (webapp, _) = resolve_webapp(app, key)?;
+    app.state::<WindowManager>()
+        .inject_script(app, &webapp.id, script)
+        .map_err(|e| e.to_string())
+}
+
+/// 构建脚本引擎，注册对窗口管理器、配置管理器和脚本注入的 API
+///
+/// 脚本可用函数：
+/// - `open(key)` / `close(key)` / `toggle(key)`：按 ID 或名称操作小程序窗口
+/// - `inject(key, script)`：向小程序窗口注入 JavaScript
+/// - `is_open(key)`：窗口是否处于活跃状态
+/// - `list_webapps()`：返回 `[#{id, name, url}]`
+/// - `sleep(ms)`：等待指定毫秒（例如等待页面加载）
+fn build_engine(app: AppHandle) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|s| log::info!("[automation] {}", s));
+    engine.on_debug(|s, _, pos| log::debug!("[automation] {:?} {}", pos, s));
+
+    let handle = app.clone();
+    engine.register_fn("open", move |key: &str| -> Result<(), Box<EvalAltResult>> {
+        open_webapp(&handle, key).map_err(Into::into)
+    });
+
+    let handle = app.clone();
+    engine.register_fn("close", move |key: &str| -> Result<(), Box<EvalAltResult>> {
+        close_webapp(&handle, key).map_err(Into::into)
+    });
+
+    let handle = app.clone();
+    engine.register_fn("toggle", move |key: &str| -> Result<(), Box<EvalAltResult>> {
+        toggle_webapp(&handle, key).map_err(Into::into)
+    });
+
+    let handle = app.clone();
+    engine.register_fn(
+        "inject",
+        move |key: &str, script: &str| -> Result<(), Box<EvalAltResult>> {
+            inject_script(&handle, key, script).map_err(Into::into)
+        },
+    );
+
+    let handle = app.clone();
+    engine.register_fn("is_open", move |key: &str| -> bool {
+        resolve_webapp(&handle, key)
+            .map(|(w, _)| handle.state::<WindowManager>().is_window_active(&w.id))
+            .unwrap_or(false)
+    });
+
+    let handle = app;
+    engine.register_fn("list_webapps", move || -> Array {
+        let config = handle.state::<ConfigManager>().read();
+        config
+            .webapps
+            .iter()
+            .map(|w| {
+                let mut map = Map::new();
+                map.insert("id".into(), Dynamic::from(w.id.clone()));
+                map.insert("name".into(), Dynamic::from(w.name.clone()));
+                map.insert("url".into(), Dynamic::from(w.url.clone()));
+                Dynamic::from_map(map)
+            })
+            .collect()
+    });
+
+    engine.register_fn("sleep", |ms: i64| {
+        std::thread::sleep(Duration::from_millis(ms.max(0) as u64));
+    });
+
+    engine
+}
+
+/// 在后台线程中运行脚本（脚本中的 sleep 不会阻塞异步运行时）
+pub async fn run_script(app: AppHandle, source: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let engine = build_engine(app);
+        engine.run(&source).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 解析自动化脚本的运行时间（两位数的 `HH:MM`，如 `09:05`）
+pub fn parse_run_at(run_at: &str) -> Option<NaiveTime> {
+    if run_at.len() != 5 || run_at.as_bytes()[2] != b':' {
+        return None;
+    }
+    NaiveTime::parse_from_str(run_at, "%H:%M").ok()
+}
+
+/// 保存前检查自动化脚本的运行时间，空字符串视为不定时运行
+pub fn validate_schedules(automations: &mut [Automation]) -> AppResult<()> {
+    for automation in automations {
+        automation.run_at = automation.run_at.take().filter(|t| !t.trim().is_empty());
+        if let Some(run_at) = automation.run_at.as_deref() {
+            if parse_run_at(run_at).is_none() {
+                return Err(AppError::invalid_input(format!(
+                    "自动化脚本 {} 的运行时间无效: {}（格式为 HH:MM）",
+                    automation.name, run_at
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 到了运行时间且今天还没有运行过的已启用脚本，并记录为今天已运行
+fn due_automations<'a>(
+    automations: &'a [Automation],
+    now: NaiveDateTime,
+    last_runs: &mut HashMap<String, NaiveDate>,
+) -> Vec<&'a Automation> {
+    let today = now.date();
+    automations
+        .iter()
+        .filter(|a| a.enabled)
+        .filter(|a| {
+            a.run_at
+                .as_deref()
+                .and_then(parse_run_at)
+                .is_some_and(|at| at.hour() == now.hour() && at.minute() == now.minute())
+        })
+        .filter(|a| last_runs.insert(a.id.clone(), today) != Some(today))
+        .collect()
+}
+
+/// 启动定时调度器：每天在 `run_at` 指定的时间运行已启用的自动化脚本
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // 记录每个脚本最后一次运行的日期，避免同一分钟内重复触发
+        let mut last_runs: HashMap<String, NaiveDate> = HashMap::new();
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let now = chrono::Local::now().naive_local();
+            let config = app.state::<ConfigManager>().read();
+
+            for automation in due_automations(&config.automations, now, &mut last_runs) {
+                log::info!("Running scheduled automation: {}", automation.name);
+                let name = automation.name.clone();
+                let source = automation.source.clone();
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = run_script(app, source).await {
+                        log::error!("Automation {} failed: {}", name, e);
+                    }
+                });
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn automation(id: &str, run_at: Option<&str>) -> Automation {
+        Automation {
+            id: id.to_string(),
+            name: id.to_string(),
+            source: String::new(),
+            run_at: run_at.map(str::to_string),
+            enabled: true,
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32, second: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day)
+            .and_then(|d| d.and_hms_opt(hour, minute, second))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_run_at() {
+        assert_eq!(parse_run_at("09:05"), NaiveTime::from_hms_opt(9, 5, 0));
+        assert_eq!(parse_run_at("23:59"), NaiveTime::from_hms_opt(23, 59, 0));
+        for invalid in ["9:05", "24:00", "09:60", "0905", "09:05:00", ""] {
+            assert_eq!(parse_run_at(invalid), None, "{}", invalid);
+        }
+
+        let mut automations = vec![automation("a", Some("")), automation("b", Some("07:30"))];
+        validate_schedules(&mut automations).unwrap();
+        assert_eq!(automations[0].run_at, None);
+        assert!(validate_schedules(&mut [automation("c", Some("7:30"))]).is_err());
+    }
+
+    #[test]
+    fn test_scheduler_runs_once_per_day() {
+        let mut disabled = automation("disabled", Some("09:05"));
+        disabled.enabled = false;
+        let automations = vec![
+            automation("morning", Some("09:05")),
+            automation("evening", Some("18:00")),
+            automation("manual", None),
+            disabled,
+        ];
+        let mut last_runs = HashMap::new();
+        let ids = |due: Vec<&Automation>| due.into_iter().map(|a| a.id.clone()).collect::<Vec<_>>();
+
+        assert!(due_automations(&automations, at(2, 9, 4, 50), &mut last_runs).is_empty());
+        assert_eq!(ids(due_automations(&automations, at(2, 9, 5, 10), &mut last_runs)), ["morning"]);
+        // 同一分钟内的下一次检查不再运行
+        assert!(due_automations(&automations, at(2, 9, 5, 40), &mut last_runs).is_empty());
+        assert_eq!(ids(due_automations(&automations, at(2, 18, 0, 0), &mut last_runs)), ["evening"]);
+        // 第二天再次运行
+        assert_eq!(ids(due_automations(&automations, at(3, 9, 5, 0), &mut last_runs)), ["morning"]);
+    }
+}
@@ -0,0 +1,161 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::models::{AppConfig, ProxyConfig};
+
+/// 敏感信息（如代理密码）保存在系统钥匙串（macOS Keychain、Windows 凭据管理器、
+/// Linux Secret Service）中，不写入配置文件。这是钥匙串中使用的服务名
+const SERVICE: &str = "WebApp Hub";
+
+fn entry(key: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, key).map_err(|e| e.to_string())
+}
+
+/// 读取保存的值，不存在或钥匙串不可用时返回 None
+pub fn get(key: &str) -> Option<String> {
+    match entry(key).and_then(|e| e.get_password().map_err(|e| e.to_string())) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::debug!("Secret {} unavailable: {}", key, e);
+            None
+        }
+    }
+}
+
+/// 保存值（覆盖已有的值）
+pub fn set(key: &str, value: &str) -> Result<(), String> {
+    entry(key)?
+        .set_password(value)
+        .map_err(|e| format!("无法保存到系统钥匙串: {}", e))
+}
+
+/// 删除保存的值（不存在时视为成功）
+pub fn delete(key: &str) -> Result<(), String> {
+    match entry(key)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("无法从系统钥匙串删除: {}", e)),
+    }
+}
+
+/// 已保存到钥匙串的代理密码（键 -> 密码），避免每次写入配置都访问钥匙串
+static PROXY_PASSWORDS: Mutex<Option<HashMap<String, String>>> = parking_lot::const_mutex(None);
+
+/// 钥匙串不可用的警告只记录一次
+static KEYCHAIN_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 配置中的所有代理：全局代理、小程序单独的代理和代理路由规则
+fn proxies(config: &mut AppConfig) -> Vec<&mut ProxyConfig> {
+    let mut proxies = vec![&mut config.proxy];
+    proxies.extend(config.webapps.iter_mut().filter_map(|w| w.proxy_override.as_mut()));
+    for route in &mut config.proxy_routes {
+        proxies.push(&mut route.proxy);
+        proxies.extend(route.fallback.as_mut());
+    }
+    proxies
+}
+
+fn password_key(proxy: &ProxyConfig) -> String {
+    format!(
+        "proxy-password:{}@{}:{}",
+        proxy.username.as_deref().unwrap_or_default(),
+        proxy.host,
+        proxy.port
+    )
+}
+
+/// 配置文件中是否还有明文保存的代理密码（加载后写回一次，把密码移到钥匙串）
+pub fn has_plaintext_passwords(config: &AppConfig) -> bool {
+    std::iter::once(&config.proxy)
+        .chain(config.webapps.iter().filter_map(|w| w.proxy_override.as_ref()))
+        .chain(
+            config
+                .proxy_routes
+                .iter()
+                .flat_map(|route| std::iter::once(&route.proxy).chain(route.fallback.as_ref())),
+        )
+        .any(|proxy| proxy.password.is_some())
+}
+
+/// 写入配置文件前把代理密码移到钥匙串，文件中只保留引用（`passwordRef`）；
+/// 钥匙串不可用时密码仍以明文保存在文件中
+pub fn store_proxy_passwords(config: &mut AppConfig) {
+    let mut stored = PROXY_PASSWORDS.lock();
+    let stored = stored.get_or_insert_with(HashMap::new);
+    for proxy in proxies(config) {
+        let password = match proxy.password.take() {
+            Some(password) => password,
+            None => continue,
+        };
+        let key = password_key(proxy);
+        if stored.get(&key) != Some(&password) {
+            if let Err(e) = set(&key, &password) {
+                if !KEYCHAIN_WARNED.swap(true, Ordering::Relaxed) {
+                    log::warn!("Keeping proxy passwords in the config file: {}", e);
+                }
+                proxy.password = Some(password);
+                continue;
+            }
+            stored.insert(key.clone(), password);
+        }
+        proxy.password_ref = Some(key);
+    }
+}
+
+/// 读取配置文件后用钥匙串中的密码替换引用；找不到密码时保留引用，下次写入时不会丢失
+pub fn resolve_proxy_passwords(config: &mut AppConfig) {
+    let mut stored = PROXY_PASSWORDS.lock();
+    let stored = stored.get_or_insert_with(HashMap::new);
+    for proxy in proxies(config) {
+        let key = match proxy.password_ref.clone() {
+            Some(key) => key,
+            None => continue,
+        };
+        // 手动在文件中填写了明文密码时以明文为准
+        if proxy.password.is_some() {
+            proxy.password_ref = None;
+            continue;
+        }
+        match stored.get(&key).cloned().or_else(|| get(&key)) {
+            Some(password) => {
+                stored.insert(key, password.clone());
+                proxy.password = Some(password);
+                proxy.password_ref = None;
+            }
+            None => log::warn!("Proxy password {} not found in the keychain", key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_passwords_round_trip_through_references() {
+        let mut config = AppConfig::default();
+        config.proxy.host = "proxy.test".to_string();
+        config.proxy.port = 3128;
+        config.proxy.username = Some("alice".to_string());
+        config.proxy.password = Some("secret".to_string());
+        assert!(has_plaintext_passwords(&config));
+
+        // 已缓存的密码不再访问钥匙串
+        let key = password_key(&config.proxy);
+        PROXY_PASSWORDS
+            .lock()
+            .get_or_insert_with(HashMap::new)
+            .insert(key.clone(), "secret".to_string());
+
+        let mut saved = config.clone();
+        store_proxy_passwords(&mut saved);
+        assert_eq!(saved.proxy.password, None);
+        assert_eq!(saved.proxy.password_ref.as_deref(), Some(key.as_str()));
+        assert!(!serde_json::to_string(&saved).unwrap().contains("secret"));
+        assert!(!has_plaintext_passwords(&saved));
+
+        resolve_proxy_passwords(&mut saved);
+        assert_eq!(saved.proxy.password.as_deref(), Some("secret"));
+        assert_eq!(saved.proxy.password_ref, None);
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::speech;
+use crate::template;
+use crate::window::{window_label, OpenOptions, WindowManager};
+
+/// 选中文本的用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SelectionPurpose {
+    /// 用系统语音朗读
+    Speak,
+    /// 在翻译小程序中翻译
+    Translate,
+}
+
+impl SelectionPurpose {
+    fn as_str(self) -> &'static str {
+        match self {
+            SelectionPurpose::Speak => "speak",
+            SelectionPurpose::Translate => "translate",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectionPayload {
+    purpose: SelectionPurpose,
+    #[serde(default)]
+    text: String,
+}
+
+/// 请求小程序窗口上报当前选中的文本（结果通过桥接 `selection` 事件返回）
+pub fn request(app: &AppHandle, webapp_id: &str, purpose: SelectionPurpose) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&window_label(webapp_id))
+        .ok_or("窗口未打开")?;
+    let script = format!(
+        "window.__WEBAPP_HUB__ && window.__WEBAPP_HUB__.sendSelection('{}')",
+        purpose.as_str()
+    );
+    window.eval(&script).map_err(|e| e.to_string())
+}
+
+/// 处理页面上报的选中文本
+pub fn handle_event(app: &AppHandle, webapp_id: &str, payload: Value) -> Result<(), String> {
+    let payload: SelectionPayload = serde_json::from_value(payload).map_err(|e| e.to_string())?;
+    let text = payload.text.trim();
+    if text.is_empty() {
+        log::debug!("No text selected in {}", webapp_id);
+        return Ok(());
+    }
+
+    match payload.purpose {
+        SelectionPurpose::Speak => speech::speak(app, text),
+        SelectionPurpose::Translate => translate(app, text),
+    }
+}
+
+/// 按网址模板在翻译小程序中打开选中的文本
+fn translate(app: &AppHandle, text: &str) -> Result<(), String> {
+    let config = app.state::<ConfigManager>().read();
+    let settings = &config.translation;
+    let webapp_id = settings.webapp_id.as_deref().ok_or("未设置翻译小程序")?;
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or("翻译小程序不存在")?;
+
+    let url = template::render_url(
+        &settings.url_template,
+        &[("text", text), ("lang", &settings.target_language)],
+    );
+    let target_url = url::Url::parse(&url).map_err(|e| format!("无效的翻译网址: {}", e))?;
+
+    app.state::<WindowManager>().open_webapp_with(
+        app,
+        webapp,
+        config.proxy_url_for(webapp),
+        OpenOptions {
+            target_url: Some(target_url),
+            ..OpenOptions::default()
+        },
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::models::{AppConfig, WebApp};
+use crate::window::{window_label, OpenOptions, WindowManager};
+
+/// 会话中的单个窗口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEntry {
+    pub webapp_id: String,
+    pub visible: bool,
+}
+
+/// 上次退出时打开的小程序窗口（最久未使用的在前）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    #[serde(default)]
+    pub windows: Vec<SessionEntry>,
+}
+
+fn session_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_default()
+        .join("session.json")
+}
+
+/// 记录当前打开的小程序窗口
+pub fn capture(app: &AppHandle) -> SessionState {
+    let window_manager = match app.try_state::<WindowManager>() {
+        Some(wm) => wm,
+        None => return SessionState::default(),
+    };
+
+    // LRU 顺序为最近使用在前，保存时反转，使恢复后最近使用的窗口位于最上层
+    let windows = window_manager
+        .get_active_window_ids()
+        .into_iter()
+        .rev()
+        .filter_map(|webapp_id| {
+            let window = app.get_webview_window(&window_label(&webapp_id))?;
+            Some(SessionEntry {
+                visible: window.is_visible().unwrap_or(false),
+                webapp_id,
+            })
+        })
+        .collect();
+
+    SessionState { windows }
+}
+
+/// 保存当前会话到文件
+pub fn save(app: &AppHandle) {
+    let state = capture(app);
+    let path = session_path(app);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    let result = serde_json::to_string_pretty(&state)
+        .map_err(|e| e.to_string())
+        .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to save session to {:?}: {}", path, e);
+    }
+}
+
+fn load(app: &AppHandle) -> SessionState {
+    std::fs::read_to_string(session_path(app))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 启动时要打开的窗口：启动时打开的小程序（按 order）优先，其次是会话中的可见窗口和隐藏窗口，
+/// 重复的只保留一次（会话中可见的保持可见），总数不超过最大活跃窗口数
+fn startup_plan(config: &AppConfig, session: Vec<SessionEntry>) -> Vec<SessionEntry> {
+    let mut launch: Vec<&WebApp> = config
+        .webapps
+        .iter()
+        .filter(|w| w.enabled && w.launch_on_start)
+        .collect();
+    launch.sort_by_key(|w| w.order);
+
+    let mut session = if config.restore_session { session } else { Vec::new() };
+    // 稳定排序：可见窗口在前，同组内保持原有顺序
+    session.sort_by_key(|entry| !entry.visible);
+
+    let mut plan: Vec<SessionEntry> = launch
+        .into_iter()
+        .map(|w| SessionEntry {
+            webapp_id: w.id.clone(),
+            visible: session.iter().any(|e| e.webapp_id == w.id && e.visible),
+        })
+        .collect();
+    for entry in session {
+        if !plan.iter().any(|e| e.webapp_id == entry.webapp_id) {
+            plan.push(entry);
+        }
+    }
+    plan.truncate(config.max_active_windows.max(1));
+    plan
+}
+
+/// 启动时分批打开启动小程序并恢复上次的会话：窗口之间按配置间隔错开创建，
+/// 避免同时创建大量 WebView 导致 CPU 峰值
+pub fn restore(app: AppHandle) {
+    let config = app.state::<ConfigManager>().read();
+    let entries = startup_plan(&config, load(&app).windows);
+    if entries.is_empty() {
+        return;
+    }
+
+    let delay = Duration::from_millis(config.session_restore_delay_ms);
+
+    tauri::async_runtime::spawn(async move {
+        for (index, entry) in entries.into_iter().enumerate() {
+            let config = app.state::<ConfigManager>().read();
+            let webapp = match config.webapps.iter().find(|w| w.id == entry.webapp_id) {
+                Some(webapp) => webapp.clone(),
+                None => continue,
+            };
+
+            // 第一个窗口立即恢复，之后每个窗口间隔 delay，再叠加小程序自身的启动延迟
+            let mut wait = Duration::from_millis(webapp.startup_delay_ms.unwrap_or(0) as u64);
+            if index > 0 {
+                wait += delay;
+            }
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+
+            let options = OpenOptions {
+                hidden: !entry.visible,
+                ..Default::default()
+            };
+            let proxy_url = config.proxy_url_for(&webapp);
+            match app
+                .state::<WindowManager>()
+                .open_webapp_with(&app, &webapp, proxy_url, options)
+            {
+                Ok(()) => log::info!("Opened webapp window at startup: {}", webapp.id),
+                Err(e) => log::warn!("Failed to open webapp {} at startup: {}", webapp.id, e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, visible: bool) -> SessionEntry {
+        SessionEntry {
+            webapp_id: id.to_string(),
+            visible,
+        }
+    }
+
+    #[test]
+    fn test_startup_plan() {
+        let mut config = AppConfig::default();
+        let make = |id: &str, order: u32, launch: bool| {
+            let mut webapp = WebApp::new(id.to_string(), format!("https://{}.example.com", id));
+            webapp.id = id.to_string();
+            webapp.order = order;
+            webapp.launch_on_start = launch;
+            webapp
+        };
+        config.webapps = vec![make("chat", 2, true), make("mail", 1, true), make("docs", 3, false)];
+        config.max_active_windows = 3;
+
+        config.restore_session = true;
+        let session = vec![entry("docs", false), entry("notes", true), entry("chat", true)];
+        let plan = startup_plan(&config, session.clone());
+        assert_eq!(plan, vec![entry("mail", false), entry("chat", true), entry("notes", true)]);
+
+        config.restore_session = false;
+        let plan = startup_plan(&config, session);
+        assert_eq!(plan, vec![entry("mail", false), entry("chat", false)]);
+    }
+}
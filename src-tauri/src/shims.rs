@@ -0,0 +1,141 @@
+//! 在页面脚本运行之前注入的 Web API 垫片（通过 initialization_script）
+
+/// 时区垫片：覆盖 `Intl.DateTimeFormat` 的默认时区，以及 `Date` 的本地时间读取、
+/// 本地化格式化和 `getTimezoneOffset`。本地时间的 setter 与字符串解析仍使用系统时区。
+const TIMEZONE_SHIM: &str = r#"(function() {
+    var tz = __TIMEZONE__;
+    var NativeDateTimeFormat = Intl.DateTimeFormat;
+    try {
+        new NativeDateTimeFormat('en-US', { timeZone: tz });
+    } catch (e) {
+        console.warn('[WebApp Hub] Invalid timezone override:', tz);
+        return;
+    }
+
+    var partsFormatter = new NativeDateTimeFormat('en-US', {
+        timeZone: tz, hourCycle: 'h23',
+        year: 'numeric', month: 'numeric', day: 'numeric',
+        hour: 'numeric', minute: 'numeric', second: 'numeric'
+    });
+
+    // 与 getTimezoneOffset 语义一致：UTC - 本地时间（分钟）
+    function offsetMinutes(date) {
+        var time = date.getTime();
+        if (isNaN(time)) return NaN;
+        var p = {};
+        partsFormatter.formatToParts(date).forEach(function(part) { p[part.type] = part.value; });
+        var asUtc = Date.UTC(+p.year, +p.month - 1, +p.day, +p.hour % 24, +p.minute, +p.second);
+        return Math.round((Math.floor(time / 1000) * 1000 - asUtc) / 60000);
+    }
+
+    function shifted(date) {
+        return new Date(date.getTime() - offsetMinutes(date) * 60000);
+    }
+
+    function withTimeZone(options) {
+        options = Object.assign({}, options);
+        if (!options.timeZone) options.timeZone = tz;
+        return options;
+    }
+
+    function DateTimeFormat(locales, options) {
+        return new NativeDateTimeFormat(locales, withTimeZone(options));
+    }
+    DateTimeFormat.prototype = NativeDateTimeFormat.prototype;
+    DateTimeFormat.supportedLocalesOf = NativeDateTimeFormat.supportedLocalesOf;
+    Intl.DateTimeFormat = DateTimeFormat;
+
+    var proto = Date.prototype;
+    proto.getTimezoneOffset = function() { return offsetMinutes(this); };
+
+    ['FullYear', 'Month', 'Date', 'Day', 'Hours', 'Minutes', 'Seconds'].forEach(function(name) {
+        var utcGetter = proto['getUTC' + name];
+        proto['get' + name] = function() { return utcGetter.call(shifted(this)); };
+    });
+
+    ['toLocaleString', 'toLocaleDateString', 'toLocaleTimeString'].forEach(function(name) {
+        var nativeMethod = proto[name];
+        proto[name] = function(locales, options) {
+            return nativeMethod.call(this, locales, withTimeZone(options));
+        };
+    });
+
+    var days = ['Sun', 'Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat'];
+    var months = ['Jan', 'Feb', 'Mar', 'Apr', 'May', 'Jun', 'Jul', 'Aug', 'Sep', 'Oct', 'Nov', 'Dec'];
+    function pad(n) { return (n < 10 ? '0' : '') + n; }
+
+    proto.toString = function() {
+        if (isNaN(this.getTime())) return 'Invalid Date';
+        var s = shifted(this);
+        var offset = offsetMinutes(this);
+        var abs = Math.abs(offset);
+        return days[s.getUTCDay()] + ' ' + months[s.getUTCMonth()] + ' ' + pad(s.getUTCDate()) + ' ' +
+            s.getUTCFullYear() + ' ' + pad(s.getUTCHours()) + ':' + pad(s.getUTCMinutes()) + ':' +
+            pad(s.getUTCSeconds()) + ' GMT' + (offset <= 0 ? '+' : '-') + pad(Math.floor(abs / 60)) + pad(abs % 60);
+    };
+})();"#;
+
+/// 媒体设备垫片：页面请求麦克风/摄像头但未指定设备时，优先选择名称匹配的设备。
+/// 设备名称在首次授权前不可见，因此首次调用可能仍使用系统默认设备。
+const MEDIA_DEVICE_SHIM: &str = r#"(function() {
+    var prefs = { audio: __AUDIO__, video: __VIDEO__ };
+    var kinds = { audio: 'audioinput', video: 'videoinput' };
+    var mediaDevices = navigator.mediaDevices;
+    if (!mediaDevices || !mediaDevices.getUserMedia) return;
+    var nativeGetUserMedia = mediaDevices.getUserMedia.bind(mediaDevices);
+
+    function findDevice(devices, kind, preference) {
+        var needle = preference.toLowerCase();
+        for (var i = 0; i < devices.length; i++) {
+            var device = devices[i];
+            if (device.kind !== kind) continue;
+            if (device.deviceId === preference || device.label.toLowerCase().indexOf(needle) !== -1) {
+                return device.deviceId;
+            }
+        }
+        return null;
+    }
+
+    mediaDevices.getUserMedia = function(constraints) {
+        constraints = Object.assign({}, constraints);
+        return mediaDevices.enumerateDevices().then(function(devices) {
+            ['audio', 'video'].forEach(function(type) {
+                var preference = prefs[type];
+                var current = constraints[type];
+                if (!preference || !current) return;
+                // 页面显式指定了设备时尊重页面的选择
+                if (typeof current === 'object' && current.deviceId) return;
+                var deviceId = findDevice(devices, kinds[type], preference);
+                if (!deviceId) return;
+                constraints[type] = Object.assign(
+                    typeof current === 'object' ? current : {},
+                    { deviceId: { ideal: deviceId } }
+                );
+            });
+            return nativeGetUserMedia(constraints);
+        }, function() {
+            return nativeGetUserMedia(constraints);
+        });
+    };
+})();"#;
+
+/// 转换为 JavaScript 字符串字面量
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// 生成时区覆盖脚本（IANA 时区名，如 `America/New_York`）
+pub fn timezone_shim(timezone: &str) -> String {
+    TIMEZONE_SHIM.replace("__TIMEZONE__", &js_string(timezone))
+}
+
+/// 生成媒体设备偏好脚本（按设备名称子串或设备 ID 匹配）
+pub fn media_device_shim(audio_input: Option<&str>, video_input: Option<&str>) -> String {
+    let literal = |value: Option<&str>| match value {
+        Some(v) => js_string(v),
+        None => "null".to_string(),
+    };
+    MEDIA_DEVICE_SHIM
+        .replace("__AUDIO__", &literal(audio_input))
+        .replace("__VIDEO__", &literal(video_input))
+}
@@ -0,0 +1,193 @@
+use serde::Serialize;
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::models::{AppConfig, ShortcutAction};
+use crate::zoom::ZoomStep;
+
+/// 系统保留的快捷键（注册后会与系统功能冲突，或根本无法注册）
+#[cfg(target_os = "windows")]
+const RESERVED: &[(&str, &str)] = &[
+    ("Alt+Tab", "切换窗口"),
+    ("Alt+F4", "关闭窗口"),
+    ("Ctrl+Alt+Delete", "安全选项"),
+    ("Ctrl+Shift+Escape", "任务管理器"),
+    ("Super+D", "显示桌面"),
+    ("Super+E", "文件资源管理器"),
+    ("Super+L", "锁定屏幕"),
+    ("Super+R", "运行"),
+    ("Super+Tab", "任务视图"),
+];
+
+#[cfg(target_os = "macos")]
+const RESERVED: &[(&str, &str)] = &[
+    ("Super+Space", "聚焦搜索"),
+    ("Super+Tab", "切换应用"),
+    ("Super+Q", "退出应用"),
+    ("Super+H", "隐藏应用"),
+    ("Super+M", "最小化窗口"),
+    ("Super+Alt+Escape", "强制退出"),
+    ("Super+Shift+3", "截屏"),
+    ("Super+Shift+4", "截取部分屏幕"),
+    ("Ctrl+Super+Q", "锁定屏幕"),
+];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const RESERVED: &[(&str, &str)] = &[
+    ("Alt+Tab", "切换窗口"),
+    ("Alt+F2", "运行命令"),
+    ("Alt+F4", "关闭窗口"),
+    ("Ctrl+Alt+Delete", "注销"),
+    ("Ctrl+Alt+T", "打开终端"),
+    ("Super+L", "锁定屏幕"),
+];
+
+/// 已注册的快捷键及其目标
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredShortcut {
+    pub shortcut: String,
+    pub action: ShortcutAction,
+    /// 目标小程序 ID（或 `__main__` 等特殊目标）
+    pub target: String,
+    /// 在界面上显示的目标名称（小程序、分组、脚本或宏的名称）
+    pub label: String,
+}
+
+/// 快捷键检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutStatus {
+    /// 可以使用
+    Available,
+    /// 无法解析
+    Invalid,
+    /// 已绑定到其他动作
+    Conflict,
+    /// 系统保留
+    Reserved,
+}
+
+/// 保存快捷键之前的检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutCheck {
+    pub shortcut: String,
+    pub status: ShortcutStatus,
+    /// 不可用时的原因
+    pub message: Option<String>,
+    /// 冲突的已注册快捷键
+    pub conflict: Option<RegisteredShortcut>,
+}
+
+/// 动作的目标名称：小程序、分组、脚本和宏使用配置中的名称，其他动作使用说明文字
+pub fn describe(config: &AppConfig, action: &ShortcutAction) -> String {
+    let webapp_name = |id: &str| config.webapps.iter().find(|w| w.id == id).map(|w| w.name.clone());
+    let name = match action {
+        ShortcutAction::ToggleWebApp { webapp_id } => webapp_name(webapp_id),
+        ShortcutAction::OpenInBrowser { webapp_id: Some(webapp_id) } => {
+            webapp_name(webapp_id).map(|name| format!("在浏览器中打开 {}", name))
+        }
+        ShortcutAction::OpenInBrowser { webapp_id: None } => Some("在浏览器中打开当前小程序".to_string()),
+        ShortcutAction::OpenGroup { group_id } | ShortcutAction::CycleGroup { group_id } => {
+            config.groups.iter().find(|g| &g.id == group_id).map(|g| g.name.clone())
+        }
+        ShortcutAction::RunScript { automation_id } => {
+            config.automations.iter().find(|a| &a.id == automation_id).map(|a| a.name.clone())
+        }
+        ShortcutAction::RunMacro { macro_id } => {
+            config.macros.iter().find(|m| &m.id == macro_id).map(|m| m.name.clone())
+        }
+        ShortcutAction::ToggleMainWindow => Some("主窗口".to_string()),
+        ShortcutAction::ToggleSwitcher => Some("快速切换".to_string()),
+        ShortcutAction::MuteAll => Some("全部静音".to_string()),
+        ShortcutAction::HideAll => Some("隐藏全部窗口".to_string()),
+        ShortcutAction::Screenshot => Some("截图".to_string()),
+        ShortcutAction::ToggleAccessibility => Some("无障碍模式".to_string()),
+        ShortcutAction::ToggleReaderMode => Some("阅读模式".to_string()),
+        ShortcutAction::SpeakSelection => Some("朗读选中文本".to_string()),
+        ShortcutAction::TranslateSelection => Some("翻译选中文本".to_string()),
+        ShortcutAction::ReopenClosed => Some("重新打开关闭的小程序".to_string()),
+        ShortcutAction::Zoom { step } => Some(
+            match step {
+                ZoomStep::In => "放大当前小程序",
+                ZoomStep::Out => "缩小当前小程序",
+                ZoomStep::Reset => "恢复当前小程序的缩放",
+            }
+            .to_string(),
+        ),
+    };
+    name.unwrap_or_else(|| action.target_key())
+}
+
+/// 已注册的快捷键列表（附带目标名称）
+pub fn list(config: &AppConfig, registered: Vec<(String, ShortcutAction)>) -> Vec<RegisteredShortcut> {
+    registered
+        .into_iter()
+        .map(|(shortcut, action)| RegisteredShortcut {
+            label: describe(config, &action),
+            target: action.target_key(),
+            shortcut,
+            action,
+        })
+        .collect()
+}
+
+/// 检查快捷键能否使用：先解析，再与已注册的快捷键（按按键比较，不区分修饰键的写法和顺序）
+/// 和系统保留的快捷键比较；`action` 为正在编辑的绑定，已注册到同一动作时不算冲突
+pub fn check(
+    shortcut: &str,
+    registered: &[RegisteredShortcut],
+    action: Option<&ShortcutAction>,
+) -> ShortcutCheck {
+    let result = |status, message: Option<String>, conflict| ShortcutCheck {
+        shortcut: shortcut.to_string(),
+        status,
+        message,
+        conflict,
+    };
+    let parsed = match shortcut.trim().parse::<Shortcut>() {
+        Ok(parsed) => parsed,
+        Err(e) => return result(ShortcutStatus::Invalid, Some(format!("无效的快捷键: {}", e)), None),
+    };
+
+    let same_keys = |other: &str| other.parse::<Shortcut>().is_ok_and(|other| other == parsed);
+    if let Some(existing) = registered
+        .iter()
+        .find(|r| same_keys(&r.shortcut) && Some(&r.action) != action)
+    {
+        let message = format!("快捷键 {} 已被 {} 使用", existing.shortcut, existing.label);
+        return result(ShortcutStatus::Conflict, Some(message), Some(existing.clone()));
+    }
+
+    if let Some((_, purpose)) = RESERVED.iter().find(|(reserved, _)| same_keys(reserved)) {
+        let message = format!("快捷键 {} 是系统保留的快捷键（{}）", shortcut, purpose);
+        return result(ShortcutStatus::Reserved, Some(message), None);
+    }
+
+    result(ShortcutStatus::Available, None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WebApp;
+
+    #[test]
+    fn test_check_shortcut() {
+        let mut config = AppConfig::default();
+        let webapp = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        let toggle = ShortcutAction::ToggleWebApp { webapp_id: webapp.id.clone() };
+        config.webapps = vec![webapp];
+        let registered = list(&config, vec![("Ctrl+Shift+M".to_string(), toggle.clone())]);
+        assert_eq!(registered[0].label, "Mail");
+
+        let conflict = check("Shift+Ctrl+M", &registered, None);
+        assert_eq!(conflict.status, ShortcutStatus::Conflict);
+        assert_eq!(conflict.conflict.map(|c| c.action), Some(toggle.clone()));
+        // 正在编辑的绑定本身不算冲突
+        assert_eq!(check("Ctrl+Shift+M", &registered, Some(&toggle)).status, ShortcutStatus::Available);
+        assert_eq!(check("Ctrl+Shift+N", &registered, None).status, ShortcutStatus::Available);
+        assert_eq!(check("Ctrl+Nope", &registered, None).status, ShortcutStatus::Invalid);
+        assert_eq!(check(RESERVED[0].0, &registered, None).status, ShortcutStatus::Reserved);
+    }
+}
@@ -1,16 +1,63 @@
 use parking_lot::Mutex;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-use crate::models::AppConfig;
+use crate::accessibility;
+use crate::audio;
+use crate::backend::ShortcutRegistrar;
+use crate::browser;
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Channel};
+use crate::groups;
+use crate::macros;
+use crate::metrics;
+use crate::models::{AppConfig, ShortcutAction};
+use crate::placement;
+use crate::platform::{self, ShortcutBackend};
+use crate::policy;
+#[cfg(target_os = "linux")]
+use crate::portal;
+use crate::quiet_hours;
+use crate::reader;
+use crate::recent;
+use crate::safe_mode;
+use crate::scripting;
+use crate::selection::{self, SelectionPurpose};
+use crate::switcher;
+use crate::window::{focused_webapp_id, window_label, ToggleResult, WindowManager};
+use crate::zoom;
+
+/// 分组循环快捷键：在这段时间内再次按下时切换到分组中的下一个小程序
+const CYCLE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// 快捷键注册失败记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutFailure {
+    /// 目标小程序 ID（或 `__main__` 等特殊目标）
+    pub webapp_id: String,
+    pub shortcut: String,
+    pub action: ShortcutAction,
+    pub reason: String,
+}
 
 /// 快捷键管理器状态
 pub struct ShortcutManager {
-    /// 已注册的快捷键映射: shortcut_string -> webapp_id
-    registered: Mutex<HashMap<String, String>>,
+    /// 已注册的快捷键映射: shortcut_string -> action
+    registered: Mutex<HashMap<String, ShortcutAction>>,
     /// App handle for callbacks
     app_handle: Mutex<Option<AppHandle>>,
+    /// 注册代数，每次清除全部快捷键时递增，旧回调据此失效
+    generation: AtomicU64,
+    /// 注册失败的绑定: shortcut_string -> failure
+    failures: Mutex<HashMap<String, ShortcutFailure>>,
+    /// 分组循环的进度: 分组 ID -> (当前小程序在分组中的位置, 上次按下的时间)
+    group_cycles: Mutex<HashMap<String, (usize, Instant)>>,
 }
 
 impl ShortcutManager {
@@ -18,6 +65,9 @@ impl ShortcutManager {
         Self {
             registered: Mutex::new(HashMap::new()),
             app_handle: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            failures: Mutex::new(HashMap::new()),
+            group_cycles: Mutex::new(HashMap::new()),
         }
     }
 
@@ -25,58 +75,127 @@ impl ShortcutManager {
         *self.app_handle.lock() = Some(handle);
     }
 
+    /// 记录一次分组循环快捷键，返回这次应切换到的小程序位置（超时后从第一个重新开始）
+    fn advance_group_cycle(&self, group_id: &str, len: usize) -> usize {
+        let now = Instant::now();
+        let mut cycles = self.group_cycles.lock();
+        let index = next_cycle_index(cycles.get(group_id).copied(), now, len);
+        cycles.insert(group_id.to_string(), (index, now));
+        index
+    }
+
     /// 注册快捷键
-    pub fn register(
+    pub fn register<B: ShortcutRegistrar>(
         &self,
-        app: &AppHandle,
+        backend: &B,
         shortcut_str: &str,
-        webapp_id: &str,
-    ) -> Result<(), String> {
-        let shortcut: Shortcut = shortcut_str
-            .parse()
-            .map_err(|e| format!("无效的快捷键: {}", e))?;
+        action: ShortcutAction,
+    ) -> AppResult<()> {
+        parse_shortcut(shortcut_str)?;
 
         // 检查是否已注册
         {
             let registered = self.registered.lock();
             if registered.contains_key(shortcut_str) {
-                return Err(format!("快捷键 {} 已被使用", shortcut_str));
+                return Err(AppError::ShortcutConflict(format!("快捷键 {} 已被使用", shortcut_str)));
             }
         }
 
-        let webapp_id_clone = webapp_id.to_string();
-        let app_handle = app.clone();
+        // Wayland 上无法直接抓取按键，改为通过门户绑定全部快捷键
+        if backend.uses_portal() {
+            self.registered
+                .lock()
+                .insert(shortcut_str.to_string(), action);
+            self.rebind_portal(backend);
+            return Ok(());
+        }
 
-        // 注册快捷键并设置处理器
-        app.global_shortcut()
-            .on_shortcut(shortcut, move |_app, _shortcut, event| {
-                if event.state == ShortcutState::Pressed {
-                    handle_shortcut_trigger(&app_handle, &webapp_id_clone);
-                }
-            })
-            .map_err(|e| format!("注册快捷键失败: {}", e))?;
+        let generation = self.generation.load(Ordering::SeqCst);
+        backend
+            .register(shortcut_str, &action, generation)
+            .map_err(AppError::InvalidShortcut)?;
+
+        log::info!(
+            "Registered shortcut: {} for {}",
+            shortcut_str,
+            action.target_key()
+        );
 
         // 记录映射
         let mut registered = self.registered.lock();
-        registered.insert(shortcut_str.to_string(), webapp_id.to_string());
+        registered.insert(shortcut_str.to_string(), action);
+        Ok(())
+    }
 
-        log::info!(
-            "Registered shortcut: {} for webapp: {}",
+    /// 注册快捷键并记录结果：失败时保存原因并发送 `shortcut-registration-failed` 事件
+    pub fn register_tracked<B: ShortcutRegistrar>(
+        &self,
+        backend: &B,
+        shortcut_str: &str,
+        action: ShortcutAction,
+    ) -> AppResult<()> {
+        match self.register(backend, shortcut_str, action.clone()) {
+            Ok(()) => {
+                self.failures.lock().remove(shortcut_str);
+                Ok(())
+            }
+            Err(reason) => {
+                self.record_failure(backend, shortcut_str, action, reason.to_string());
+                Err(reason)
+            }
+        }
+    }
+
+    /// 记录注册失败的绑定并发送 `shortcut-registration-failed` 事件
+    pub fn record_failure<B: ShortcutRegistrar>(
+        &self,
+        backend: &B,
+        shortcut_str: &str,
+        action: ShortcutAction,
+        reason: String,
+    ) {
+        log::warn!(
+            "Failed to register shortcut {} for {}: {}",
             shortcut_str,
-            webapp_id
+            action.target_key(),
+            reason
         );
-        Ok(())
+        let failure = ShortcutFailure {
+            webapp_id: action.target_key(),
+            shortcut: shortcut_str.to_string(),
+            action,
+            reason,
+        };
+        backend.report_failure(&failure);
+        self.failures.lock().insert(shortcut_str.to_string(), failure);
+    }
+
+    /// 用当前映射重新绑定门户快捷键（仅 Wayland）
+    fn rebind_portal<B: ShortcutRegistrar>(&self, backend: &B) {
+        let bindings: Vec<(String, ShortcutAction)> = self
+            .registered
+            .lock()
+            .iter()
+            .map(|(shortcut, action)| (shortcut.clone(), action.clone()))
+            .collect();
+        backend.bind_portal(bindings);
+    }
+
+    /// 获取当前注册失败的绑定
+    pub fn get_failures(&self) -> Vec<ShortcutFailure> {
+        self.failures.lock().values().cloned().collect()
     }
 
     /// 注销快捷键
-    pub fn unregister(&self, app: &AppHandle, shortcut_str: &str) -> Result<(), String> {
-        let shortcut: Shortcut = shortcut_str
-            .parse()
-            .map_err(|e| format!("无效的快捷键: {}", e))?;
+    pub fn unregister<B: ShortcutRegistrar>(&self, backend: &B, shortcut_str: &str) -> AppResult<()> {
+        if backend.uses_portal() {
+            self.registered.lock().remove(shortcut_str);
+            self.rebind_portal(backend);
+            log::info!("Unregistered shortcut: {}", shortcut_str);
+            return Ok(());
+        }
 
-        app.global_shortcut()
-            .unregister(shortcut)
-            .map_err(|e| format!("注销快捷键失败: {}", e))?;
+        backend.unregister(shortcut_str).map_err(AppError::InvalidShortcut)?;
 
         let mut registered = self.registered.lock();
         registered.remove(shortcut_str);
@@ -85,21 +204,102 @@ impl ShortcutManager {
         Ok(())
     }
 
-    /// 获取快捷键对应的webapp_id
-    pub fn get_webapp_id(&self, shortcut_str: &str) -> Option<String> {
+    /// 当前已注册的快捷键和动作（按快捷键排序）
+    pub fn registered(&self) -> Vec<(String, ShortcutAction)> {
+        let mut registered: Vec<(String, ShortcutAction)> = self
+            .registered
+            .lock()
+            .iter()
+            .map(|(shortcut, action)| (shortcut.clone(), action.clone()))
+            .collect();
+        registered.sort_by(|a, b| a.0.cmp(&b.0));
+        registered
+    }
+
+    /// 获取快捷键对应的动作
+    pub fn get_action(&self, shortcut_str: &str) -> Option<ShortcutAction> {
         let registered = self.registered.lock();
         registered.get(shortcut_str).cloned()
     }
 
+    /// 按期望的绑定（快捷键, 动作）增量更新：
+    /// 只注销已移除或已改绑的快捷键，只注册新增的快捷键，未变化的绑定保持有效
+    /// 注册失败的绑定通过 `register_tracked` 记录
+    pub fn sync<B: ShortcutRegistrar>(&self, backend: &B, desired: &[(String, ShortcutAction)]) {
+        let mut wanted: HashMap<&str, &ShortcutAction> = HashMap::new();
+        for (shortcut, action) in desired {
+            wanted.entry(shortcut.as_str()).or_insert(action);
+        }
+
+        // 门户会话每次都绑定全部快捷键，直接替换映射后重新绑定一次
+        if backend.uses_portal() {
+            self.failures.lock().clear();
+            let mut registered = HashMap::new();
+            for (shortcut, action) in wanted {
+                match parse_shortcut(shortcut) {
+                    Ok(_) => {
+                        registered.insert(shortcut.to_string(), action.clone());
+                    }
+                    Err(reason) => self.record_failure(backend, shortcut, action.clone(), reason.to_string()),
+                }
+            }
+            *self.registered.lock() = registered;
+            self.rebind_portal(backend);
+            return;
+        }
+
+        // 不再需要的绑定不再报告失败
+        self.failures.lock().retain(|shortcut, failure| {
+            desired
+                .iter()
+                .any(|(s, a)| s == shortcut && *a == failure.action)
+        });
+
+        let stale: Vec<String> = {
+            let registered = self.registered.lock();
+            registered
+                .iter()
+                .filter(|(shortcut, action)| wanted.get(shortcut.as_str()) != Some(action))
+                .map(|(shortcut, _)| shortcut.clone())
+                .collect()
+        };
+        for shortcut in stale {
+            if let Err(e) = self.unregister(backend, &shortcut) {
+                log::warn!("Failed to unregister shortcut {}: {}", shortcut, e);
+            }
+        }
+
+        for (shortcut, action) in desired {
+            if self.get_action(shortcut).as_ref() == Some(action) {
+                continue;
+            }
+            let _ = self.register_tracked(backend, shortcut, action.clone());
+        }
+    }
+
+    /// 回调是否仍对应当前注册（代数一致且快捷键仍映射到同一动作）
+    pub fn is_current(&self, generation: u64, shortcut_str: &str, action: &ShortcutAction) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+            && self.get_action(shortcut_str).as_ref() == Some(action)
+    }
+
     /// 清除所有快捷键
-    pub fn clear_all(&self, app: &AppHandle) -> Result<(), String> {
+    pub fn clear_all<B: ShortcutRegistrar>(&self, backend: &B) -> AppResult<()> {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+
+        if backend.uses_portal() {
+            self.registered.lock().clear();
+            self.rebind_portal(backend);
+            return Ok(());
+        }
+
         let shortcuts: Vec<String> = {
             let registered = self.registered.lock();
             registered.keys().cloned().collect()
         };
 
         for shortcut_str in shortcuts {
-            let _ = self.unregister(app, &shortcut_str);
+            let _ = self.unregister(backend, &shortcut_str);
         }
 
         Ok(())
@@ -112,109 +312,483 @@ impl Default for ShortcutManager {
     }
 }
 
+fn parse_shortcut(shortcut_str: &str) -> AppResult<Shortcut> {
+    shortcut_str
+        .parse()
+        .map_err(|e| AppError::InvalidShortcut(format!("无效的快捷键: {}", e)))
+}
+
+/// 通过全局快捷键插件（或 Wayland 上的门户）注册
+impl ShortcutRegistrar for AppHandle {
+    fn uses_portal(&self) -> bool {
+        platform::shortcut_backend() == ShortcutBackend::Portal
+    }
+
+    fn bind_portal(&self, bindings: Vec<(String, ShortcutAction)>) {
+        #[cfg(target_os = "linux")]
+        portal::bind(self, bindings);
+
+        #[cfg(not(target_os = "linux"))]
+        let _ = bindings;
+    }
+
+    fn register(&self, shortcut_str: &str, action: &ShortcutAction, generation: u64) -> Result<(), String> {
+        let shortcut = parse_shortcut(shortcut_str).map_err(|e| e.to_string())?;
+        let action_clone = action.clone();
+        let shortcut_clone = shortcut_str.to_string();
+
+        // 注册快捷键并设置处理器
+        self.global_shortcut()
+            .on_shortcut(shortcut, move |app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+
+                // 回调捕获的映射可能已过期（配置已重新加载或快捷键已改绑）
+                let is_current = app
+                    .try_state::<ShortcutManager>()
+                    .map(|m| m.is_current(generation, &shortcut_clone, &action_clone))
+                    .unwrap_or(false);
+                if !is_current {
+                    log::debug!("Ignored stale shortcut handler: {}", shortcut_clone);
+                    return;
+                }
+
+                handle_shortcut_trigger(app, &action_clone);
+            })
+            .map_err(|e| format!("注册快捷键失败: {}", e))
+    }
+
+    fn unregister(&self, shortcut_str: &str) -> Result<(), String> {
+        self.global_shortcut()
+            .unregister(parse_shortcut(shortcut_str).map_err(|e| e.to_string())?)
+            .map_err(|e| format!("注销快捷键失败: {}", e))
+    }
+
+    fn report_failure(&self, failure: &ShortcutFailure) {
+        events::emit(self, Channel::Shortcuts, "shortcut-registration-failed", failure);
+    }
+}
+
 /// 初始化快捷键系统
 pub fn setup_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     let shortcut_manager = ShortcutManager::new();
     shortcut_manager.set_app_handle(app.handle().clone());
     app.manage(shortcut_manager);
 
+    #[cfg(target_os = "linux")]
+    if platform::shortcut_backend() == ShortcutBackend::Portal {
+        log::info!("Wayland session detected, using the GlobalShortcuts portal");
+        app.manage(portal::PortalShortcuts::new());
+    }
+
     Ok(())
 }
 
 /// 处理快捷键触发
-fn handle_shortcut_trigger(app: &AppHandle, webapp_id: &str) {
-    // 处理主窗口快捷键
-    if webapp_id == "__main__" {
-        if let Some(main_window) = app.get_webview_window("main") {
-            let is_visible = main_window.is_visible().unwrap_or(false);
-            let is_focused = main_window.is_focused().unwrap_or(false);
-            
-            if is_visible && is_focused {
-                let _ = main_window.hide();
-            } else {
-                let _ = main_window.show();
-                let _ = main_window.set_focus();
+pub(crate) fn handle_shortcut_trigger(app: &AppHandle, action: &ShortcutAction) {
+    metrics::shortcut_triggered();
+    match action {
+        ShortcutAction::ToggleWebApp { webapp_id } => toggle_webapp(app, webapp_id),
+        ShortcutAction::ToggleMainWindow => toggle_main_window(app),
+        ShortcutAction::ToggleSwitcher => {
+            if let Err(e) = switcher::toggle(app) {
+                log::error!("Failed to toggle quick switcher: {}", e);
+            }
+        }
+        ShortcutAction::RunScript { automation_id } => run_automation(app, automation_id),
+        ShortcutAction::RunMacro { macro_id } => macros::spawn_macro(app, macro_id),
+        ShortcutAction::MuteAll => {
+            if let Err(e) = policy::ensure_unlocked(app) {
+                log::warn!("Mute all shortcut refused: {}", e);
+            } else if let Err(e) = audio::toggle_mute_all(app) {
+                log::error!("Failed to toggle mute all: {}", e);
+            }
+        }
+        ShortcutAction::HideAll => hide_all(app),
+        ShortcutAction::Screenshot => request_screenshot(app),
+        ShortcutAction::ToggleReaderMode => toggle_reader_mode(app),
+        ShortcutAction::SpeakSelection => request_selection(app, SelectionPurpose::Speak),
+        ShortcutAction::TranslateSelection => request_selection(app, SelectionPurpose::Translate),
+        ShortcutAction::ReopenClosed => {
+            if let Err(e) = recent::reopen_last(app) {
+                log::warn!("Failed to reopen closed webapp: {}", e);
+            }
+        }
+        ShortcutAction::ToggleAccessibility => {
+            if let Err(e) = policy::ensure_unlocked(app) {
+                log::warn!("Accessibility shortcut refused: {}", e);
+            } else if let Err(e) = accessibility::toggle(app) {
+                log::error!("Failed to toggle accessibility styles: {}", e);
+            }
+        }
+        ShortcutAction::CycleGroup { group_id } => cycle_group(app, group_id),
+        ShortcutAction::OpenInBrowser { webapp_id } => open_in_browser(app, webapp_id.as_deref()),
+        ShortcutAction::OpenGroup { group_id } => {
+            let app = app.clone();
+            let group_id = group_id.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = groups::open(&app, &group_id).await {
+                    log::warn!("Failed to open group {}: {}", group_id, e);
+                }
+            });
+        }
+        ShortcutAction::Zoom { step } => {
+            if let Some(webapp_id) = focused_webapp_id(app) {
+                if let Err(e) = zoom::step_zoom(app, &webapp_id, *step) {
+                    log::warn!("Failed to zoom webapp {}: {}", webapp_id, e);
+                }
             }
         }
-        return;
     }
 
-    // 小程序快捷键：切换对应的小程序窗口
-    let window_label = format!("webapp-{}", webapp_id);
-    
-    if let Some(window) = app.get_webview_window(&window_label) {
-        let is_visible = window.is_visible().unwrap_or(false);
-        let is_focused = window.is_focused().unwrap_or(false);
-        
+    log::info!("Shortcut triggered: {}", action.target_key());
+}
+
+/// 切换主窗口
+fn toggle_main_window(app: &AppHandle) {
+    if let Some(main_window) = app.get_webview_window("main") {
+        let is_visible = main_window.is_visible().unwrap_or(false);
+        let is_focused = main_window.is_focused().unwrap_or(false);
+
         if is_visible && is_focused {
-            // 窗口可见且有焦点 → 隐藏
-            let _ = window.hide();
+            let _ = main_window.hide();
         } else {
-            // 窗口不可见或无焦点 → 显示并聚焦
-            let _ = window.show();
-            let _ = window.set_focus();
-        }
-    } else {
-        // 窗口不存在，从配置读取并创建
-        let config_path = app
-            .path()
-            .app_data_dir()
-            .unwrap_or_default()
-            .join("config.json");
-
-        if let Ok(content) = std::fs::read_to_string(&config_path) {
-            if let Ok(config) = serde_json::from_str::<AppConfig>(&content) {
-                if let Some(webapp) = config.webapps.iter().find(|w| w.id == webapp_id) {
-                    if let Ok(url) = webapp.url.parse::<url::Url>() {
-                        let _ = tauri::WebviewWindowBuilder::new(
-                            app,
-                            &window_label,
-                            tauri::WebviewUrl::External(url),
-                        )
-                        .title(&webapp.name)
-                        .inner_size(webapp.width as f64, webapp.height as f64)
-                        .resizable(true)
-                        .center()
-                        .build();
-                        
-                        log::info!("Created webapp window via shortcut: {}", webapp_id);
-                    }
-                }
-            }
+            let _ = main_window.show();
+            let _ = main_window.set_focus();
         }
     }
-    
-    log::info!("Shortcut triggered for webapp: {}", webapp_id);
 }
 
-/// 从配置中加载并注册所有快捷键
-pub fn load_shortcuts_from_config(app: &AppHandle, config: &AppConfig) -> Result<(), String> {
-    let manager = app
-        .try_state::<ShortcutManager>()
-        .ok_or("快捷键管理器未初始化")?;
+/// 小程序快捷键：使用当前配置切换对应的小程序窗口（与 WindowManager 共享代理、注入和 LRU 逻辑）
+fn toggle_webapp(app: &AppHandle, webapp_id: &str) {
+    let config = match app.try_state::<ConfigManager>() {
+        Some(config_manager) => config_manager.read(),
+        None => return,
+    };
+    let webapp = match config.webapps.iter().find(|w| w.id == webapp_id) {
+        Some(webapp) => webapp,
+        None => {
+            log::warn!("Shortcut triggered for unknown webapp: {}", webapp_id);
+            return;
+        }
+    };
+    if quiet_hours::blocks_shortcut(app, webapp) {
+        log::info!("Ignoring shortcut for {} during quiet hours", webapp_id);
+        return;
+    }
+    let window_manager = match app.try_state::<WindowManager>() {
+        Some(wm) => wm,
+        None => return,
+    };
 
-    // 清除现有快捷键
-    manager.clear_all(app)?;
+    match window_manager.toggle_webapp(app, webapp, config.proxy_url_for(webapp)) {
+        Ok(ToggleResult::Hidden) => accessibility::announce(app, &format!("已隐藏 {}", webapp.name)),
+        Ok(ToggleResult::ShownExisting) => {
+            accessibility::announce(app, &format!("已显示 {}", webapp.name));
+            // 快捷键呼出的窗口跟随鼠标所在位置
+            if let Some(window) = app.get_webview_window(&window_label(webapp_id)) {
+                placement::move_existing(app, &window, config.window_placement);
+            }
 
-    // 注册每个webapp的快捷键
-    for webapp in &config.webapps {
-        if let Some(shortcut) = &webapp.shortcut {
-            if !shortcut.is_empty() {
-                if let Err(e) = manager.register(app, shortcut, &webapp.id) {
-                    log::warn!("Failed to register shortcut for {}: {}", webapp.name, e);
-                }
+            // 显示已存在的窗口时注入快捷键脚本（新窗口由 inject_on_load 处理）
+            if let Err(e) = window_manager.inject_shortcut_script(app, webapp) {
+                log::warn!("Failed to inject shortcut script for {}: {}", webapp_id, e);
             }
         }
+        Ok(ToggleResult::CreatedNew) => accessibility::announce(app, &format!("已打开 {}", webapp.name)),
+        Err(e) => log::error!("Failed to toggle webapp {} via shortcut: {}", webapp_id, e),
     }
+}
 
-    // 注册主窗口快捷键
-    if let Some(main_shortcut) = &config.main_window_shortcut {
-        if !main_shortcut.is_empty() {
-            if let Err(e) = manager.register(app, main_shortcut, "__main__") {
-                log::warn!("Failed to register main window shortcut: {}", e);
+/// 上次按下后未超时则前进到下一个（到末尾后回到第一个），否则从第一个开始
+fn next_cycle_index(last: Option<(usize, Instant)>, now: Instant, len: usize) -> usize {
+    match last {
+        Some((index, at)) if len > 0 && now.duration_since(at) <= CYCLE_TIMEOUT => (index + 1) % len,
+        _ => 0,
+    }
+}
+
+/// 分组循环：首次按下切换第一个小程序，连续按下时依次显示分组中的下一个（按分组中的顺序）
+fn cycle_group(app: &AppHandle, group_id: &str) {
+    let config = app.state::<ConfigManager>().read();
+    let members = match config.groups.iter().find(|g| g.id == group_id) {
+        Some(group) => groups::members(&config, group),
+        None => Vec::new(),
+    };
+    if members.is_empty() {
+        log::warn!("Shortcut triggered for empty or unknown group: {}", group_id);
+        return;
+    }
+
+    let index = app
+        .state::<ShortcutManager>()
+        .advance_group_cycle(group_id, members.len());
+    let webapp = members[index];
+    if index == 0 {
+        toggle_webapp(app, &webapp.id);
+        return;
+    }
+
+    let window_manager = app.state::<WindowManager>();
+    if let Err(e) = window_manager.open_webapp(app, webapp, config.proxy_url_for(webapp)) {
+        log::warn!("Failed to open {} in group {}: {}", webapp.id, group_id, e);
+        return;
+    }
+    if let Some(window) = app.get_webview_window(&window_label(&webapp.id)) {
+        placement::move_existing(app, &window, config.window_placement);
+    }
+}
+
+/// 在后台运行自动化脚本
+fn run_automation(app: &AppHandle, automation_id: &str) {
+    let config = app.state::<ConfigManager>().read();
+    let automation = match config.automations.iter().find(|a| a.id == automation_id) {
+        Some(automation) => automation.clone(),
+        None => {
+            log::warn!("Shortcut triggered for unknown automation: {}", automation_id);
+            return;
+        }
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = scripting::run_script(app, automation.source).await {
+            log::error!("Automation {} failed: {}", automation.name, e);
+        }
+    });
+}
+
+/// 隐藏所有可见的小程序窗口
+fn hide_all(app: &AppHandle) {
+    for webapp_id in app.state::<WindowManager>().get_active_window_ids() {
+        if let Some(window) = app.get_webview_window(&window_label(&webapp_id)) {
+            if window.is_visible().unwrap_or(false) {
+                let _ = window.hide();
             }
         }
     }
+}
+
+/// 通知前端对当前聚焦的小程序窗口截图
+fn request_screenshot(app: &AppHandle) {
+    events::emit(
+        app,
+        Channel::Shortcuts,
+        "shortcut://screenshot",
+        serde_json::json!({ "webappId": focused_webapp_id(app) }),
+    );
+}
+
+/// 请求当前聚焦的小程序窗口上报选中的文本
+fn request_selection(app: &AppHandle, purpose: SelectionPurpose) {
+    let webapp_id = match focused_webapp_id(app) {
+        Some(id) => id,
+        None => return,
+    };
+    if let Err(e) = selection::request(app, &webapp_id, purpose) {
+        log::warn!("Failed to request selection from {}: {}", webapp_id, e);
+    }
+}
+
+/// 切换当前聚焦的小程序窗口的阅读模式
+fn toggle_reader_mode(app: &AppHandle) {
+    let webapp_id = match focused_webapp_id(app) {
+        Some(id) => id,
+        None => return,
+    };
+    if let Err(e) = reader::toggle(app, &webapp_id) {
+        log::warn!("Failed to toggle reader mode for {}: {}", webapp_id, e);
+    }
+}
+
+/// 在系统浏览器中打开指定的小程序，未指定时为当前聚焦的小程序窗口
+fn open_in_browser(app: &AppHandle, webapp_id: Option<&str>) {
+    let webapp_id = match webapp_id.map(str::to_string).or_else(|| focused_webapp_id(app)) {
+        Some(id) => id,
+        None => return,
+    };
+    if let Err(e) = browser::open_in_browser(app, &webapp_id) {
+        log::warn!("Failed to open {} in system browser: {}", webapp_id, e);
+    }
+}
+
+/// 配置中的所有快捷键绑定（按优先级排列，同一快捷键只有第一个生效）
+pub fn desired_bindings(config: &AppConfig) -> Vec<(String, ShortcutAction)> {
+    let mut desired = Vec::new();
+
+    // 每个已启用webapp的快捷键
+    for webapp in config.webapps.iter().filter(|w| w.enabled) {
+        if let Some(shortcut) = webapp.shortcut.as_deref().filter(|s| !s.is_empty()) {
+            desired.push((
+                shortcut.to_string(),
+                ShortcutAction::ToggleWebApp {
+                    webapp_id: webapp.id.clone(),
+                },
+            ));
+        }
+    }
+
+    // 分组快捷键
+    for group in &config.groups {
+        if let Some(shortcut) = group.shortcut.as_deref().filter(|s| !s.is_empty()) {
+            desired.push((
+                shortcut.to_string(),
+                ShortcutAction::OpenGroup {
+                    group_id: group.id.clone(),
+                },
+            ));
+        }
+    }
+
+    // 主窗口快捷键
+    if let Some(main_shortcut) = config.main_window_shortcut.as_deref().filter(|s| !s.is_empty()) {
+        desired.push((main_shortcut.to_string(), ShortcutAction::ToggleMainWindow));
+    }
+
+    // 快速切换快捷键
+    if let Some(switcher_shortcut) = config.switcher_shortcut.as_deref().filter(|s| !s.is_empty()) {
+        desired.push((switcher_shortcut.to_string(), ShortcutAction::ToggleSwitcher));
+    }
+
+    // 全部静音快捷键
+    if let Some(mute_shortcut) = config.mute_all_shortcut.as_deref().filter(|s| !s.is_empty()) {
+        desired.push((mute_shortcut.to_string(), ShortcutAction::MuteAll));
+    }
+
+    // 通用动作绑定
+    for binding in config
+        .shortcut_bindings
+        .iter()
+        .filter(|b| b.enabled && !b.shortcut.is_empty())
+    {
+        desired.push((binding.shortcut.clone(), binding.action.clone()));
+    }
+
+    desired
+}
+
+/// 从配置中加载并注册所有快捷键（增量更新，未变化的快捷键不会被短暂注销）
+pub fn load_shortcuts_from_config(app: &AppHandle, config: &AppConfig) -> AppResult<()> {
+    let manager = app
+        .try_state::<ShortcutManager>()
+        .ok_or("快捷键管理器未初始化")?;
+
+    // 安全模式下不注册全局快捷键（注销已注册的）
+    if safe_mode::is_active(app) {
+        log::info!("Safe mode is active, global shortcuts are not registered");
+        manager.sync(app, &[]);
+        return Ok(());
+    }
+    manager.sync(app, &desired_bindings(config));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockShortcuts;
+    use crate::models::{ShortcutBinding, WebApp};
+
+    #[test]
+    fn action_serializes_with_type_tag() {
+        let action = ShortcutAction::ToggleWebApp {
+            webapp_id: "abc".to_string(),
+        };
+        let json = serde_json::to_value(&action).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "toggleWebApp", "webappId": "abc" }));
+
+        let parsed: ShortcutAction = serde_json::from_value(serde_json::json!({ "type": "hideAll" })).unwrap();
+        assert_eq!(parsed, ShortcutAction::HideAll);
+    }
+
+    #[test]
+    fn desired_bindings_includes_legacy_fields_and_bindings() {
+        let mut config = AppConfig::default();
+        let mut webapp = WebApp::new("Test".to_string(), "https://example.com".to_string());
+        webapp.shortcut = Some("Ctrl+1".to_string());
+        let mut disabled = WebApp::new("Off".to_string(), "https://example.org".to_string());
+        disabled.shortcut = Some("Ctrl+2".to_string());
+        disabled.enabled = false;
+        config.webapps = vec![webapp.clone(), disabled];
+        config.main_window_shortcut = Some("Ctrl+M".to_string());
+        config.shortcut_bindings = vec![
+            ShortcutBinding {
+                shortcut: "Ctrl+H".to_string(),
+                action: ShortcutAction::HideAll,
+                enabled: true,
+            },
+            ShortcutBinding {
+                shortcut: "Ctrl+S".to_string(),
+                action: ShortcutAction::Screenshot,
+                enabled: false,
+            },
+        ];
+
+        let desired = desired_bindings(&config);
+        assert_eq!(
+            desired,
+            vec![
+                (
+                    "Ctrl+1".to_string(),
+                    ShortcutAction::ToggleWebApp { webapp_id: webapp.id }
+                ),
+                ("Ctrl+M".to_string(), ShortcutAction::ToggleMainWindow),
+                ("Ctrl+H".to_string(), ShortcutAction::HideAll),
+            ]
+        );
+    }
+
+    #[test]
+    fn cycle_index_advances_within_timeout() {
+        let start = Instant::now();
+        let soon = start + Duration::from_millis(500);
+        let late = start + CYCLE_TIMEOUT + Duration::from_millis(1);
+
+        assert_eq!(next_cycle_index(None, start, 3), 0);
+        assert_eq!(next_cycle_index(Some((0, start)), soon, 3), 1);
+        assert_eq!(next_cycle_index(Some((2, start)), soon, 3), 0);
+        assert_eq!(next_cycle_index(Some((1, start)), late, 3), 0);
+    }
+
+    #[test]
+    fn sync_only_touches_changed_bindings() {
+        let backend = MockShortcuts::default();
+        backend.taken.lock().insert("Ctrl+3".to_string());
+        let manager = ShortcutManager::new();
+        let toggle = |id: &str| ShortcutAction::ToggleWebApp { webapp_id: id.to_string() };
+
+        manager.sync(
+            &backend,
+            &[
+                ("Ctrl+1".to_string(), toggle("a")),
+                ("Ctrl+2".to_string(), toggle("b")),
+                ("Ctrl+3".to_string(), toggle("c")),
+            ],
+        );
+        assert_eq!(backend.registered.lock().len(), 2);
+        assert_eq!(backend.failures.lock().len(), 1);
+        assert_eq!(manager.get_failures()[0].shortcut, "Ctrl+3");
+
+        // Ctrl+2 改绑，Ctrl+3 移除后不再报告失败
+        manager.sync(
+            &backend,
+            &[("Ctrl+1".to_string(), toggle("a")), ("Ctrl+2".to_string(), toggle("c"))],
+        );
+        assert_eq!(backend.registered.lock().get("Ctrl+2"), Some(&toggle("c")));
+        assert_eq!(manager.get_action("Ctrl+1"), Some(toggle("a")));
+        assert!(manager.get_failures().is_empty());
+
+        assert!(matches!(
+            manager.register(&backend, "Ctrl+1", ShortcutAction::HideAll),
+            Err(AppError::ShortcutConflict(_))
+        ));
+        assert!(matches!(
+            manager.register(&backend, "not a shortcut", ShortcutAction::HideAll),
+            Err(AppError::InvalidShortcut(_))
+        ));
+        manager.clear_all(&backend).unwrap();
+        assert!(backend.registered.lock().is_empty());
+    }
+}
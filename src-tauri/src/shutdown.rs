@@ -0,0 +1,101 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, ExitRequestApi, Manager};
+
+use crate::config::ConfigManager;
+use crate::main_window;
+use crate::processes::ProcessManager;
+use crate::session;
+use crate::shortcuts::ShortcutManager;
+use crate::window::{window_label, WindowManager};
+
+/// 关闭脚本执行后等待的时间，之后再真正退出
+const CLOSE_SCRIPT_GRACE: Duration = Duration::from_millis(800);
+
+/// 退出流程状态
+pub struct ShutdownState {
+    /// 是否已经执行过退出清理
+    started: AtomicBool,
+}
+
+impl ShutdownState {
+    pub fn new() -> Self {
+        Self {
+            started: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Default for ShutdownState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 是否正在退出
+pub fn in_progress(app: &AppHandle) -> bool {
+    app.try_state::<ShutdownState>()
+        .map(|state| state.started.load(Ordering::SeqCst))
+        .unwrap_or(false)
+}
+
+/// 在所有打开的小程序窗口中执行关闭脚本，返回执行的脚本数
+fn run_close_scripts(app: &AppHandle) -> usize {
+    let config = app.state::<ConfigManager>().read();
+    let window_manager = app.state::<WindowManager>();
+    let mut count = 0;
+
+    for webapp in &config.webapps {
+        let script = match webapp.on_close_script.as_deref() {
+            Some(script) if !script.trim().is_empty() => script,
+            _ => continue,
+        };
+        if app.get_webview_window(&window_label(&webapp.id)).is_none() {
+            continue;
+        }
+
+        match window_manager.inject_script(app, &webapp.id, script) {
+            Ok(()) => count += 1,
+            Err(e) => log::warn!("Failed to run close script for {}: {}", webapp.id, e),
+        }
+    }
+
+    count
+}
+
+/// 处理退出请求：保存会话、执行关闭脚本、注销快捷键
+/// 有关闭脚本时先阻止退出，等待脚本执行后再次退出（第二次请求直接放行）
+pub fn handle_exit_requested(app: &AppHandle, code: Option<i32>, api: &ExitRequestApi) {
+    let state = app.state::<ShutdownState>();
+    if state.started.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    log::info!("Shutting down, cleaning up webapp windows");
+
+    // 窗口销毁前保存会话和窗口位置
+    session::save(app);
+    if let Some(manager) = app.try_state::<WindowManager>() {
+        manager.save_all_geometry(app);
+    }
+    main_window::save(app);
+
+    let scripts = run_close_scripts(app);
+
+    if let Some(manager) = app.try_state::<ShortcutManager>() {
+        let _ = manager.clear_all(app);
+    }
+
+    if let Some(processes) = app.try_state::<ProcessManager>() {
+        processes.stop_all();
+    }
+
+    if scripts > 0 {
+        api.prevent_exit();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(CLOSE_SCRIPT_GRACE).await;
+            app.exit(code.unwrap_or(0));
+        });
+    }
+}
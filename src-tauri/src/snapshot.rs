@@ -0,0 +1,247 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::maintenance::{self, FileEntry};
+use crate::models::AppConfig;
+
+/// 快照格式版本
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// 快照文件扩展名
+pub const SNAPSHOT_EXTENSION: &str = "whsnap";
+
+/// 快照包含的应用数据（相对于应用数据目录）：会话窗口布局、使用统计、页面位置、图标和证书
+const INCLUDED_FILES: &[&str] = &["session.json", "usage.json", "view_state.json"];
+const INCLUDED_DIRS: &[&str] = &["icons", "certs"];
+
+/// 单个文件大小上限，防止误把大文件打包进快照
+const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotFile {
+    /// 相对于应用数据目录的路径（使用 `/` 分隔）
+    path: String,
+    /// 文件内容（base64）
+    data: String,
+}
+
+/// 完整的应用状态快照（gzip 压缩的 JSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StateSnapshot {
+    version: u32,
+    app_version: String,
+    created_at: u64,
+    config: AppConfig,
+    #[serde(default)]
+    files: Vec<SnapshotFile>,
+}
+
+/// 快照结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSummary {
+    pub path: String,
+    pub webapps: usize,
+    pub files: usize,
+}
+
+/// 校验快照中的相对路径，拒绝绝对路径和 `..`
+fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let safe = !path.as_os_str().is_empty()
+        && path.components().all(|c| matches!(c, Component::Normal(_)));
+    safe.then(|| path.to_path_buf())
+}
+
+fn relative_name(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    Some(parts.join("/"))
+}
+
+fn collect(data_dir: &Path) -> Vec<SnapshotFile> {
+    let mut entries: Vec<FileEntry> = Vec::new();
+    for name in INCLUDED_DIRS {
+        maintenance::collect_files(&data_dir.join(name), &mut entries);
+    }
+    for name in INCLUDED_FILES {
+        let path = data_dir.join(name);
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            entries.push(FileEntry {
+                path,
+                size: metadata.len(),
+                modified: 0,
+            });
+        }
+    }
+
+    entries
+        .into_iter()
+        .filter(|entry| {
+            if entry.size > MAX_FILE_SIZE {
+                log::warn!("Skipping large file in snapshot: {}", entry.path.display());
+                return false;
+            }
+            true
+        })
+        .filter_map(|entry| {
+            let data = std::fs::read(&entry.path).ok()?;
+            Some(SnapshotFile {
+                path: relative_name(data_dir, &entry.path)?,
+                data: STANDARD.encode(data),
+            })
+        })
+        .collect()
+}
+
+fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+/// 默认的快照位置：备份目录（已设置时）或应用数据目录下的 snapshots
+fn default_path(app: &AppHandle, config: &AppConfig) -> Result<PathBuf, String> {
+    let dir = match config.backup.directory.as_deref().filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => data_dir(app)?.join("snapshots"),
+    };
+    Ok(dir.join(format!(
+        "webapp-hub-snapshot-{}.{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        SNAPSHOT_EXTENSION
+    )))
+}
+
+fn encode(snapshot: &StateSnapshot) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(snapshot).map_err(|e| e.to_string())?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&json).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+fn decode(bytes: &[u8]) -> Result<StateSnapshot, String> {
+    let mut json = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut json)
+        .map_err(|_| "不是有效的快照文件".to_string())?;
+    let snapshot: StateSnapshot =
+        serde_json::from_slice(&json).map_err(|e| format!("快照内容无效: {}", e))?;
+    if snapshot.version > SNAPSHOT_VERSION {
+        return Err("快照由更新版本的 WebApp Hub 创建，请先升级".to_string());
+    }
+    Ok(snapshot)
+}
+
+/// 保存完整的应用状态快照（配置、图标、证书、使用统计和窗口布局），
+/// path 为空时保存到默认位置
+pub fn snapshot(app: &AppHandle, path: Option<PathBuf>) -> Result<SnapshotSummary, String> {
+    let config = app.state::<ConfigManager>().read();
+    let path = match path {
+        Some(path) => path,
+        None => default_path(app, &config)?,
+    };
+    let files = collect(&data_dir(app)?);
+    let summary = SnapshotSummary {
+        path: path.display().to_string(),
+        webapps: config.webapps.len(),
+        files: files.len(),
+    };
+
+    let snapshot = StateSnapshot {
+        version: SNAPSHOT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        config,
+        files,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("无法创建快照目录: {}", e))?;
+    }
+    std::fs::write(&path, encode(&snapshot)?).map_err(|e| format!("写入快照失败: {}", e))?;
+
+    log::info!("State snapshot saved to {} ({} files)", path.display(), summary.files);
+    Ok(summary)
+}
+
+/// 从快照恢复：写回数据文件并替换当前配置，返回恢复后的配置
+/// （会话、使用统计等在下次启动时生效）
+pub fn restore(app: &AppHandle, path: &Path) -> AppResult<AppConfig> {
+    let bytes = std::fs::read(path).map_err(|e| format!("无法读取快照: {}", e))?;
+    let snapshot = decode(&bytes)?;
+    let data_dir = data_dir(app)?;
+
+    // 先全部解码校验，避免恢复到一半失败
+    let mut files = Vec::new();
+    for file in &snapshot.files {
+        let relative = safe_relative_path(&file.path).ok_or_else(|| format!("快照中的路径无效: {}", file.path))?;
+        let data = STANDARD
+            .decode(&file.data)
+            .map_err(|_| format!("快照中的文件已损坏: {}", file.path))?;
+        files.push((data_dir.join(relative), data));
+    }
+
+    for (target, data) in &files {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(target, data).map_err(|e| format!("写入 {} 失败: {}", target.display(), e))?;
+    }
+    app.state::<ConfigManager>().replace(snapshot.config)?;
+
+    log::info!(
+        "Restored state snapshot from {} (created by {}, {} files)",
+        path.display(),
+        snapshot.app_version,
+        files.len()
+    );
+    Ok(app.state::<ConfigManager>().read())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert_eq!(safe_relative_path("icons/mail.png"), Some(PathBuf::from("icons/mail.png")));
+        assert!(safe_relative_path("../config.json").is_none());
+        assert!(safe_relative_path("/etc/passwd").is_none());
+        assert!(safe_relative_path("icons/../../x").is_none());
+        assert!(safe_relative_path("").is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let snapshot = StateSnapshot {
+            version: SNAPSHOT_VERSION,
+            app_version: "1.0.0".to_string(),
+            created_at: 1,
+            config: AppConfig::default(),
+            files: vec![SnapshotFile {
+                path: "session.json".to_string(),
+                data: STANDARD.encode(b"{}"),
+            }],
+        };
+
+        let decoded = decode(&encode(&snapshot).unwrap()).unwrap();
+        assert_eq!(decoded.files.len(), 1);
+        assert_eq!(decoded.files[0].path, "session.json");
+        assert!(decode(b"plain text").is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use parking_lot::Mutex;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use tauri::{AppHandle, Manager};
+
+/// 单次朗读的最大字符数，避免误选整页内容后长时间朗读
+const MAX_CHARS: usize = 20_000;
+
+/// 当前的朗读进程（新的朗读会打断上一次）
+pub struct SpeechState {
+    current: Mutex<Option<Child>>,
+}
+
+impl SpeechState {
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for SpeechState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 系统朗读命令候选（按顺序尝试），文本通过标准输入传入以避免转义问题
+fn speech_commands() -> Vec<Command> {
+    #[cfg(target_os = "macos")]
+    {
+        vec![Command::new("say")]
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = Command::new("powershell");
+        cmd.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+             $s.Speak([Console]::In.ReadToEnd())",
+        ]);
+        vec![cmd]
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let mut spd = Command::new("spd-say");
+        spd.args(["--wait", "--pipe-mode"]);
+        let mut espeak = Command::new("espeak");
+        espeak.arg("--stdin");
+        vec![spd, espeak]
+    }
+}
+
+fn truncate(text: &str) -> &str {
+    match text.char_indices().nth(MAX_CHARS) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+/// 用系统语音朗读文本
+pub fn speak(app: &AppHandle, text: &str) -> Result<(), String> {
+    stop(app);
+
+    let text = truncate(text);
+    let mut last_error = String::from("没有可用的系统朗读程序");
+
+    for mut command in speech_commands() {
+        let spawned = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                last_error = format!("无法启动朗读程序: {}", e);
+                continue;
+            }
+        };
+
+        // 写完后关闭标准输入，朗读程序才会开始读
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Err(e) = stdin.write_all(text.as_bytes()) {
+                log::warn!("Failed to send text to speech engine: {}", e);
+            }
+        }
+
+        log::info!("Speaking {} characters", text.chars().count());
+        if let Some(state) = app.try_state::<SpeechState>() {
+            *state.current.lock() = Some(child);
+        }
+        return Ok(());
+    }
+
+    Err(last_error)
+}
+
+/// 停止当前的朗读
+pub fn stop(app: &AppHandle) {
+    let child = app
+        .try_state::<SpeechState>()
+        .and_then(|state| state.current.lock().take());
+
+    if let Some(mut child) = child {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_on_char_boundary() {
+        let long: String = "字".repeat(MAX_CHARS + 10);
+        assert_eq!(truncate(&long).chars().count(), MAX_CHARS);
+        assert_eq!(truncate("hello"), "hello");
+    }
+}
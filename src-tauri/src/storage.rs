@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::maintenance::{collect_files, FileEntry};
+use crate::profiles;
+
+/// WebView 数据的分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Cache,
+    Cookies,
+    /// localStorage、IndexedDB 等网站存储
+    LocalStorage,
+    Other,
+}
+
+/// 一个 WebView 配置文件的磁盘占用
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileUsage {
+    /// 使用该配置文件的小程序
+    pub webapp_ids: Vec<String>,
+    /// 配置文件所在的目录
+    pub paths: Vec<String>,
+    pub cache_bytes: u64,
+    pub cookie_bytes: u64,
+    pub local_storage_bytes: u64,
+    pub other_bytes: u64,
+    pub total_bytes: u64,
+}
+
+impl ProfileUsage {
+    fn add(&mut self, category: Category, size: u64) {
+        match category {
+            Category::Cache => self.cache_bytes += size,
+            Category::Cookies => self.cookie_bytes += size,
+            Category::LocalStorage => self.local_storage_bytes += size,
+            Category::Other => self.other_bytes += size,
+        }
+        self.total_bytes += size;
+    }
+}
+
+/// 按 WebView 引擎的目录和文件名判断数据类型（路径相对于配置文件目录）
+fn categorize(relative: &Path) -> Category {
+    let file_name = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    if file_name.starts_with("cookies") {
+        return Category::Cookies;
+    }
+
+    let components: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_ascii_lowercase())
+        .collect();
+    if components.iter().any(|c| c.contains("cache")) {
+        Category::Cache
+    } else if components
+        .iter()
+        .any(|c| c.contains("storage") || c == "indexeddb" || c == "databases")
+    {
+        Category::LocalStorage
+    } else {
+        Category::Other
+    }
+}
+
+/// 配置文件中的文件是否属于缓存（路径相对于配置文件目录）
+pub(crate) fn is_cache_file(relative: &Path) -> bool {
+    categorize(relative) == Category::Cache
+}
+
+/// 共享 WebView 配置文件的数据目录
+fn shared_profile_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = [app.path().app_local_data_dir(), app.path().app_cache_dir()]
+        .into_iter()
+        .filter_map(|d| d.ok())
+        .collect();
+
+    // WKWebView 的网站数据保存在 ~/Library/WebKit/<identifier>
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(home) = std::env::var_os("HOME") {
+            dirs.push(
+                PathBuf::from(home)
+                    .join("Library/WebKit")
+                    .join(&app.config().identifier),
+            );
+        }
+    }
+
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+fn measure(dirs: &[PathBuf], exclude: Option<&Path>) -> ProfileUsage {
+    let mut usage = ProfileUsage {
+        paths: dirs.iter().map(|d| d.display().to_string()).collect(),
+        ..ProfileUsage::default()
+    };
+
+    for dir in dirs {
+        let mut files: Vec<FileEntry> = Vec::new();
+        collect_files(dir, &mut files);
+        for file in files {
+            if exclude.is_some_and(|excluded| file.path.starts_with(excluded)) {
+                continue;
+            }
+            let relative = file.path.strip_prefix(dir).unwrap_or(&file.path);
+            usage.add(categorize(relative), file.size);
+        }
+    }
+    usage
+}
+
+/// 统计每个 WebView 配置文件的磁盘占用（会阻塞，需在后台线程调用）
+/// 第一项为共享配置文件，其后为各个独立配置文件
+pub fn usage(app: &AppHandle) -> Vec<ProfileUsage> {
+    let config = app.state::<ConfigManager>().read();
+    let mut shared_ids = Vec::new();
+    let mut isolated: Vec<(PathBuf, Vec<String>)> = Vec::new();
+    for webapp in &config.webapps {
+        match profiles::data_directory(app, webapp) {
+            Some(dir) => match isolated.iter_mut().find(|(d, _)| *d == dir) {
+                Some((_, ids)) => ids.push(webapp.id.clone()),
+                None => isolated.push((dir, vec![webapp.id.clone()])),
+            },
+            None => shared_ids.push(webapp.id.clone()),
+        }
+    }
+
+    // 独立配置文件在共享数据目录之下，统计共享配置文件时排除
+    let root = profiles::profiles_root(app);
+    let mut shared = measure(&shared_profile_dirs(app), Some(root.as_path()));
+    shared.webapp_ids = shared_ids;
+
+    let mut result = vec![shared];
+    for (dir, webapp_ids) in isolated {
+        let mut usage = measure(&[dir], None);
+        usage.webapp_ids = webapp_ids;
+        result.push(usage);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categorize() {
+        assert_eq!(categorize(Path::new("EBWebView/Default/Network/Cookies")), Category::Cookies);
+        assert_eq!(categorize(Path::new("cookies.sqlite")), Category::Cookies);
+        assert_eq!(categorize(Path::new("EBWebView/Default/Code Cache/js/index")), Category::Cache);
+        assert_eq!(categorize(Path::new("WebKitCache/Version 16/Records/abc")), Category::Cache);
+        assert_eq!(
+            categorize(Path::new("EBWebView/Default/Local Storage/leveldb/000003.log")),
+            Category::LocalStorage
+        );
+        assert_eq!(categorize(Path::new("indexeddb/v1/example.sqlite3")), Category::LocalStorage);
+        assert_eq!(categorize(Path::new("config.json")), Category::Other);
+    }
+}
@@ -0,0 +1,196 @@
+use serde::Serialize;
+use std::cmp::Reverse;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::badges;
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::icons;
+use crate::models::{AppConfig, WebApp};
+use crate::window::{ToggleResult, WindowManager};
+
+/// 快速切换窗口的标签（不是小程序窗口，不受活跃窗口数量限制）
+pub const SWITCHER_LABEL: &str = "switcher";
+
+/// 快速切换窗口的大小
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 400.0;
+
+/// 最多返回的搜索结果数
+const MAX_RESULTS: usize = 20;
+
+/// 快速切换的一条搜索结果，图标为引用，由 `get_webapp_icon` 按需加载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitcherResult {
+    pub webapp_id: String,
+    pub name: String,
+    pub url: String,
+    pub icon: Option<String>,
+    pub badge: Option<u32>,
+    pub running: bool,
+}
+
+/// 模糊匹配得分：查询中的字符按顺序出现在文本中即匹配（不区分大小写、忽略空白），
+/// 连续的字符和单词开头的字符得分更高；不匹配时返回 None
+fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query {
+        let index = (next..text.len()).find(|i| text[*i] == wanted)?;
+        score += 1;
+        if index > 0 && previous == Some(index - 1) {
+            score += 5;
+        }
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(index);
+        next = index + 1;
+    }
+    Some(score)
+}
+
+/// 网址去掉协议和 `www.` 前缀后参与匹配
+fn match_url(url: &str) -> &str {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    url.strip_prefix("www.").unwrap_or(url)
+}
+
+/// 按名称和网址模糊搜索启用的小程序：名称匹配优先，得分相同时保持小程序的顺序；
+/// 查询为空时按顺序返回所有小程序
+fn rank<'a>(config: &'a AppConfig, query: &str) -> Vec<&'a WebApp> {
+    let mut webapps: Vec<&WebApp> = config.webapps.iter().filter(|w| w.enabled).collect();
+    webapps.sort_by_key(|w| w.order);
+
+    let mut scored: Vec<(u32, &WebApp)> = webapps
+        .into_iter()
+        .filter_map(|w| {
+            let name = fuzzy_score(query, &w.name).map(|score| score * 2);
+            let url = fuzzy_score(query, match_url(&w.url));
+            name.max(url).map(|score| (score, w))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored.into_iter().take(MAX_RESULTS).map(|(_, w)| w).collect()
+}
+
+/// 搜索快速切换的结果
+pub fn search(app: &AppHandle, query: &str) -> Vec<SwitcherResult> {
+    let config = app.state::<ConfigManager>().read();
+    let window_manager = app.state::<WindowManager>();
+    rank(&config, query)
+        .into_iter()
+        .map(|w| SwitcherResult {
+            webapp_id: w.id.clone(),
+            name: w.name.clone(),
+            url: w.url.clone(),
+            icon: icons::reference(w),
+            badge: badges::count_for(app, &w.id),
+            running: window_manager.is_window_active(&w.id),
+        })
+        .collect()
+}
+
+/// 隐藏快速切换窗口
+pub fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(SWITCHER_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// 快捷键呼出或隐藏快速切换窗口（首次呼出时创建，之后只显示和隐藏），失去焦点时自动隐藏
+pub fn toggle(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(SWITCHER_LABEL) {
+        if window.is_visible().unwrap_or(false) {
+            return window.hide().map_err(|e| e.to_string());
+        }
+        window.center().map_err(|e| e.to_string())?;
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        // 页面据此清空上次的查询
+        let _ = app.emit_to(SWITCHER_LABEL, "switcher://shown", ());
+        return Ok(());
+    }
+
+    let window = WebviewWindowBuilder::new(app, SWITCHER_LABEL, WebviewUrl::App("index.html#/switcher".into()))
+        .title("快速切换")
+        .inner_size(WIDTH, HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .focused(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let window_clone = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(false) = event {
+            let _ = window_clone.hide();
+        }
+    });
+
+    log::info!("Opened quick switcher window");
+    Ok(())
+}
+
+/// 打开搜索结果中的小程序：先隐藏快速切换窗口，再切换小程序窗口（与快捷键共享代理、注入和 LRU 逻辑）
+pub fn activate(app: &AppHandle, webapp_id: &str) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = config
+        .webapps
+        .iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or("小程序不存在")?;
+    hide(app);
+
+    let window_manager = app.state::<WindowManager>();
+    let result = window_manager.toggle_webapp(app, webapp, config.proxy_url_for(webapp))?;
+    if result == ToggleResult::ShownExisting {
+        window_manager.inject_shortcut_script(app, webapp)?;
+    }
+    log::info!("Activated webapp {} from quick switcher", webapp_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_search() {
+        assert!(fuzzy_score("gml", "Gmail").is_some());
+        assert_eq!(fuzzy_score("mg", "Gmail"), None);
+        assert!(fuzzy_score("mail", "Gmail") > fuzzy_score("mail", "My Awesome Inbox Log"));
+        assert!(fuzzy_score("gc", "Google Calendar") > fuzzy_score("gc", "Magic"));
+        assert_eq!(match_url("https://www.notion.so/team"), "notion.so/team");
+
+        let mut config = AppConfig {
+            webapps: vec![
+                WebApp::new("Notion".to_string(), "https://www.notion.so".to_string()),
+                WebApp::new("Gmail".to_string(), "https://mail.google.com".to_string()),
+                WebApp::new("Calendar".to_string(), "https://calendar.google.com".to_string()),
+            ],
+            ..Default::default()
+        };
+        for (i, webapp) in config.webapps.iter_mut().enumerate() {
+            webapp.order = i as u32;
+        }
+        config.webapps[0].enabled = false;
+
+        let names = |query: &str| -> Vec<String> { rank(&config, query).iter().map(|w| w.name.clone()).collect() };
+        assert_eq!(names(""), ["Gmail", "Calendar"]);
+        assert_eq!(names("google"), ["Gmail", "Calendar"]);
+        assert_eq!(names("cal"), ["Calendar"]);
+        assert!(names("notion").is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+/// 渲染网址模板：将 `{name}` 替换为 URL 编码后的值，未知的占位符保持原样
+pub fn render_url(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (end, utf8_percent_encode(value, NON_ALPHANUMERIC).to_string()))
+        });
+
+        match value {
+            Some((end, encoded)) => {
+                result.push_str(&encoded);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_url_encodes_values() {
+        assert_eq!(
+            render_url(
+                "https://translate.example.com/?tl={lang}&text={text}",
+                &[("text", "你好 world&more"), ("lang", "en")]
+            ),
+            "https://translate.example.com/?tl=en&text=%E4%BD%A0%E5%A5%BD%20world%26more"
+        );
+    }
+
+    #[test]
+    fn test_render_url_keeps_unknown_placeholders() {
+        assert_eq!(render_url("a{b}c{", &[("x", "1")]), "a{b}c{");
+    }
+}
@@ -0,0 +1,134 @@
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager, Theme, WebviewWindow};
+
+use crate::config::ConfigManager;
+use crate::events::{self, Channel};
+use crate::models::WebApp;
+use crate::window::window_label;
+
+/// 注入的深色样式 <style> 元素 ID（与 `css` 模块的样式分开，切换外观时只替换这一部分）
+const STYLE_ELEMENT_ID: &str = "__webapp_hub_theme_css__";
+
+/// 强制深色：整页反色，再把图片和视频反色回来
+const FORCE_DARK_CSS: &str = "html { filter: invert(1) hue-rotate(180deg) !important; background: #fff !important; }\n\
+img, video, picture, canvas, iframe, [style*=\"background-image\"] { filter: invert(1) hue-rotate(180deg) !important; }";
+
+/// 最近一次收到的系统外观（每个窗口都会收到外观变化事件，据此只处理一次）
+static LAST_DARK: Mutex<Option<bool>> = parking_lot::const_mutex(None);
+
+/// 当前系统是否为深色外观（还没有收到外观变化事件时读取主窗口的外观）
+pub fn is_dark(app: &AppHandle) -> bool {
+    if let Some(dark) = *LAST_DARK.lock() {
+        return dark;
+    }
+    app.get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+        .is_some_and(|theme| theme == Theme::Dark)
+}
+
+/// 小程序在指定外观下的深色样式，浅色外观时为空
+fn theme_css(webapp: &WebApp, dark: bool) -> String {
+    if !dark {
+        return String::new();
+    }
+    let parts: Vec<&str> = [
+        webapp.force_dark.then_some(FORCE_DARK_CSS),
+        webapp.dark_css.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|css| !css.trim().is_empty())
+    .collect();
+    parts.join("\n")
+}
+
+/// 应用外观的脚本：替换深色样式，在 `<html>` 上标记 `data-webapp-hub-theme`，
+/// 并触发 `webapphub:themechange` 事件（用户脚本可以据此切换页面的主题）
+///
+/// 脚本可重复执行，外观没有变化时不触发事件
+pub fn theme_script(webapp: &WebApp, dark: bool) -> String {
+    let css_literal = serde_json::to_string(&theme_css(webapp, dark)).unwrap_or_else(|_| "\"\"".to_string());
+    format!(
+        r#"(function() {{
+    var css = {css};
+    var theme = '{theme}';
+    function apply() {{
+        var style = document.getElementById('{id}');
+        if (!style) {{
+            style = document.createElement('style');
+            style.id = '{id}';
+            (document.head || document.documentElement).appendChild(style);
+        }}
+        style.textContent = css;
+        var root = document.documentElement;
+        if (root.dataset.webappHubTheme === theme) return;
+        root.dataset.webappHubTheme = theme;
+        window.dispatchEvent(new CustomEvent('webapphub:themechange', {{ detail: {{ theme: theme }} }}));
+    }}
+    if (document.documentElement) {{
+        apply();
+    }} else {{
+        document.addEventListener('DOMContentLoaded', apply);
+    }}
+}})();"#,
+        css = css_literal,
+        theme = if dark { "dark" } else { "light" },
+        id = STYLE_ELEMENT_ID
+    )
+}
+
+fn apply(window: &WebviewWindow, webapp: &WebApp, dark: bool) {
+    if let Err(e) = window.eval(theme_script(webapp, dark)) {
+        log::debug!("Could not apply theme for webapp {}: {}", webapp.id, e);
+    }
+}
+
+/// 页面加载完成后重新应用当前外观（初始化脚本使用的是创建窗口时的外观）
+pub fn apply_after_load(app: &AppHandle, window: &WebviewWindow, webapp_id: &str) {
+    let config = app.state::<ConfigManager>().read();
+    if let Some(webapp) = config.webapps.iter().find(|w| w.id == webapp_id) {
+        apply(window, webapp, is_dark(app));
+    }
+}
+
+/// 小程序的深色设置修改后应用到已打开的窗口
+pub fn refresh_window(app: &AppHandle, webapp: &WebApp) {
+    if let Some(window) = app.get_webview_window(&window_label(&webapp.id)) {
+        apply(&window, webapp, is_dark(app));
+    }
+}
+
+/// 系统外观变化（`WindowEvent::ThemeChanged`）：把新的外观应用到所有已打开的小程序窗口
+pub fn handle_theme_changed(app: &AppHandle, theme: Theme) {
+    let dark = theme == Theme::Dark;
+    if LAST_DARK.lock().replace(dark) == Some(dark) {
+        return;
+    }
+    let config = app.state::<ConfigManager>().read();
+    for webapp in &config.webapps {
+        if let Some(window) = app.get_webview_window(&window_label(&webapp.id)) {
+            apply(&window, webapp, dark);
+        }
+    }
+    log::info!("System appearance changed to {}", if dark { "dark" } else { "light" });
+    events::emit(app, Channel::Config, "theme://changed", serde_json::json!({ "dark": dark }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_css_only_in_dark() {
+        let mut webapp = WebApp::new("Docs".to_string(), "https://docs.example.com".to_string());
+        webapp.dark_css = Some("body { background: #111; }".to_string());
+        assert_eq!(theme_css(&webapp, false), "");
+        assert_eq!(theme_css(&webapp, true), "body { background: #111; }");
+
+        webapp.force_dark = true;
+        let css = theme_css(&webapp, true);
+        assert!(css.starts_with(FORCE_DARK_CSS));
+        assert!(css.ends_with("body { background: #111; }"));
+        assert!(theme_script(&webapp, false).contains("var theme = 'light'"));
+    }
+}
@@ -0,0 +1,179 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::models::NetworkProfile;
+use crate::window::window_label;
+
+/// 自定义延迟的上限
+const MAX_LATENCY_MS: u32 = 60_000;
+
+/// 网络模拟脚本：包装 fetch 和 XMLHttpRequest，离线时请求直接失败，限速时延迟发出请求，
+/// fetch 的响应带有 Content-Length 时再按下载速度延迟返回（不限制流式读取的速度）。
+/// 重复执行时只更新网络条件，用于在已打开的页面上实时切换
+const THROTTLE_SCRIPT: &str = r#"(function(conditions) {
+    var state = window.__WEBAPP_HUB_NETWORK__;
+    if (state) {
+        state.set(conditions);
+        return;
+    }
+    if (!conditions.offline && !conditions.latencyMs && !conditions.downloadKbps) return;
+
+    state = window.__WEBAPP_HUB_NETWORK__ = { conditions: { offline: false, latencyMs: 0, downloadKbps: null } };
+    state.set = function(next) {
+        var wasOffline = state.conditions.offline;
+        state.conditions = next;
+        if (wasOffline !== next.offline) {
+            window.dispatchEvent(new Event(next.offline ? 'offline' : 'online'));
+        }
+    };
+
+    var onLine = Object.getOwnPropertyDescriptor(Navigator.prototype, 'onLine');
+    Object.defineProperty(navigator, 'onLine', {
+        configurable: true,
+        get: function() {
+            return !state.conditions.offline && (!onLine || onLine.get.call(navigator));
+        }
+    });
+
+    function delay(ms) {
+        return new Promise(function(resolve) { setTimeout(resolve, ms); });
+    }
+
+    function transferMs(bytes) {
+        var kbps = state.conditions.downloadKbps;
+        return kbps && bytes ? Math.round(bytes * 8 / kbps) : 0;
+    }
+
+    if (window.fetch) {
+        var nativeFetch = window.fetch;
+        window.fetch = function() {
+            var self = this;
+            var args = arguments;
+            var c = state.conditions;
+            if (c.offline) return Promise.reject(new TypeError('Failed to fetch'));
+            if (!c.latencyMs && !c.downloadKbps) return nativeFetch.apply(self, args);
+            return delay(c.latencyMs).then(function() {
+                return nativeFetch.apply(self, args);
+            }).then(function(response) {
+                var length = Number(response.headers.get('content-length')) || 0;
+                return delay(transferMs(length)).then(function() { return response; });
+            });
+        };
+    }
+
+    var nativeOpen = XMLHttpRequest.prototype.open;
+    var nativeSend = XMLHttpRequest.prototype.send;
+    XMLHttpRequest.prototype.open = function(method, url, async) {
+        this.__hubAsync = arguments.length < 3 || !!async;
+        return nativeOpen.apply(this, arguments);
+    };
+    XMLHttpRequest.prototype.send = function() {
+        var xhr = this;
+        var args = arguments;
+        var c = state.conditions;
+        if (c.offline) {
+            setTimeout(function() {
+                xhr.dispatchEvent(new ProgressEvent('error'));
+                xhr.dispatchEvent(new ProgressEvent('loadend'));
+            }, 0);
+            return;
+        }
+        // 同步请求无法延迟
+        if (!c.latencyMs || xhr.__hubAsync === false) return nativeSend.apply(xhr, args);
+        setTimeout(function() { nativeSend.apply(xhr, args); }, c.latencyMs);
+    };
+})(__CONDITIONS__);"#;
+
+/// 传给脚本的网络条件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Conditions {
+    offline: bool,
+    latency_ms: u32,
+    download_kbps: Option<u32>,
+}
+
+fn conditions(profile: Option<&NetworkProfile>) -> Conditions {
+    match profile {
+        None => Conditions::default(),
+        Some(NetworkProfile::Offline) => Conditions {
+            offline: true,
+            ..Default::default()
+        },
+        Some(NetworkProfile::Slow3g) => Conditions {
+            latency_ms: 2000,
+            download_kbps: Some(400),
+            ..Default::default()
+        },
+        Some(NetworkProfile::Fast3g) => Conditions {
+            latency_ms: 560,
+            download_kbps: Some(1600),
+            ..Default::default()
+        },
+        Some(NetworkProfile::Custom {
+            latency_ms,
+            download_kbps,
+        }) => Conditions {
+            latency_ms: (*latency_ms).min(MAX_LATENCY_MS),
+            download_kbps: download_kbps.filter(|kbps| *kbps > 0),
+            ..Default::default()
+        },
+    }
+}
+
+/// 生成网络模拟脚本（作为 initialization_script 注入，或在已打开的页面上执行以切换条件）
+pub fn throttle_script(profile: Option<&NetworkProfile>) -> String {
+    let conditions = serde_json::to_string(&conditions(profile)).unwrap_or_else(|_| "{}".to_string());
+    THROTTLE_SCRIPT.replace("__CONDITIONS__", &conditions)
+}
+
+/// 设置小程序模拟的网络条件（None 为不限制），并应用到已打开的窗口
+///
+/// 窗口打开时的条件作为初始化脚本注入；在已打开的窗口上切换后，之后的导航仍使用打开窗口时的条件，
+/// 重新打开窗口后完全生效
+pub fn set_conditions(app: &AppHandle, webapp_id: &str, profile: Option<NetworkProfile>) -> AppResult<()> {
+    let script = throttle_script(profile.as_ref());
+    app.state::<ConfigManager>()
+        .update(|config| {
+            let webapp = config.webapps.iter_mut().find(|w| w.id == webapp_id)?;
+            webapp.network_profile = profile.clone();
+            Some(())
+        })?
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+
+    if let Some(window) = app.get_webview_window(&window_label(webapp_id)) {
+        window.eval(&script).map_err(AppError::window)?;
+    }
+    log::info!("Set network conditions of {} to {:?}", webapp_id, profile);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_conditions() {
+        assert_eq!(conditions(None), Conditions::default());
+        assert!(conditions(Some(&NetworkProfile::Offline)).offline);
+        let custom = NetworkProfile::Custom {
+            latency_ms: 120_000,
+            download_kbps: Some(0),
+        };
+        assert_eq!(
+            conditions(Some(&custom)),
+            Conditions {
+                offline: false,
+                latency_ms: MAX_LATENCY_MS,
+                download_kbps: None,
+            }
+        );
+
+        let parsed: NetworkProfile =
+            serde_json::from_value(serde_json::json!({ "type": "custom", "latencyMs": 300 })).unwrap();
+        assert!(throttle_script(Some(&parsed)).contains(r#""latencyMs":300"#));
+        assert!(!throttle_script(None).contains("__CONDITIONS__"));
+    }
+}
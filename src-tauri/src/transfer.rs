@@ -0,0 +1,224 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::migrate;
+use crate::error::AppResult;
+use crate::models::{AppConfig, ChangePreview, ShortcutAction, WebApp};
+use crate::proxy::ProxyManager;
+use crate::redact;
+use crate::secrets;
+use crate::shortcuts::desired_bindings;
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    /// 导入的小程序数
+    pub webapps: usize,
+    /// 因 ID 重复而重新生成 ID 的小程序数
+    pub renamed_ids: usize,
+    /// 导入带来的变更（合并导入时为空）
+    pub changes: ChangePreview,
+}
+
+/// 为与 taken 中或前面的小程序 ID 重复的小程序生成新 ID，返回 旧 ID -> 新 ID（只记录第一次重命名）
+fn dedupe_ids(webapps: &mut [WebApp], taken: &HashSet<String>) -> HashMap<String, String> {
+    let mut seen = taken.clone();
+    let mut renamed = HashMap::new();
+    for webapp in webapps {
+        if webapp.id.is_empty() || !seen.insert(webapp.id.clone()) {
+            let new_id = Uuid::new_v4().to_string();
+            renamed.entry(webapp.id.clone()).or_insert_with(|| new_id.clone());
+            webapp.id = new_id.clone();
+            seen.insert(new_id);
+        }
+    }
+    renamed
+}
+
+/// 导出完整配置到文件（锁定设置和代理密码等凭据不导出）
+pub fn export(config: &AppConfig, path: &Path) -> Result<(), String> {
+    let mut config = config.clone();
+    redact::credentials(&mut config);
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| format!("无法写入导出文件: {}", e))
+}
+
+/// 读取导出的配置文件并校验
+fn read(path: &Path) -> AppResult<AppConfig> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("无法读取配置文件: {}", e))?;
+    let mut config = migrate::parse(&content)?.config;
+    // 本机的备份中代理密码是钥匙串引用
+    secrets::resolve_proxy_passwords(&mut config);
+    ProxyManager::validate_config(&config.proxy)?;
+    for webapp in &config.webapps {
+        url::Url::parse(&webapp.url).map_err(|e| format!("小程序 {} 的网址无效: {}", webapp.name, e))?;
+    }
+    Ok(config)
+}
+
+/// 合并导入：把文件中的小程序及其快捷键绑定追加到当前配置，重复的 ID 重新生成
+fn merge(current: &mut AppConfig, mut imported: AppConfig) -> ImportSummary {
+    let taken: HashSet<String> = current.webapps.iter().map(|w| w.id.clone()).collect();
+    let renamed = dedupe_ids(&mut imported.webapps, &taken);
+    let imported_ids: HashSet<String> = imported.webapps.iter().map(|w| w.id.clone()).collect();
+
+    let next_order = current.webapps.iter().map(|w| w.order + 1).max().unwrap_or(0);
+    let count = imported.webapps.len();
+    for (i, mut webapp) in imported.webapps.into_iter().enumerate() {
+        webapp.order = next_order + i as u32;
+        current.webapps.push(webapp);
+    }
+
+    // 导入小程序引用、当前配置中还没有的脚本
+    let referenced: HashSet<String> = current
+        .webapps
+        .iter()
+        .filter(|w| imported_ids.contains(&w.id))
+        .flat_map(|w| w.script_ids.iter().cloned())
+        .collect();
+    for script in imported.scripts {
+        if referenced.contains(&script.id) && !current.scripts.iter().any(|s| s.id == script.id) {
+            current.scripts.push(script);
+        }
+    }
+
+    for mut binding in imported.shortcut_bindings {
+        if let ShortcutAction::ToggleWebApp { webapp_id } = &mut binding.action {
+            if let Some(new_id) = renamed.get(webapp_id.as_str()) {
+                *webapp_id = new_id.clone();
+            }
+            if !imported_ids.contains(webapp_id.as_str()) {
+                continue;
+            }
+        } else {
+            continue;
+        }
+        if !current.shortcut_bindings.iter().any(|b| b.shortcut == binding.shortcut) {
+            current.shortcut_bindings.push(binding);
+        }
+    }
+
+    ImportSummary {
+        webapps: count,
+        renamed_ids: renamed.len(),
+        changes: ChangePreview::default(),
+    }
+}
+
+/// 从文件导入配置：merge 为 true 时追加小程序，否则替换整份配置（锁定设置保持不变）
+pub fn import(current: &AppConfig, path: &Path, merge_into: bool) -> AppResult<(AppConfig, ImportSummary)> {
+    let mut imported = read(path)?;
+    if merge_into {
+        let mut config = current.clone();
+        let summary = merge(&mut config, imported);
+        return Ok((config, summary));
+    }
+
+    let renamed = dedupe_ids(&mut imported.webapps, &HashSet::new());
+    imported.policy = current.policy.clone();
+    let summary = ImportSummary {
+        webapps: imported.webapps.len(),
+        renamed_ids: renamed.len(),
+        changes: ChangePreview::default(),
+    };
+    Ok((imported, summary))
+}
+
+/// 用 imported 替换 current 时删除和替换的小程序，以及不再注册的快捷键（窗口由调用方按已打开的窗口填写）
+pub fn replacement_changes(current: &AppConfig, imported: &AppConfig) -> ChangePreview {
+    let ids: HashSet<&str> = imported.webapps.iter().map(|w| w.id.as_str()).collect();
+    let (replaced, removed): (Vec<&WebApp>, Vec<&WebApp>) =
+        current.webapps.iter().partition(|w| ids.contains(w.id.as_str()));
+    let kept = desired_bindings(imported);
+    ChangePreview {
+        removed_webapps: removed.iter().map(|w| w.id.clone()).collect(),
+        replaced_webapps: replaced.iter().map(|w| w.id.clone()).collect(),
+        released_shortcuts: desired_bindings(current)
+            .into_iter()
+            .filter(|binding| !kept.contains(binding))
+            .map(|(shortcut, _)| shortcut)
+            .collect(),
+        ..ChangePreview::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ShortcutBinding;
+
+    fn webapp(id: &str) -> WebApp {
+        let mut webapp = WebApp::new(id.to_string(), format!("https://{}.example.com", id));
+        webapp.id = id.to_string();
+        webapp
+    }
+
+    #[test]
+    fn test_dedupe_ids() {
+        let mut webapps = vec![webapp("a"), webapp("b"), webapp("a")];
+        let taken: HashSet<String> = ["b".to_string()].into_iter().collect();
+        let renamed = dedupe_ids(&mut webapps, &taken);
+
+        assert_eq!(webapps[0].id, "a");
+        assert_ne!(webapps[1].id, "b");
+        assert_ne!(webapps[2].id, "a");
+        assert_eq!(renamed.get("b"), Some(&webapps[1].id));
+        assert_eq!(renamed.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_remaps_bindings() {
+        let mut current = AppConfig {
+            webapps: vec![webapp("mail")],
+            ..Default::default()
+        };
+        let imported = AppConfig {
+            webapps: vec![webapp("mail"), webapp("chat")],
+            shortcut_bindings: vec![ShortcutBinding {
+                shortcut: "Ctrl+1".to_string(),
+                action: ShortcutAction::ToggleWebApp {
+                    webapp_id: "mail".to_string(),
+                },
+                enabled: true,
+            }],
+            ..Default::default()
+        };
+
+        let summary = merge(&mut current, imported);
+        assert_eq!(summary.webapps, 2);
+        assert_eq!(summary.renamed_ids, 1);
+        assert_eq!(current.webapps.len(), 3);
+        let new_mail = &current.webapps[1];
+        assert_ne!(new_mail.id, "mail");
+        assert_eq!(
+            current.shortcut_bindings[0].action,
+            ShortcutAction::ToggleWebApp {
+                webapp_id: new_mail.id.clone()
+            }
+        );
+    }
+
+    #[test]
+    fn test_replacement_changes() {
+        let mut current = AppConfig {
+            webapps: vec![webapp("mail"), webapp("chat")],
+            ..Default::default()
+        };
+        current.webapps[0].shortcut = Some("Ctrl+1".to_string());
+        current.webapps[1].shortcut = Some("Ctrl+2".to_string());
+        let mut imported = AppConfig {
+            webapps: vec![webapp("mail"), webapp("docs")],
+            ..Default::default()
+        };
+        imported.webapps[0].shortcut = Some("Ctrl+1".to_string());
+
+        let changes = replacement_changes(&current, &imported);
+        assert_eq!(changes.removed_webapps, ["chat"]);
+        assert_eq!(changes.replaced_webapps, ["mail"]);
+        assert_eq!(changes.released_shortcuts, ["Ctrl+2"]);
+        assert!(!changes.dry_run);
+    }
+}
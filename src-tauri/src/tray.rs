@@ -0,0 +1,85 @@
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::audio;
+use crate::config::ConfigManager;
+use crate::dnd;
+use crate::policy;
+
+const MENU_SHOW: &str = "show";
+const MENU_DND: &str = "dnd";
+const MENU_MUTE_ALL: &str = "mute_all";
+const MENU_QUIT: &str = "quit";
+
+/// 托盘菜单中需要动态更新状态的菜单项
+pub struct TrayMenuState {
+    pub dnd: CheckMenuItem<Wry>,
+    pub mute_all: CheckMenuItem<Wry>,
+}
+
+/// 创建系统托盘图标及菜单
+pub fn setup_tray(app: &tauri::App) -> tauri::Result<()> {
+    let dnd_active = dnd::current_status(app.handle()).active;
+    let mute_all = app.state::<ConfigManager>().read().mute_all;
+
+    let show = MenuItem::with_id(app, MENU_SHOW, "显示主窗口", true, None::<&str>)?;
+    let dnd_item = CheckMenuItem::with_id(app, MENU_DND, "勿扰模式", true, dnd_active, None::<&str>)?;
+    let mute_item =
+        CheckMenuItem::with_id(app, MENU_MUTE_ALL, "全部静音", true, mute_all, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, "退出", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &dnd_item, &mute_item, &separator, &quit])?;
+
+    let mut builder = TrayIconBuilder::new()
+        .menu(&menu)
+        .tooltip("WebApp Hub")
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayMenuState {
+        dnd: dnd_item,
+        mute_all: mute_item,
+    });
+    Ok(())
+}
+
+/// 管理员锁定时拒绝修改配置，并恢复菜单项被点击前的勾选状态
+fn refuse_locked(app: &AppHandle) -> bool {
+    let Err(e) = policy::ensure_unlocked(app) else {
+        return false;
+    };
+    log::warn!("Tray menu action refused: {}", e);
+    if let Some(tray) = app.try_state::<TrayMenuState>() {
+        let _ = tray.dnd.set_checked(dnd::current_status(app).active);
+        let _ = tray.mute_all.set_checked(app.state::<ConfigManager>().read().mute_all);
+    }
+    true
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        MENU_SHOW => {
+            if let Some(main_window) = app.get_webview_window("main") {
+                let _ = main_window.show();
+                let _ = main_window.set_focus();
+            }
+        }
+        MENU_DND | MENU_MUTE_ALL if refuse_locked(app) => {}
+        MENU_DND => {
+            if let Err(e) = dnd::toggle(app) {
+                log::error!("Failed to toggle Do Not Disturb: {}", e);
+            }
+        }
+        MENU_MUTE_ALL => {
+            if let Err(e) = audio::toggle_mute_all(app) {
+                log::error!("Failed to toggle mute all: {}", e);
+            }
+        }
+        MENU_QUIT => app.exit(0),
+        _ => {}
+    }
+}
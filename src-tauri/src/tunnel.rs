@@ -0,0 +1,136 @@
+use std::process::Command;
+use url::Url;
+
+use crate::models::{SshTunnel, WebApp};
+
+/// 隧道监听的本地地址
+pub const LOCAL_HOST: &str = "127.0.0.1";
+
+/// 拒绝以 `-` 开头或包含空白的值，避免被 ssh 当作选项解析
+fn check_arg(name: &str, value: &str) -> Result<(), String> {
+    if value.is_empty() || value.starts_with('-') || value.chars().any(char::is_whitespace) {
+        return Err(format!("无效的 SSH {}: {}", name, value));
+    }
+    Ok(())
+}
+
+/// 构建 ssh 端口转发参数
+/// 远程主机和端口未设置时使用小程序网址的主机名和端口
+fn ssh_args(settings: &SshTunnel, webapp_url: &str) -> Result<Vec<String>, String> {
+    let url = Url::parse(webapp_url).map_err(|e| e.to_string())?;
+    let remote_host = match settings.remote_host.as_deref().filter(|h| !h.is_empty()) {
+        Some(host) => host.to_string(),
+        None => url.host_str().ok_or("网址缺少主机名")?.to_string(),
+    };
+    let remote_port = match settings.remote_port {
+        Some(port) => port,
+        None => url.port_or_known_default().ok_or("无法确定网址端口")?,
+    };
+    if settings.local_port == 0 {
+        return Err("SSH 隧道需要本地端口".to_string());
+    }
+
+    check_arg("主机", &settings.host)?;
+    check_arg("远程主机", &remote_host)?;
+
+    let mut args = vec![
+        "-N".to_string(),
+        "-o".to_string(),
+        "ExitOnForwardFailure=yes".to_string(),
+        // 没有终端可以输入密码，只使用密钥或 agent 认证
+        "-o".to_string(),
+        "BatchMode=yes".to_string(),
+        "-L".to_string(),
+        format!("{}:{}:{}:{}", LOCAL_HOST, settings.local_port, remote_host, remote_port),
+    ];
+    if let Some(port) = settings.ssh_port {
+        args.push("-p".to_string());
+        args.push(port.to_string());
+    }
+    if let Some(identity) = settings.identity_file.as_deref().filter(|i| !i.is_empty()) {
+        args.push("-i".to_string());
+        args.push(identity.to_string());
+    }
+
+    let destination = match settings.user.as_deref().filter(|u| !u.is_empty()) {
+        Some(user) => {
+            check_arg("用户名", user)?;
+            format!("{}@{}", user, settings.host)
+        }
+        None => settings.host.clone(),
+    };
+    args.push(destination);
+
+    Ok(args)
+}
+
+/// 构建建立隧道的 ssh 命令
+pub fn ssh_command(settings: &SshTunnel, webapp_url: &str) -> Result<Command, String> {
+    let mut command = Command::new("ssh");
+    command.args(ssh_args(settings, webapp_url)?);
+    Ok(command)
+}
+
+/// 将网址改写为指向隧道本地端口
+pub fn local_url(url: &Url, local_port: u16) -> Result<Url, String> {
+    let mut local = url.clone();
+    local
+        .set_host(Some(LOCAL_HOST))
+        .map_err(|e| e.to_string())?;
+    local
+        .set_port(Some(local_port))
+        .map_err(|_| "无法设置隧道端口".to_string())?;
+    Ok(local)
+}
+
+/// 有隧道的小程序把网址改写到本地端口，其余小程序原样返回
+pub fn route_url(webapp: &WebApp, url: Url) -> Result<Url, String> {
+    match &webapp.ssh_tunnel {
+        Some(settings) if url.host_str() != Some(LOCAL_HOST) => local_url(&url, settings.local_port),
+        _ => Ok(url),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> SshTunnel {
+        SshTunnel {
+            host: "bastion.example.com".to_string(),
+            user: Some("deploy".to_string()),
+            ssh_port: Some(2222),
+            local_port: 18080,
+            remote_host: None,
+            remote_port: None,
+            identity_file: None,
+        }
+    }
+
+    #[test]
+    fn test_ssh_args_default_to_webapp_address() {
+        let args = ssh_args(&settings(), "http://grafana.internal:3000/d/abc").unwrap();
+        assert!(args.contains(&"127.0.0.1:18080:grafana.internal:3000".to_string()));
+        assert_eq!(args[args.len() - 3..], ["-p", "2222", "deploy@bastion.example.com"]);
+    }
+
+    #[test]
+    fn test_ssh_args_reject_option_injection() {
+        let mut bad = settings();
+        bad.host = "-oProxyCommand=evil".to_string();
+        assert!(ssh_args(&bad, "http://grafana.internal").is_err());
+
+        let mut bad = settings();
+        bad.user = Some("-x".to_string());
+        assert!(ssh_args(&bad, "http://grafana.internal").is_err());
+    }
+
+    #[test]
+    fn test_local_url_keeps_path_and_query() {
+        let url = Url::parse("http://grafana.internal:3000/d/abc?from=now-1h").unwrap();
+        assert_eq!(
+            local_url(&url, 18080).unwrap().as_str(),
+            "http://127.0.0.1:18080/d/abc?from=now-1h"
+        );
+    }
+}
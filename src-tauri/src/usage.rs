@@ -0,0 +1,259 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::models::WebApp;
+
+/// 单个小程序的使用统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAppUsage {
+    /// 页面崩溃或无响应的次数
+    #[serde(default)]
+    pub crashes: u32,
+    /// 最近一次崩溃的时间（Unix 秒）
+    #[serde(default)]
+    pub last_crash_at: Option<u64>,
+    /// 打开窗口的次数（不包括在后台恢复的窗口）
+    #[serde(default)]
+    pub open_count: u32,
+    /// 最近一次打开的时间（Unix 秒）
+    #[serde(default)]
+    pub last_opened_at: Option<u64>,
+    /// 窗口处于前台（有焦点）的累计时间（毫秒）
+    #[serde(default)]
+    pub foreground_ms: u64,
+}
+
+/// 带名称的小程序使用统计（供启动器按使用频率排序）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAppStats {
+    pub webapp_id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub usage: WebAppUsage,
+}
+
+/// 使用统计（保存在应用数据目录的 usage.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    #[serde(default)]
+    pub webapps: HashMap<String, WebAppUsage>,
+}
+
+impl UsageStats {
+    fn record_crash(&mut self, webapp_id: &str, at: u64) -> &WebAppUsage {
+        let usage = self.webapps.entry(webapp_id.to_string()).or_default();
+        usage.crashes = usage.crashes.saturating_add(1);
+        usage.last_crash_at = Some(at);
+        usage
+    }
+
+    fn record_open(&mut self, webapp_id: &str, at: u64) {
+        let usage = self.webapps.entry(webapp_id.to_string()).or_default();
+        usage.open_count = usage.open_count.saturating_add(1);
+        usage.last_opened_at = Some(at);
+    }
+
+    fn add_foreground(&mut self, webapp_id: &str, ms: u64) {
+        let usage = self.webapps.entry(webapp_id.to_string()).or_default();
+        usage.foreground_ms = usage.foreground_ms.saturating_add(ms);
+    }
+
+    /// 配置中各小程序的统计，按打开次数、前台时间从多到少排序
+    fn ranked(&self, webapps: &[WebApp]) -> Vec<WebAppStats> {
+        let mut ranked: Vec<WebAppStats> = webapps
+            .iter()
+            .map(|webapp| WebAppStats {
+                webapp_id: webapp.id.clone(),
+                name: webapp.name.clone(),
+                usage: self.webapps.get(&webapp.id).cloned().unwrap_or_default(),
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.usage
+                .open_count
+                .cmp(&a.usage.open_count)
+                .then(b.usage.foreground_ms.cmp(&a.usage.foreground_ms))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        ranked
+    }
+}
+
+/// 使用统计的运行时状态
+pub struct UsageState {
+    stats: Mutex<UsageStats>,
+    /// 当前有焦点的窗口及获得焦点的时间
+    focused_since: Mutex<HashMap<String, Instant>>,
+}
+
+impl UsageState {
+    /// 读取保存的统计，文件不存在或损坏时从零开始
+    pub fn load(app: &AppHandle) -> Self {
+        let stats = std::fs::read_to_string(usage_path(app))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            stats: Mutex::new(stats),
+            focused_since: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+fn usage_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_default()
+        .join("usage.json")
+}
+
+fn save(app: &AppHandle, stats: &UsageStats) {
+    let path = usage_path(app);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let result = serde_json::to_string_pretty(stats)
+        .map_err(|e| e.to_string())
+        .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to save usage stats to {:?}: {}", path, e);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 记录一次崩溃，返回更新后的统计
+pub fn record_crash(app: &AppHandle, webapp_id: &str) -> WebAppUsage {
+    let state = match app.try_state::<UsageState>() {
+        Some(state) => state,
+        None => return WebAppUsage::default(),
+    };
+
+    let mut stats = state.stats.lock();
+    let usage = stats.record_crash(webapp_id, now_secs()).clone();
+    save(app, &stats);
+    usage
+}
+
+/// 记录一次打开窗口
+pub fn record_open(app: &AppHandle, webapp_id: &str) {
+    if let Some(state) = app.try_state::<UsageState>() {
+        let mut stats = state.stats.lock();
+        stats.record_open(webapp_id, now_secs());
+        save(app, &stats);
+    }
+}
+
+/// 窗口焦点变化：失去焦点（或窗口关闭）时累计前台时间
+pub fn focus_changed(app: &AppHandle, webapp_id: &str, focused: bool) {
+    let state = match app.try_state::<UsageState>() {
+        Some(state) => state,
+        None => return,
+    };
+    if focused {
+        state.focused_since.lock().entry(webapp_id.to_string()).or_insert_with(Instant::now);
+        return;
+    }
+    let since = match state.focused_since.lock().remove(webapp_id) {
+        Some(since) => since,
+        None => return,
+    };
+    let ms = since.elapsed().as_millis().min(u64::MAX as u128) as u64;
+    let mut stats = state.stats.lock();
+    stats.add_foreground(webapp_id, ms);
+    save(app, &stats);
+}
+
+/// 清除统计：指定小程序时只清除该小程序的统计
+pub fn reset(app: &AppHandle, webapp_id: Option<&str>) {
+    let state = match app.try_state::<UsageState>() {
+        Some(state) => state,
+        None => return,
+    };
+    let mut stats = state.stats.lock();
+    // 有焦点的窗口从现在开始重新累计前台时间
+    let mut focused_since = state.focused_since.lock();
+    match webapp_id {
+        Some(webapp_id) => {
+            stats.webapps.remove(webapp_id);
+            if let Some(since) = focused_since.get_mut(webapp_id) {
+                *since = Instant::now();
+            }
+        }
+        None => {
+            stats.webapps.clear();
+            focused_since.values_mut().for_each(|since| *since = Instant::now());
+        }
+    }
+    save(app, &stats);
+    log::info!("Reset usage stats for {}", webapp_id.unwrap_or("all webapps"));
+}
+
+/// 配置中各小程序的使用统计，最常用的在前
+pub fn ranked(app: &AppHandle, webapps: &[WebApp]) -> Vec<WebAppStats> {
+    app.try_state::<UsageState>()
+        .map(|state| state.stats.lock().ranked(webapps))
+        .unwrap_or_else(|| UsageStats::default().ranked(webapps))
+}
+
+/// 当前的使用统计
+pub fn snapshot(app: &AppHandle) -> UsageStats {
+    app.try_state::<UsageState>()
+        .map(|state| state.stats.lock().clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_crash() {
+        let mut stats = UsageStats::default();
+        stats.record_crash("mail", 100);
+        let usage = stats.record_crash("mail", 200).clone();
+
+        assert_eq!(usage.crashes, 2);
+        assert_eq!(usage.last_crash_at, Some(200));
+        assert!(!stats.webapps.contains_key("chat"));
+    }
+
+    #[test]
+    fn test_ranked_by_usage() {
+        let webapps: Vec<WebApp> = ["Mail", "Chat", "Docs"]
+            .iter()
+            .map(|name| {
+                let mut webapp = WebApp::new(name.to_string(), format!("https://{}.example.com", name));
+                webapp.id = name.to_lowercase();
+                webapp
+            })
+            .collect();
+        let mut stats = UsageStats::default();
+        stats.record_open("chat", 100);
+        stats.record_open("chat", 200);
+        stats.record_open("mail", 150);
+        stats.add_foreground("mail", 1500);
+        stats.add_foreground("mail", 500);
+        stats.record_open("deleted", 300);
+
+        let ranked = stats.ranked(&webapps);
+        let ids: Vec<&str> = ranked.iter().map(|s| s.webapp_id.as_str()).collect();
+        assert_eq!(ids, ["chat", "mail", "docs"]);
+        assert_eq!(ranked[0].usage.open_count, 2);
+        assert_eq!(ranked[0].usage.last_opened_at, Some(200));
+        assert_eq!(ranked[1].usage.foreground_ms, 2000);
+        assert_eq!(ranked[2].usage, WebAppUsage::default());
+    }
+}
@@ -0,0 +1,270 @@
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use url::Url;
+use uuid::Uuid;
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::models::{AppConfig, InjectTiming, UserScript, WebApp};
+use crate::window::{window_label, WindowManager};
+
+/// 最多记录的最近运行组合数
+const MAX_RECENT_RUNS: usize = 10;
+
+/// 最近在指定窗口中运行的脚本（脚本 ID, 小程序 ID, 运行时间），新的在前，仅在本次运行中保留
+static RECENT_RUNS: Mutex<VecDeque<(String, String, u64)>> = parking_lot::const_mutex(VecDeque::new());
+
+/// 最近运行过的脚本和窗口组合（用于命令面板中快速重复）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRun {
+    pub script_id: String,
+    pub script_name: String,
+    pub webapp_id: String,
+    pub webapp_name: String,
+    /// 运行时间（Unix 秒）
+    pub ran_at: u64,
+}
+
+/// 网址匹配模式转换为正则表达式（整个网址匹配，`*` 匹配任意字符）
+fn pattern_regex(pattern: &str) -> String {
+    let parts: Vec<String> = pattern.trim().split('*').map(regex::escape).collect();
+    format!("^{}$", parts.join(".*"))
+}
+
+/// 网址是否匹配脚本的匹配模式（没有模式时匹配所有页面）
+pub fn url_matches(script: &UserScript, url: &Url) -> bool {
+    script.matches.is_empty()
+        || script
+            .matches
+            .iter()
+            .filter_map(|p| Regex::new(&pattern_regex(p)).ok())
+            .any(|re| re.is_match(url.as_str()))
+}
+
+/// 小程序引用的已启用脚本（按小程序中的引用顺序）
+pub fn scripts_for<'a>(config: &'a AppConfig, webapp: &WebApp) -> Vec<&'a UserScript> {
+    webapp
+        .script_ids
+        .iter()
+        .filter_map(|id| config.scripts.iter().find(|s| &s.id == id))
+        .filter(|s| s.enabled && !s.script.trim().is_empty())
+        .collect()
+}
+
+/// 生成窗口的初始化脚本：每次导航时在页面中检查网址，匹配时按注入时机执行（手动注入的脚本返回 None）
+pub fn initialization_script(script: &UserScript) -> Option<String> {
+    let patterns: Vec<String> = script.matches.iter().map(|p| pattern_regex(p)).collect();
+    let source = serde_json::to_string(&script.script).ok()?;
+    let run = match script.run_at {
+        InjectTiming::DocumentStart => "run();",
+        InjectTiming::DocumentEnd => {
+            "if (document.readyState === 'loading') { document.addEventListener('DOMContentLoaded', run); } else { run(); }"
+        }
+        InjectTiming::Manual => return None,
+    };
+    Some(format!(
+        r#"(function() {{
+    var patterns = {patterns};
+    if (patterns.length && !patterns.some(function(p) {{ return new RegExp(p).test(location.href); }})) return;
+    function run() {{
+        try {{
+            eval({source});
+        }} catch (e) {{
+            console.error('[WebApp Hub] Script "' + {name} + '" error:', e);
+        }}
+    }}
+    {run}
+}})();"#,
+        patterns = serde_json::to_string(&patterns).ok()?,
+        source = source,
+        name = serde_json::to_string(&script.name).ok()?,
+        run = run,
+    ))
+}
+
+fn validate(script: &UserScript) -> Result<(), String> {
+    if script.name.trim().is_empty() {
+        return Err("脚本名称不能为空".to_string());
+    }
+    for pattern in &script.matches {
+        Regex::new(&pattern_regex(pattern)).map_err(|e| format!("无效的网址匹配模式 {}: {}", pattern, e))?;
+    }
+    Ok(())
+}
+
+/// 添加或修改脚本库中的脚本（ID 为空时新建），返回保存后的脚本
+pub fn save(app: &AppHandle, mut script: UserScript) -> AppResult<UserScript> {
+    validate(&script)?;
+    script.matches.retain(|p| !p.trim().is_empty());
+    if script.id.is_empty() {
+        script.id = Uuid::new_v4().to_string();
+    }
+
+    let saved = script.clone();
+    app.state::<ConfigManager>().update(|config| {
+        match config.scripts.iter_mut().find(|s| s.id == script.id) {
+            Some(existing) => *existing = script,
+            None => config.scripts.push(script),
+        }
+    })?;
+    log::info!("Saved user script {} ({})", saved.name, saved.id);
+    Ok(saved)
+}
+
+/// 删除脚本，并移除小程序中对它的引用
+pub fn delete(app: &AppHandle, script_id: &str) -> AppResult<()> {
+    app.state::<ConfigManager>().update(|config| {
+        let before = config.scripts.len();
+        config.scripts.retain(|s| s.id != script_id);
+        if config.scripts.len() == before {
+            return Err(AppError::not_found("脚本不存在"));
+        }
+        for webapp in &mut config.webapps {
+            webapp.script_ids.retain(|id| id != script_id);
+        }
+        Ok(())
+    })??;
+    log::info!("Deleted user script {}", script_id);
+    Ok(())
+}
+
+/// 在小程序窗口的当前页面中执行脚本库中的脚本（手动注入，网址需匹配脚本的匹配模式）
+pub fn run(app: &AppHandle, webapp_id: &str, script_id: &str) -> Result<(), String> {
+    let config = app.state::<ConfigManager>().read();
+    let script = config
+        .scripts
+        .iter()
+        .find(|s| s.id == script_id)
+        .ok_or("脚本不存在")?;
+    let window = app
+        .get_webview_window(&window_label(webapp_id))
+        .ok_or("小程序窗口未打开")?;
+    let url = window.url().map_err(|e| e.to_string())?;
+    if !url_matches(script, &url) {
+        return Err("当前页面不匹配该脚本的网址模式".to_string());
+    }
+
+    let manual = UserScript {
+        run_at: InjectTiming::DocumentEnd,
+        ..script.clone()
+    };
+    let source = initialization_script(&manual).ok_or("无法生成脚本")?;
+    window.eval(&source).map_err(|e| e.to_string())?;
+    log::info!("Ran user script {} in webapp {}", script.name, webapp_id);
+    Ok(())
+}
+
+/// 记录一次运行：同一组合只保留最近的一条
+fn remember(runs: &mut VecDeque<(String, String, u64)>, script_id: &str, webapp_id: &str, at: u64) {
+    runs.retain(|(script, webapp, _)| script != script_id || webapp != webapp_id);
+    runs.push_front((script_id.to_string(), webapp_id.to_string(), at));
+    runs.truncate(MAX_RECENT_RUNS);
+}
+
+/// 在任意打开的小程序窗口中运行脚本库中的脚本（用户明确选择了窗口，不检查网址匹配模式和是否启用）
+pub fn run_in(app: &AppHandle, script_id: &str, webapp_id: &str) -> AppResult<()> {
+    let config = app.state::<ConfigManager>().read();
+    let script = config
+        .scripts
+        .iter()
+        .find(|s| s.id == script_id)
+        .ok_or_else(|| AppError::not_found("脚本不存在"))?;
+    if app.get_webview_window(&window_label(webapp_id)).is_none() {
+        return Err(AppError::window("小程序窗口未打开"));
+    }
+
+    app.state::<WindowManager>().inject_script(app, webapp_id, &script.script)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    remember(&mut RECENT_RUNS.lock(), script_id, webapp_id, now);
+    log::info!("Ran user script {} in webapp {}", script.name, webapp_id);
+    Ok(())
+}
+
+/// 最近运行过的组合，新的在前（已删除的脚本或小程序不返回）
+pub fn recent_runs(config: &AppConfig) -> Vec<ScriptRun> {
+    RECENT_RUNS
+        .lock()
+        .iter()
+        .filter_map(|(script_id, webapp_id, ran_at)| {
+            let script = config.scripts.iter().find(|s| &s.id == script_id)?;
+            let webapp = config.webapps.iter().find(|w| &w.id == webapp_id)?;
+            Some(ScriptRun {
+                script_id: script.id.clone(),
+                script_name: script.name.clone(),
+                webapp_id: webapp.id.clone(),
+                webapp_name: webapp.name.clone(),
+                ran_at: *ran_at,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(matches: &[&str]) -> UserScript {
+        UserScript {
+            id: "s1".to_string(),
+            name: "Dark".to_string(),
+            script: "document.body.classList.add('dark');".to_string(),
+            run_at: InjectTiming::DocumentEnd,
+            matches: matches.iter().map(|m| m.to_string()).collect(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_url_matches() {
+        let inbox = Url::parse("https://mail.example.com/inbox/42").unwrap();
+        assert!(url_matches(&script(&[]), &inbox));
+        assert!(url_matches(&script(&["https://mail.example.com/inbox/*"]), &inbox));
+        assert!(url_matches(&script(&["https://*.example.com/*"]), &inbox));
+        assert!(!url_matches(&script(&["https://mail.example.com/settings*"]), &inbox));
+        // 只有 `*` 是通配符
+        assert!(!url_matches(&script(&["https://mail?example.com/*"]), &inbox));
+    }
+
+    #[test]
+    fn test_scripts_for_webapp() {
+        let mut config = AppConfig::default();
+        let mut disabled = script(&[]);
+        disabled.id = "s2".to_string();
+        disabled.enabled = false;
+        config.scripts = vec![script(&[]), disabled];
+
+        let mut webapp = WebApp::new("Mail".to_string(), "https://mail.example.com".to_string());
+        webapp.script_ids = vec!["s2".to_string(), "s1".to_string(), "missing".to_string()];
+        let ids: Vec<&str> = scripts_for(&config, &webapp).iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["s1"]);
+
+        let mut manual = script(&[]);
+        manual.run_at = InjectTiming::Manual;
+        assert!(initialization_script(&manual).is_none());
+        assert!(initialization_script(&script(&[])).unwrap().contains("DOMContentLoaded"));
+    }
+
+    #[test]
+    fn test_remember_recent_runs() {
+        let mut runs = VecDeque::new();
+        remember(&mut runs, "s1", "mail", 1);
+        remember(&mut runs, "s2", "mail", 2);
+        remember(&mut runs, "s1", "mail", 3);
+        let order: Vec<(&str, u64)> = runs.iter().map(|(script, _, at)| (script.as_str(), *at)).collect();
+        assert_eq!(order, vec![("s1", 3), ("s2", 2)]);
+
+        for i in 0..MAX_RECENT_RUNS + 3 {
+            remember(&mut runs, "s1", &format!("webapp-{}", i), i as u64);
+        }
+        assert_eq!(runs.len(), MAX_RECENT_RUNS);
+        assert_eq!(runs[0].1, format!("webapp-{}", MAX_RECENT_RUNS + 2));
+    }
+}
@@ -0,0 +1,223 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use url::Url;
+
+use crate::window::window_label;
+
+/// 允许恢复的缩放范围（百分比），与设置中的范围一致
+const MIN_ZOOM: u32 = 25;
+const MAX_ZOOM: u32 = 500;
+
+/// 页面上报的滚动位置和缩放（桥接事件 `view`）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewReport {
+    pub url: String,
+    pub scroll_x: f64,
+    pub scroll_y: f64,
+    /// 页面的 devicePixelRatio（= 显示器缩放 × 页面缩放）
+    pub device_pixel_ratio: f64,
+}
+
+/// 小程序上次的页面位置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewState {
+    pub url: String,
+    pub scroll_x: i64,
+    pub scroll_y: i64,
+    /// 页面缩放（百分比），无法计算时为 None
+    #[serde(default)]
+    pub zoom_percent: Option<u32>,
+}
+
+/// 各小程序的页面位置（保存在应用数据目录的 view_state.json）
+pub struct ViewStates {
+    states: Mutex<HashMap<String, ViewState>>,
+    /// 当前窗口生命周期内已经恢复过的小程序（之后的导航不再恢复）
+    restored: Mutex<HashSet<String>>,
+    /// 写入队列：由后台任务写入文件，避免在桥接事件处理中阻塞
+    writer: UnboundedSender<HashMap<String, ViewState>>,
+}
+
+impl ViewStates {
+    /// 读取保存的页面位置，文件不存在或损坏时从空开始
+    pub fn load(app: &AppHandle) -> Self {
+        let path = state_path(app);
+        let states = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let (writer, receiver) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(run_writer(path, receiver));
+        Self {
+            states: Mutex::new(states),
+            restored: Mutex::new(HashSet::new()),
+            writer,
+        }
+    }
+}
+
+fn state_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_default()
+        .join("view_state.json")
+}
+
+fn save(path: &Path, states: &HashMap<String, ViewState>) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let result = serde_json::to_string(states)
+        .map_err(|e| e.to_string())
+        .and_then(|content| std::fs::write(path, content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to save view state to {:?}: {}", path, e);
+    }
+}
+
+/// 页面位置写入任务：积压时只写最新的一份（滚动停止时的上报可能很频繁）
+async fn run_writer(path: PathBuf, mut receiver: UnboundedReceiver<HashMap<String, ViewState>>) {
+    while let Some(mut states) = receiver.recv().await {
+        while let Ok(newer) = receiver.try_recv() {
+            states = newer;
+        }
+        let path = path.clone();
+        if let Err(e) = tokio::task::spawn_blocking(move || save(&path, &states)).await {
+            log::error!("View state write task failed: {}", e);
+        }
+    }
+}
+
+/// 由 devicePixelRatio 和显示器缩放计算页面缩放（百分比）
+fn zoom_percent(device_pixel_ratio: f64, scale_factor: f64) -> Option<u32> {
+    if !(device_pixel_ratio > 0.0 && scale_factor > 0.0) {
+        return None;
+    }
+    let zoom = (device_pixel_ratio / scale_factor * 100.0).round() as u32;
+    Some(zoom.clamp(MIN_ZOOM, MAX_ZOOM))
+}
+
+/// 是否为同一页面（忽略锚点）
+fn same_page(saved: &str, current: &Url) -> bool {
+    match Url::parse(saved) {
+        Ok(mut saved) => {
+            let mut current = current.clone();
+            saved.set_fragment(None);
+            current.set_fragment(None);
+            saved == current
+        }
+        Err(_) => false,
+    }
+}
+
+/// 页面隐藏、离开或停止滚动后上报位置
+pub fn handle_event(app: &AppHandle, webapp_id: &str, report: ViewReport) {
+    let state = match app.try_state::<ViewStates>() {
+        Some(state) => state,
+        None => return,
+    };
+    let scale_factor = app
+        .get_webview_window(&window_label(webapp_id))
+        .and_then(|window| window.scale_factor().ok())
+        .unwrap_or(1.0);
+    let view = ViewState {
+        url: report.url,
+        scroll_x: report.scroll_x.max(0.0).round() as i64,
+        scroll_y: report.scroll_y.max(0.0).round() as i64,
+        zoom_percent: zoom_percent(report.device_pixel_ratio, scale_factor),
+    };
+
+    let mut states = state.states.lock();
+    if states.get(webapp_id) == Some(&view) {
+        return;
+    }
+    states.insert(webapp_id.to_string(), view);
+    let _ = state.writer.send(states.clone());
+}
+
+/// 窗口打开后第一次加载完成时，回到同一页面上次的滚动位置并恢复缩放
+pub fn restore(app: &AppHandle, window: &WebviewWindow, webapp_id: &str, url: &Url) {
+    let state = match app.try_state::<ViewStates>() {
+        Some(state) => state,
+        None => return,
+    };
+    if !state.restored.lock().insert(webapp_id.to_string()) {
+        return;
+    }
+    let view = match state.states.lock().get(webapp_id) {
+        Some(view) => view.clone(),
+        None => return,
+    };
+
+    if let Some(zoom) = view.zoom_percent {
+        if let Err(e) = window.set_zoom(zoom as f64 / 100.0) {
+            log::debug!("Could not restore zoom for webapp {}: {}", webapp_id, e);
+        }
+    }
+    if same_page(&view.url, url) && (view.scroll_x > 0 || view.scroll_y > 0) {
+        let _ = window.eval(format!(
+            "window.__WEBAPP_HUB__ && window.__WEBAPP_HUB__.restoreScroll({}, {});",
+            view.scroll_x, view.scroll_y
+        ));
+    }
+}
+
+/// 窗口关闭后，下次打开时重新恢复
+pub fn forget(app: &AppHandle, webapp_id: &str) {
+    if let Some(state) = app.try_state::<ViewStates>() {
+        state.restored.lock().remove(webapp_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_percent() {
+        assert_eq!(zoom_percent(2.0, 2.0), Some(100));
+        assert_eq!(zoom_percent(3.0, 2.0), Some(150));
+        assert_eq!(zoom_percent(1.1, 1.0), Some(110));
+        assert_eq!(zoom_percent(0.0, 1.0), None);
+        assert_eq!(zoom_percent(20.0, 1.0), Some(MAX_ZOOM));
+    }
+
+    #[test]
+    fn test_same_page_ignores_fragment() {
+        let current = Url::parse("https://dash.example.com/board?id=1#top").unwrap();
+        assert!(same_page("https://dash.example.com/board?id=1", &current));
+        assert!(!same_page("https://dash.example.com/board?id=2", &current));
+        assert!(!same_page("not a url", &current));
+    }
+
+    #[tokio::test]
+    async fn test_writer_saves_latest_states() {
+        let dir = std::env::temp_dir().join(format!("webapp-hub-viewstate-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("view_state.json");
+
+        let (writer, receiver) = mpsc::unbounded_channel();
+        for scroll_y in [100, 200, 300] {
+            let view = ViewState {
+                url: "https://dash.example.com/".to_string(),
+                scroll_x: 0,
+                scroll_y,
+                zoom_percent: None,
+            };
+            writer.send(HashMap::from([("dash".to_string(), view)])).unwrap();
+        }
+        drop(writer);
+        run_writer(path.clone(), receiver).await;
+
+        let saved: HashMap<String, ViewState> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["dash"].scroll_y, 300);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,222 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::ConfigManager;
+use crate::error::AppResult;
+use crate::events::{self, Channel};
+use crate::models::CrashRecovery;
+use crate::usage::{self, WebAppUsage};
+use crate::window::{window_label, WindowManager};
+
+/// 检查间隔：每次检查向可见的小程序窗口发送一次心跳请求
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 超过这个时间没有心跳回应即视为崩溃或无响应
+const UNRESPONSIVE_AFTER: Duration = Duration::from_secs(30);
+
+/// 心跳请求：页面通过桥接回应 `pong`，页面崩溃或卡死时收不到回应
+const PING_SCRIPT: &str = "window.__WEBAPP_HUB__ && window.__WEBAPP_HUB__.emit('pong', null);";
+
+/// 无响应事件（`watchdog://unresponsive`）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnresponsiveEvent {
+    pub webapp_id: String,
+    pub name: String,
+    pub action: CrashRecovery,
+    pub usage: WebAppUsage,
+}
+
+/// 看门狗状态
+pub struct WatchdogState {
+    /// 各窗口最近一次回应的时间
+    last_seen: Mutex<HashMap<String, Instant>>,
+    /// 已判定无响应、等待恢复的窗口（避免重复处理）
+    pending: Mutex<HashSet<String>>,
+}
+
+impl WatchdogState {
+    pub fn new() -> Self {
+        Self {
+            last_seen: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+impl Default for WatchdogState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 已超时的窗口
+fn overdue(last_seen: &HashMap<String, Instant>, pending: &HashSet<String>, now: Instant) -> Vec<String> {
+    last_seen
+        .iter()
+        .filter(|(id, at)| !pending.contains(*id) && now.duration_since(**at) > UNRESPONSIVE_AFTER)
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+/// 页面有回应（心跳或页面加载）时调用
+pub fn touch(app: &AppHandle, webapp_id: &str) {
+    if let Some(state) = app.try_state::<WatchdogState>() {
+        state.last_seen.lock().insert(webapp_id.to_string(), Instant::now());
+        if state.pending.lock().remove(webapp_id) {
+            log::info!("Webapp {} is responding again", webapp_id);
+        }
+    }
+}
+
+/// 窗口关闭时停止监视
+pub fn forget(app: &AppHandle, webapp_id: &str) {
+    if let Some(state) = app.try_state::<WatchdogState>() {
+        state.last_seen.lock().remove(webapp_id);
+        state.pending.lock().remove(webapp_id);
+    }
+}
+
+fn reload(app: &AppHandle, webapp_id: &str) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&window_label(webapp_id))
+        .ok_or("小程序窗口未打开")?;
+    window.reload().map_err(|e| e.to_string())?;
+    touch(app, webapp_id);
+    Ok(())
+}
+
+fn handle_unresponsive(app: &AppHandle, webapp_id: &str) {
+    let config = app.state::<ConfigManager>().read();
+    let webapp = match config.webapps.iter().find(|w| w.id == webapp_id) {
+        Some(webapp) => webapp,
+        None => return,
+    };
+    let action = webapp.crash_recovery;
+    let usage = usage::record_crash(app, webapp_id);
+    log::warn!(
+        "Webapp {} is unresponsive (crash #{}), recovery: {:?}",
+        webapp_id,
+        usage.crashes,
+        action
+    );
+
+    match action {
+        CrashRecovery::Reload => {
+            if let Err(e) = reload(app, webapp_id) {
+                log::error!("Failed to reload unresponsive webapp {}: {}", webapp_id, e);
+            }
+        }
+        CrashRecovery::Ask => {
+            if let Err(e) = app
+                .notification()
+                .builder()
+                .title(format!("{} 没有响应", webapp.name))
+                .body("可以在 WebApp Hub 中选择重新加载或关闭")
+                .show()
+            {
+                log::warn!("Failed to show unresponsive notification: {}", e);
+            }
+        }
+        CrashRecovery::LeaveClosed => {
+            if let Err(e) = app.state::<WindowManager>().close_webapp(app, webapp_id) {
+                log::error!("Failed to close unresponsive webapp {}: {}", webapp_id, e);
+            }
+        }
+    }
+
+    events::emit(
+        app,
+        Channel::Prompts,
+        "watchdog://unresponsive",
+        UnresponsiveEvent {
+            webapp_id: webapp_id.to_string(),
+            name: webapp.name.clone(),
+            action,
+            usage,
+        },
+    );
+}
+
+/// 用户对无响应提示的选择：重新加载或关闭窗口
+pub fn recover(app: &AppHandle, webapp_id: &str, reload_page: bool) -> AppResult<()> {
+    if reload_page {
+        Ok(reload(app, webapp_id)?)
+    } else {
+        app.state::<WindowManager>().close_webapp(app, webapp_id)
+    }
+}
+
+fn check(app: &AppHandle, state: &WatchdogState) {
+    let window_manager = app.state::<WindowManager>();
+    let config = app.state::<ConfigManager>().read();
+    let now = Instant::now();
+
+    for webapp_id in window_manager.get_active_window_ids() {
+        // 禁用 JavaScript 的页面无法回应心跳
+        if config
+            .webapps
+            .iter()
+            .any(|w| w.id == webapp_id && !w.javascript_enabled)
+        {
+            state.last_seen.lock().remove(&webapp_id);
+            continue;
+        }
+        let window = match app.get_webview_window(&window_label(&webapp_id)) {
+            Some(window) => window,
+            None => continue,
+        };
+        // 隐藏的窗口可能被系统暂停，不作判断；重新显示后从头计时
+        if !window.is_visible().unwrap_or(false) {
+            state.last_seen.lock().insert(webapp_id, now);
+            continue;
+        }
+        state.last_seen.lock().entry(webapp_id).or_insert(now);
+        let _ = window.eval(PING_SCRIPT);
+    }
+
+    let overdue = {
+        let last_seen = state.last_seen.lock();
+        let mut pending = state.pending.lock();
+        let overdue = overdue(&last_seen, &pending, now);
+        pending.extend(overdue.iter().cloned());
+        overdue
+    };
+    for webapp_id in overdue {
+        handle_unresponsive(app, &webapp_id);
+    }
+}
+
+/// 启动看门狗
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app.state::<WatchdogState>();
+            check(&app, &state);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overdue() {
+        let start = Instant::now();
+        let now = start + Duration::from_secs(100);
+        let mut last_seen = HashMap::new();
+        last_seen.insert("fresh".to_string(), start + Duration::from_secs(95));
+        last_seen.insert("stale".to_string(), start + Duration::from_secs(55));
+        last_seen.insert("waiting".to_string(), start + Duration::from_secs(10));
+        let pending: HashSet<String> = ["waiting".to_string()].into_iter().collect();
+
+        assert_eq!(overdue(&last_seen, &pending, now), vec!["stale".to_string()]);
+    }
+}
@@ -0,0 +1,60 @@
+use serde_json::{json, Value};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::ConfigManager;
+use crate::models::WebhookEvent;
+use crate::net;
+
+/// 单个 Webhook 请求的超时时间
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 向所有订阅了该事件的 Webhook 异步发送通知，不阻塞调用方
+pub fn dispatch(app: &AppHandle, event: WebhookEvent, data: Value) {
+    let targets: Vec<String> = match app.try_state::<ConfigManager>() {
+        Some(config_manager) => config_manager
+            .read()
+            .webhooks
+            .iter()
+            .filter(|hook| hook.accepts(event))
+            .map(|hook| hook.url.clone())
+            .collect(),
+        None => return,
+    };
+
+    if targets.is_empty() {
+        return;
+    }
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let body = json!({
+        "event": event,
+        "timestamp": timestamp,
+        "data": data,
+    });
+
+    let (client, policy) = net::client(app);
+    tauri::async_runtime::spawn(async move {
+        for url in targets {
+            let result = net::send_with_retry(&policy, || {
+                client.post(&url).timeout(WEBHOOK_TIMEOUT).json(&body)
+            })
+            .await;
+
+            match result {
+                Ok(resp) if resp.status().is_success() => {
+                    log::debug!("Webhook delivered: {} -> {}", body["event"], url);
+                }
+                Ok(resp) => {
+                    log::warn!("Webhook {} responded with {}", url, resp.status());
+                }
+                Err(e) => {
+                    log::warn!("Webhook {} failed: {}", url, e);
+                }
+            }
+        }
+    });
+}
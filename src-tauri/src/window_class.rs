@@ -0,0 +1,117 @@
+use tauri::WebviewWindow;
+
+use crate::models::WebApp;
+use crate::platform;
+
+/// 窗口类的最大长度（AppUserModelID 的上限为 128 个字符）
+const MAX_LEN: usize = 128;
+
+/// 窗口类只允许字母、数字和 `.`、`-`、`_`，不能以点开头或结尾
+pub fn validate(class: &str) -> Result<(), String> {
+    let valid = !class.is_empty()
+        && class.len() <= MAX_LEN
+        && !class.starts_with('.')
+        && !class.ends_with('.')
+        && class.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的窗口类: {}（只能包含字母、数字、点、横线和下划线）", class))
+    }
+}
+
+/// 为小程序窗口设置窗口类（Linux X11 上的 WM_CLASS，Windows 上的 AppUserModelID），
+/// 让任务栏和窗口管理器把小程序当作独立的应用分组
+///
+/// 未设置窗口类或平台不支持时不做任何操作
+pub fn apply(window: &WebviewWindow, webapp: &WebApp) {
+    let class = match webapp.window_class.as_deref().filter(|c| !c.is_empty()) {
+        Some(class) => class.to_string(),
+        None => return,
+    };
+    if !platform::capabilities().window_class {
+        log::debug!("Window class is not supported on this platform, ignoring {}", class);
+        return;
+    }
+
+    // GTK 和窗口属性只能在主线程中修改
+    let target = window.clone();
+    let webapp_id = webapp.id.clone();
+    let result = window.run_on_main_thread(move || match set_native(&target, &class) {
+        Ok(()) => log::info!("Set window class of {} to {}", webapp_id, class),
+        Err(e) => log::warn!("Failed to set window class of {}: {}", webapp_id, e),
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to set window class of {}: {}", webapp.id, e);
+    }
+}
+
+/// 设置 X11 窗口的 WM_CLASS（实例名和类名相同），窗口管理器会响应属性变化
+#[cfg(target_os = "linux")]
+fn set_native(window: &WebviewWindow, class: &str) -> Result<(), String> {
+    use gtk::glib::translate::ToGlibPtr;
+    use gtk::glib::Cast;
+    use gtk::prelude::WidgetExt;
+    use std::ffi::CString;
+
+    let gtk_window = window.gtk_window().map_err(|e| e.to_string())?;
+    let gdk_window = gtk_window.window().ok_or("窗口尚未创建")?;
+    let x11_display = gdk_window
+        .display()
+        .downcast::<gdkx11::X11Display>()
+        .map_err(|_| "只有 X11 窗口支持设置窗口类".to_string())?;
+    let x11_window = gdk_window
+        .downcast::<gdkx11::X11Window>()
+        .map_err(|_| "只有 X11 窗口支持设置窗口类".to_string())?;
+    let name = CString::new(class).map_err(|e| e.to_string())?;
+
+    unsafe {
+        let display = gdkx11::ffi::gdk_x11_display_get_xdisplay(x11_display.to_glib_none().0);
+        let mut hint = x11::xlib::XClassHint {
+            res_name: name.as_ptr() as *mut _,
+            res_class: name.as_ptr() as *mut _,
+        };
+        x11::xlib::XSetClassHint(display, x11_window.xid(), &mut hint);
+        x11::xlib::XFlush(display);
+    }
+    Ok(())
+}
+
+/// 设置窗口的 AppUserModelID，任务栏按它分组窗口并使用窗口自己的图标
+#[cfg(target_os = "windows")]
+fn set_native(window: &WebviewWindow, class: &str) -> Result<(), String> {
+    use windows::core::PROPVARIANT;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Storage::EnhancedStorage::PKEY_AppUserModel_ID;
+    use windows::Win32::UI::Shell::PropertiesSystem::{IPropertyStore, SHGetPropertyStoreForWindow};
+
+    let hwnd = window.hwnd().map_err(|e| e.to_string())?;
+    unsafe {
+        let store: IPropertyStore =
+            SHGetPropertyStoreForWindow(HWND(hwnd.0 as _)).map_err(|e| e.to_string())?;
+        store
+            .SetValue(&PKEY_AppUserModel_ID, &PROPVARIANT::from(class))
+            .and_then(|_| store.Commit())
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn set_native(_window: &WebviewWindow, _class: &str) -> Result<(), String> {
+    Err("当前平台不支持设置窗口类".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_window_class() {
+        assert!(validate("webapp-hub.mail").is_ok());
+        assert!(validate("Company.Chat_2").is_ok());
+        assert!(validate("").is_err());
+        assert!(validate(".hidden").is_err());
+        assert!(validate("has space").is_err());
+        assert!(validate(&"a".repeat(MAX_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use tauri::utils::config::WindowEffectsConfig;
+use tauri::window::{Effect, EffectsBuilder};
+use tauri::{AppHandle, WebviewWindowBuilder};
+
+use crate::models::{WebApp, WindowBackground};
+use crate::platform;
+
+/// 窗口背景效果在当前平台上对应的系统效果（macOS 的 NSVisualEffectView 材质，
+/// Windows 的 DWM 背景），平台不支持时返回 None
+fn effect_for(background: WindowBackground) -> Option<Effect> {
+    if background == WindowBackground::Opaque {
+        return None;
+    }
+    #[cfg(target_os = "macos")]
+    {
+        Some(match background {
+            WindowBackground::Vibrancy => Effect::UnderWindowBackground,
+            WindowBackground::Acrylic => Effect::HudWindow,
+            _ => Effect::Sidebar,
+        })
+    }
+    #[cfg(target_os = "windows")]
+    {
+        // Mica 需要 Windows 11，Acrylic 和 Blur 需要 Windows 10
+        Some(match background {
+            WindowBackground::Vibrancy => Effect::Blur,
+            WindowBackground::Acrylic => Effect::Acrylic,
+            _ => Effect::Mica,
+        })
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+fn effects_config(background: WindowBackground) -> Option<WindowEffectsConfig> {
+    effect_for(background).map(|effect| EffectsBuilder::new().effect(effect).build())
+}
+
+/// 按小程序的设置为窗口加上半透明背景效果，只能在创建窗口时设置
+///
+/// 窗口本身会变为透明，页面背景透明（或用自定义 CSS 去掉背景）时才能看到效果
+pub fn apply<'a>(
+    builder: WebviewWindowBuilder<'a, tauri::Wry, AppHandle>,
+    webapp: &WebApp,
+) -> WebviewWindowBuilder<'a, tauri::Wry, AppHandle> {
+    if webapp.window_background == WindowBackground::Opaque {
+        return builder;
+    }
+    match effects_config(webapp.window_background).filter(|_| platform::capabilities().window_effects) {
+        Some(effects) => {
+            log::debug!("Using {:?} background for {}", webapp.window_background, webapp.id);
+            builder.transparent(true).effects(effects)
+        }
+        None => {
+            log::debug!(
+                "Window background effects are not supported on this platform, ignoring {:?} for {}",
+                webapp.window_background,
+                webapp.id
+            );
+            builder
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effect_for_background() {
+        assert_eq!(effect_for(WindowBackground::Opaque), None);
+        let supported = cfg!(any(target_os = "macos", target_os = "windows"));
+        for background in [WindowBackground::Vibrancy, WindowBackground::Acrylic, WindowBackground::Mica] {
+            assert_eq!(effect_for(background).is_some(), supported);
+        }
+        let parsed: WindowBackground = serde_json::from_str("\"acrylic\"").unwrap();
+        assert_eq!(parsed, WindowBackground::Acrylic);
+    }
+}
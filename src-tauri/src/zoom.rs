@@ -0,0 +1,273 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::config::ConfigManager;
+use crate::error::{AppError, AppResult};
+use crate::models::{MonitorZoom, WebApp};
+use crate::policy;
+use crate::window::window_label;
+
+/// 允许的缩放范围（百分比）
+const MIN_ZOOM: u32 = 25;
+const MAX_ZOOM: u32 = 500;
+
+/// 逐步放大缩小时使用的缩放比例（与常见浏览器一致）
+const ZOOM_STEPS: &[u32] = &[25, 33, 50, 67, 75, 80, 90, 100, 110, 125, 150, 175, 200, 250, 300, 400, 500];
+
+/// 键盘缩放的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ZoomStep {
+    In,
+    Out,
+    /// 恢复为 100%
+    Reset,
+}
+
+/// 各窗口上次应用缩放时所在的显示器，用于判断是否移动到了另一台显示器
+pub struct ZoomState {
+    monitors: Mutex<HashMap<String, Option<String>>>,
+    /// 管理员锁定时键盘缩放的比例：只应用到窗口，不保存到配置，窗口关闭后恢复
+    unsaved: Mutex<HashMap<String, u32>>,
+}
+
+impl ZoomState {
+    pub fn new() -> Self {
+        Self {
+            monitors: Mutex::new(HashMap::new()),
+            unsaved: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for ZoomState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 小程序在指定显示器上的缩放比例（百分比）：显示器有单独设置时使用它，否则使用默认缩放
+pub fn zoom_for(webapp: &WebApp, monitor: Option<&str>) -> u32 {
+    let zoom = monitor
+        .and_then(|name| webapp.monitor_zoom.iter().find(|m| m.monitor == name))
+        .map(|m| m.zoom_percent)
+        .or(webapp.zoom_percent)
+        .unwrap_or(100);
+    zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// 从当前缩放放大或缩小一档后的比例
+fn stepped(current: u32, step: ZoomStep) -> u32 {
+    match step {
+        ZoomStep::In => ZOOM_STEPS.iter().copied().find(|zoom| *zoom > current).unwrap_or(MAX_ZOOM),
+        ZoomStep::Out => ZOOM_STEPS.iter().rev().copied().find(|zoom| *zoom < current).unwrap_or(MIN_ZOOM),
+        ZoomStep::Reset => 100,
+    }
+}
+
+fn monitor_name(window: &WebviewWindow) -> Option<String> {
+    window.current_monitor().ok().flatten().and_then(|m| m.name().cloned())
+}
+
+fn set_zoom(window: &WebviewWindow, webapp: &WebApp, monitor: Option<&str>) {
+    let zoom = zoom_for(webapp, monitor);
+    if let Err(e) = window.set_zoom(zoom as f64 / 100.0) {
+        log::debug!("Could not set zoom for webapp {}: {}", webapp.id, e);
+    }
+}
+
+/// 按窗口当前所在的显示器应用缩放（打开窗口和修改设置后调用）
+pub fn apply(app: &AppHandle, webapp: &WebApp) {
+    let window = match app.get_webview_window(&window_label(&webapp.id)) {
+        Some(window) => window,
+        None => return,
+    };
+    let monitor = monitor_name(&window);
+    set_zoom(&window, webapp, monitor.as_deref());
+    if let Some(state) = app.try_state::<ZoomState>() {
+        state.monitors.lock().insert(webapp.id.clone(), monitor);
+    }
+}
+
+/// 窗口移动或显示器缩放变化时调用：换到另一台显示器或 DPI 变化后重新应用缩放
+pub fn handle_display_change(app: &AppHandle, webapp_id: &str, scale_changed: bool) {
+    let window = match app.get_webview_window(&window_label(webapp_id)) {
+        Some(window) => window,
+        None => return,
+    };
+    let monitor = monitor_name(&window);
+    if let Some(state) = app.try_state::<ZoomState>() {
+        let mut monitors = state.monitors.lock();
+        if !scale_changed && monitors.get(webapp_id) == Some(&monitor) {
+            return;
+        }
+        monitors.insert(webapp_id.to_string(), monitor.clone());
+        // 锁定时键盘缩放过的窗口保持当前的缩放
+        if state.unsaved.lock().contains_key(webapp_id) {
+            return;
+        }
+    }
+
+    let config = app.state::<ConfigManager>().read();
+    if let Some(webapp) = config.webapps.iter().find(|w| w.id == webapp_id) {
+        log::debug!(
+            "Webapp {} moved to monitor {:?}, applying zoom {}%",
+            webapp_id,
+            monitor,
+            zoom_for(webapp, monitor.as_deref())
+        );
+        set_zoom(&window, webapp, monitor.as_deref());
+    }
+}
+
+/// 窗口关闭时清理记录
+pub fn forget(app: &AppHandle, webapp_id: &str) {
+    if let Some(state) = app.try_state::<ZoomState>() {
+        state.monitors.lock().remove(webapp_id);
+        state.unsaved.lock().remove(webapp_id);
+    }
+}
+
+/// 为窗口当前所在的显示器设置单独的缩放（zoom_percent 为 None 时删除该显示器的设置）
+pub fn set_monitor_zoom(app: &AppHandle, webapp_id: &str, zoom_percent: Option<u32>) -> AppResult<WebApp> {
+    let window = app
+        .get_webview_window(&window_label(webapp_id))
+        .ok_or_else(|| AppError::not_found("小程序窗口未打开"))?;
+    let monitor = monitor_name(&window).ok_or_else(|| AppError::window("无法识别窗口所在的显示器"))?;
+
+    let webapp = app.state::<ConfigManager>().update(|config| {
+        let webapp = config
+            .webapps
+            .iter_mut()
+            .find(|w| w.id == webapp_id)
+            .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+        webapp.monitor_zoom.retain(|m| m.monitor != monitor);
+        if let Some(zoom_percent) = zoom_percent {
+            webapp.monitor_zoom.push(MonitorZoom {
+                monitor: monitor.clone(),
+                zoom_percent: zoom_percent.clamp(MIN_ZOOM, MAX_ZOOM),
+            });
+        }
+        Ok::<_, AppError>(webapp.clone())
+    })??;
+
+    apply(app, &webapp);
+    Ok(webapp)
+}
+
+/// 设置小程序的默认缩放并应用到打开的窗口，zoom_percent 为 None 或 100 时不缩放
+pub fn set_zoom_percent(app: &AppHandle, webapp_id: &str, zoom_percent: Option<u32>) -> AppResult<WebApp> {
+    let webapp = app.state::<ConfigManager>().update(|config| {
+        let webapp = config
+            .webapps
+            .iter_mut()
+            .find(|w| w.id == webapp_id)
+            .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+        webapp.zoom_percent = zoom_percent
+            .map(|zoom| zoom.clamp(MIN_ZOOM, MAX_ZOOM))
+            .filter(|zoom| *zoom != 100);
+        Ok::<_, AppError>(webapp.clone())
+    })??;
+
+    apply(app, &webapp);
+    Ok(webapp)
+}
+
+/// 管理员锁定时的键盘缩放：只改变窗口当前的缩放，不修改配置
+fn step_unsaved(app: &AppHandle, webapp_id: &str, step: ZoomStep, monitor: Option<&str>) -> AppResult<WebApp> {
+    let webapp = app
+        .state::<ConfigManager>()
+        .read()
+        .webapps
+        .into_iter()
+        .find(|w| w.id == webapp_id)
+        .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+    let window = app
+        .get_webview_window(&window_label(webapp_id))
+        .ok_or_else(|| AppError::not_found("小程序窗口未打开"))?;
+
+    let state = app.state::<ZoomState>();
+    let mut unsaved = state.unsaved.lock();
+    let current = unsaved
+        .get(webapp_id)
+        .copied()
+        .unwrap_or_else(|| zoom_for(&webapp, monitor));
+    let zoom = stepped(current, step);
+    unsaved.insert(webapp_id.to_string(), zoom);
+    if let Err(e) = window.set_zoom(zoom as f64 / 100.0) {
+        log::debug!("Could not set zoom for webapp {}: {}", webapp_id, e);
+    }
+    log::debug!("Zoomed webapp {} to {}% without saving (config locked)", webapp_id, zoom);
+    Ok(webapp)
+}
+
+/// 放大、缩小或恢复窗口的缩放：窗口所在的显示器有单独的缩放时修改该设置，否则修改默认缩放
+///
+/// 管理员锁定时仍可缩放，但只应用到打开的窗口，不保存到配置
+pub fn step_zoom(app: &AppHandle, webapp_id: &str, step: ZoomStep) -> AppResult<WebApp> {
+    let monitor = app
+        .get_webview_window(&window_label(webapp_id))
+        .and_then(|window| monitor_name(&window));
+    if policy::is_locked(app) {
+        return step_unsaved(app, webapp_id, step, monitor.as_deref());
+    }
+
+    let webapp = app.state::<ConfigManager>().update(|config| {
+        let webapp = config
+            .webapps
+            .iter_mut()
+            .find(|w| w.id == webapp_id)
+            .ok_or_else(|| AppError::not_found("小程序不存在"))?;
+        let zoom = stepped(zoom_for(webapp, monitor.as_deref()), step);
+        let monitor_zoom = monitor
+            .as_ref()
+            .and_then(|name| webapp.monitor_zoom.iter_mut().find(|m| &m.monitor == name));
+        match monitor_zoom {
+            Some(monitor_zoom) => monitor_zoom.zoom_percent = zoom,
+            None => webapp.zoom_percent = Some(zoom).filter(|zoom| *zoom != 100),
+        }
+        Ok::<_, AppError>(webapp.clone())
+    })??;
+
+    log::debug!("Zoomed webapp {} to {}%", webapp_id, zoom_for(&webapp, monitor.as_deref()));
+    apply(app, &webapp);
+    Ok(webapp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_for_monitor_override() {
+        let mut webapp = WebApp::new("Docs".to_string(), "https://docs.example.com".to_string());
+        assert_eq!(zoom_for(&webapp, Some("DELL U2720Q")), 100);
+
+        webapp.zoom_percent = Some(125);
+        webapp.monitor_zoom.push(MonitorZoom {
+            monitor: "DELL U2720Q".to_string(),
+            zoom_percent: 150,
+        });
+        assert_eq!(zoom_for(&webapp, Some("DELL U2720Q")), 150);
+        assert_eq!(zoom_for(&webapp, Some("Built-in Retina Display")), 125);
+        assert_eq!(zoom_for(&webapp, None), 125);
+
+        webapp.zoom_percent = Some(5000);
+        assert_eq!(zoom_for(&webapp, None), MAX_ZOOM);
+    }
+
+    #[test]
+    fn test_zoom_steps() {
+        assert_eq!(stepped(100, ZoomStep::In), 110);
+        assert_eq!(stepped(100, ZoomStep::Out), 90);
+        assert_eq!(stepped(90, ZoomStep::Out), 80);
+        // 不在档位上的缩放移到相邻的档位
+        assert_eq!(stepped(85, ZoomStep::In), 90);
+        assert_eq!(stepped(85, ZoomStep::Out), 80);
+        assert_eq!(stepped(MAX_ZOOM, ZoomStep::In), MAX_ZOOM);
+        assert_eq!(stepped(MIN_ZOOM, ZoomStep::Out), MIN_ZOOM);
+        assert_eq!(stepped(250, ZoomStep::Reset), 100);
+    }
+}
@@ -0,0 +1,230 @@
+//! 快捷键到窗口的端到端流程：快捷键注册、切换窗口、LRU 淘汰，以及重新加载配置后的变化
+//!
+//! 运行：`cargo test --features e2e --test shortcut_flows`
+//!
+//! 配置管理器、快捷键管理器和窗口管理器使用正式的实现，窗口和系统快捷键换成内存中的后端
+//! （`webapp_hub_lib::e2e`，通过 `WindowBackend`、`WindowOpener` 和 `ShortcutRegistrar`），不需要创建 WebView
+
+use std::path::PathBuf;
+
+use webapp_hub_lib::e2e::{
+    desired_bindings, window_label, AppConfig, ConfigManager, JsonFileStore, MockShortcuts, MockWindows,
+    ShortcutAction, ShortcutManager, ToggleResult, WebApp, WindowManager, WindowStatus,
+};
+
+/// 没有界面的 WebApp Hub：按正式运行时的顺序调用各个管理器
+struct Hub {
+    dir: PathBuf,
+    config: ConfigManager,
+    shortcuts: ShortcutManager,
+    windows: WindowManager,
+    system_shortcuts: MockShortcuts,
+    system_windows: MockWindows,
+}
+
+impl Hub {
+    /// 用指定的配置启动，`taken` 中的快捷键模拟已被其他程序占用
+    fn start(config: &AppConfig, taken: &[&str]) -> Self {
+        let dir = std::env::temp_dir().join(format!("webapp-hub-e2e-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+        std::fs::write(&path, serde_json::to_string(config).unwrap()).unwrap();
+
+        let config = ConfigManager::new(Box::new(JsonFileStore::new(path)));
+        let windows = WindowManager::new(config.read().max_active_windows);
+        let system_shortcuts = MockShortcuts::default();
+        system_shortcuts.taken.lock().extend(taken.iter().map(|s| s.to_string()));
+
+        let hub = Self {
+            dir,
+            config,
+            shortcuts: ShortcutManager::new(),
+            windows,
+            system_shortcuts,
+            system_windows: MockWindows::default(),
+        };
+        hub.load_shortcuts();
+        hub
+    }
+
+    /// 与 `load_shortcuts_from_config` 相同：按配置增量同步快捷键
+    fn load_shortcuts(&self) {
+        self.shortcuts
+            .sync(&self.system_shortcuts, &desired_bindings(&self.config.read()));
+    }
+
+    /// 在外部修改配置文件并重新加载（与修改设置后 `apply_config` 的处理相同）
+    fn edit_config_file(&self, edit: impl FnOnce(&mut AppConfig)) {
+        assert!(self.config.flush());
+        let mut config = self.config.read();
+        edit(&mut config);
+        std::fs::write(self.dir.join("config.json"), serde_json::to_string(&config).unwrap()).unwrap();
+
+        self.config.reload().unwrap();
+        self.windows.set_max_windows(self.config.read().max_active_windows);
+        self.load_shortcuts();
+    }
+
+    fn webapp(&self, name: &str) -> WebApp {
+        self.config
+            .read()
+            .webapps
+            .into_iter()
+            .find(|w| w.name == name)
+            .unwrap()
+    }
+
+    /// 按下注册到系统的快捷键：像正式的回调一样先检查注册是否过期，再切换窗口（没有窗口时打开）
+    fn press(&self, shortcut: &str) -> ToggleResult {
+        let action = self.system_shortcuts.registered.lock().get(shortcut).cloned();
+        let generation = self.system_shortcuts.generations.lock().get(shortcut).copied();
+        let (Some(action), Some(generation)) = (action, generation) else {
+            panic!("快捷键 {} 没有注册到系统", shortcut);
+        };
+        assert!(self.shortcuts.is_current(generation, shortcut, &action), "快捷键 {} 的注册已过期", shortcut);
+        let ShortcutAction::ToggleWebApp { webapp_id } = action else {
+            panic!("快捷键 {} 不是切换小程序: {:?}", shortcut, action);
+        };
+        let webapp = self.config.read().webapps.into_iter().find(|w| w.id == webapp_id).unwrap();
+        self.windows
+            .toggle_with(&self.system_windows, &webapp_id, || {
+                self.windows.open_new(&self.system_windows, &webapp)
+            })
+            .unwrap()
+    }
+
+    fn status(&self, name: &str) -> Option<WindowStatus> {
+        self.system_windows.get(&window_label(&self.webapp(name).id))
+    }
+
+    /// 活跃窗口的小程序名称（最近使用的在前）
+    fn active(&self) -> Vec<String> {
+        let config = self.config.read();
+        self.windows
+            .get_active_window_ids()
+            .iter()
+            .map(|id| config.webapps.iter().find(|w| &w.id == id).unwrap().name.clone())
+            .collect()
+    }
+}
+
+impl Drop for Hub {
+    fn drop(&mut self) {
+        self.config.flush();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn config_with(webapps: &[(&str, &str)], max_active_windows: usize) -> AppConfig {
+    let webapps = webapps
+        .iter()
+        .enumerate()
+        .map(|(i, (name, shortcut))| {
+            let mut webapp = WebApp::new(name.to_string(), format!("https://{}.example.com", name.to_lowercase()));
+            webapp.order = i as u32;
+            webapp.shortcut = Some(shortcut.to_string()).filter(|s| !s.is_empty());
+            webapp
+        })
+        .collect();
+    AppConfig {
+        max_active_windows,
+        webapps,
+        ..AppConfig::default()
+    }
+}
+
+#[test]
+fn shortcut_toggles_window_through_its_lifecycle() {
+    let hub = Hub::start(&config_with(&[("Mail", "Ctrl+1"), ("Chat", "Ctrl+2")], 5), &[]);
+
+    assert_eq!(hub.press("Ctrl+1"), ToggleResult::CreatedNew);
+    let status = hub.status("Mail").unwrap();
+    assert!(status.visible && status.focused);
+
+    // 可见且有焦点时隐藏，再按一次显示
+    assert_eq!(hub.press("Ctrl+1"), ToggleResult::Hidden);
+    assert!(!hub.status("Mail").unwrap().visible);
+    assert_eq!(hub.press("Ctrl+1"), ToggleResult::ShownExisting);
+    assert!(hub.status("Mail").unwrap().focused);
+
+    // 可见但焦点在其他窗口时取回焦点，而不是隐藏
+    assert_eq!(hub.press("Ctrl+2"), ToggleResult::CreatedNew);
+    assert!(!hub.status("Mail").unwrap().focused);
+    assert_eq!(hub.press("Ctrl+1"), ToggleResult::ShownExisting);
+    let status = hub.status("Mail").unwrap();
+    assert!(status.visible && status.focused);
+    assert_eq!(hub.active(), ["Mail", "Chat"]);
+}
+
+#[test]
+fn opening_past_the_limit_evicts_least_recently_used() {
+    let hub = Hub::start(
+        &config_with(&[("Mail", "Ctrl+1"), ("Chat", "Ctrl+2"), ("Docs", "Ctrl+3")], 2),
+        &[],
+    );
+
+    hub.press("Ctrl+1");
+    hub.press("Ctrl+2");
+    // 通过快捷键取回焦点也算使用，Chat 成为最久未使用的窗口
+    assert_eq!(hub.press("Ctrl+1"), ToggleResult::ShownExisting);
+    assert_eq!(hub.press("Ctrl+3"), ToggleResult::CreatedNew);
+    assert_eq!(hub.active(), ["Docs", "Mail"]);
+    assert_eq!(hub.status("Chat"), None);
+
+    // 有未保存内容的窗口不会被淘汰
+    hub.windows.set_dirty(&hub.webapp("Mail").id, true);
+    assert_eq!(hub.windows.next_eviction(), Some(hub.webapp("Docs").id));
+    assert_eq!(hub.press("Ctrl+2"), ToggleResult::CreatedNew);
+    assert_eq!(hub.active(), ["Chat", "Mail"]);
+    assert_eq!(hub.status("Docs"), None);
+
+    // 全部都有未保存内容时暂时超出上限
+    hub.windows.set_dirty(&hub.webapp("Chat").id, true);
+    let evictions = hub.windows.take_evictions();
+    assert!(evictions.evicted.is_empty());
+    assert_eq!(evictions.skipped.len(), 2);
+    assert_eq!(hub.press("Ctrl+3"), ToggleResult::CreatedNew);
+    assert_eq!(hub.active(), ["Docs", "Chat", "Mail"]);
+}
+
+#[test]
+fn config_reload_updates_shortcuts_and_window_limit() {
+    let hub = Hub::start(
+        &config_with(&[("Mail", "Ctrl+1"), ("Chat", "Ctrl+2"), ("Docs", "Ctrl+3")], 5),
+        &["Ctrl+3"],
+    );
+    assert_eq!(hub.system_shortcuts.registered.lock().len(), 2);
+    let failures = hub.shortcuts.get_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].shortcut, "Ctrl+3");
+
+    hub.press("Ctrl+1");
+    hub.press("Ctrl+2");
+
+    // 改绑 Mail、停用 Chat、把 Docs 换到没有被占用的快捷键，并把窗口上限降为 2
+    hub.edit_config_file(|config| {
+        for webapp in &mut config.webapps {
+            match webapp.name.as_str() {
+                "Mail" => webapp.shortcut = Some("Ctrl+4".to_string()),
+                "Chat" => webapp.enabled = false,
+                _ => webapp.shortcut = Some("Ctrl+5".to_string()),
+            }
+        }
+        config.max_active_windows = 2;
+    });
+
+    let mut registered: Vec<String> = hub.system_shortcuts.registered.lock().keys().cloned().collect();
+    registered.sort();
+    assert_eq!(registered, ["Ctrl+4", "Ctrl+5"]);
+    assert!(hub.shortcuts.get_failures().is_empty());
+    assert_eq!(hub.shortcuts.get_action("Ctrl+1"), None);
+
+    // 改绑后的快捷键操作同一个窗口
+    assert_eq!(hub.press("Ctrl+4"), ToggleResult::ShownExisting);
+    assert_eq!(hub.active(), ["Mail", "Chat"]);
+
+    // 新的上限在下次打开窗口时生效
+    assert_eq!(hub.press("Ctrl+5"), ToggleResult::CreatedNew);
+    assert_eq!(hub.active(), ["Docs", "Mail"]);
+    assert_eq!(hub.status("Chat"), None);
+}
@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { Toaster } from '@/components/ui/Toaster';
 import { AppSidebar } from '@/components/layout/AppSidebar';
 import { WebAppView } from '@/components/WebAppView';
@@ -28,6 +29,16 @@ function App() {
     loadConfig();
   }, [loadConfig]);
 
+  // 配置文件被外部修改（手动编辑或云盘同步）后重新加载
+  useEffect(() => {
+    const unlisten = listen('config://changed', () => {
+      loadConfig();
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [loadConfig]);
+
   const handleNavigate = (view: ViewType) => {
     if (view === 'webapp' && appState.activeWebAppId) {
       setAppState({ view: 'webapp', activeWebAppId: appState.activeWebAppId });
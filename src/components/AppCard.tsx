@@ -4,7 +4,7 @@ import { useAppStore } from '@/stores/appStore';
 import { Dialog, DialogContent } from '@/components/ui/Dialog';
 import { Button } from '@/components/ui/Button';
 import { Input } from '@/components/ui/Input';
-import { useShortcutRecorder } from '@/hooks/useTauri';
+import { useShortcutRecorder, useWebAppIcon } from '@/hooks/useTauri';
 import { ExternalLink, Edit2, Trash2, Keyboard, Globe, Code, ChevronDown, ChevronRight } from 'lucide-react';
 import type { WebApp } from '@/types';
 
@@ -24,7 +24,7 @@ export function AppCard({ webapp, onOpen }: AppCardProps) {
     }
   };
 
-  const iconUrl = webapp.icon || getFaviconUrl(webapp.url);
+  const iconUrl = useWebAppIcon(webapp.icon) || getFaviconUrl(webapp.url);
 
   return (
     <>
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { cn } from '@/lib/utils';
 import { ExternalLink, Loader2, Monitor } from 'lucide-react';
 import { openUrl } from '@tauri-apps/plugin-opener';
+import { useWebAppIcon } from '@/hooks/useTauri';
 import type { WebApp } from '@/types';
 
 interface WebAppViewProps {
@@ -12,6 +13,7 @@ interface WebAppViewProps {
 export function WebAppView({ webapp }: WebAppViewProps) {
   const [isOpening, setIsOpening] = useState(false);
   const [hasOpened, setHasOpened] = useState(false);
+  const iconUrl = useWebAppIcon(webapp.icon);
 
   useEffect(() => {
     // 当选择新的 webapp 时，自动打开窗口
@@ -43,9 +45,9 @@ export function WebAppView({ webapp }: WebAppViewProps) {
       <div className="max-w-md text-center">
         {/* App Icon */}
         <div className="w-20 h-20 rounded-2xl bg-gradient-to-br from-hub-accent/20 to-hub-accent/5 flex items-center justify-center mx-auto mb-6">
-          {webapp.icon ? (
+          {iconUrl ? (
             <img
-              src={webapp.icon}
+              src={iconUrl}
               alt={webapp.name}
               className="w-12 h-12 rounded-lg object-contain"
             />
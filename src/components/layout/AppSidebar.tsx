@@ -1,5 +1,6 @@
 import { cn, getFaviconUrl } from '@/lib/utils';
 import { Settings, Plus, LayoutGrid, Globe } from 'lucide-react';
+import { useWebAppIcon } from '@/hooks/useTauri';
 import type { WebApp } from '@/types';
 import type { ViewType } from '@/App';
 
@@ -61,7 +62,6 @@ export function AppSidebar({
       <nav className="flex-1 flex flex-col items-center gap-1.5 overflow-y-auto w-full px-3">
         {webapps.map((webapp) => {
           const isActive = currentView === 'webapp' && activeWebAppId === webapp.id;
-          const iconUrl = webapp.icon || getFaviconUrl(webapp.url);
           
           return (
             <button
@@ -76,18 +76,7 @@ export function AppSidebar({
               )}
               title={webapp.name}
             >
-              {iconUrl ? (
-                <img
-                  src={iconUrl}
-                  alt={webapp.name}
-                  className="w-6 h-6 rounded object-contain"
-                  onError={(e) => {
-                    (e.target as HTMLImageElement).style.display = 'none';
-                    (e.target as HTMLImageElement).nextElementSibling?.classList.remove('hidden');
-                  }}
-                />
-              ) : null}
-              <Globe className={cn('w-5 h-5 text-hub-text-muted', iconUrl && 'hidden')} />
+              <SidebarIcon webapp={webapp} />
               
               {/* Active indicator */}
               {isActive && (
@@ -138,3 +127,24 @@ export function AppSidebar({
   );
 }
 
+function SidebarIcon({ webapp }: { webapp: WebApp }) {
+  const iconUrl = useWebAppIcon(webapp.icon) || getFaviconUrl(webapp.url);
+
+  return (
+    <>
+      {iconUrl ? (
+        <img
+          src={iconUrl}
+          alt={webapp.name}
+          className="w-6 h-6 rounded object-contain"
+          onError={(e) => {
+            (e.target as HTMLImageElement).style.display = 'none';
+            (e.target as HTMLImageElement).nextElementSibling?.classList.remove('hidden');
+          }}
+        />
+      ) : null}
+      <Globe className={cn('w-5 h-5 text-hub-text-muted', iconUrl && 'hidden')} />
+    </>
+  );
+}
+
@@ -1,5 +1,8 @@
 import { useEffect, useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { getCurrentWindow } from '@tauri-apps/api/window';
+import type { IconTheme } from '@/types';
 
 /**
  * Hook for window controls
@@ -152,3 +155,66 @@ export function useShortcutRecorder() {
   };
 }
 
+const ICON_REF_PREFIX = 'icon-ref:';
+const iconCache = new Map<string, Promise<string>>();
+const iconThemeListeners = new Set<() => void>();
+let iconThemeWatched = false;
+
+// 切换图标主题后清空缓存，已显示的图标重新获取
+function watchIconTheme(onChange: () => void): () => void {
+  if (!iconThemeWatched) {
+    iconThemeWatched = true;
+    listen('icons://theme-changed', () => {
+      iconCache.clear();
+      iconThemeListeners.forEach((listener) => listener());
+    });
+  }
+  iconThemeListeners.add(onChange);
+  return () => {
+    iconThemeListeners.delete(onChange);
+  };
+}
+
+/**
+ * Hook for resolving webapp icons
+ * get_config 返回的内嵌图标为 `icon-ref:<id>` 引用，按需获取图标数据并缓存为 blob URL；
+ * 未指定 theme 时使用设置中的图标主题
+ */
+export function useWebAppIcon(icon?: string, theme?: IconTheme): string | undefined {
+  const [url, setUrl] = useState(() =>
+    icon && !icon.startsWith(ICON_REF_PREFIX) ? icon : undefined
+  );
+  const [themeVersion, setThemeVersion] = useState(0);
+
+  useEffect(() => watchIconTheme(() => setThemeVersion((v) => v + 1)), []);
+
+  useEffect(() => {
+    if (!icon || !icon.startsWith(ICON_REF_PREFIX)) {
+      setUrl(icon);
+      return;
+    }
+
+    let cancelled = false;
+    const key = theme ? `${icon}#${theme}` : icon;
+    let pending = iconCache.get(key);
+    if (!pending) {
+      const webappId = icon.slice(ICON_REF_PREFIX.length);
+      pending = invoke<ArrayBuffer>('get_webapp_icon', { webappId, theme }).then((data) =>
+        URL.createObjectURL(new Blob([data]))
+      );
+      // 失败时移除缓存，下次重新获取
+      pending.catch(() => iconCache.delete(key));
+      iconCache.set(key, pending);
+    }
+    pending
+      .then((resolved) => !cancelled && setUrl(resolved))
+      .catch((err) => console.error('Failed to load webapp icon:', err));
+
+    return () => {
+      cancelled = true;
+    };
+  }, [icon, theme, themeVersion]);
+
+  return url;
+}
+
@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '@/lib/utils';
 import type {
   WebApp,
   AppConfig,
@@ -65,7 +66,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       const config = await invoke<AppConfig>('get_config');
       set({ config, isLoading: false });
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       set({ error, isLoading: false });
       get().addToast({
         type: 'error',
@@ -88,7 +89,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         title: '配置已保存',
       });
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       get().addToast({
         type: 'error',
         title: '保存配置失败',
@@ -101,17 +102,7 @@ export const useAppStore = create<AppState>((set, get) => ({
   // 添加小程序
   addWebApp: async (input) => {
     try {
-      const webapp = await invoke<WebApp>('add_webapp', {
-        name: input.name,
-        url: input.url,
-        icon: input.icon,
-        shortcut: input.shortcut,
-        width: input.width,
-        height: input.height,
-        injectScript: input.injectScript,
-        injectOnLoad: input.injectOnLoad,
-        injectOnShortcut: input.injectOnShortcut,
-      });
+      const webapp = await invoke<WebApp>('add_webapp', { input });
 
       set((state) => ({
         config: {
@@ -128,7 +119,7 @@ export const useAppStore = create<AppState>((set, get) => ({
 
       return webapp;
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       get().addToast({
         type: 'error',
         title: '添加小程序失败',
@@ -141,20 +132,7 @@ export const useAppStore = create<AppState>((set, get) => ({
   // 更新小程序
   updateWebApp: async (input) => {
     try {
-      const webapp = await invoke<WebApp>('update_webapp', {
-        id: input.id,
-        name: input.name,
-        url: input.url,
-        icon: input.icon,
-        shortcut: input.shortcut,
-        width: input.width,
-        height: input.height,
-        useProxy: input.useProxy,
-        order: input.order,
-        injectScript: input.injectScript,
-        injectOnLoad: input.injectOnLoad,
-        injectOnShortcut: input.injectOnShortcut,
-      });
+      const webapp = await invoke<WebApp>('update_webapp', { patch: input });
 
       set((state) => ({
         config: {
@@ -173,7 +151,7 @@ export const useAppStore = create<AppState>((set, get) => ({
 
       return webapp;
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       get().addToast({
         type: 'error',
         title: '更新小程序失败',
@@ -200,7 +178,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         title: '小程序已删除',
       });
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       get().addToast({
         type: 'error',
         title: '删除小程序失败',
@@ -215,7 +193,7 @@ export const useAppStore = create<AppState>((set, get) => ({
     try {
       await invoke('open_webapp', { id });
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       get().addToast({
         type: 'error',
         title: '打开小程序失败',
@@ -230,7 +208,7 @@ export const useAppStore = create<AppState>((set, get) => ({
     try {
       await invoke('close_webapp', { id });
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       get().addToast({
         type: 'error',
         title: '关闭小程序失败',
@@ -258,8 +236,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       await Promise.all(
         webapps.map((webapp, i) =>
           invoke('update_webapp', {
-            id: webapp.id,
-            order: i,
+            patch: { id: webapp.id, order: i },
           })
         )
       );
@@ -275,7 +252,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       get().addToast({
         type: 'error',
         title: '排序失败',
-        description: err instanceof Error ? err.message : String(err),
+        description: errorMessage(err),
       });
     }
   },
@@ -296,7 +273,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         description: `最大活跃窗口数: ${max}`,
       });
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       get().addToast({
         type: 'error',
         title: '设置失败',
@@ -321,7 +298,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         title: '代理设置已更新',
       });
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = errorMessage(err);
       get().addToast({
         type: 'error',
         title: '代理设置失败',
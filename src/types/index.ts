@@ -34,6 +34,7 @@ export interface AppConfig {
   proxy: ProxyConfig;
   maxActiveWindows: number;
   mainWindowShortcut?: string;
+  muteAllShortcut?: string;
   autoStart: boolean;
   minimizeToTray: boolean;
 }
@@ -99,6 +100,93 @@ export interface Toast {
   duration?: number;
 }
 
+// 后端命令返回的错误
+export type AppErrorCode =
+  | 'notFound'
+  | 'shortcutConflict'
+  | 'invalidShortcut'
+  | 'invalidProxy'
+  | 'invalidInput'
+  | 'locked'
+  | 'io'
+  | 'window'
+  | 'other';
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+}
+
+// 事件频道（通过 subscribe 命令订阅，默认不接收 requests）
+export type EventChannel =
+  | 'windows'
+  | 'config'
+  | 'shortcuts'
+  | 'downloads'
+  | 'badges'
+  | 'prompts'
+  | 'requests';
+
+// 小程序使用统计（get_webapp_stats 返回，按使用频率排序）
+export interface WebAppStats {
+  webappId: string;
+  name: string;
+  crashes: number;
+  lastCrashAt?: number | null;
+  openCount: number;
+  lastOpenedAt?: number | null;
+  foregroundMs: number;
+}
+
+// 配置的存储方式（get_storage_backend / set_storage_backend）
+export type StorageBackend = 'json' | 'sqlite';
+
+// 小程序窗口的下载（list_downloads 返回，download://started / download://finished 事件的内容）
+export interface DownloadItem {
+  id: string;
+  webappId: string;
+  url: string;
+  path: string;
+  state: 'inProgress' | 'completed' | 'failed';
+  startedAt: number;
+  finishedAt?: number | null;
+}
+
+// 小程序窗口的背景效果（add_webapp / update_webapp 的 windowBackground）
+export type WindowBackground = 'opaque' | 'vibrancy' | 'acrylic' | 'mica';
+
+// 安全加固预设（add_webapp / update_webapp 的 securityPreset）
+export type SecurityPreset = 'off' | 'balanced' | 'strict';
+
+// 停靠的屏幕边缘（add_webapp / update_webapp 的 dockEdge）
+export type DockEdge = 'off' | 'left' | 'right' | 'top' | 'bottom';
+
+// 启动器图标主题（set_icon_theme 的 theme，get_webapp_icon 的可选 theme）
+export type IconTheme = 'original' | 'monochrome' | 'rounded';
+
+// 快速切换窗口的搜索结果（search_webapps 返回）
+export interface SwitcherResult {
+  webappId: string;
+  name: string;
+  url: string;
+  icon?: string | null;
+  badge?: number | null;
+  running: boolean;
+}
+
+// 静默时段状态
+export interface QuietHoursStatus {
+  active: boolean;
+  scheduled: boolean;
+  overrideUntil?: number | null;
+}
+
+// 安全模式状态
+export interface SafeModeStatus {
+  active: boolean;
+  automatic: boolean;
+}
+
 // 视图类型
 export type ViewType = 'apps' | 'settings';
 